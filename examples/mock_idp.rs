@@ -0,0 +1,259 @@
+// examples/mock_idp.rs
+// Identity Provider จำลองสำหรับทดสอบ Login ผ่าน OIDC บนเครื่อง (ห้ามใช้งานจริง)
+//
+//   cargo run --example mock_idp
+//
+// แล้วรัน API ด้วย OIDC_ISSUER_URL=http://127.0.0.1:9000 OIDC_CLIENT_ID=room-booking
+// หน้า /authorize แสดงฟอร์มให้กรอก username และกลุ่ม (ไม่ตรวจรหัสผ่าน)
+// ID Token ถูกเซ็นด้วย EdDSA key ที่สุ่มใหม่ทุกครั้งที่เริ่ม (kid = "mock-1")
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::{Html, IntoResponse, Redirect, Response},
+    routing::{get, post},
+    Form, Json, Router,
+};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use ed25519_dalek::pkcs8::{spki::der::pem::LineEnding, EncodePrivateKey};
+use jsonwebtoken::jwk::{
+    AlgorithmParameters, CommonParameters, EllipticCurve, Jwk, JwkSet, KeyAlgorithm, OctetKeyPairParameters,
+    OctetKeyPairType, PublicKeyUse,
+};
+use jsonwebtoken::{encode, Algorithm, EncodingKey, Header};
+use rand::RngCore;
+use serde::Deserialize;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+
+const KID: &str = "mock-1";
+
+// Authorization Code ที่ออกแล้วแต่ยังไม่ถูกแลก
+struct PendingCode {
+    client_id: String,
+    redirect_uri: String,
+    nonce: Option<String>,
+    code_challenge: String,
+    username: String,
+    groups: Vec<String>,
+}
+
+struct Idp {
+    issuer: String,
+    encoding: EncodingKey,
+    jwks: JwkSet,
+    codes: Mutex<HashMap<String, PendingCode>>,
+}
+
+#[derive(Deserialize)]
+struct AuthorizeParams {
+    response_type: String,
+    client_id: String,
+    redirect_uri: String,
+    state: Option<String>,
+    nonce: Option<String>,
+    code_challenge: Option<String>,
+    code_challenge_method: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ApproveForm {
+    client_id: String,
+    redirect_uri: String,
+    state: Option<String>,
+    nonce: Option<String>,
+    code_challenge: String,
+    username: String,
+    groups: String, // คั่นด้วย comma
+}
+
+#[derive(Deserialize)]
+struct TokenForm {
+    grant_type: String,
+    code: String,
+    redirect_uri: String,
+    client_id: String,
+    code_verifier: Option<String>,
+}
+
+fn random_token() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn token_error(error: &str) -> Response {
+    (StatusCode::BAD_REQUEST, Json(json!({ "error": error }))).into_response()
+}
+
+async fn discovery(State(idp): State<Arc<Idp>>) -> Json<serde_json::Value> {
+    Json(json!({
+        "issuer": idp.issuer,
+        "authorization_endpoint": format!("{}/authorize", idp.issuer),
+        "token_endpoint": format!("{}/token", idp.issuer),
+        "jwks_uri": format!("{}/jwks", idp.issuer),
+        "response_types_supported": ["code"],
+        "subject_types_supported": ["public"],
+        "id_token_signing_alg_values_supported": ["EdDSA"],
+        "code_challenge_methods_supported": ["S256"],
+        "scopes_supported": ["openid", "profile", "email", "groups"],
+    }))
+}
+
+async fn jwks(State(idp): State<Arc<Idp>>) -> Json<JwkSet> {
+    Json(idp.jwks.clone())
+}
+
+// หน้าฟอร์ม "Login" ของ Identity Provider (ต้องใช้ PKCE แบบ S256)
+async fn authorize_form(Query(params): Query<AuthorizeParams>) -> Response {
+    if params.response_type != "code" {
+        return (StatusCode::BAD_REQUEST, "response_type must be 'code'").into_response();
+    }
+    let (Some(challenge), Some("S256")) = (params.code_challenge.as_deref(), params.code_challenge_method.as_deref()) else {
+        return (StatusCode::BAD_REQUEST, "PKCE with code_challenge_method=S256 is required").into_response();
+    };
+
+    let hidden = [
+        ("client_id", Some(params.client_id.as_str())),
+        ("redirect_uri", Some(params.redirect_uri.as_str())),
+        ("state", params.state.as_deref()),
+        ("nonce", params.nonce.as_deref()),
+        ("code_challenge", Some(challenge)),
+    ]
+    .into_iter()
+    .filter_map(|(name, value)| value.map(|v| format!(r#"<input type="hidden" name="{}" value="{}">"#, name, escape(v))))
+    .collect::<String>();
+
+    Html(format!(
+        r#"<!doctype html><title>Mock IdP</title><h1>Mock identity provider</h1>
+<form method="post" action="/authorize">{}
+<p><label>Username <input name="username" value="staff.member"></label></p>
+<p><label>Groups (comma-separated) <input name="groups" value="staff"></label></p>
+<p><button type="submit">Sign in</button></p></form>"#,
+        hidden
+    ))
+    .into_response()
+}
+
+// อนุมัติ Login: ออก Authorization Code แล้ว Redirect กลับไปที่ redirect_uri
+async fn authorize_approve(State(idp): State<Arc<Idp>>, Form(form): Form<ApproveForm>) -> Response {
+    let code = random_token();
+    let mut location = match reqwest::Url::parse(&form.redirect_uri) {
+        Ok(url) => url,
+        Err(_) => return (StatusCode::BAD_REQUEST, "invalid redirect_uri").into_response(),
+    };
+    location.query_pairs_mut().append_pair("code", &code);
+    if let Some(state) = &form.state {
+        location.query_pairs_mut().append_pair("state", state);
+    }
+
+    idp.codes.lock().unwrap().insert(
+        code,
+        PendingCode {
+            client_id: form.client_id,
+            redirect_uri: form.redirect_uri,
+            nonce: form.nonce,
+            code_challenge: form.code_challenge,
+            username: form.username.trim().to_string(),
+            groups: form.groups.split(',').map(str::trim).filter(|g| !g.is_empty()).map(str::to_string).collect(),
+        },
+    );
+    Redirect::to(location.as_str()).into_response()
+}
+
+// แลก Authorization Code (ใช้ได้ครั้งเดียว) เป็น ID Token หลังตรวจ PKCE
+async fn token(State(idp): State<Arc<Idp>>, Form(form): Form<TokenForm>) -> Response {
+    if form.grant_type != "authorization_code" {
+        return token_error("unsupported_grant_type");
+    }
+    let Some(pending) = idp.codes.lock().unwrap().remove(&form.code) else {
+        return token_error("invalid_grant");
+    };
+    if pending.client_id != form.client_id || pending.redirect_uri != form.redirect_uri {
+        return token_error("invalid_grant");
+    }
+    let verifier = form.code_verifier.unwrap_or_default();
+    if URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes())) != pending.code_challenge {
+        return token_error("invalid_grant");
+    }
+
+    let now = chrono::Utc::now().timestamp();
+    let claims = json!({
+        "iss": idp.issuer,
+        "sub": format!("mock|{}", pending.username),
+        "aud": pending.client_id,
+        "iat": now,
+        "exp": now + 300,
+        "nonce": pending.nonce,
+        "preferred_username": pending.username,
+        "name": format!("Mock {}", pending.username),
+        "email": format!("{}@example.com", pending.username),
+        "groups": pending.groups,
+    });
+    let mut header = Header::new(Algorithm::EdDSA);
+    header.kid = Some(KID.to_string());
+    match encode(&header, &claims, &idp.encoding) {
+        Ok(id_token) => Json(json!({
+            "access_token": random_token(),
+            "token_type": "Bearer",
+            "expires_in": 300,
+            "id_token": id_token,
+        }))
+        .into_response(),
+        Err(_) => (StatusCode::INTERNAL_SERVER_ERROR, "failed to sign ID token").into_response(),
+    }
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let port = std::env::var("MOCK_IDP_PORT").unwrap_or_else(|_| "9000".to_string());
+    let issuer = std::env::var("MOCK_IDP_ISSUER").unwrap_or_else(|_| format!("http://127.0.0.1:{}", port));
+
+    let mut secret = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut secret);
+    let signing_key = ed25519_dalek::SigningKey::from_bytes(&secret);
+    let pem = signing_key.to_pkcs8_pem(LineEnding::LF)?;
+    let jwk = Jwk {
+        common: CommonParameters {
+            public_key_use: Some(PublicKeyUse::Signature),
+            key_id: Some(KID.to_string()),
+            key_algorithm: Some(KeyAlgorithm::EdDSA),
+            ..Default::default()
+        },
+        algorithm: AlgorithmParameters::OctetKeyPair(OctetKeyPairParameters {
+            key_type: OctetKeyPairType::OctetKeyPair,
+            curve: EllipticCurve::Ed25519,
+            x: URL_SAFE_NO_PAD.encode(signing_key.verifying_key().as_bytes()),
+        }),
+    };
+
+    let idp = Arc::new(Idp {
+        issuer: issuer.clone(),
+        encoding: EncodingKey::from_ed_pem(pem.as_bytes())?,
+        jwks: JwkSet { keys: vec![jwk] },
+        codes: Mutex::new(HashMap::new()),
+    });
+
+    let app = Router::new()
+        .route("/.well-known/openid-configuration", get(discovery))
+        .route("/jwks", get(jwks))
+        .route("/authorize", get(authorize_form).post(authorize_approve))
+        .route("/token", post(token))
+        .with_state(idp);
+
+    let listener = tokio::net::TcpListener::bind(format!("127.0.0.1:{}", port)).await?;
+    println!("mock IdP listening on {} (issuer {})", listener.local_addr()?, issuer);
+    axum::serve(listener, app).await?;
+    Ok(())
+}
@@ -0,0 +1,137 @@
+// examples/mock_ldap.rs
+// LDAP directory จำลองสำหรับทดสอบ Login ผ่าน LDAP บนเครื่อง (ห้ามใช้งานจริง)
+//
+//   cargo run --example mock_ldap
+//
+// แล้วรัน API ด้วย AUTH_PROVIDERS=ldap,local LDAP_URL=ldap://127.0.0.1:3389 LDAP_BASE_DN=dc=example,dc=com
+// LDAP_BIND_DN=cn=search,dc=example,dc=com LDAP_BIND_PASSWORD=search-secret
+// ผู้ใช้ทดสอบ (รหัสผ่าน "password"): alice (กลุ่ม staff, room-admins), bob (กลุ่ม staff)
+// รองรับเฉพาะ simple bind และ search ด้วย filter แบบ (attr=value) หรือ (&...)/(|...) ของเงื่อนไขเหล่านั้น
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use futures_util::{SinkExt, StreamExt};
+use ldap3_proto::simple::{
+    LdapFilter, LdapMsg, LdapPartialAttribute, LdapResultCode, LdapSearchResultEntry, SearchRequest, ServerOps,
+    SimpleBindRequest,
+};
+use ldap3_proto::LdapCodec;
+use tokio::net::{TcpListener, TcpStream};
+use tokio_util::codec::{FramedRead, FramedWrite};
+
+const SEARCH_DN: &str = "cn=search,dc=example,dc=com";
+const SEARCH_PASSWORD: &str = "search-secret";
+
+struct Entry {
+    dn: String,
+    password: String,
+    attributes: Vec<(String, Vec<String>)>,
+}
+
+fn person(uid: &str, name: &str, groups: &[&str]) -> Entry {
+    Entry {
+        dn: format!("uid={},ou=people,dc=example,dc=com", uid),
+        password: "password".to_string(),
+        attributes: vec![
+            ("objectClass".to_string(), vec!["inetOrgPerson".to_string()]),
+            ("uid".to_string(), vec![uid.to_string()]),
+            ("displayName".to_string(), vec![name.to_string()]),
+            (
+                "memberOf".to_string(),
+                groups.iter().map(|g| format!("cn={},ou=groups,dc=example,dc=com", g)).collect(),
+            ),
+        ],
+    }
+}
+
+impl Entry {
+    fn values(&self, name: &str) -> &[String] {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, values)| values.as_slice())
+            .unwrap_or(&[])
+    }
+
+    fn matches(&self, filter: &LdapFilter) -> bool {
+        match filter {
+            LdapFilter::And(filters) => filters.iter().all(|f| self.matches(f)),
+            LdapFilter::Or(filters) => filters.iter().any(|f| self.matches(f)),
+            LdapFilter::Not(filter) => !self.matches(filter),
+            LdapFilter::Equality(name, value) => self.values(name).iter().any(|v| v.eq_ignore_ascii_case(value)),
+            LdapFilter::Present(name) => !self.values(name).is_empty(),
+            _ => false,
+        }
+    }
+}
+
+fn bind(directory: &[Entry], request: &SimpleBindRequest) -> LdapMsg {
+    let accepted = (request.dn == SEARCH_DN && request.pw == SEARCH_PASSWORD)
+        || (request.dn.is_empty() && request.pw.is_empty())
+        || directory
+            .iter()
+            .any(|e| e.dn.eq_ignore_ascii_case(&request.dn) && e.password == request.pw);
+    if accepted { request.gen_success() } else { request.gen_invalid_cred() }
+}
+
+fn search(directory: &[Entry], request: &SearchRequest) -> Vec<LdapMsg> {
+    let base = request.base.to_lowercase();
+    let mut messages: Vec<LdapMsg> = directory
+        .iter()
+        .filter(|e| e.dn.to_lowercase().ends_with(&base) && e.matches(&request.filter))
+        .map(|e| {
+            request.gen_result_entry(LdapSearchResultEntry {
+                dn: e.dn.clone(),
+                attributes: e
+                    .attributes
+                    .iter()
+                    .filter(|(key, _)| request.attrs.is_empty() || request.attrs.iter().any(|a| a.eq_ignore_ascii_case(key)))
+                    .map(|(key, values)| LdapPartialAttribute {
+                        atype: key.clone(),
+                        vals: values.iter().map(|v| v.as_bytes().to_vec()).collect(),
+                    })
+                    .collect(),
+            })
+        })
+        .collect();
+    messages.push(request.gen_success());
+    messages
+}
+
+async fn handle_client(directory: Arc<Vec<Entry>>, socket: TcpStream) {
+    let (reader, writer) = tokio::io::split(socket);
+    let mut requests = FramedRead::new(reader, LdapCodec::default());
+    let mut responses = FramedWrite::new(writer, LdapCodec::default());
+
+    while let Some(Ok(message)) = requests.next().await {
+        let replies = match ServerOps::try_from(message) {
+            Ok(ServerOps::SimpleBind(request)) => vec![bind(&directory, &request)],
+            Ok(ServerOps::Search(request)) => search(&directory, &request),
+            Ok(ServerOps::Unbind(_)) | Err(_) => return,
+            Ok(ServerOps::Whoami(request)) => vec![request.gen_operror("unsupported")],
+            Ok(ServerOps::Compare(request)) => vec![request.gen_error(LdapResultCode::UnwillingToPerform, "unsupported".to_string())],
+        };
+        for reply in replies {
+            if responses.send(reply).await.is_err() {
+                return;
+            }
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let port: u16 = std::env::var("MOCK_LDAP_PORT").ok().and_then(|v| v.parse().ok()).unwrap_or(3389);
+    let directory = Arc::new(vec![
+        person("alice", "Alice Example", &["staff", "room-admins"]),
+        person("bob", "Bob Example", &["staff"]),
+    ]);
+
+    let listener = TcpListener::bind(SocketAddr::from(([127, 0, 0, 1], port))).await?;
+    println!("mock LDAP listening on ldap://{} (base dc=example,dc=com)", listener.local_addr()?);
+    loop {
+        let (socket, _) = listener.accept().await?;
+        tokio::spawn(handle_client(directory.clone(), socket));
+    }
+}
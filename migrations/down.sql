@@ -1,4 +1,19 @@
 -- This file should undo anything in `up.sql`
+DROP TABLE IF EXISTS oidc_login_states;
+DROP TABLE IF EXISTS external_identities;
+DROP TABLE IF EXISTS api_keys;
+DROP TABLE IF EXISTS mfa_challenges;
+DROP TABLE IF EXISTS mfa_recovery_codes;
+DROP TABLE IF EXISTS mfa_totp;
+DROP TABLE IF EXISTS login_events;
+DROP TABLE IF EXISTS login_throttles;
+DROP TABLE IF EXISTS password_reset_tokens;
+DROP TABLE IF EXISTS admin_invitations;
+DROP TABLE IF EXISTS role_assignments;
+DROP TABLE IF EXISTS role_permissions;
+DROP TABLE IF EXISTS roles;
+DROP TABLE IF EXISTS revoked_tokens;
+DROP TABLE IF EXISTS refresh_tokens;
 DROP TABLE IF EXISTS bookings; 
 DROP TABLE IF EXISTS rooms;
 DROP TABLE IF EXISTS users;
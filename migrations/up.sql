@@ -1,8 +1,47 @@
 -- Your SQL goes here
+DROP TABLE IF EXISTS audit_log;
+DROP TABLE IF EXISTS impersonation_actions;
+DROP TABLE IF EXISTS impersonations;
+DROP TABLE IF EXISTS room_allowed_users;
+DROP TABLE IF EXISTS room_allowed_groups;
+DROP TABLE IF EXISTS group_members;
+DROP TABLE IF EXISTS groups;
+DROP TABLE IF EXISTS room_shares;
+DROP TABLE IF EXISTS erasure_requests;
+DROP TABLE IF EXISTS oidc_login_states;
+DROP TABLE IF EXISTS external_identities;
+DROP TABLE IF EXISTS api_keys;
+DROP TABLE IF EXISTS mfa_challenges;
+DROP TABLE IF EXISTS mfa_recovery_codes;
+DROP TABLE IF EXISTS mfa_totp;
+DROP TABLE IF EXISTS login_events;
+DROP TABLE IF EXISTS login_throttles;
+DROP TABLE IF EXISTS password_reset_tokens;
+DROP TABLE IF EXISTS admin_invitations;
+DROP TABLE IF EXISTS role_assignments;
+DROP TABLE IF EXISTS role_permissions;
+DROP TABLE IF EXISTS roles;
+DROP TABLE IF EXISTS revoked_tokens;
+DROP TABLE IF EXISTS refresh_tokens;
 DROP TABLE IF EXISTS bookings;
 DROP TABLE IF EXISTS rooms;
 DROP TABLE IF EXISTS users;
 DROP TABLE IF EXISTS admins;
+DROP TABLE IF EXISTS organizations;
+
+-- องค์กร (Tenant) ที่ใช้ระบบร่วมกัน ผู้ใช้ ห้อง และ Admin แต่ละคนสังกัดองค์กรเดียว
+-- self_registration: เปิดให้สมัครผ่าน POST /register เข้าองค์กรนี้ได้เอง (ระบุด้วย slug)
+CREATE TABLE organizations (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    name VARCHAR(255) NOT NULL UNIQUE,
+    slug VARCHAR(64) NOT NULL UNIQUE,
+    self_registration BOOLEAN NOT NULL DEFAULT 0,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- องค์กรเริ่มต้น (id 1) ข้อมูลที่มีอยู่เดิมและผู้ใช้ที่สมัครโดยไม่ระบุองค์กรอยู่ที่นี่
+INSERT INTO organizations (id, name, slug, self_registration) VALUES (1, 'Default', 'default', 1);
 
 CREATE TABLE admins (
     id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
@@ -10,25 +49,88 @@ CREATE TABLE admins (
     "password_hash" VARCHAR(255) NOT NULL,
     created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
     updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
-    deleted_at TIMESTAMP
+    deleted_at TIMESTAMP,
+    organization_id INTEGER REFERENCES organizations(id) -- NULL = Platform Admin (ดูแลทุกองค์กร)
 );
 
+-- password_hash เป็น NULL สำหรับผู้ใช้ที่ Login ผ่าน Identity Provider (OIDC) หรือ LDAP เท่านั้น
+-- display_name และ directory_groups (JSON array) ซิงก์จาก OIDC/LDAP ทุกครั้งที่ Login
+-- email, phone, department, timezone และ language ผู้ใช้แก้เองได้ผ่าน PATCH /users/me
+-- email_verified_at: เวลาที่ยืนยันอีเมลปัจจุบัน (ล้างเมื่อเปลี่ยนอีเมล)
+-- email_verification_pending: สมัครตอนเปิด EMAIL_VERIFICATION_REQUIRED และยังไม่ยืนยัน (จองห้องไม่ได้)
 CREATE TABLE users (
     id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
     username VARCHAR(255) NOT NULL UNIQUE,
-    "password_hash" VARCHAR(255) NOT NULL,
+    "password_hash" VARCHAR(255),
     created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
     updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
-    deleted_at TIMESTAMP
+    deleted_at TIMESTAMP,
+    display_name VARCHAR(255),
+    directory_groups TEXT,
+    email VARCHAR(255),
+    phone VARCHAR(32),
+    department VARCHAR(100),
+    timezone VARCHAR(64),
+    language VARCHAR(35),
+    email_verified_at TIMESTAMP,
+    email_verification_pending BOOLEAN NOT NULL DEFAULT 0,
+    erased_at TIMESTAMP, -- ข้อมูลส่วนตัวถูกลบแบบถาวรแล้ว (กู้คืนไม่ได้)
+    organization_id INTEGER NOT NULL DEFAULT 1 REFERENCES organizations(id)
 );
 
+-- ชื่อห้องไม่ซ้ำภายในองค์กรเดียวกัน
 CREATE TABLE rooms (
     id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
-    name TEXT NOT NULL UNIQUE,
-    status TEXT NOT NULL CHECK(status IN ('available', 'booked')),
+    name TEXT NOT NULL,
+    status TEXT NOT NULL CHECK(status IN ('available', 'booked', 'maintenance')),
     created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
     updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
-    deleted_at TIMESTAMP
+    deleted_at TIMESTAMP,
+    organization_id INTEGER NOT NULL DEFAULT 1 REFERENCES organizations(id),
+    UNIQUE (organization_id, name)
+);
+
+-- ห้องที่องค์กรเจ้าของเปิดให้องค์กรอื่นเห็นและจองได้ด้วย (เช่น ห้องประชุมส่วนกลางของอาคาร)
+CREATE TABLE room_shares (
+    room_id INTEGER NOT NULL REFERENCES rooms(id),
+    organization_id INTEGER NOT NULL REFERENCES organizations(id),
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (room_id, organization_id)
+);
+
+-- กลุ่มผู้ใช้ภายในองค์กร (เช่น ทีมที่ใช้ห้องแล็บ) ชื่อไม่ซ้ำภายในองค์กร
+CREATE TABLE groups (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    organization_id INTEGER NOT NULL REFERENCES organizations(id),
+    name VARCHAR(100) NOT NULL,
+    description TEXT,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (organization_id, name)
+);
+
+-- สมาชิกของกลุ่ม (ผู้ใช้ในองค์กรเดียวกับกลุ่มเท่านั้น)
+CREATE TABLE group_members (
+    group_id INTEGER NOT NULL REFERENCES groups(id),
+    user_id INTEGER NOT NULL REFERENCES users(id),
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (group_id, user_id)
+);
+
+-- กฎการเข้าถึงห้อง: ห้องที่ไม่มีกฎเลยจองได้ทุกคนที่เห็นห้อง
+-- ห้องที่มีกฎจองได้เฉพาะผู้ใช้ที่ระบุ หรือสมาชิกของกลุ่มที่ระบุ
+CREATE TABLE room_allowed_groups (
+    room_id INTEGER NOT NULL REFERENCES rooms(id),
+    group_id INTEGER NOT NULL REFERENCES groups(id),
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (room_id, group_id)
+);
+
+CREATE TABLE room_allowed_users (
+    room_id INTEGER NOT NULL REFERENCES rooms(id),
+    user_id INTEGER NOT NULL REFERENCES users(id),
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (room_id, user_id)
 );
 
 
@@ -46,5 +148,285 @@ CREATE TABLE bookings (
   FOREIGN KEY (user_id) REFERENCES users(id)
 );
 
+-- Refresh token เก็บเฉพาะค่า hash (SHA-256) ไม่เก็บตัว token จริง
+-- token ทุกตัวที่ถูก rotate ต่อกันมาจาก login ครั้งเดียวกันอยู่ใน family_id เดียวกัน
+-- subject_id อ้างถึง users หรือ admins ตาม role
+CREATE TABLE refresh_tokens (
+  id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+  token_hash TEXT NOT NULL UNIQUE,
+  family_id TEXT NOT NULL,
+  subject_id INTEGER NOT NULL,
+  role TEXT NOT NULL,
+  access_jti TEXT NOT NULL,
+  access_expires_at TIMESTAMP NOT NULL,
+  expires_at TIMESTAMP NOT NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  used_at TIMESTAMP,
+  revoked_at TIMESTAMP
+);
+
+CREATE INDEX idx_refresh_tokens_family ON refresh_tokens(family_id);
+CREATE INDEX idx_refresh_tokens_subject ON refresh_tokens(role, subject_id);
+
+-- jti ของ Access Token ที่ถูกเพิกถอนก่อนหมดอายุ (ลบได้เมื่อเลย expires_at)
+CREATE TABLE revoked_tokens (
+  jti TEXT PRIMARY KEY NOT NULL,
+  expires_at TIMESTAMP NOT NULL,
+  revoked_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Role และ Permission ของแต่ละ Role (Permission เป็น string เช่น 'rooms:write')
+-- Role 'user' และ 'admin' เป็น Role พื้นฐานที่บัญชีได้รับเสมอตามประเภทบัญชี
+-- require_mfa: บัญชีที่มี Role นี้ต้องใช้ 2FA (TOTP) ตอน Login
+CREATE TABLE roles (
+  name TEXT PRIMARY KEY NOT NULL,
+  description TEXT NOT NULL DEFAULT '',
+  require_mfa BOOLEAN NOT NULL DEFAULT 0
+);
+
+CREATE TABLE role_permissions (
+  role_name TEXT NOT NULL REFERENCES roles(name) ON DELETE CASCADE,
+  permission TEXT NOT NULL,
+  PRIMARY KEY (role_name, permission)
+);
+
+-- Role เพิ่มเติมที่ Admin กำหนดให้บัญชี (principal_kind: 'user' หรือ 'admin')
+-- source: 'manual' = Admin กำหนดเอง, อื่นๆ = ซิงก์จากกลุ่มของ Identity Provider (เช่น 'oidc') ทุกครั้งที่ Login
+CREATE TABLE role_assignments (
+  id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+  principal_kind TEXT NOT NULL CHECK(principal_kind IN ('user', 'admin')),
+  principal_id INTEGER NOT NULL,
+  role_name TEXT NOT NULL REFERENCES roles(name) ON DELETE CASCADE,
+  source TEXT NOT NULL DEFAULT 'manual',
+  created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  UNIQUE (principal_kind, principal_id, role_name)
+);
+
+INSERT INTO roles (name, description) VALUES
+  ('user', 'Regular user: books rooms and manages own bookings'),
+  ('admin', 'Administrator: full access'),
+  ('room_manager', 'Manages rooms and all bookings'),
+  ('auditor', 'Read-only access to bookings, users and reports');
+
+INSERT INTO role_permissions (role_name, permission) VALUES
+  ('user', 'bookings:write'),
+  ('admin', 'rooms:write'),
+  ('admin', 'bookings:write'),
+  ('admin', 'bookings:read_all'),
+  ('admin', 'bookings:manage'),
+  ('admin', 'reports:read'),
+  ('admin', 'users:read'),
+  ('admin', 'users:write'),
+  ('admin', 'roles:manage'),
+  ('admin', 'admins:manage'),
+  ('admin', 'audit:read'),
+  ('room_manager', 'rooms:write'),
+  ('room_manager', 'bookings:read_all'),
+  ('room_manager', 'bookings:manage'),
+  ('auditor', 'bookings:read_all'),
+  ('auditor', 'reports:read'),
+  ('auditor', 'users:read'),
+  ('auditor', 'audit:read');
+
+-- คำเชิญให้สร้างบัญชี Admin (ตัว Token เป็น JWT ที่เซ็นแล้ว เก็บเฉพาะ jti เพื่อให้ใช้ได้ครั้งเดียวและเพิกถอนได้)
+CREATE TABLE admin_invitations (
+  id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+  jti TEXT NOT NULL UNIQUE,
+  invited_by INTEGER NOT NULL REFERENCES admins(id),
+  expires_at TIMESTAMP NOT NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  accepted_at TIMESTAMP,
+  accepted_admin_id INTEGER REFERENCES admins(id),
+  revoked_at TIMESTAMP,
+  organization_id INTEGER REFERENCES organizations(id) -- องค์กรของ Admin ที่จะถูกสร้าง (NULL = Platform Admin)
+);
+
+-- Token รีเซ็ตรหัสผ่าน (เก็บเฉพาะ SHA-256 hash, ใช้ได้ครั้งเดียว และมีวันหมดอายุ)
+CREATE TABLE password_reset_tokens (
+  id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+  user_id INTEGER NOT NULL REFERENCES users(id),
+  token_hash TEXT NOT NULL UNIQUE,
+  expires_at TIMESTAMP NOT NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  used_at TIMESTAMP
+);
+
+CREATE INDEX idx_password_reset_tokens_user ON password_reset_tokens(user_id);
+
+-- จำนวนครั้งที่ Login ผิดต่อ username และต่อ IP (scope: 'username' หรือ 'ip')
+-- throttle_key ของ scope username เป็น '<account_kind>:<username>' เพื่อแยกบัญชีผู้ใช้และ Admin
+CREATE TABLE login_throttles (
+  id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+  scope TEXT NOT NULL CHECK(scope IN ('username', 'ip')),
+  throttle_key TEXT NOT NULL,
+  failed_count INTEGER NOT NULL DEFAULT 0,
+  last_failed_at TIMESTAMP NOT NULL,
+  locked_until TIMESTAMP,
+  UNIQUE (scope, throttle_key)
+);
+
+-- ประวัติการ Login ทั้งที่สำเร็จและไม่สำเร็จ (reason: invalid_credentials, locked_out)
+CREATE TABLE login_events (
+  id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+  account_kind TEXT NOT NULL CHECK(account_kind IN ('user', 'admin')),
+  username TEXT NOT NULL,
+  ip TEXT NOT NULL,
+  success BOOLEAN NOT NULL,
+  reason TEXT,
+  created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_login_events_username ON login_events(username);
+
+-- Authenticator (TOTP) ของบัญชี (บัญชีละ 1 ตัว) confirmed_at เป็น NULL ระหว่างรอยืนยันรหัสแรก
+-- last_used_step: time step ของรหัสล่าสุดที่ใช้แล้ว (รหัสเดิมใช้ซ้ำไม่ได้)
+CREATE TABLE mfa_totp (
+  id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+  principal_kind TEXT NOT NULL CHECK(principal_kind IN ('user', 'admin')),
+  principal_id INTEGER NOT NULL,
+  secret TEXT NOT NULL,
+  last_used_step BIGINT,
+  confirmed_at TIMESTAMP,
+  created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  UNIQUE (principal_kind, principal_id)
+);
+
+-- Recovery Code สำรองเมื่อไม่มี Authenticator (เก็บเฉพาะ SHA-256 hash ใช้ได้ครั้งเดียว)
+CREATE TABLE mfa_recovery_codes (
+  id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+  principal_kind TEXT NOT NULL CHECK(principal_kind IN ('user', 'admin')),
+  principal_id INTEGER NOT NULL,
+  code_hash TEXT NOT NULL UNIQUE,
+  created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  used_at TIMESTAMP
+);
+
+CREATE INDEX idx_mfa_recovery_codes_principal ON mfa_recovery_codes(principal_kind, principal_id);
+
+-- Challenge ของการ Login ขั้นที่สอง (รหัสผ่านถูกแล้ว รอรหัส 2FA) เก็บเฉพาะ SHA-256 hash ของ Token
+-- enrollment: บัญชียังไม่มี 2FA แต่ Role กำหนดให้ต้องมี จึงต้องลงทะเบียนก่อน Login สำเร็จ
+CREATE TABLE mfa_challenges (
+  id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+  token_hash TEXT NOT NULL UNIQUE,
+  principal_kind TEXT NOT NULL CHECK(principal_kind IN ('user', 'admin')),
+  principal_id INTEGER NOT NULL,
+  enrollment BOOLEAN NOT NULL,
+  attempts INTEGER NOT NULL DEFAULT 0,
+  expires_at TIMESTAMP NOT NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  completed_at TIMESTAMP
+);
+
+-- API Key ส่วนตัวสำหรับสคริปต์/อุปกรณ์ (เก็บเฉพาะ SHA-256 hash ของ Key และ prefix ที่ใช้ระบุ Key)
+-- scopes: Permission ที่ Key ใช้ได้ คั่นด้วยช่องว่าง (มีผลเฉพาะที่บัญชียังมีอยู่จริง)
+CREATE TABLE api_keys (
+  id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+  principal_kind TEXT NOT NULL CHECK(principal_kind IN ('user', 'admin')),
+  principal_id INTEGER NOT NULL,
+  name TEXT NOT NULL,
+  prefix TEXT NOT NULL UNIQUE,
+  key_hash TEXT NOT NULL UNIQUE,
+  scopes TEXT NOT NULL,
+  expires_at TIMESTAMP NOT NULL,
+  last_used_at TIMESTAMP,
+  created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  revoked_at TIMESTAMP
+);
+
+CREATE INDEX idx_api_keys_principal ON api_keys(principal_kind, principal_id);
+
+-- บัญชีผู้ใช้ที่ผูกกับ Identity Provider ภายนอก (issuer + sub จาก ID Token)
+-- ผู้ใช้ถูกสร้างอัตโนมัติเมื่อ Login ผ่าน OIDC ครั้งแรก
+CREATE TABLE external_identities (
+  id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+  user_id INTEGER NOT NULL REFERENCES users(id),
+  issuer TEXT NOT NULL,
+  subject TEXT NOT NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  last_login_at TIMESTAMP,
+  UNIQUE (issuer, subject)
+);
+
+-- สถานะของ Login ผ่าน OIDC ที่รอ Callback (state เก็บแบบ hash, ใช้ได้ครั้งเดียว)
+-- code_verifier: PKCE verifier ที่ใช้แลก Authorization Code, nonce: ต้องตรงกับใน ID Token
+CREATE TABLE oidc_login_states (
+  id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+  state_hash TEXT NOT NULL UNIQUE,
+  nonce TEXT NOT NULL,
+  code_verifier TEXT NOT NULL,
+  mode TEXT NOT NULL CHECK(mode IN ('bearer', 'cookie')),
+  expires_at TIMESTAMP NOT NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  used_at TIMESTAMP
+);
+
+-- บันทึกการลบข้อมูลส่วนตัว (Right to Erasure) แถว users ถูกทำให้ไม่ระบุตัวตนแต่ยังคงอยู่
+-- เพื่อให้การจองเดิมยังนับในรายงานได้ requested_by_kind: ผู้ใช้ขอเองหรือ Admin ดำเนินการ
+CREATE TABLE erasure_requests (
+  id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+  user_id INTEGER NOT NULL REFERENCES users(id),
+  requested_by_kind TEXT NOT NULL CHECK(requested_by_kind IN ('user', 'admin')),
+  requested_by_id INTEGER NOT NULL,
+  reason TEXT,
+  bookings_cancelled INTEGER NOT NULL,
+  bookings_retained INTEGER NOT NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- การสวมสิทธิ์ผู้ใช้โดย Admin (Support ดูสิ่งที่ผู้ใช้เห็น) Token มี claim `act` และใช้ได้จนถึง expires_at
+-- ended_at: Admin จบการสวมสิทธิ์ก่อนหมดอายุ (jti ถูกเพิกถอน)
+CREATE TABLE impersonations (
+  id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+  admin_id INTEGER NOT NULL REFERENCES admins(id),
+  user_id INTEGER NOT NULL REFERENCES users(id),
+  reason TEXT NOT NULL,
+  jti TEXT NOT NULL UNIQUE,
+  created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  expires_at TIMESTAMP NOT NULL,
+  ended_at TIMESTAMP
+);
+
+-- ทุก Request ที่ส่งมาด้วย Token ของการสวมสิทธิ์ (รวม Request ที่ถูกปฏิเสธ)
+CREATE TABLE impersonation_actions (
+  id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+  impersonation_id INTEGER NOT NULL REFERENCES impersonations(id),
+  method TEXT NOT NULL,
+  path TEXT NOT NULL,
+  status INTEGER NOT NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Audit Log ของทุกการเปลี่ยนแปลงข้อมูล (เพิ่มได้อย่างเดียว แก้ไข/ลบไม่ได้ด้วย Trigger)
+-- hash = SHA-256 ของ prev_hash และทุกคอลัมน์ของแถว (แถวแรกใช้ prev_hash เป็น 0 ทั้งหมด)
+-- แก้ไขแถวใดแถวหนึ่งนอก API จะทำให้ hash ของแถวนั้นและลิงก์ไปยังแถวถัดไปไม่ตรง (ตรวจด้วย verify-audit)
+-- organization_id: องค์กรของผู้กระทำ (NULL = Platform Admin) ใช้จำกัดว่า Admin องค์กรไหนเห็น
+CREATE TABLE audit_log (
+  id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+  created_at TIMESTAMP NOT NULL,
+  organization_id INTEGER,
+  actor_kind TEXT NOT NULL CHECK(actor_kind IN ('user', 'admin', 'anonymous')),
+  actor_id INTEGER,
+  impersonator_id INTEGER,
+  action TEXT NOT NULL,
+  entity_type TEXT NOT NULL,
+  entity_id TEXT,
+  before_json TEXT,
+  after_json TEXT,
+  request_id TEXT,
+  ip TEXT,
+  prev_hash TEXT NOT NULL,
+  hash TEXT NOT NULL UNIQUE
+);
+
+CREATE INDEX audit_log_entity ON audit_log (entity_type, entity_id);
+CREATE INDEX audit_log_organization ON audit_log (organization_id, created_at);
 
+CREATE TRIGGER audit_log_no_update BEFORE UPDATE ON audit_log
+BEGIN
+  SELECT RAISE(ABORT, 'audit_log is append-only');
+END;
 
+CREATE TRIGGER audit_log_no_delete BEFORE DELETE ON audit_log
+BEGIN
+  SELECT RAISE(ABORT, 'audit_log is append-only');
+END;
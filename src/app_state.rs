@@ -1,7 +1,23 @@
 // src/app_state.rs
 
 // หรือ path ที่ถูกต้องของ AdminService
+use crate::application::api_key_service::ApiKeyService;
+use crate::application::audit_service::AuditService;
+use crate::application::auth_service::AuthService;
 use crate::application::booking_service::BookingService;
+use crate::application::email_verification_service::EmailVerificationService;
+use crate::application::group_service::GroupService;
+use crate::application::impersonation_service::ImpersonationService;
+use crate::application::invitation_service::InvitationService;
+use crate::application::login_throttle_service::LoginThrottleService;
+use crate::application::mfa_service::MfaService;
+use crate::application::oidc_service::OidcService;
+use crate::application::organization_service::OrganizationService;
+use crate::application::password_service::PasswordService;
+use crate::application::privacy_service::PrivacyService;
+use crate::application::report_service::ReportService;
+use crate::application::role_service::RoleService;
+use crate::middleware::session::SessionCookies;
 use crate::{application::{admin_service::AdminService, room_service::RoomService, user_service::UserService}, infrastructure::{database::DbPool, jwt::JwtService}};
 
 #[derive(Clone)]
@@ -12,6 +28,23 @@ pub struct AppState {
     pub admin_service: AdminService,
     pub jwt_service: JwtService,
     pub booking_service: BookingService,
+    pub report_service: ReportService,
+    pub auth_service: AuthService,
+    pub role_service: RoleService,
+    pub invitation_service: InvitationService,
+    pub password_service: PasswordService,
+    pub email_verification_service: EmailVerificationService,
+    pub login_throttle_service: LoginThrottleService,
+    pub mfa_service: MfaService,
+    pub api_key_service: ApiKeyService,
+    pub privacy_service: PrivacyService,
+    pub organization_service: OrganizationService,
+    pub group_service: GroupService,
+    pub impersonation_service: ImpersonationService,
+    pub audit_service: AuditService,
+    pub oidc_service: Option<OidcService>, // None เมื่อไม่ได้ตั้งค่า OIDC_ISSUER_URL
+    pub session_cookies: SessionCookies,
+    pub trust_proxy_headers: bool, // ใช้ X-Forwarded-For เป็น IP ของ Client (เมื่ออยู่หลัง Reverse Proxy)
     
     // pub booking_service: BookingService, // ถ้ามี
 }
\ No newline at end of file
@@ -1,36 +1,89 @@
-use anyhow::Result;
+use std::sync::Arc;
+
 use diesel::SqliteConnection;
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use tokio::sync::Mutex;
 
 use crate::{
     domain::{
         admin::LoginCredentials,
-        admin::{Admin, NewAdmin, RegisterAdminRequest},
+        admin::{Admin, NewAdmin, SetupAdminRequest},
+        audit::PendingAudit,
     },
+    error::AppError,
     infrastructure::admin_repository::AdminRepository,
 };
 
+// AdminService: login ของ Admin และการสร้าง Admin คนแรก (bootstrap)
+// - เมื่อยังไม่มี Admin ระบบจะเปิดให้สร้าง Admin คนแรกด้วย Setup Token แบบใช้ครั้งเดียว
+// - หลังจากนั้น Admin ใหม่ต้องมาจากคำเชิญของ Admin ที่มีอยู่ (InvitationService)
 #[derive(Debug, Clone)]
 pub struct AdminService {
     repo: AdminRepository,
+    setup_token_hash: Arc<Mutex<Option<String>>>, // SHA-256 ของ Setup Token (None = ปิดการ bootstrap)
+}
+
+fn hash_setup_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
 }
 
 impl AdminService {
     pub fn new(repo: AdminRepository) -> Self {
-        AdminService { repo }
+        AdminService {
+            repo,
+            setup_token_hash: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    // เปิดการ bootstrap ถ้ายังไม่มี Admin ในระบบ
+    // ใช้ configured_token (ADMIN_SETUP_TOKEN) ถ้ามี ไม่เช่นนั้นสุ่มใหม่
+    // คืนค่า Token ที่สุ่มขึ้นเพื่อแสดงตอนเริ่มระบบ (ไม่คืนค่าที่ตั้งไว้ใน env)
+    pub async fn start_bootstrap(&self, configured_token: Option<String>) -> Result<Option<String>, AppError> {
+        if self.repo.count_admins().await? > 0 {
+            return Ok(None);
+        }
+
+        let (token, generated) = match configured_token.filter(|t| !t.trim().is_empty()) {
+            Some(token) => (token, false),
+            None => {
+                let mut bytes = [0u8; 32];
+                rand::thread_rng().fill_bytes(&mut bytes);
+                (hex::encode(bytes), true)
+            }
+        };
+        *self.setup_token_hash.lock().await = Some(hash_setup_token(&token));
+        Ok(generated.then_some(token))
     }
 
-    pub async fn register_admin(&self, conn: &mut SqliteConnection,request: RegisterAdminRequest) -> Result<Admin, String> {
-        let hashed_password = bcrypt::hash(&request.password, bcrypt::DEFAULT_COST)
-            .map_err(|e| format!("Failed to hash password: {}", e))?;
+    // สร้าง Admin คนแรกด้วย Setup Token (ใช้ได้ครั้งเดียว และเฉพาะเมื่อยังไม่มี Admin)
+    pub async fn setup_first_admin(
+        &self,
+        request: SetupAdminRequest,
+        audit: PendingAudit<'_, Admin>,
+    ) -> Result<Admin, AppError> {
+        let mut setup_token_hash = self.setup_token_hash.lock().await;
+        let expected = setup_token_hash
+            .as_deref()
+            .ok_or_else(|| AppError::Forbidden("Admin setup is not available.".to_string()))?;
+        if hash_setup_token(&request.setup_token) != expected {
+            return Err(AppError::Unauthorized("Invalid setup token.".to_string()));
+        }
+
+        let hashed_password = bcrypt::hash(&request.password, bcrypt::DEFAULT_COST)?;
         let new_admin = NewAdmin {
             username: &request.username,
             password_hash: &hashed_password,
+            organization_id: None, // Admin คนแรกเป็น Platform Admin
         };
+        let admin = self.repo.create_first_admin(new_admin, audit).await?;
 
-        self.repo.register_admin(conn, new_admin).await // 
+        // Setup Token ใช้ได้ครั้งเดียว
+        *setup_token_hash = None;
+        Ok(admin)
     }
 
-    pub async fn login_admin(&self,_conn: &mut SqliteConnection, credentials: LoginCredentials) -> Result<i32, String> {
+    pub async fn login_admin(&self,_conn: &mut SqliteConnection, credentials: LoginCredentials) -> Result<i32, AppError> {
         let admin = self
             .repo
             .find_admin_by_username_and_verify_password(credentials)
@@ -39,3 +92,17 @@ impl AdminService {
         Ok(admin.id)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn setup_token_is_kept_only_as_hash() {
+        let hash = hash_setup_token("setup-token");
+        assert_eq!(hash, hash_setup_token("setup-token"));
+        assert_ne!(hash, hash_setup_token("setup-token "));
+        assert!(!hash.contains("setup-token"));
+        assert_eq!(hash.len(), 64);
+    }
+}
@@ -0,0 +1,149 @@
+use std::collections::BTreeSet;
+
+use chrono::{Duration, Utc};
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+
+use crate::{
+    domain::{
+        audit::PendingAudit,
+        api_key::{ApiKey, ApiKeyResponse, CreateApiKeyRequest, CreatedApiKeyResponse, NewApiKey, API_KEY_PREFIX},
+        permission::{Permission, PrincipalKind},
+    },
+    error::AppError,
+    infrastructure::api_key_repository::ApiKeyRepository,
+};
+
+// ApiKeyService: API Key ส่วนตัวสำหรับสคริปต์และอุปกรณ์ (เช่น แท็บเล็ตหน้าห้อง) แทนการเก็บรหัสผ่าน
+// - รูปแบบ Key: rbk_<prefix 8 ตัว>_<secret> prefix ใช้ระบุ Key ในรายการและ log
+// - เก็บใน DB เฉพาะ SHA-256 hash ของ Key ตัว Key แสดงครั้งเดียวตอนสร้าง
+// - Permission ที่ใช้ได้ = scopes ของ Key ที่บัญชียังมีอยู่ ณ ตอนใช้งาน
+#[derive(Debug, Clone)]
+pub struct ApiKeyService {
+    repo: ApiKeyRepository,
+    default_ttl_days: i64,
+}
+
+// AuthenticatedKey: ผลการตรวจ API Key (บัญชีเจ้าของ และ scopes ของ Key)
+#[derive(Debug, Clone)]
+pub struct AuthenticatedKey {
+    pub key_id: i32,
+    pub kind: PrincipalKind,
+    pub principal_id: i32,
+    pub scopes: BTreeSet<Permission>,
+}
+
+fn hash_key(key: &str) -> String {
+    hex::encode(Sha256::digest(key.as_bytes()))
+}
+
+fn random_hex(len: usize) -> String {
+    let mut bytes = vec![0u8; len];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+pub fn is_api_key(token: &str) -> bool {
+    token.starts_with(API_KEY_PREFIX)
+}
+
+impl ApiKeyService {
+    pub fn new(repo: ApiKeyRepository, default_ttl_days: i64) -> Self {
+        ApiKeyService { repo, default_ttl_days }
+    }
+
+    // สร้าง Key ใหม่ให้บัญชี (scopes ต้องเป็น Permission ที่บัญชีมีอยู่ตอนสร้าง)
+    pub async fn create(
+        &self,
+        kind: PrincipalKind,
+        principal_id: i32,
+        permissions: &BTreeSet<Permission>,
+        request: CreateApiKeyRequest,
+        audit: PendingAudit<'_, ApiKey>,
+    ) -> Result<CreatedApiKeyResponse, AppError> {
+        let scopes: BTreeSet<Permission> = request.scopes.into_iter().collect();
+        if let Some(missing) = scopes.iter().find(|scope| !permissions.contains(scope)) {
+            return Err(AppError::Forbidden(format!(
+                "Cannot grant '{}' to an API key: your account does not have it.",
+                missing.as_str()
+            )));
+        }
+
+        let prefix = format!("{}{}", API_KEY_PREFIX, random_hex(4));
+        let key = format!("{}_{}", prefix, random_hex(32));
+        let scopes = scopes.iter().map(|scope| scope.as_str()).collect::<Vec<_>>().join(" ");
+        let ttl = Duration::days(request.expires_in_days.unwrap_or(self.default_ttl_days));
+
+        let new_key = NewApiKey {
+            principal_kind: kind.as_str(),
+            principal_id,
+            name: request.name.trim(),
+            prefix: &prefix,
+            key_hash: &hash_key(&key),
+            scopes: &scopes,
+            expires_at: (Utc::now() + ttl).naive_utc(),
+        };
+        let api_key = self.repo.insert(new_key, audit).await?;
+        Ok(CreatedApiKeyResponse { api_key: api_key.into(), key })
+    }
+
+    pub async fn list(&self, kind: PrincipalKind, principal_id: i32) -> Result<Vec<ApiKeyResponse>, AppError> {
+        let keys = self.repo.list_for(kind.as_str(), principal_id).await?;
+        Ok(keys.into_iter().map(ApiKeyResponse::from).collect())
+    }
+
+    pub async fn revoke(
+        &self,
+        kind: PrincipalKind,
+        principal_id: i32,
+        key_id: i32,
+        audit: PendingAudit<'_, ()>,
+    ) -> Result<(), AppError> {
+        if !self
+            .repo
+            .revoke(kind.as_str(), principal_id, key_id, Utc::now().naive_utc(), audit)
+            .await?
+        {
+            return Err(AppError::NotFound("API key not found".to_string()));
+        }
+        Ok(())
+    }
+
+    // ตรวจ API Key จาก Request และบันทึกเวลาที่ใช้ล่าสุด (ความละเอียดระดับนาที)
+    pub async fn authenticate(&self, key: &str) -> Result<AuthenticatedKey, AppError> {
+        let now = Utc::now().naive_utc();
+        let api_key: ApiKey = self
+            .repo
+            .find_active_by_hash(&hash_key(key), now)
+            .await?
+            .ok_or_else(|| AppError::Unauthorized("Invalid, expired or revoked API key.".to_string()))?;
+        let kind = PrincipalKind::parse(&api_key.principal_kind)
+            .ok_or_else(|| AppError::Unauthorized("Invalid API key owner.".to_string()))?;
+
+        self.repo.touch(api_key.id, now, now - Duration::minutes(1)).await?;
+        Ok(AuthenticatedKey {
+            key_id: api_key.id,
+            kind,
+            principal_id: api_key.principal_id,
+            scopes: api_key.scope_list().into_iter().collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_prefixed_tokens_are_api_keys() {
+        assert!(is_api_key("rbk_12345678_abcdef"));
+        assert!(!is_api_key("eyJhbGciOiJIUzI1NiJ9.e30.sig"));
+    }
+
+    #[test]
+    fn keys_are_stored_as_sha256() {
+        assert_eq!(hash_key("rbk_a_b").len(), 64);
+        assert_ne!(hash_key("rbk_a_b"), hash_key("rbk_a_c"));
+        assert_eq!(random_hex(4).len(), 8);
+    }
+}
@@ -0,0 +1,35 @@
+use crate::{
+    domain::{
+        audit::{AuditEntryResponse, AuditQuery, AuditVerification},
+        organization::Tenant,
+        pagination::Page,
+    },
+    error::AppError,
+    infrastructure::audit_repository::AuditRepository,
+};
+
+// AuditService: อ่านและตรวจสอบบันทึกว่าใครเปลี่ยนแปลงอะไร เมื่อไหร่ จาก Request ไหน
+// - แต่ละแถวถูกเขียนใน Transaction เดียวกับการเปลี่ยนแปลง (Handler ส่ง PendingAudit ไปกับการเรียก Service)
+// - ทุกแถวเก็บ hash ของแถวก่อนหน้า (Hash Chain) การแก้ไขข้อมูลย้อนหลังตรวจพบได้ด้วย verify
+// - ตารางถูกกันการ UPDATE/DELETE ด้วย Trigger ใน DB
+#[derive(Clone)]
+pub struct AuditService {
+    repo: AuditRepository,
+}
+
+impl AuditService {
+    pub fn new(repo: AuditRepository) -> Self {
+        AuditService { repo }
+    }
+
+    pub async fn list(&self, tenant: Tenant, query: AuditQuery) -> Result<Page<AuditEntryResponse>, AppError> {
+        let paging = query.paging();
+        let offset = paging.offset()?;
+        let (items, total) = self.repo.list(tenant, &query, offset, paging.limit()).await?;
+        Ok(Page::new(items, total, paging.limit(), offset).map(AuditEntryResponse::from))
+    }
+
+    pub async fn verify(&self) -> Result<AuditVerification, AppError> {
+        self.repo.verify().await
+    }
+}
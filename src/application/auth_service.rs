@@ -0,0 +1,161 @@
+use chrono::{DateTime, Duration, Utc};
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+
+use crate::{
+    domain::auth::{NewRefreshToken, TokenPair},
+    error::AppError,
+    infrastructure::{
+        jwt::{Claims, JwtService},
+        organization_repository::OrganizationRepository,
+        token_repository::TokenRepository,
+    },
+};
+
+// AuthService: ออก/หมุนเวียน/เพิกถอน Token
+// - Access Token เป็น JWT อายุสั้น มี jti สำหรับตรวจสอบการเพิกถอน
+// - Refresh Token เป็นค่าสุ่ม เก็บใน DB เฉพาะ SHA-256 hash และใช้ได้ครั้งเดียว (rotate ทุกครั้งที่ refresh)
+// - ถ้า Refresh Token ที่ถูกใช้ไปแล้วถูกส่งมาอีก จะเพิกถอนทั้ง family (ทุก token ที่มาจาก login ครั้งเดียวกัน)
+#[derive(Clone)]
+pub struct AuthService {
+    repo: TokenRepository,
+    organizations: OrganizationRepository,
+    jwt: JwtService,
+    refresh_ttl: Duration,
+}
+
+fn hash_refresh_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+fn generate_refresh_token() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+fn timestamp_to_naive(timestamp: usize) -> Result<chrono::NaiveDateTime, AppError> {
+    DateTime::<Utc>::from_timestamp(timestamp as i64, 0)
+        .map(|dt| dt.naive_utc())
+        .ok_or_else(|| AppError::Internal("Invalid token timestamp".to_string()))
+}
+
+impl AuthService {
+    pub fn new(repo: TokenRepository, organizations: OrganizationRepository, jwt: JwtService, refresh_ttl_days: i64) -> Self {
+        AuthService {
+            repo,
+            organizations,
+            jwt,
+            refresh_ttl: Duration::days(refresh_ttl_days),
+        }
+    }
+
+    // ออก Token คู่ใหม่หลัง login สำเร็จ (เริ่ม family ใหม่)
+    pub async fn issue_tokens(&self, subject_id: i32, role: &str) -> Result<TokenPair, AppError> {
+        let family_id = uuid::Uuid::new_v4().to_string();
+        self.issue_in_family(subject_id, role, &family_id).await
+    }
+
+    async fn issue_in_family(&self, subject_id: i32, role: &str, family_id: &str) -> Result<TokenPair, AppError> {
+        // อ่านองค์กรจาก DB ทุกครั้งที่ออก Token เพื่อให้การย้ายองค์กรมีผลเมื่อ refresh
+        let org = self.organizations.organization_of(role, subject_id).await?;
+        let access = self.jwt.create_token(subject_id, role, org)?;
+        let refresh_token = generate_refresh_token();
+        let token_hash = hash_refresh_token(&refresh_token);
+
+        self.repo
+            .insert_refresh_token(NewRefreshToken {
+                token_hash: &token_hash,
+                family_id,
+                subject_id,
+                role,
+                access_jti: &access.claims.jti,
+                access_expires_at: timestamp_to_naive(access.claims.exp)?,
+                expires_at: (Utc::now() + self.refresh_ttl).naive_utc(),
+            })
+            .await?;
+
+        Ok(TokenPair {
+            token: access.token,
+            refresh_token,
+            role: role.to_string(),
+            expires_in: self.jwt.access_ttl_seconds(),
+            refresh_expires_in: self.refresh_ttl.num_seconds(),
+        })
+    }
+
+    // แลก Refresh Token เป็น Token คู่ใหม่ (Refresh Token เดิมใช้ไม่ได้อีก)
+    pub async fn refresh(&self, refresh_token: &str) -> Result<TokenPair, AppError> {
+        let invalid = || AppError::Unauthorized("Invalid or expired refresh token.".to_string());
+        let now = Utc::now().naive_utc();
+        self.repo.purge_expired(now).await?;
+
+        let stored = self
+            .repo
+            .find_refresh_token(&hash_refresh_token(refresh_token))
+            .await?
+            .ok_or_else(invalid)?;
+
+        if stored.revoked_at.is_some() || stored.expires_at <= now {
+            return Err(invalid());
+        }
+
+        // ใช้ซ้ำ: token นี้ถูก rotate ไปแล้ว แสดงว่าอาจถูกขโมย จึงเพิกถอนทั้ง family
+        if stored.used_at.is_some() || !self.repo.mark_refresh_token_used(stored.id).await? {
+            self.repo.revoke_family(&stored.family_id).await?;
+            return Err(AppError::Unauthorized(
+                "Refresh token reuse detected. All sessions from this login have been revoked.".to_string(),
+            ));
+        }
+
+        self.issue_in_family(stored.subject_id, &stored.role, &stored.family_id).await
+    }
+
+    // Logout: เพิกถอน Refresh Token และ Access Token ทั้ง family ของ session นี้
+    // token ที่ไม่รู้จักจะถูกละเว้น เพื่อให้เรียกซ้ำได้โดยไม่เกิด error
+    pub async fn logout(&self, refresh_token: &str) -> Result<(), AppError> {
+        if let Some(stored) = self.repo.find_refresh_token(&hash_refresh_token(refresh_token)).await? {
+            self.repo.revoke_family(&stored.family_id).await?;
+        }
+        Ok(())
+    }
+
+    // เพิกถอนทุก session ของ subject (เช่น เมื่อบัญชีถูกลบ) รวม Token ของการสวมสิทธิ์ที่ยังเปิดอยู่
+    pub async fn revoke_all_sessions(&self, subject_id: i32, role: &str) -> Result<(), AppError> {
+        self.repo.revoke_all_for_subject(subject_id, role).await?;
+        Ok(())
+    }
+
+    // ตรวจสอบ Access Token: ลายเซ็น, วันหมดอายุ และต้องไม่อยู่ในรายการที่ถูกเพิกถอน (jti)
+    pub async fn authenticate(&self, token: &str) -> Result<Claims, AppError> {
+        let claims = self.jwt.decode_token(token)?;
+        if self.repo.is_jti_revoked(&claims.jti).await? {
+            return Err(AppError::Unauthorized("Token has been revoked.".to_string()));
+        }
+        Ok(claims)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn refresh_tokens_are_random_and_stored_hashed() {
+        let first = generate_refresh_token();
+        let second = generate_refresh_token();
+        assert_eq!(first.len(), 64);
+        assert_ne!(first, second);
+
+        let hash = hash_refresh_token(&first);
+        assert_eq!(hash, hash_refresh_token(&first));
+        assert_ne!(hash, first);
+        assert_eq!(hash.len(), 64);
+    }
+
+    #[test]
+    fn timestamps_convert_to_utc() {
+        let naive = timestamp_to_naive(86_400).unwrap();
+        assert_eq!(naive.to_string(), "1970-01-02 00:00:00");
+    }
+}
@@ -1,45 +1,15 @@
 // src/application/booking_service.rs
 
+use crate::domain::audit::PendingAudit;
 use crate::domain::booking::{Booking,InternalCreateBookingRequest}; // เพิ่ม InternalCreateBookingRequest
+use crate::domain::organization::Tenant;
+use crate::domain::pagination::{ListQuery, Page};
+use crate::error::AppError;
 use crate::infrastructure::booking_repository::BookingRepository;
 use crate::infrastructure::database::DbPool; // ต้อง import DbPool
 use diesel::sqlite::SqliteConnection; // ต้อง import SqliteConnection
 use diesel::r2d2::PooledConnection; // ต้อง import PooledConnection
 
-#[derive(Debug)]
-pub enum BookingServiceError {
-    DbError(String),
-    InvalidInput(String),
-    NotFound,
-    Conflict,
-    Unauthorized,
-}
-
-impl std::fmt::Display for BookingServiceError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            BookingServiceError::DbError(msg) => write!(f, "Database error: {}", msg),
-            BookingServiceError::InvalidInput(msg) => write!(f, "Invalid input: {}", msg),
-            BookingServiceError::NotFound => write!(f, "Not found"),
-            BookingServiceError::Conflict => write!(f, "Conflict"),
-            BookingServiceError::Unauthorized => write!(f, "Unauthorized"),
-        }
-    }
-}
-
-impl From<diesel::result::Error> for BookingServiceError {
-    fn from(error: diesel::result::Error) -> Self {
-        BookingServiceError::DbError(error.to_string())
-    }
-}
-
-impl From<r2d2::Error> for BookingServiceError {
-    fn from(error: r2d2::Error) -> Self {
-        BookingServiceError::DbError(format!("Connection pool error: {}", error))
-    }
-}
-
-
 #[derive(Clone)]
 pub struct BookingService {
     pool: DbPool, // BookingService จะเก็บ DbPool แทน BookingRepository
@@ -51,45 +21,90 @@ impl BookingService {
         BookingService { pool }
     }
 
-    fn get_connection(&self) -> Result<PooledConnection<diesel::r2d2::ConnectionManager<SqliteConnection>>, BookingServiceError> {
-        self.pool.get().map_err(|e| BookingServiceError::DbError(format!("Failed to get DB connection: {}", e)))
+    fn get_connection(&self) -> Result<PooledConnection<diesel::r2d2::ConnectionManager<SqliteConnection>>, AppError> {
+        Ok(self.pool.get()?)
     }
 
     // *** แก้ไข create_booking ให้รับ InternalCreateBookingRequest ***
     // NewBooking ควรสร้างใน Repository หรือ Service ก่อนส่งให้ Repository
-    pub async fn create_booking(&self, request: InternalCreateBookingRequest) -> Result<Booking, BookingServiceError> {
+    pub async fn create_booking(
+        &self,
+        request: InternalCreateBookingRequest,
+        audit: PendingAudit<'_, Booking>,
+    ) -> Result<Booking, AppError> {
         let conn = &mut self.get_connection()?; // ดึง Connection
         // เนื่องจาก BookingRepository ไม่มี state เราสามารถเรียกใช้ method ได้เลย
-        BookingRepository::create_booking(conn, request)
-            .map_err(|e| BookingServiceError::DbError(e.to_string()))
+        // Conflict ถ้าห้องถูกจองช่วงเวลาที่ทับกันอยู่แล้ว
+        BookingRepository::create_booking(conn, request, audit)
     }
 
-    pub async fn get_bookings_by_user_id(&self, user_id: i32) -> Result<Vec<Booking>, BookingServiceError> {
-        let conn = &mut self.get_connection()?;
-        BookingRepository::get_user_bookings(conn, user_id)
-            .map_err(|e| BookingServiceError::DbError(e.to_string()))
+    // การจองของผู้ใช้คนเดียว: บังคับ user_id จาก token ไม่ว่า query จะส่งอะไรมา
+    pub async fn get_bookings_by_user_id(&self, user_id: i32, mut query: ListQuery) -> Result<Page<Booking>, AppError> {
+        query.user_id = Some(user_id);
+        self.list_bookings(Tenant::All, query).await
     }
 
-    pub async fn cancel_booking(&self, booking_id: i32, user_id: i32) -> Result<bool, BookingServiceError> {
+    // owner_id = None: ยกเลิกได้โดยไม่ตรวจสอบเจ้าของ (ผู้มี Permission bookings:manage)
+    pub async fn cancel_booking(
+        &self,
+        tenant: Tenant,
+        booking_id: i32,
+        owner_id: Option<i32>,
+        audit: PendingAudit<'_, Booking>,
+    ) -> Result<(), AppError> {
         let conn = &mut self.get_connection()?;
         // ใน BookingRepository คุณมีการ filter user_id ใน cancel_booking อยู่แล้ว
-        // ดังนั้นตรงนี้แค่เรียกใช้ได้เลย
-        BookingRepository::cancel_booking(conn, booking_id, user_id)
-            .map_err(|e| BookingServiceError::DbError(e.to_string())) // ควรจัดการ NotFound/Unauthorized ใน Service
+        // ดังนั้นถ้าไม่มีแถวถูกอัปเดต แปลว่าไม่พบการจองหรือไม่ใช่ของผู้ใช้คนนี้
+        if BookingRepository::cancel_booking(conn, tenant, booking_id, owner_id, audit)? {
+            Ok(())
+        } else {
+            Err(AppError::NotFound("Booking not found or not owned by user".to_string()))
+        }
     }
 
-    pub async fn get_all_bookings(&self) -> Result<Vec<Booking>, BookingServiceError> {
+    pub async fn get_all_bookings(&self, tenant: Tenant, query: ListQuery) -> Result<Page<Booking>, AppError> {
+        self.list_bookings(tenant, query).await
+    }
+
+    async fn list_bookings(&self, tenant: Tenant, query: ListQuery) -> Result<Page<Booking>, AppError> {
+        query.sort_field(BookingRepository::SORT_FIELDS, "created_at")?;
+        let offset = query.offset()?;
+
+        let conn = &mut self.get_connection()?;
+        let (bookings, total) = BookingRepository::list_bookings(conn, tenant, &query, offset)?;
+        Ok(Page::new(bookings, total, query.limit(), offset))
+    }
+
+    // การจองตาม ID รวมที่ถูกยกเลิกแล้ว
+    pub async fn find_booking(&self, booking_id: i32) -> Result<Booking, AppError> {
+        let conn = &mut self.get_connection()?;
+        BookingRepository::find_booking(conn, booking_id)?
+            .ok_or_else(|| AppError::NotFound("Booking not found".to_string()))
+    }
+
+    pub async fn mark_no_show(&self, tenant: Tenant, booking_id: i32, audit: PendingAudit<'_, Booking>) -> Result<Booking, AppError> {
         let conn = &mut self.get_connection()?;
-        BookingRepository::get_all_bookings(conn)
-            .map_err(|e| BookingServiceError::DbError(e.to_string()))
+        BookingRepository::mark_no_show(conn, tenant, booking_id, audit).map_err(booking_not_found)
     }
 
-    pub async fn delete_booking(&self, booking_id: i32) -> Result<Booking, BookingServiceError> {
+    pub async fn delete_booking(&self, tenant: Tenant, booking_id: i32, audit: PendingAudit<'_, Booking>) -> Result<Booking, AppError> {
         // Acquires a database connection from the pool.
         let conn = &mut self.get_connection()?;
         // Delegates to the BookingRepository to delete the booking.
-        BookingRepository::delete_booking(conn, booking_id)
-            .map_err(|e| BookingServiceError::DbError(e.to_string()))
+        BookingRepository::delete_booking(conn, tenant, booking_id, audit).map_err(booking_not_found)
+    }
+
+    // กู้คืนการจองที่ถูกยกเลิก (โดยผู้จอง Admin หรือตอนลบผู้ใช้)
+    pub async fn restore_booking(&self, tenant: Tenant, booking_id: i32, audit: PendingAudit<'_, Booking>) -> Result<Booking, AppError> {
+        let conn = &mut self.get_connection()?;
+        BookingRepository::restore_booking(conn, tenant, booking_id, audit)
     }
     
-}
\ No newline at end of file
+}
+
+fn booking_not_found(e: diesel::result::Error) -> AppError {
+    match e {
+        diesel::result::Error::NotFound => AppError::NotFound("Booking not found".to_string()),
+        e => e.into(),
+    }
+}
@@ -0,0 +1,104 @@
+use std::sync::Arc;
+
+use chrono::{Duration, Utc};
+
+use crate::{
+    domain::audit::PendingAudit,
+    domain::user::User,
+    error::AppError,
+    infrastructure::{
+        jwt::JwtService,
+        notifier::{Notification, Notifier},
+        user_repository::UserRepository,
+    },
+};
+
+// EmailVerificationService: ยืนยันอีเมลของผู้ใช้ด้วยลิงก์ที่เซ็นแล้วและมีวันหมดอายุ
+// - Token เป็น JWT (aud = "email-verification") ผูกกับอีเมล ณ ตอนส่ง จึงไม่ต้องเก็บใน DB
+// - เมื่อเปิด EMAIL_VERIFICATION_REQUIRED ผู้ใช้ที่สมัครใหม่จะจองห้องไม่ได้จนกว่าจะยืนยัน (หรือ Admin ยืนยันแทน)
+#[derive(Clone)]
+pub struct EmailVerificationService {
+    users: UserRepository,
+    jwt: JwtService,
+    notifier: Arc<dyn Notifier>,
+    ttl: Duration,
+    verify_url: String,
+    required: bool,
+}
+
+impl EmailVerificationService {
+    pub fn new(
+        users: UserRepository,
+        jwt: JwtService,
+        notifier: Arc<dyn Notifier>,
+        ttl_hours: i64,
+        verify_url: String,
+        required: bool,
+    ) -> Self {
+        EmailVerificationService {
+            users,
+            jwt,
+            notifier,
+            ttl: Duration::hours(ttl_hours),
+            verify_url,
+            required,
+        }
+    }
+
+    // ผู้ใช้ที่สมัครใหม่ต้องมีอีเมลและยืนยันก่อนจองห้องหรือไม่
+    pub fn required(&self) -> bool {
+        self.required
+    }
+
+    // ส่งลิงก์ยืนยันไปยังอีเมลปัจจุบันของผู้ใช้ (ไม่มีอีเมลหรือยืนยันแล้วจะไม่ส่ง)
+    pub async fn send_link(&self, user: &User) -> Result<(), AppError> {
+        let Some(email) = user.email.as_deref().filter(|_| user.email_verified_at.is_none()) else {
+            return Ok(());
+        };
+        let token = self.jwt.create_email_verification_token(user.id, email, self.ttl)?;
+        self.notifier
+            .send(Notification {
+                recipient: email.to_string(),
+                subject: "Verify your email address".to_string(),
+                body: format!(
+                    "Use this link to verify your email address. It expires in {} hours:\n{}?token={}",
+                    self.ttl.num_hours(),
+                    self.verify_url,
+                    token
+                ),
+            })
+            .await
+    }
+
+    // ขอลิงก์ยืนยันใหม่ (ลิงก์เดิมที่ยังไม่หมดอายุยังใช้ได้ถ้าอีเมลไม่เปลี่ยน)
+    pub async fn resend(&self, user_id: i32) -> Result<(), AppError> {
+        let user = self.users.get_user_by_id(user_id).await?;
+        if user.email.is_none() {
+            return Err(AppError::Validation("Add an email address to your profile first.".to_string()));
+        }
+        if user.email_verified_at.is_some() {
+            return Err(AppError::Conflict("Email address is already verified.".to_string()));
+        }
+        self.send_link(&user).await
+    }
+
+    // ยืนยันอีเมลด้วย Token จากลิงก์ (ใช้ไม่ได้ถ้าอีเมลของผู้ใช้เปลี่ยนหลังส่งลิงก์) คืน id ของผู้ใช้
+    pub async fn verify(&self, token: &str, audit: PendingAudit<'_, User>) -> Result<i32, AppError> {
+        let claims = self.jwt.decode_email_verification_token(token)?;
+        let user_id = claims
+            .sub
+            .parse::<i32>()
+            .map_err(|_| AppError::Unauthorized("Invalid email verification token.".to_string()))?;
+        if !self.users.mark_email_verified(user_id, &claims.email, Utc::now().naive_utc(), audit).await? {
+            return Err(AppError::Unauthorized(
+                "Invalid email verification token. The email address has changed.".to_string(),
+            ));
+        }
+        Ok(user_id)
+    }
+
+    // Admin ยืนยันแทนผู้ใช้ (เช่น ผู้ใช้ไม่ได้รับอีเมล)
+    pub async fn override_verification(&self, user_id: i32, audit: PendingAudit<'_, User>) -> Result<User, AppError> {
+        self.users.override_email_verification(user_id, Utc::now().naive_utc(), audit).await
+    }
+}
@@ -0,0 +1,134 @@
+use chrono::Utc;
+
+use crate::{
+    domain::{
+        audit::PendingAudit,
+        group::{CreateGroupRequest, Group, GroupChangeset, NewGroup, RoomAccessRequest, RoomAccessResponse, UpdateGroupRequest},
+        organization::{Tenant, DEFAULT_ORGANIZATION_ID},
+        user::User,
+    },
+    error::AppError,
+    infrastructure::group_repository::GroupRepository,
+};
+
+// GroupService: กลุ่มผู้ใช้ และกฎการเข้าถึงห้อง (ห้องที่จองได้เฉพาะบางกลุ่มหรือบางคน)
+#[derive(Debug, Clone)]
+pub struct GroupService {
+    repo: GroupRepository,
+}
+
+impl GroupService {
+    pub fn new(repo: GroupRepository) -> Self {
+        GroupService { repo }
+    }
+
+    pub async fn list(&self, tenant: Tenant) -> Result<Vec<Group>, AppError> {
+        self.repo.list(tenant).await
+    }
+
+    pub async fn get(&self, tenant: Tenant, id: i32) -> Result<Group, AppError> {
+        self.repo
+            .find(tenant, id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Group not found".to_string()))
+    }
+
+    // กลุ่มใหม่อยู่ในองค์กรของผู้สร้าง Platform Admin เลือกองค์กรได้ด้วย organization_id
+    pub async fn create(
+        &self,
+        tenant: Tenant,
+        request: CreateGroupRequest,
+        audit: PendingAudit<'_, Group>,
+    ) -> Result<Group, AppError> {
+        let organization_id = match (tenant, request.organization_id) {
+            (Tenant::All, requested) => requested.unwrap_or(DEFAULT_ORGANIZATION_ID),
+            (Tenant::Organization(own), Some(requested)) if requested != own => {
+                return Err(AppError::Validation(
+                    "`organization_id` can only be set by platform admins.".to_string(),
+                ));
+            }
+            (Tenant::Organization(own), _) => own,
+        };
+        let group = NewGroup {
+            organization_id,
+            name: request.name.trim(),
+            description: request.description.as_deref(),
+        };
+        self.repo.insert(group, audit).await
+    }
+
+    pub async fn update(
+        &self,
+        tenant: Tenant,
+        id: i32,
+        request: UpdateGroupRequest,
+        audit: PendingAudit<'_, Group>,
+    ) -> Result<Group, AppError> {
+        self.get(tenant, id).await?;
+        let changes = GroupChangeset {
+            name: request.name.map(|name| name.trim().to_string()),
+            description: request.description,
+            updated_at: Utc::now().naive_utc(),
+        };
+        self.repo.update(id, changes, audit).await
+    }
+
+    pub async fn delete(&self, tenant: Tenant, id: i32, audit: PendingAudit<'_, ()>) -> Result<(), AppError> {
+        self.get(tenant, id).await?;
+        self.repo.delete(id, audit).await
+    }
+
+    pub async fn members(&self, tenant: Tenant, id: i32) -> Result<Vec<User>, AppError> {
+        self.get(tenant, id).await?;
+        self.repo.members(id).await
+    }
+
+    pub async fn add_member(
+        &self,
+        tenant: Tenant,
+        id: i32,
+        user_id: i32,
+        audit: PendingAudit<'_, ()>,
+    ) -> Result<(), AppError> {
+        let group = self.get(tenant, id).await?;
+        self.repo.add_member(&group, user_id, audit).await
+    }
+
+    pub async fn remove_member(
+        &self,
+        tenant: Tenant,
+        id: i32,
+        user_id: i32,
+        audit: PendingAudit<'_, ()>,
+    ) -> Result<(), AppError> {
+        self.get(tenant, id).await?;
+        self.repo.remove_member(id, user_id, audit).await
+    }
+
+    pub async fn room_access(&self, room_id: i32) -> Result<RoomAccessResponse, AppError> {
+        let (group_ids, user_ids) = self.repo.room_access(room_id).await?;
+        Ok(RoomAccessResponse {
+            room_id,
+            restricted: !group_ids.is_empty() || !user_ids.is_empty(),
+            group_ids,
+            user_ids,
+        })
+    }
+
+    // owner_id: องค์กรเจ้าของห้อง (จาก OrganizationService::ensure_room)
+    pub async fn set_room_access(
+        &self,
+        room_id: i32,
+        owner_id: i32,
+        request: RoomAccessRequest,
+        audit: PendingAudit<'_, RoomAccessResponse>,
+    ) -> Result<RoomAccessResponse, AppError> {
+        let mut group_ids = request.group_ids;
+        group_ids.sort_unstable();
+        group_ids.dedup();
+        let mut user_ids = request.user_ids;
+        user_ids.sort_unstable();
+        user_ids.dedup();
+        self.repo.set_room_access(room_id, owner_id, &group_ids, &user_ids, audit).await
+    }
+}
@@ -0,0 +1,88 @@
+use chrono::{Duration, Utc};
+
+use crate::{
+    domain::{
+        audit::PendingAudit,
+        impersonation::{Impersonation, ImpersonationAction, ImpersonationResponse, NewImpersonation},
+        organization::Tenant,
+        pagination::{ListQuery, Page},
+        user::User,
+    },
+    error::AppError,
+    infrastructure::{impersonation_repository::ImpersonationRepository, jwt::JwtService},
+};
+
+// ImpersonationService: Admin สวมสิทธิ์ผู้ใช้เพื่อดูสิ่งที่ผู้ใช้เห็น
+// - Token อายุสั้น (ไม่มี Refresh Token) มี claim `act` ระบุ Admin ที่กระทำ
+// - ทุก Request ที่ใช้ Token นี้ถูกบันทึกใน impersonation_actions
+// - งานของเจ้าของบัญชี (เปลี่ยนรหัสผ่าน, 2FA, API Key ฯลฯ) ใช้ไม่ได้ (ดู Interactive)
+#[derive(Clone)]
+pub struct ImpersonationService {
+    repo: ImpersonationRepository,
+    jwt: JwtService,
+    ttl: Duration,
+}
+
+impl ImpersonationService {
+    pub fn new(repo: ImpersonationRepository, jwt: JwtService, ttl_seconds: i64) -> Self {
+        ImpersonationService {
+            repo,
+            jwt,
+            ttl: Duration::seconds(ttl_seconds),
+        }
+    }
+
+    pub async fn start(
+        &self,
+        admin_id: i32,
+        user: &User,
+        reason: &str,
+        audit: PendingAudit<'_, Impersonation>,
+    ) -> Result<ImpersonationResponse, AppError> {
+        let access = self
+            .jwt
+            .create_impersonation_token(user.id, user.organization_id, admin_id, self.ttl)?;
+        let new_impersonation = NewImpersonation {
+            admin_id,
+            user_id: user.id,
+            reason: reason.trim(),
+            jti: &access.claims.jti,
+            expires_at: (Utc::now() + self.ttl).naive_utc(),
+        };
+        let impersonation = self.repo.insert(new_impersonation, audit).await?;
+        Ok(ImpersonationResponse {
+            impersonation_id: impersonation.id,
+            user_id: user.id,
+            token: access.token,
+            expires_in: self.ttl.num_seconds(),
+        })
+    }
+
+    pub async fn get(&self, tenant: Tenant, id: i32) -> Result<Impersonation, AppError> {
+        self.repo
+            .find(tenant, id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Impersonation not found".to_string()))
+    }
+
+    pub async fn list(&self, tenant: Tenant, query: ListQuery) -> Result<Page<Impersonation>, AppError> {
+        query.sort_field(ImpersonationRepository::SORT_FIELDS, "created_at")?;
+        let offset = query.offset()?;
+        let (items, total) = self.repo.list(tenant, &query, offset).await?;
+        Ok(Page::new(items, total, query.limit(), offset))
+    }
+
+    pub async fn actions(&self, tenant: Tenant, id: i32) -> Result<Vec<ImpersonationAction>, AppError> {
+        self.get(tenant, id).await?;
+        self.repo.actions(id).await
+    }
+
+    pub async fn record_action(&self, jti: &str, method: &str, path: &str, status: u16) -> Result<(), AppError> {
+        self.repo.record_action(jti, method, path, status as i32).await
+    }
+
+    pub async fn end(&self, tenant: Tenant, id: i32, audit: PendingAudit<'_, Impersonation>) -> Result<Impersonation, AppError> {
+        let impersonation = self.get(tenant, id).await?;
+        self.repo.end(&impersonation, audit).await
+    }
+}
@@ -0,0 +1,103 @@
+use chrono::{DateTime, Duration, Utc};
+
+use crate::{
+    domain::audit::PendingAudit,
+    domain::organization::Tenant,
+    domain::admin::{AcceptInvitationRequest, Admin, AdminInvitation, CreateInvitationRequest, InvitationResponse, NewAdmin, NewAdminInvitation},
+    error::AppError,
+    infrastructure::{admin_repository::AdminRepository, jwt::JwtService},
+};
+
+// InvitationService: Admin ที่มีอยู่เชิญ Admin ใหม่ผ่านลิงก์ที่เซ็นแล้วและมีวันหมดอายุ
+// - Token เป็น JWT (aud = "admin-invitation") เซ็นด้วย key เดียวกับ Access Token
+// - DB เก็บ jti ของคำเชิญ เพื่อให้ใช้ได้ครั้งเดียวและเพิกถอนได้ก่อนหมดอายุ
+#[derive(Clone)]
+pub struct InvitationService {
+    repo: AdminRepository,
+    jwt: JwtService,
+    default_ttl: Duration,
+    accept_url: String,
+}
+
+impl InvitationService {
+    pub fn new(repo: AdminRepository, jwt: JwtService, default_ttl_hours: i64, accept_url: String) -> Self {
+        InvitationService {
+            repo,
+            jwt,
+            default_ttl: Duration::hours(default_ttl_hours),
+            accept_url,
+        }
+    }
+
+    // Admin ขององค์กรเชิญได้เฉพาะเข้าองค์กรตัวเอง Platform Admin เลือก organization_id ได้ (ไม่ระบุ = Platform Admin)
+    pub async fn create_invitation(
+        &self,
+        invited_by: i32,
+        tenant: Tenant,
+        request: CreateInvitationRequest,
+        audit: PendingAudit<'_, AdminInvitation>,
+    ) -> Result<InvitationResponse, AppError> {
+        let organization_id = match (tenant, request.organization_id) {
+            (Tenant::All, requested) => requested,
+            (Tenant::Organization(own), Some(requested)) if requested != own => {
+                return Err(AppError::Validation(
+                    "`organization_id` can only be set by platform admins.".to_string(),
+                ));
+            }
+            (Tenant::Organization(own), _) => Some(own),
+        };
+        let ttl = request.expires_in_hours.map(Duration::hours).unwrap_or(self.default_ttl);
+        let jti = uuid::Uuid::new_v4().to_string();
+        let (token, claims) = self.jwt.create_invitation_token(invited_by, &jti, ttl)?;
+        let expires_at = DateTime::<Utc>::from_timestamp(claims.exp as i64, 0)
+            .ok_or_else(|| AppError::Internal("Invalid invitation expiry".to_string()))?
+            .naive_utc();
+
+        let new_invitation = NewAdminInvitation {
+            jti: &jti,
+            invited_by,
+            expires_at,
+            organization_id,
+        };
+        let invitation = self.repo.insert_invitation(new_invitation, audit).await?;
+
+        Ok(InvitationResponse {
+            invitation,
+            invite_url: format!("{}?token={}", self.accept_url, token),
+            token,
+        })
+    }
+
+    pub async fn list_pending(&self, tenant: Tenant) -> Result<Vec<AdminInvitation>, AppError> {
+        self.repo.list_pending_invitations(tenant, Utc::now().naive_utc()).await
+    }
+
+    pub async fn revoke_invitation(&self, tenant: Tenant, id: i32, audit: PendingAudit<'_, ()>) -> Result<(), AppError> {
+        if !self.repo.revoke_invitation(tenant, id, Utc::now().naive_utc(), audit).await? {
+            return Err(AppError::NotFound("Invitation not found or already used".to_string()));
+        }
+        Ok(())
+    }
+
+    // สร้างบัญชี Admin จากคำเชิญ: ตรวจสอบลายเซ็นและวันหมดอายุของ Token แล้วใช้คำเชิญใน DB
+    pub async fn accept_invitation(
+        &self,
+        request: AcceptInvitationRequest,
+        audit: PendingAudit<'_, Admin>,
+    ) -> Result<Admin, AppError> {
+        let claims = self.jwt.decode_invitation_token(&request.token)?;
+        let hashed_password = bcrypt::hash(&request.password, bcrypt::DEFAULT_COST)?;
+        self.repo
+            .accept_invitation(
+                &claims.jti,
+                NewAdmin {
+                    username: &request.username,
+                    password_hash: &hashed_password,
+                    organization_id: None, // ใช้องค์กรของคำเชิญ
+                },
+                Utc::now().naive_utc(),
+                audit,
+            )
+            .await
+    }
+}
@@ -0,0 +1,160 @@
+use chrono::{NaiveDateTime, Utc};
+
+use crate::{
+    domain::audit::PendingAudit,
+    domain::login_throttle::{
+        LockoutPolicy, LoginEvent, LoginEventQuery, LoginThrottle, NewLoginEvent, SCOPE_IP, SCOPE_USERNAME,
+    },
+    error::AppError,
+    infrastructure::login_throttle_repository::LoginThrottleRepository,
+};
+
+// LoginThrottleService: ป้องกันการเดารหัสผ่านที่ /login/user และ /login/admin
+// - นับการ Login ผิดแยกต่อ username (ตามประเภทบัญชี) และต่อ IP ของ Client
+// - ผิดครบตามกำหนดจะถูกล็อกชั่วคราวแบบ exponential backoff ระหว่างนั้นตอบ 429 พร้อม Retry-After
+// - รหัส 2FA ที่ผิดนับรวมกับตัวนับเดียวกัน
+// - Login สำเร็จครบทุกขั้น (รวม 2FA) จึงจะล้างตัวนับของ username (ตัวนับของ IP หมดอายุเองตาม window)
+// - ทุกครั้งที่ Login จะถูกบันทึกใน login_events
+#[derive(Debug, Clone)]
+pub struct LoginThrottleService {
+    repo: LoginThrottleRepository,
+    username_policy: LockoutPolicy,
+    ip_policy: LockoutPolicy,
+}
+
+fn username_key(account_kind: &str, username: &str) -> String {
+    format!("{}:{}", account_kind, username)
+}
+
+fn locked_out(locked_until: NaiveDateTime, now: NaiveDateTime) -> AppError {
+    // ปัดขึ้นเป็นวินาที เพื่อไม่ให้ Client ลองใหม่ก่อนปลดล็อก
+    let retry_after = ((locked_until - now).num_milliseconds() + 999).div_euclid(1000).max(1) as u64;
+    AppError::TooManyRequests(
+        format!("Too many failed login attempts. Try again in {}s.", retry_after),
+        retry_after,
+    )
+}
+
+impl LoginThrottleService {
+    pub fn new(repo: LoginThrottleRepository, username_policy: LockoutPolicy, ip_policy: LockoutPolicy) -> Self {
+        LoginThrottleService { repo, username_policy, ip_policy }
+    }
+
+    // ตรวจก่อนตรวจรหัสผ่าน: ถ้า username หรือ IP ยังถูกล็อกอยู่จะไม่ตรวจรหัสผ่านเลย
+    pub async fn check(&self, account_kind: &str, username: &str, ip: &str) -> Result<(), AppError> {
+        let now = Utc::now().naive_utc();
+        let key = username_key(account_kind, username);
+        let locked = self
+            .repo
+            .find_locked(&[(SCOPE_USERNAME, key.as_str()), (SCOPE_IP, ip)], now)
+            .await?;
+        let Some(locked_until) = locked.iter().filter_map(|throttle| throttle.locked_until).max() else {
+            return Ok(());
+        };
+
+        self.repo
+            .insert_event(NewLoginEvent {
+                account_kind,
+                username,
+                ip,
+                success: false,
+                reason: Some("locked_out"),
+            })
+            .await?;
+        Err(locked_out(locked_until, now))
+    }
+
+    // บันทึกผลการตรวจรหัสผ่าน (Unauthorized นับเป็นการ Login ผิด) แล้วคืนผลเดิมให้ Handler
+    // รหัสผ่านถูกยังไม่ล้างตัวนับ บัญชีที่มี 2FA ต้องผ่าน record_mfa ก่อน ที่เหลือเรียก record_success
+    pub async fn record_password<T>(
+        &self,
+        account_kind: &str,
+        username: &str,
+        ip: &str,
+        result: Result<T, AppError>,
+    ) -> Result<T, AppError> {
+        if let Err(AppError::Unauthorized(_)) = &result {
+            self.record_failure(account_kind, username, ip, "invalid_credentials").await?;
+        }
+        result
+    }
+
+    // บันทึกผลการยืนยันรหัส 2FA: รหัสผิดนับรวมกับตัวนับเดียวกับรหัสผ่าน
+    // ขอ Challenge ใหม่จึงเดารหัสต่อไม่ได้ไม่จำกัด
+    pub async fn record_mfa<T>(
+        &self,
+        account_kind: &str,
+        username: &str,
+        ip: &str,
+        result: Result<T, AppError>,
+    ) -> Result<T, AppError> {
+        match &result {
+            Ok(_) => self.record_success(account_kind, username, ip).await?,
+            Err(AppError::Unauthorized(_)) => self.record_failure(account_kind, username, ip, "invalid_mfa_code").await?,
+            Err(_) => {}
+        }
+        result
+    }
+
+    // Login ครบทุกขั้นแล้ว: บันทึกและล้างตัวนับของ username
+    pub async fn record_success(&self, account_kind: &str, username: &str, ip: &str) -> Result<(), AppError> {
+        self.repo
+            .insert_event(NewLoginEvent { account_kind, username, ip, success: true, reason: None })
+            .await?;
+        self.repo.clear(SCOPE_USERNAME, &username_key(account_kind, username)).await
+    }
+
+    async fn record_failure(&self, account_kind: &str, username: &str, ip: &str, reason: &'static str) -> Result<(), AppError> {
+        self.repo
+            .insert_event(NewLoginEvent {
+                account_kind,
+                username,
+                ip,
+                success: false,
+                reason: Some(reason),
+            })
+            .await?;
+        let now = Utc::now().naive_utc();
+        let key = username_key(account_kind, username);
+        self.repo.record_failure(SCOPE_USERNAME, &key, &self.username_policy, now).await?;
+        self.repo.record_failure(SCOPE_IP, ip, &self.ip_policy, now).await?;
+        Ok(())
+    }
+
+    // รายการที่ถูกล็อกอยู่หรือยังมีการ Login ผิดที่นับอยู่
+    pub async fn list_lockouts(&self) -> Result<Vec<LoginThrottle>, AppError> {
+        let now = Utc::now().naive_utc();
+        let window = self.username_policy.window.max(self.ip_policy.window);
+        self.repo.list_active(now, now - window).await
+    }
+
+    pub async fn clear_lockout(&self, throttle_id: i32, audit: PendingAudit<'_, ()>) -> Result<(), AppError> {
+        if !self.repo.delete_by_id(throttle_id, audit).await? {
+            return Err(AppError::NotFound("Lockout not found".to_string()));
+        }
+        Ok(())
+    }
+
+    pub async fn list_events(&self, query: LoginEventQuery) -> Result<Vec<LoginEvent>, AppError> {
+        self.repo.list_events(&query).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retry_after_rounds_up_to_whole_seconds() {
+        let now = Utc::now().naive_utc();
+        let error = locked_out(now + chrono::Duration::milliseconds(1500), now);
+        assert!(matches!(error, AppError::TooManyRequests(_, 2)));
+        let error = locked_out(now, now);
+        assert!(matches!(error, AppError::TooManyRequests(_, 1)));
+    }
+
+    #[test]
+    fn username_counters_are_separate_per_account_kind() {
+        assert_ne!(username_key("user", "root"), username_key("admin", "root"));
+    }
+}
@@ -0,0 +1,384 @@
+use chrono::{Duration, Utc};
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use totp_rs::{Algorithm, Secret, TOTP};
+
+use crate::{
+    domain::{
+        audit::PendingAudit,
+        mfa::{
+            MfaChallenge, MfaChallengeResponse, MfaStatusResponse, MfaVerifyRequest, NewMfaChallenge, NewTotpFactor,
+            TotpCodeRequest, TotpEnrollmentResponse, TotpFactor,
+        },
+        permission::PrincipalKind,
+    },
+    error::AppError,
+    infrastructure::{mfa_repository::MfaRepository, role_repository::RoleRepository},
+};
+
+const TOTP_DIGITS: usize = 6;
+const TOTP_STEP_SECONDS: u64 = 30;
+const TOTP_SKEW_STEPS: i64 = 1; // ยอมรับรหัสของ step ก่อนหน้า/ถัดไป (นาฬิกาเหลื่อม)
+const RECOVERY_CODE_COUNT: usize = 10;
+const MAX_CHALLENGE_ATTEMPTS: i32 = 5;
+
+// MfaService: 2FA แบบ TOTP (RFC 6238) สำหรับบัญชีผู้ใช้และ Admin
+// - ลงทะเบียน: สร้าง Secret -> ผู้ใช้เพิ่มใน Authenticator App -> ยืนยันด้วยรหัสแรก -> ได้ Recovery Code
+// - Login: รหัสผ่านถูกแล้วได้ Challenge Token (อายุสั้น ผิดได้ไม่เกิน MAX_CHALLENGE_ATTEMPTS ครั้ง)
+//   แล้วยืนยันด้วยรหัส TOTP หรือ Recovery Code เพื่อรับ Access/Refresh Token
+//   รหัสที่ผิดยังนับใน LoginThrottleService ของบัญชี (ขอ Challenge ใหม่ไม่ได้ล้างตัวนับ)
+// - Role ที่ตั้ง require_mfa ทำให้บัญชีต้องลงทะเบียน 2FA ระหว่าง Login และปิด 2FA เองไม่ได้
+#[derive(Debug, Clone)]
+pub struct MfaService {
+    repo: MfaRepository,
+    roles: RoleRepository,
+    issuer: String,
+    challenge_ttl: Duration,
+}
+
+// ผลของการยืนยัน Challenge: บัญชีที่ Login สำเร็จ และ Recovery Code ถ้าเพิ่งลงทะเบียน
+#[derive(Debug, Clone)]
+pub struct VerifiedChallenge {
+    pub kind: PrincipalKind,
+    pub id: i32,
+    pub recovery_codes: Option<Vec<String>>,
+}
+
+fn hash_secret_value(value: &str) -> String {
+    hex::encode(Sha256::digest(value.as_bytes()))
+}
+
+fn generate_token() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+// Recovery Code รูปแบบ xxxxx-xxxxx (hex ตัวเล็ก)
+fn generate_recovery_codes() -> Vec<String> {
+    (0..RECOVERY_CODE_COUNT)
+        .map(|_| {
+            let mut bytes = [0u8; 5];
+            rand::thread_rng().fill_bytes(&mut bytes);
+            let code = hex::encode(bytes);
+            format!("{}-{}", &code[..5], &code[5..])
+        })
+        .collect()
+}
+
+fn normalize_recovery_code(code: &str) -> String {
+    code.trim().to_ascii_lowercase()
+}
+
+fn normalize_code(code: &str) -> String {
+    code.chars().filter(|c| !c.is_whitespace()).collect()
+}
+
+fn invalid_code() -> AppError {
+    AppError::Unauthorized("Invalid verification code.".to_string())
+}
+
+fn parse_kind(kind: &str) -> Result<PrincipalKind, AppError> {
+    PrincipalKind::parse(kind).ok_or_else(|| AppError::Internal(format!("Unknown principal kind '{}'", kind)))
+}
+
+impl MfaService {
+    pub fn new(repo: MfaRepository, roles: RoleRepository, issuer: String, challenge_ttl_seconds: i64) -> Self {
+        MfaService {
+            repo,
+            roles,
+            issuer,
+            challenge_ttl: Duration::seconds(challenge_ttl_seconds),
+        }
+    }
+
+    fn totp(&self, secret: Vec<u8>, account_name: String) -> Result<TOTP, AppError> {
+        TOTP::new(
+            Algorithm::SHA1,
+            TOTP_DIGITS,
+            0,
+            TOTP_STEP_SECONDS,
+            secret,
+            Some(self.issuer.clone()),
+            account_name,
+        )
+        .map_err(|e| AppError::Internal(format!("Invalid TOTP configuration: {}", e)))
+    }
+
+    // ตรวจรหัส TOTP แล้วบันทึก step ที่ใช้ไป (รหัสเดิมใช้ซ้ำไม่ได้)
+    async fn verify_totp(&self, factor: &TotpFactor, code: &str) -> Result<(), AppError> {
+        let secret = Secret::Encoded(factor.secret.clone())
+            .to_bytes()
+            .map_err(|e| AppError::Internal(format!("Stored TOTP secret is invalid: {}", e)))?;
+        let totp = self.totp(secret, String::new())?;
+        let code = normalize_code(code);
+        let current_step = Utc::now().timestamp() / TOTP_STEP_SECONDS as i64;
+
+        let step = (current_step - TOTP_SKEW_STEPS..=current_step + TOTP_SKEW_STEPS)
+            .filter(|step| factor.last_used_step.is_none_or(|last| *step > last))
+            .find(|step| totp.check(&code, *step as u64 * TOTP_STEP_SECONDS))
+            .ok_or_else(invalid_code)?;
+        if !self.repo.use_step(factor.id, step).await? {
+            return Err(invalid_code());
+        }
+        Ok(())
+    }
+
+    // ยืนยันตัวตนด้วยรหัส TOTP หรือ Recovery Code ของบัญชีที่เปิดใช้ 2FA แล้ว
+    async fn verify_factor(&self, kind: PrincipalKind, id: i32, code: Option<&str>, recovery_code: Option<&str>) -> Result<(), AppError> {
+        let factor = self
+            .repo
+            .find_factor(kind.as_str(), id)
+            .await?
+            .filter(|factor| factor.confirmed_at.is_some())
+            .ok_or_else(|| AppError::NotFound("Two-factor authentication is not enabled.".to_string()))?;
+
+        match (code, recovery_code) {
+            (Some(code), _) => self.verify_totp(&factor, code).await,
+            (None, Some(recovery_code)) => {
+                let code_hash = hash_secret_value(&normalize_recovery_code(recovery_code));
+                if !self
+                    .repo
+                    .consume_recovery_code(kind.as_str(), id, &code_hash, Utc::now().naive_utc())
+                    .await?
+                {
+                    return Err(invalid_code());
+                }
+                Ok(())
+            }
+            (None, None) => Err(AppError::Validation("Provide `code` or `recovery_code`.".to_string())),
+        }
+    }
+
+    async fn store_recovery_codes(
+        &self,
+        kind: PrincipalKind,
+        id: i32,
+        audit: PendingAudit<'_, MfaStatusResponse>,
+    ) -> Result<Vec<String>, AppError> {
+        let codes = generate_recovery_codes();
+        let hashes: Vec<String> = codes.iter().map(|code| hash_secret_value(code)).collect();
+        self.repo.replace_recovery_codes(kind.as_str(), id, &hashes, audit).await?;
+        Ok(codes)
+    }
+
+    pub async fn status(&self, kind: PrincipalKind, id: i32) -> Result<MfaStatusResponse, AppError> {
+        self.repo.status(kind.as_str(), id).await
+    }
+
+    // เริ่มลงทะเบียน Authenticator (ต้องยืนยันด้วยรหัสแรกก่อนจึงจะมีผล)
+    pub async fn start_enrollment(
+        &self,
+        kind: PrincipalKind,
+        id: i32,
+        audit: PendingAudit<'_, MfaStatusResponse>,
+    ) -> Result<TotpEnrollmentResponse, AppError> {
+        let account_name = self
+            .repo
+            .account_name(kind.as_str(), id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Account not found".to_string()))?;
+        let secret = Secret::generate_secret()
+            .to_bytes()
+            .map_err(|e| AppError::Internal(format!("Failed to generate TOTP secret: {}", e)))?;
+        let totp = self.totp(secret, account_name)?;
+        let encoded = totp.get_secret_base32();
+
+        let factor = NewTotpFactor {
+            principal_kind: kind.as_str(),
+            principal_id: id,
+            secret: &encoded,
+        };
+        self.repo.start_enrollment(factor, audit).await?;
+        Ok(TotpEnrollmentResponse {
+            secret: encoded,
+            otpauth_uri: totp.get_url(),
+        })
+    }
+
+    // ยืนยันการลงทะเบียนด้วยรหัสแรก คืนค่า Recovery Code ชุดแรก
+    pub async fn confirm_enrollment(
+        &self,
+        kind: PrincipalKind,
+        id: i32,
+        code: &str,
+        audit: PendingAudit<'_, MfaStatusResponse>,
+    ) -> Result<Vec<String>, AppError> {
+        let factor = self
+            .repo
+            .find_factor(kind.as_str(), id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("No pending two-factor enrollment.".to_string()))?;
+        if factor.confirmed_at.is_some() {
+            return Err(AppError::Conflict("Two-factor authentication is already enabled.".to_string()));
+        }
+        self.verify_totp(&factor, code).await?;
+
+        let codes = generate_recovery_codes();
+        let hashes: Vec<String> = codes.iter().map(|code| hash_secret_value(code)).collect();
+        self.repo.confirm_factor(&factor, &hashes, Utc::now().naive_utc(), audit).await?;
+        Ok(codes)
+    }
+
+    // ปิด 2FA ของตัวเอง (ต้องยืนยันด้วยรหัส) ทำไม่ได้ถ้า Role กำหนดให้ต้องใช้ 2FA
+    pub async fn disable(
+        &self,
+        kind: PrincipalKind,
+        id: i32,
+        request: TotpCodeRequest,
+        audit: PendingAudit<'_, MfaStatusResponse>,
+    ) -> Result<(), AppError> {
+        if self.roles.mfa_required_for(kind.as_str(), id).await? {
+            return Err(AppError::Forbidden(
+                "Two-factor authentication is required for your role and cannot be disabled.".to_string(),
+            ));
+        }
+        self.verify_factor(kind, id, request.code.as_deref(), request.recovery_code.as_deref())
+            .await?;
+        self.repo.delete_factor(kind.as_str(), id, audit).await?;
+        Ok(())
+    }
+
+    // สร้าง Recovery Code ชุดใหม่ (ชุดเดิมใช้ไม่ได้อีก)
+    pub async fn regenerate_recovery_codes(
+        &self,
+        kind: PrincipalKind,
+        id: i32,
+        request: TotpCodeRequest,
+        audit: PendingAudit<'_, MfaStatusResponse>,
+    ) -> Result<Vec<String>, AppError> {
+        self.verify_factor(kind, id, request.code.as_deref(), request.recovery_code.as_deref())
+            .await?;
+        self.store_recovery_codes(kind, id, audit).await
+    }
+
+    // Admin ล้าง 2FA ของบัญชีอื่น (เช่น ทำอุปกรณ์และ Recovery Code หาย)
+    pub async fn reset(&self, kind: PrincipalKind, id: i32, audit: PendingAudit<'_, MfaStatusResponse>) -> Result<(), AppError> {
+        if !self.repo.delete_factor(kind.as_str(), id, audit).await? {
+            return Err(AppError::NotFound("Two-factor authentication is not enabled for this account.".to_string()));
+        }
+        Ok(())
+    }
+
+    // เรียกหลังตรวจรหัสผ่านแล้ว: คืนค่า Challenge ถ้าบัญชีต้องยืนยัน 2FA ก่อนได้รับ Token
+    pub async fn login_challenge(&self, kind: PrincipalKind, id: i32) -> Result<Option<MfaChallengeResponse>, AppError> {
+        let enabled = self
+            .repo
+            .find_factor(kind.as_str(), id)
+            .await?
+            .is_some_and(|factor| factor.confirmed_at.is_some());
+        let enrollment = !enabled && self.roles.mfa_required_for(kind.as_str(), id).await?;
+        if !enabled && !enrollment {
+            return Ok(None);
+        }
+
+        let token = generate_token();
+        self.repo
+            .insert_challenge(NewMfaChallenge {
+                token_hash: &hash_secret_value(&token),
+                principal_kind: kind.as_str(),
+                principal_id: id,
+                enrollment,
+                expires_at: (Utc::now() + self.challenge_ttl).naive_utc(),
+            })
+            .await?;
+        Ok(Some(MfaChallengeResponse {
+            mfa_required: true,
+            enrollment_required: enrollment,
+            challenge_token: token,
+            expires_in: self.challenge_ttl.num_seconds(),
+        }))
+    }
+
+    async fn open_challenge(&self, challenge_token: &str) -> Result<MfaChallenge, AppError> {
+        self.repo
+            .find_open_challenge(&hash_secret_value(challenge_token), Utc::now().naive_utc(), MAX_CHALLENGE_ATTEMPTS)
+            .await?
+            .ok_or_else(|| AppError::Unauthorized("Invalid or expired challenge token. Log in again.".to_string()))
+    }
+
+    // บัญชีและ username ของ Challenge ที่ยังใช้ได้ (ใช้ตรวจ/นับ Login Throttling และสร้าง Audit Event ก่อนตรวจรหัส)
+    pub async fn challenge_account(&self, challenge_token: &str) -> Result<(PrincipalKind, i32, String), AppError> {
+        let challenge = self.open_challenge(challenge_token).await?;
+        let kind = parse_kind(&challenge.principal_kind)?;
+        let username = self
+            .repo
+            .account_name(kind.as_str(), challenge.principal_id)
+            .await?
+            .ok_or_else(|| AppError::Unauthorized("Invalid or expired challenge token. Log in again.".to_string()))?;
+        Ok((kind, challenge.principal_id, username))
+    }
+
+    // ลงทะเบียน Authenticator ระหว่าง Login (เฉพาะ Challenge ที่ต้องลงทะเบียน)
+    pub async fn enroll_with_challenge(
+        &self,
+        challenge_token: &str,
+        audit: PendingAudit<'_, MfaStatusResponse>,
+    ) -> Result<TotpEnrollmentResponse, AppError> {
+        let challenge = self.open_challenge(challenge_token).await?;
+        if !challenge.enrollment {
+            return Err(AppError::Conflict("Two-factor authentication is already enabled.".to_string()));
+        }
+        let kind = parse_kind(&challenge.principal_kind)?;
+        self.start_enrollment(kind, challenge.principal_id, audit).await
+    }
+
+    // Login ขั้นที่สอง: ตรวจรหัสแล้วปิด Challenge (รหัสผิดนับจำนวนครั้ง)
+    // audit ถูกบันทึกเฉพาะเมื่อ Challenge นี้ลงทะเบียน 2FA เสร็จ (Login ปกติมี login_events อยู่แล้ว)
+    pub async fn verify_challenge(
+        &self,
+        request: MfaVerifyRequest,
+        audit: PendingAudit<'_, MfaStatusResponse>,
+    ) -> Result<VerifiedChallenge, AppError> {
+        let challenge = self.open_challenge(&request.challenge_token).await?;
+        let kind = parse_kind(&challenge.principal_kind)?;
+        let id = challenge.principal_id;
+
+        let result = if challenge.enrollment {
+            let code = request
+                .code
+                .as_deref()
+                .ok_or_else(|| AppError::Validation("Provide the `code` from your authenticator app.".to_string()))?;
+            self.confirm_enrollment(kind, id, code, audit).await.map(Some)
+        } else {
+            self.verify_factor(kind, id, request.code.as_deref(), request.recovery_code.as_deref())
+                .await
+                .map(|_| None)
+        };
+
+        let recovery_codes = match result {
+            Ok(codes) => codes,
+            Err(e @ AppError::Unauthorized(_)) => {
+                self.repo.record_failed_attempt(challenge.id).await?;
+                return Err(e);
+            }
+            Err(e) => return Err(e),
+        };
+        if !self.repo.complete_challenge(challenge.id, Utc::now().naive_utc()).await? {
+            return Err(AppError::Unauthorized("Invalid or expired challenge token. Log in again.".to_string()));
+        }
+        Ok(VerifiedChallenge { kind, id, recovery_codes })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recovery_codes_are_unique_and_formatted() {
+        let codes = generate_recovery_codes();
+        assert_eq!(codes.len(), RECOVERY_CODE_COUNT);
+        assert!(codes.iter().all(|code| code.len() == 11 && code.as_bytes()[5] == b'-'));
+        let unique: std::collections::HashSet<_> = codes.iter().collect();
+        assert_eq!(unique.len(), codes.len());
+    }
+
+    #[test]
+    fn codes_are_normalized_before_checking() {
+        assert_eq!(normalize_code(" 123 456 "), "123456");
+        assert_eq!(normalize_recovery_code(" ABCDE-12345 "), "abcde-12345");
+        assert_eq!(hash_secret_value("abc"), hash_secret_value("abc"));
+        assert_ne!(hash_secret_value("abc"), hash_secret_value("abd"));
+    }
+}
@@ -1,4 +1,19 @@
 pub mod room_service;
 pub mod user_service;
 pub mod admin_service;
-pub mod booking_service;
\ No newline at end of file
+pub mod booking_service;
+pub mod report_service;
+pub mod auth_service;
+pub mod role_service;
+pub mod invitation_service;
+pub mod password_service;
+pub mod login_throttle_service;
+pub mod mfa_service;
+pub mod api_key_service;
+pub mod oidc_service;
+pub mod email_verification_service;
+pub mod privacy_service;
+pub mod organization_service;
+pub mod group_service;
+pub mod impersonation_service;
+pub mod audit_service;
\ No newline at end of file
@@ -0,0 +1,226 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{Duration, Utc};
+use rand::RngCore;
+use reqwest::Url;
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    domain::{
+        audit::{AuditContext, AuditEvent},
+        oidc::{NewOidcLoginState, OidcCallbackQuery, OidcIdentity, OIDC_ROLE_SOURCE},
+        organization::ExternalOrganization,
+        permission::{GroupRoleMap, PrincipalKind},
+        validation::is_valid_username,
+    },
+    error::AppError,
+    infrastructure::{
+        oidc_client::OidcClient, oidc_repository::OidcRepository, role_repository::RoleRepository,
+        user_repository::UserRepository,
+    },
+    middleware::session::LoginMode,
+};
+
+// OidcConfig: ค่าตั้งค่าของ Login ผ่าน OIDC (อ่านจาก Environment ใน main.rs)
+#[derive(Debug, Clone)]
+pub struct OidcConfig {
+    pub issuer_url: String,
+    pub client_id: String,
+    pub client_secret: Option<String>,
+    pub redirect_url: String,      // Callback ของเรา (/auth/oidc/callback) ที่ลงทะเบียนไว้กับ Identity Provider
+    pub scopes: String,
+    pub username_claim: String,    // claim ที่ใช้เป็น username ตอนสร้างผู้ใช้ใหม่
+    pub groups_claim: String,
+    pub group_roles: GroupRoleMap, // กลุ่มใน Identity Provider -> Role ในระบบ
+    pub organization: ExternalOrganization, // องค์กรของผู้ใช้ใหม่ (slug คงที่ หรือจาก claim)
+    pub post_login_url: Option<String>,    // หน้าที่ Redirect ไปหลัง Login แบบ Cookie สำเร็จ
+    pub state_ttl_seconds: i64,
+}
+
+// OidcLogin: ผลของ Callback ที่สำเร็จ
+#[derive(Debug, Clone)]
+pub struct OidcLogin {
+    pub user_id: i32,
+    pub username: String,
+    pub mode: LoginMode,
+}
+
+// OidcService: Login ผ่าน Identity Provider ขององค์กรด้วย Authorization Code Flow + PKCE
+// 1. start_login: สร้าง state, nonce และ PKCE verifier เก็บใน DB แล้วส่ง URL ของหน้า Login ของ Identity Provider
+// 2. complete_login: ตรวจ state (ใช้ครั้งเดียว) แลก code เป็น ID Token และตรวจสอบ ID Token
+//    จากนั้นหา/สร้างผู้ใช้ (issuer + sub ในองค์กรตาม OidcConfig::organization) ซิงก์ชื่อที่แสดง (claim name) กลุ่ม และ Role ตามกลุ่มใน ID Token
+#[derive(Clone)]
+pub struct OidcService {
+    config: OidcConfig,
+    client: OidcClient,
+    repo: OidcRepository,
+    users: UserRepository,
+    roles: RoleRepository,
+}
+
+fn random_token() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+fn hash_state(state: &str) -> String {
+    hex::encode(Sha256::digest(state.as_bytes()))
+}
+
+// PKCE S256: code_challenge = BASE64URL(SHA256(code_verifier))
+fn pkce_challenge(verifier: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()))
+}
+
+// ค่า claim ที่เป็น string หรือ array ของ string
+fn claim_strings(claims: &Map<String, Value>, name: &str) -> Vec<String> {
+    match claims.get(name) {
+        Some(Value::String(value)) => vec![value.clone()],
+        Some(Value::Array(values)) => values.iter().filter_map(Value::as_str).map(str::to_string).collect(),
+        _ => Vec::new(),
+    }
+}
+
+impl OidcService {
+    pub fn new(
+        config: OidcConfig,
+        repo: OidcRepository,
+        users: UserRepository,
+        roles: RoleRepository,
+    ) -> Result<Self, AppError> {
+        let client = OidcClient::new(config.issuer_url.clone(), config.client_id.clone(), config.client_secret.clone())?;
+        Ok(OidcService { config, client, repo, users, roles })
+    }
+
+    pub fn post_login_url(&self) -> Option<&str> {
+        self.config.post_login_url.as_deref()
+    }
+
+    // สร้าง URL สำหรับ Redirect ผู้ใช้ไปหน้า Login ของ Identity Provider
+    pub async fn start_login(&self, mode: LoginMode) -> Result<String, AppError> {
+        let metadata = self.client.metadata().await?;
+        let state = random_token();
+        let nonce = random_token();
+        let code_verifier = random_token();
+        let now = Utc::now();
+
+        self.repo
+            .insert_state(
+                NewOidcLoginState {
+                    state_hash: &hash_state(&state),
+                    nonce: &nonce,
+                    code_verifier: &code_verifier,
+                    mode: match mode {
+                        LoginMode::Bearer => "bearer",
+                        LoginMode::Cookie => "cookie",
+                    },
+                    expires_at: (now + Duration::seconds(self.config.state_ttl_seconds)).naive_utc(),
+                },
+                now.naive_utc(),
+            )
+            .await?;
+
+        let url = Url::parse_with_params(
+            &metadata.authorization_endpoint,
+            &[
+                ("response_type", "code"),
+                ("client_id", self.client.client_id()),
+                ("redirect_uri", &self.config.redirect_url),
+                ("scope", &self.config.scopes),
+                ("state", &state),
+                ("nonce", &nonce),
+                ("code_challenge", &pkce_challenge(&code_verifier)),
+                ("code_challenge_method", "S256"),
+            ],
+        )
+        .map_err(|e| AppError::BadGateway(format!("Invalid authorization endpoint: {}", e)))?;
+        Ok(url.into())
+    }
+
+    // จัดการ Callback จาก Identity Provider และคืนผู้ใช้ที่ Login สำเร็จ
+    pub async fn complete_login(&self, query: OidcCallbackQuery, audit: &AuditContext) -> Result<OidcLogin, AppError> {
+        if let Some(error) = query.error {
+            let description = query.error_description.map(|d| format!(": {}", d)).unwrap_or_default();
+            return Err(AppError::Unauthorized(format!(
+                "The identity provider returned an error '{}'{}",
+                error, description
+            )));
+        }
+        let (Some(code), Some(state)) = (query.code, query.state) else {
+            return Err(AppError::Validation("Both 'code' and 'state' are required.".to_string()));
+        };
+
+        let now = Utc::now().naive_utc();
+        let login_state = self
+            .repo
+            .consume_state(&hash_state(&state), now)
+            .await?
+            .ok_or_else(|| AppError::Unauthorized("Invalid or expired login state. Start the login again.".to_string()))?;
+
+        let id_token = self
+            .client
+            .exchange_code(&code, &login_state.code_verifier, &self.config.redirect_url)
+            .await?;
+        let claims = self.client.validate_id_token(&id_token, &login_state.nonce).await?;
+        let identity = self.identity_from_claims(&claims)?;
+
+        let user_id = self
+            .users
+            .provision_external_user(
+                self.client.issuer(),
+                &identity.subject,
+                &identity.username,
+                &identity.organization,
+                now,
+                audit.pending(AuditEvent::user_provisioned),
+            )
+            .await?;
+        self.users
+            .sync_directory_profile(user_id, identity.display_name.as_deref(), &identity.groups)
+            .await?;
+        self.roles
+            .sync_roles(PrincipalKind::User.as_str(), user_id, OIDC_ROLE_SOURCE, &self.config.group_roles.roles_for(&identity.groups))
+            .await?;
+
+        Ok(OidcLogin {
+            user_id,
+            username: identity.username,
+            mode: if login_state.mode == "cookie" { LoginMode::Cookie } else { LoginMode::Bearer },
+        })
+    }
+
+    fn identity_from_claims(&self, claims: &Map<String, Value>) -> Result<OidcIdentity, AppError> {
+        let subject = claims
+            .get("sub")
+            .and_then(Value::as_str)
+            .ok_or_else(|| AppError::Unauthorized("Invalid ID token. Missing 'sub' claim.".to_string()))?;
+        let username = claims
+            .get(&self.config.username_claim)
+            .and_then(Value::as_str)
+            .ok_or_else(|| {
+                AppError::Forbidden(format!(
+                    "The identity provider did not send the '{}' claim used as username.",
+                    self.config.username_claim
+                ))
+            })?;
+
+        if !is_valid_username(username) {
+            return Err(AppError::Forbidden(format!(
+                "'{}' from the identity provider is not a valid username.",
+                username
+            )));
+        }
+
+        let organization_claim = self.config.organization.claim().and_then(|name| claim_strings(claims, name).into_iter().next());
+        let organization = self.config.organization.slug(organization_claim.as_deref())?;
+
+        Ok(OidcIdentity {
+            subject: subject.to_string(),
+            organization: organization.to_string(),
+            username: username.to_string(),
+            display_name: claims.get("name").and_then(Value::as_str).map(str::to_string),
+            groups: claim_strings(claims, &self.config.groups_claim),
+        })
+    }
+}
@@ -0,0 +1,147 @@
+use chrono::Utc;
+
+use crate::{
+    domain::{
+        audit::PendingAudit,
+        organization::{
+            CreateOrganizationRequest, NewOrganization, Organization, OrganizationChangeset, RoomSharesRequest,
+            RoomSharesResponse, SetOrganizationRequest, Tenant, UpdateOrganizationRequest, DEFAULT_ORGANIZATION_ID,
+        },
+        permission::PrincipalKind,
+        user::User,
+    },
+    error::AppError,
+    infrastructure::organization_repository::OrganizationRepository,
+};
+
+// OrganizationService: องค์กร (Tenant) และการตรวจว่าข้อมูลที่ถูกอ้างถึงอยู่ในขอบเขตของผู้เรียก
+// ข้อมูลขององค์กรอื่นตอบ 404 เหมือนไม่มีอยู่ เพื่อไม่ให้รู้ว่ามี id นั้นในระบบ
+#[derive(Debug, Clone)]
+pub struct OrganizationService {
+    repo: OrganizationRepository,
+}
+
+impl OrganizationService {
+    pub fn new(repo: OrganizationRepository) -> Self {
+        OrganizationService { repo }
+    }
+
+    pub async fn list(&self) -> Result<Vec<Organization>, AppError> {
+        self.repo.list().await
+    }
+
+    pub async fn create(
+        &self,
+        request: CreateOrganizationRequest,
+        audit: PendingAudit<'_, Organization>,
+    ) -> Result<Organization, AppError> {
+        let organization = NewOrganization {
+            name: request.name.trim(),
+            slug: &request.slug,
+            self_registration: request.self_registration.unwrap_or(false),
+        };
+        self.repo.insert(organization, audit).await
+    }
+
+    pub async fn get(&self, id: i32) -> Result<Organization, AppError> {
+        self.repo
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Organization not found".to_string()))
+    }
+
+    pub async fn update(
+        &self,
+        id: i32,
+        request: UpdateOrganizationRequest,
+        audit: PendingAudit<'_, Organization>,
+    ) -> Result<Organization, AppError> {
+        let changes = OrganizationChangeset {
+            name: request.name.map(|name| name.trim().to_string()),
+            self_registration: request.self_registration,
+            updated_at: Utc::now().naive_utc(),
+        };
+        self.repo.update(id, changes, audit).await
+    }
+
+    pub async fn organization_of(&self, kind: PrincipalKind, id: i32) -> Result<Option<i32>, AppError> {
+        self.repo.organization_of(kind.as_str(), id).await
+    }
+
+    // องค์กรของผู้สมัครใหม่: ไม่ระบุ = องค์กรเริ่มต้น ระบุ slug ได้เฉพาะองค์กรที่เปิดให้สมัครเอง
+    pub async fn organization_for_registration(&self, slug: Option<&str>) -> Result<i32, AppError> {
+        let Some(slug) = slug else {
+            return Ok(DEFAULT_ORGANIZATION_ID);
+        };
+        match self.repo.find_by_slug(slug).await? {
+            Some(organization) if organization.self_registration => Ok(organization.id),
+            _ => Err(AppError::Validation(
+                "Unknown organization, or it does not accept self-registration.".to_string(),
+            )),
+        }
+    }
+
+    pub async fn ensure_user(&self, tenant: Tenant, user_id: i32) -> Result<(), AppError> {
+        if !self.repo.user_in_tenant(tenant, user_id).await? {
+            return Err(AppError::NotFound("User not found".to_string()));
+        }
+        Ok(())
+    }
+
+    pub async fn ensure_admin(&self, tenant: Tenant, admin_id: i32) -> Result<(), AppError> {
+        if !self.repo.admin_in_tenant(tenant, admin_id).await? {
+            return Err(AppError::NotFound("Admin not found".to_string()));
+        }
+        Ok(())
+    }
+
+    // ห้องต้องเป็นขององค์กรผู้เรียก (ห้องที่ถูกแชร์มาให้จองได้แต่แก้ไขไม่ได้) คืนองค์กรเจ้าของ
+    pub async fn ensure_room(&self, tenant: Tenant, room_id: i32) -> Result<i32, AppError> {
+        self.repo
+            .room_owner(tenant, room_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Room not found".to_string()))
+    }
+
+    pub async fn ensure_booking(&self, tenant: Tenant, booking_id: i32) -> Result<(), AppError> {
+        if !self.repo.booking_in_tenant(tenant, booking_id).await? {
+            return Err(AppError::NotFound("Booking not found".to_string()));
+        }
+        Ok(())
+    }
+
+    pub async fn room_shares(&self, tenant: Tenant, room_id: i32) -> Result<RoomSharesResponse, AppError> {
+        let owner_id = self.ensure_room(tenant, room_id).await?;
+        Ok(RoomSharesResponse {
+            room_id,
+            organization_id: owner_id,
+            shared_with: self.repo.room_shares(room_id).await?,
+        })
+    }
+
+    pub async fn set_room_shares(
+        &self,
+        tenant: Tenant,
+        room_id: i32,
+        request: RoomSharesRequest,
+        audit: PendingAudit<'_, RoomSharesResponse>,
+    ) -> Result<RoomSharesResponse, AppError> {
+        let owner_id = self.ensure_room(tenant, room_id).await?;
+        let mut organization_ids = request.organization_ids;
+        organization_ids.sort_unstable();
+        organization_ids.dedup();
+        self.repo.set_room_shares(room_id, owner_id, &organization_ids, audit).await
+    }
+
+    pub async fn move_user(
+        &self,
+        user_id: i32,
+        request: SetOrganizationRequest,
+        audit: PendingAudit<'_, User>,
+    ) -> Result<User, AppError> {
+        if self.repo.find_by_id(request.organization_id).await?.is_none() {
+            return Err(AppError::Validation("`organization_id` does not exist.".to_string()));
+        }
+        self.repo.move_user(user_id, request.organization_id, audit).await
+    }
+}
@@ -0,0 +1,160 @@
+use std::sync::Arc;
+
+use chrono::{Duration, Utc};
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+
+use crate::{
+    application::auth_service::AuthService,
+    domain::audit::PendingAudit,
+    domain::password::{ChangePasswordRequest, NewPasswordResetToken, PasswordResetConfirmRequest},
+    domain::user::User,
+    error::AppError,
+    infrastructure::{
+        notifier::{Notification, Notifier},
+        password_reset_repository::PasswordResetRepository,
+        user_repository::UserRepository,
+    },
+};
+
+// PasswordService: เปลี่ยนรหัสผ่านและรีเซ็ตรหัสผ่านของผู้ใช้
+// - Token รีเซ็ตเป็นค่าสุ่ม ส่งให้ผู้ใช้ผ่าน Notifier และเก็บใน DB เฉพาะ SHA-256 hash
+// - Token ใช้ได้ครั้งเดียวและหมดอายุตาม reset_ttl (ขอใหม่แล้ว Token เดิมใช้ไม่ได้)
+// - เมื่อรหัสผ่านเปลี่ยน ทุก session ของผู้ใช้จะถูกเพิกถอน
+#[derive(Clone)]
+pub struct PasswordService {
+    users: UserRepository,
+    resets: PasswordResetRepository,
+    auth: AuthService,
+    notifier: Arc<dyn Notifier>,
+    reset_ttl: Duration,
+    reset_url: String,
+}
+
+fn hash_reset_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+fn generate_reset_token() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+impl PasswordService {
+    pub fn new(
+        users: UserRepository,
+        resets: PasswordResetRepository,
+        auth: AuthService,
+        notifier: Arc<dyn Notifier>,
+        reset_ttl_minutes: i64,
+        reset_url: String,
+    ) -> Self {
+        PasswordService {
+            users,
+            resets,
+            auth,
+            notifier,
+            reset_ttl: Duration::minutes(reset_ttl_minutes),
+            reset_url,
+        }
+    }
+
+    // เปลี่ยนรหัสผ่านของตัวเอง (ต้องระบุรหัสผ่านปัจจุบันให้ถูกต้อง)
+    pub async fn change_password(
+        &self,
+        user_id: i32,
+        request: ChangePasswordRequest,
+        audit: PendingAudit<'_, User>,
+    ) -> Result<(), AppError> {
+        let user = self.users.get_user_by_id(user_id).await?;
+        let password_hash = user.password_hash.ok_or_else(|| {
+            AppError::Forbidden("This account signs in through single sign-on or the LDAP directory and has no password.".to_string())
+        })?;
+        if !bcrypt::verify(&request.current_password, &password_hash)? {
+            return Err(AppError::Unauthorized("Current password is incorrect.".to_string()));
+        }
+        self.set_password(user_id, &request.new_password, audit).await
+    }
+
+    // ขอรีเซ็ตรหัสผ่าน: ถ้าไม่พบผู้ใช้จะไม่แจ้ง error เพื่อไม่ให้ใช้ตรวจสอบว่ามี username นี้หรือไม่
+    // audit ถูกบันทึกเฉพาะเมื่อพบผู้ใช้ (ได้รับ id ของผู้ใช้)
+    pub async fn request_reset(&self, username: &str, audit: PendingAudit<'_, i32>) -> Result<(), AppError> {
+        // ผู้ใช้ที่ Login ผ่าน OIDC ไม่มีรหัสผ่านให้รีเซ็ต
+        let Some(user) = self
+            .users
+            .find_active_by_username(username)
+            .await?
+            .filter(|user| user.password_hash.is_some())
+        else {
+            return Ok(());
+        };
+
+        let token = generate_reset_token();
+        let now = Utc::now();
+        self.resets
+            .replace_token(
+                NewPasswordResetToken {
+                    user_id: user.id,
+                    token_hash: &hash_reset_token(&token),
+                    expires_at: (now + self.reset_ttl).naive_utc(),
+                },
+                now.naive_utc(),
+                audit,
+            )
+            .await?;
+
+        self.notifier
+            .send(Notification {
+                recipient: user.username,
+                subject: "Reset your password".to_string(),
+                body: format!(
+                    "Use this link to set a new password. It expires in {} minutes and can be used once:\n{}?token={}",
+                    self.reset_ttl.num_minutes(),
+                    self.reset_url,
+                    token
+                ),
+            })
+            .await?;
+        Ok(())
+    }
+
+    // ตั้งรหัสผ่านใหม่ด้วย Token รีเซ็ต
+    // คืน id ของผู้ใช้ที่ตั้งรหัสผ่านใหม่
+    pub async fn confirm_reset(
+        &self,
+        request: PasswordResetConfirmRequest,
+        audit: PendingAudit<'_, User>,
+    ) -> Result<i32, AppError> {
+        let user_id = self
+            .resets
+            .consume_token(&hash_reset_token(&request.token), Utc::now().naive_utc())
+            .await?
+            .ok_or_else(|| AppError::Unauthorized("Invalid or expired password reset token.".to_string()))?;
+        self.set_password(user_id, &request.new_password, audit).await?;
+        Ok(user_id)
+    }
+
+    // บันทึกรหัสผ่านใหม่ แล้วเพิกถอนทุก session และ Token รีเซ็ตที่ยังค้างอยู่
+    async fn set_password(&self, user_id: i32, new_password: &str, audit: PendingAudit<'_, User>) -> Result<(), AppError> {
+        let password_hash = bcrypt::hash(new_password, bcrypt::DEFAULT_COST)?;
+        if !self.users.update_password(user_id, &password_hash, audit).await? {
+            return Err(AppError::NotFound("User not found".to_string()));
+        }
+        self.resets.invalidate_for_user(user_id, Utc::now().naive_utc()).await?;
+        self.auth.revoke_all_sessions(user_id, "user").await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reset_tokens_are_random_and_stored_hashed() {
+        let token = generate_reset_token();
+        assert_ne!(token, generate_reset_token());
+        assert_eq!(hash_reset_token(&token), hash_reset_token(&token));
+        assert_ne!(hash_reset_token(&token), token);
+    }
+}
@@ -0,0 +1,73 @@
+use chrono::Utc;
+
+use crate::{
+    domain::{
+        audit::PendingAudit,
+        organization::Tenant,
+        pagination::{ListQuery, Page},
+        permission::PrincipalKind,
+        privacy::{EraseAccountRequest, EraseUserRequest, ErasureRequest, UserDataExport},
+        user::UserResponse,
+    },
+    error::AppError,
+    infrastructure::privacy_repository::PrivacyRepository,
+};
+
+// PrivacyService: สิทธิ์ของเจ้าของข้อมูล (ขอสำเนาข้อมูล และขอลบข้อมูลส่วนตัว)
+// การลบไม่ลบแถว users แต่ทำให้ไม่ระบุตัวตน การจองเดิมจึงยังนับในรายงานได้
+#[derive(Debug, Clone)]
+pub struct PrivacyService {
+    repo: PrivacyRepository,
+}
+
+impl PrivacyService {
+    pub fn new(repo: PrivacyRepository) -> Self {
+        PrivacyService { repo }
+    }
+
+    pub async fn export(&self, user_id: i32) -> Result<UserDataExport, AppError> {
+        let (user, roles, bookings, login_events) = self.repo.export_data(user_id).await?;
+        Ok(UserDataExport {
+            exported_at: Utc::now().naive_utc(),
+            profile: UserResponse::from(user),
+            roles,
+            bookings,
+            login_events,
+        })
+    }
+
+    // ผู้ใช้ขอลบข้อมูลของตัวเอง (confirm_username ต้องตรงกับ username ปัจจุบัน)
+    pub async fn erase_own_account(
+        &self,
+        user_id: i32,
+        username: &str,
+        request: EraseAccountRequest,
+        audit: PendingAudit<'_, ErasureRequest>,
+    ) -> Result<ErasureRequest, AppError> {
+        if request.confirm_username != username {
+            return Err(AppError::Validation("`confirm_username` does not match your username.".to_string()));
+        }
+        self.repo.erase_user(user_id, "user", user_id, request.reason.as_deref(), audit).await
+    }
+
+    // ผู้ดูแลที่มี users:write ลบข้อมูลของผู้ใช้ (รวมผู้ใช้ที่ถูก Soft Delete ไปแล้ว)
+    pub async fn erase_user(
+        &self,
+        user_id: i32,
+        requested_by_kind: PrincipalKind,
+        requested_by_id: i32,
+        request: EraseUserRequest,
+        audit: PendingAudit<'_, ErasureRequest>,
+    ) -> Result<ErasureRequest, AppError> {
+        self.repo
+            .erase_user(user_id, requested_by_kind.as_str(), requested_by_id, request.reason.as_deref(), audit)
+            .await
+    }
+
+    pub async fn list_erasure_requests(&self, tenant: Tenant, query: ListQuery) -> Result<Page<ErasureRequest>, AppError> {
+        query.sort_field(PrivacyRepository::SORT_FIELDS, "created_at")?;
+        let offset = query.offset()?;
+        let (items, total) = self.repo.list_erasure_requests(tenant, &query, offset).await?;
+        Ok(Page::new(items, total, query.limit(), offset))
+    }
+}
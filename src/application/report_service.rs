@@ -0,0 +1,248 @@
+// src/application/report_service.rs
+use std::collections::HashMap;
+
+use chrono::{Datelike, Duration, Months, NaiveDate, NaiveDateTime, NaiveTime, Utc, Weekday};
+
+use crate::{
+    domain::organization::Tenant,
+    domain::report::{
+        BookingRates, PeakHourCell, ReportPeriod, ReportQuery, RoomUtilization, TopBooker,
+    },
+    error::AppError,
+    infrastructure::report_repository::ReportRepository,
+};
+
+const DEFAULT_RANGE_DAYS: i64 = 30;
+const DEFAULT_TOP_BOOKERS: i64 = 10;
+
+#[derive(Debug, Clone)]
+pub struct ReportService {
+    repo: ReportRepository,
+    open_hours_per_day: f64, // จำนวนชั่วโมงที่ห้องเปิดให้จองได้ต่อวัน
+}
+
+// ช่วงวันที่ของรายงานหลังใส่ค่า default แล้ว (to_exclusive = วันถัดจาก `to`)
+struct ReportRange {
+    from: NaiveDate,
+    to_exclusive: NaiveDate,
+    period: ReportPeriod,
+}
+
+impl ReportRange {
+    fn from_query(query: &ReportQuery) -> Result<Self, AppError> {
+        let to = query.to.unwrap_or_else(|| Utc::now().date_naive());
+        let from = query
+            .from
+            .unwrap_or_else(|| to - Duration::days(DEFAULT_RANGE_DAYS - 1));
+        if from > to {
+            return Err(AppError::Validation("`from` must not be after `to`".to_string()));
+        }
+        Ok(ReportRange {
+            from,
+            to_exclusive: to + Duration::days(1),
+            period: query.period.unwrap_or_default(),
+        })
+    }
+
+    fn start(&self) -> NaiveDateTime {
+        self.from.and_time(NaiveTime::MIN)
+    }
+
+    fn end(&self) -> NaiveDateTime {
+        self.to_exclusive.and_time(NaiveTime::MIN)
+    }
+
+    fn bucket_start(&self, date: NaiveDate) -> NaiveDate {
+        match self.period {
+            ReportPeriod::Day => date,
+            ReportPeriod::Week => date.week(Weekday::Mon).first_day(),
+            ReportPeriod::Month => date.with_day(1).unwrap_or(date),
+        }
+    }
+
+    fn bucket_end(&self, start: NaiveDate) -> NaiveDate {
+        match self.period {
+            ReportPeriod::Day => start + Duration::days(1),
+            ReportPeriod::Week => start + Duration::days(7),
+            ReportPeriod::Month => start + Months::new(1),
+        }
+    }
+
+    // วันแรกของทุกช่วงเวลาที่ทับกับ [from, to_exclusive)
+    fn buckets(&self) -> Vec<NaiveDate> {
+        let mut buckets = Vec::new();
+        let mut start = self.bucket_start(self.from);
+        while start < self.to_exclusive {
+            buckets.push(start);
+            start = self.bucket_end(start);
+        }
+        buckets
+    }
+
+    // จำนวนวันของช่วงเวลาที่อยู่ภายในช่วงของรายงาน (ช่วงแรก/สุดท้ายอาจไม่เต็ม)
+    fn days_in_bucket(&self, start: NaiveDate) -> i64 {
+        let end = self.bucket_end(start).min(self.to_exclusive);
+        let start = start.max(self.from);
+        (end - start).num_days().max(0)
+    }
+}
+
+fn parse_period_start(value: &str) -> Result<NaiveDate, AppError> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|e| AppError::Internal(format!("Invalid period returned by report query: {}", e)))
+}
+
+fn ratio(part: f64, total: f64) -> f64 {
+    if total > 0.0 { part / total } else { 0.0 }
+}
+
+impl ReportService {
+    pub fn new(repo: ReportRepository, open_hours_per_day: f64) -> Self {
+        ReportService {
+            repo,
+            open_hours_per_day,
+        }
+    }
+
+    pub async fn room_utilization(&self, tenant: Tenant, query: ReportQuery) -> Result<Vec<RoomUtilization>, AppError> {
+        let range = ReportRange::from_query(&query)?;
+        let rows = self
+            .repo
+            .room_utilization(range.period, range.start(), range.end(), query.room_id, tenant.organization_id())
+            .await?;
+
+        // รวมผลลัพธ์จาก SQL ตามห้อง แล้วเติมช่วงเวลาที่ไม่มีการจองด้วยค่า 0
+        // เพื่อให้ห้องที่ไม่ถูกใช้งานปรากฏในรายงานด้วย
+        let mut rooms: Vec<(i32, String)> = Vec::new();
+        let mut booked: HashMap<(i32, NaiveDate), (i64, f64)> = HashMap::new();
+        for row in rows {
+            if !rooms.iter().any(|(id, _)| *id == row.room_id) {
+                rooms.push((row.room_id, row.room_name.clone()));
+            }
+            if let Some(period_start) = row.period_start.as_deref() {
+                let period_start = parse_period_start(period_start)?;
+                booked.insert((row.room_id, period_start), (row.bookings, row.booked_hours));
+            }
+        }
+
+        let buckets = range.buckets();
+        let mut report = Vec::with_capacity(rooms.len() * buckets.len());
+        for (room_id, room_name) in rooms {
+            for period_start in &buckets {
+                let (bookings, booked_hours) = booked
+                    .get(&(room_id, *period_start))
+                    .copied()
+                    .unwrap_or((0, 0.0));
+                let open_hours = range.days_in_bucket(*period_start) as f64 * self.open_hours_per_day;
+                report.push(RoomUtilization {
+                    room_id,
+                    room_name: room_name.clone(),
+                    period_start: *period_start,
+                    bookings,
+                    booked_hours,
+                    open_hours,
+                    utilization: ratio(booked_hours, open_hours),
+                });
+            }
+        }
+        Ok(report)
+    }
+
+    pub async fn peak_hours(&self, tenant: Tenant, query: ReportQuery) -> Result<Vec<PeakHourCell>, AppError> {
+        let range = ReportRange::from_query(&query)?;
+        self.repo
+            .peak_hours(range.start(), range.end(), query.room_id, tenant.organization_id())
+            .await
+    }
+
+    pub async fn booking_rates(&self, tenant: Tenant, query: ReportQuery) -> Result<Vec<BookingRates>, AppError> {
+        let range = ReportRange::from_query(&query)?;
+        let rows = self
+            .repo
+            .booking_rates(range.period, range.start(), range.end(), query.room_id, tenant.organization_id())
+            .await?;
+
+        rows.into_iter()
+            .map(|row| {
+                Ok(BookingRates {
+                    room_id: row.room_id,
+                    room_name: row.room_name,
+                    period_start: parse_period_start(&row.period_start)?,
+                    total: row.total,
+                    cancelled: row.cancelled,
+                    no_show: row.no_show,
+                    cancellation_rate: ratio(row.cancelled as f64, row.total as f64),
+                    no_show_rate: ratio(row.no_show as f64, row.total as f64),
+                })
+            })
+            .collect()
+    }
+
+    pub async fn top_bookers(&self, tenant: Tenant, query: ReportQuery) -> Result<Vec<TopBooker>, AppError> {
+        let range = ReportRange::from_query(&query)?;
+        let limit = query.limit.unwrap_or(DEFAULT_TOP_BOOKERS).clamp(1, 100);
+        self.repo
+            .top_bookers(range.start(), range.end(), query.room_id, tenant.organization_id(), limit)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn range(from: &str, to: &str, period: ReportPeriod) -> ReportRange {
+        let query = ReportQuery {
+            from: Some(from.parse().unwrap()),
+            to: Some(to.parse().unwrap()),
+            period: Some(period),
+            ..Default::default()
+        };
+        ReportRange::from_query(&query).unwrap()
+    }
+
+    fn date(value: &str) -> NaiveDate {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn rejects_from_after_to() {
+        let query = ReportQuery {
+            from: Some(date("2024-05-02")),
+            to: Some(date("2024-05-01")),
+            ..Default::default()
+        };
+        assert!(matches!(ReportRange::from_query(&query), Err(AppError::Validation(_))));
+    }
+
+    #[test]
+    fn to_is_inclusive() {
+        let range = range("2024-05-01", "2024-05-01", ReportPeriod::Day);
+        assert_eq!(range.buckets(), vec![date("2024-05-01")]);
+        assert_eq!(range.end(), date("2024-05-02").and_time(NaiveTime::MIN));
+    }
+
+    #[test]
+    fn weekly_buckets_start_on_monday_and_clip_partial_weeks() {
+        // 2024-05-01 เป็นวันพุธ
+        let range = range("2024-05-01", "2024-05-08", ReportPeriod::Week);
+        assert_eq!(range.buckets(), vec![date("2024-04-29"), date("2024-05-06")]);
+        assert_eq!(range.days_in_bucket(date("2024-04-29")), 5);
+        assert_eq!(range.days_in_bucket(date("2024-05-06")), 3);
+    }
+
+    #[test]
+    fn monthly_buckets_follow_calendar_months() {
+        let range = range("2024-01-15", "2024-03-10", ReportPeriod::Month);
+        assert_eq!(range.buckets(), vec![date("2024-01-01"), date("2024-02-01"), date("2024-03-01")]);
+        assert_eq!(range.days_in_bucket(date("2024-01-01")), 17);
+        assert_eq!(range.days_in_bucket(date("2024-02-01")), 29);
+        assert_eq!(range.days_in_bucket(date("2024-03-01")), 10);
+    }
+
+    #[test]
+    fn ratio_of_empty_total_is_zero() {
+        assert_eq!(ratio(3.0, 0.0), 0.0);
+        assert_eq!(ratio(1.0, 4.0), 0.25);
+    }
+}
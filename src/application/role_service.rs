@@ -0,0 +1,94 @@
+use std::collections::BTreeSet;
+
+use crate::{
+    domain::audit::PendingAudit,
+    domain::permission::{NewRoleAssignment, Permission, PrincipalKind, RoleResponse, UserRolesResponse},
+    error::AppError,
+    infrastructure::role_repository::RoleRepository,
+};
+
+// RoleService: แปลง Role ของบัญชีเป็นชุด Permission และจัดการการกำหนด Role (โดย Admin)
+#[derive(Debug, Clone)]
+pub struct RoleService {
+    repo: RoleRepository,
+}
+
+impl RoleService {
+    pub fn new(repo: RoleRepository) -> Self {
+        RoleService { repo }
+    }
+
+    // Permission ที่มีผลของบัญชี (อ่านจาก DB ทุกครั้ง การเปลี่ยน Role จึงมีผลทันที)
+    pub async fn permissions_for(&self, kind: PrincipalKind, principal_id: i32) -> Result<BTreeSet<Permission>, AppError> {
+        let permissions = self.repo.permissions_for(kind.as_str(), principal_id).await?;
+        Ok(permissions.iter().filter_map(|p| Permission::parse(p)).collect())
+    }
+
+    pub async fn list_roles(&self) -> Result<Vec<RoleResponse>, AppError> {
+        self.repo.list_roles().await
+    }
+
+    // กำหนดว่าบัญชีที่มี Role นี้ต้องใช้ 2FA หรือไม่ (มีผลกับการ Login ครั้งถัดไป)
+    pub async fn set_mfa_policy(
+        &self,
+        role: &str,
+        required: bool,
+        audit: PendingAudit<'_, RoleResponse>,
+    ) -> Result<RoleResponse, AppError> {
+        self.repo
+            .set_require_mfa(role, required, audit)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Role '{}' not found", role)))
+    }
+
+    pub async fn user_roles(&self, user_id: i32) -> Result<UserRolesResponse, AppError> {
+        self.repo.user_roles(user_id).await
+    }
+
+    // กำหนด Role เพิ่มให้ผู้ใช้ (Role พื้นฐาน 'user' ได้รับอยู่แล้ว จึงไม่ต้องกำหนด)
+    // ผู้กำหนดต้องถือทุก Permission ของ Role นั้นเอง และ Role พื้นฐานของ Admin ให้ผู้ใช้ไม่ได้
+    pub async fn assign_user_role(
+        &self,
+        granter: &BTreeSet<Permission>,
+        user_id: i32,
+        role: &str,
+        audit: PendingAudit<'_, UserRolesResponse>,
+    ) -> Result<UserRolesResponse, AppError> {
+        let kind = PrincipalKind::User;
+        if role == kind.as_str() {
+            return Err(AppError::Conflict(format!("Role '{}' is granted to every user", role)));
+        }
+        let Some(permissions) = self.repo.role_permissions(role).await? else {
+            return Err(AppError::NotFound(format!("Role '{}' not found", role)));
+        };
+        if role == PrincipalKind::Admin.as_str() {
+            return Err(AppError::Forbidden(format!("Role '{}' is only for admin accounts", role)));
+        }
+        let exceeds = permissions
+            .iter()
+            .filter_map(|p| Permission::parse(p))
+            .any(|p| !granter.contains(&p));
+        if exceeds {
+            return Err(AppError::Forbidden(format!("Role '{}' has permissions you do not hold", role)));
+        }
+
+        let assignment = NewRoleAssignment {
+            principal_kind: kind.as_str(),
+            principal_id: user_id,
+            role_name: role,
+        };
+        self.repo.assign_user_role(assignment, audit).await
+    }
+
+    pub async fn remove_user_role(
+        &self,
+        user_id: i32,
+        role: &str,
+        audit: PendingAudit<'_, UserRolesResponse>,
+    ) -> Result<(), AppError> {
+        if !self.repo.remove_user_role(user_id, role, audit).await? {
+            return Err(AppError::NotFound(format!("Role '{}' is not assigned to this user", role)));
+        }
+        Ok(())
+    }
+}
@@ -1,8 +1,11 @@
-use anyhow::Result;
 use chrono::Utc;
 
 use crate::{
+    domain::audit::PendingAudit,
+    domain::organization::{Tenant, DEFAULT_ORGANIZATION_ID},
+    domain::pagination::{ListQuery, Page},
     domain::room::{AddRoomRequest, NewRoom, Room, RoomChangeset, UpdateRoomRequest},
+    error::AppError,
     infrastructure::room_repository::RoomRepository,
 };
 
@@ -16,40 +19,102 @@ impl RoomService {
         RoomService { repo }
     }
 
-    pub async fn add_room(&self, request: AddRoomRequest) -> Result<Room, String> {
+    // ห้องใหม่อยู่ในองค์กรของผู้สร้าง Platform Admin เลือกองค์กรได้ด้วย organization_id
+    pub async fn add_room(
+        &self,
+        tenant: Tenant,
+        request: AddRoomRequest,
+        audit: PendingAudit<'_, Room>,
+    ) -> Result<Room, AppError> {
+        let organization_id = match (tenant, request.organization_id) {
+            (Tenant::All, requested) => requested.unwrap_or(DEFAULT_ORGANIZATION_ID),
+            (Tenant::Organization(own), Some(requested)) if requested != own => {
+                return Err(AppError::Validation(
+                    "`organization_id` can only be set by platform admins.".to_string(),
+                ));
+            }
+            (Tenant::Organization(own), _) => own,
+        };
         let new_room = NewRoom {
             name: &request.name,
             status: &request.status,
+            organization_id,
         };
 
-        self.repo.add_room(new_room).await
+        self.repo.add_room(new_room, audit).await
+    }
+
+    // restricted_to: ผู้ใช้ที่เห็นเฉพาะห้องที่ตัวเองจองได้ (ดู Principal::rooms_restricted_to)
+    pub async fn get_all_room(
+        &self,
+        tenant: Tenant,
+        restricted_to: Option<i32>,
+        query: ListQuery,
+    ) -> Result<Page<Room>, AppError> {
+        self.list_rooms(tenant, restricted_to, query, false).await
+    }
+    pub async fn get_room_by_id(&self,tenant:Tenant,restricted_to:Option<i32>,room_id:i32)->Result<Room,AppError>{
+        self.repo.get_room_by_id(tenant,restricted_to,room_id).await
+    }
+
+    // ห้องตาม ID รวมที่ถูก Soft Delete (สำหรับผู้ดูแลห้องที่ตรวจองค์กรแล้ว)
+    pub async fn find_room(&self, room_id: i32) -> Result<Room, AppError> {
+        self.repo
+            .find_room(room_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Room not found".to_string()))
     }
 
-    pub async fn get_all_room(&self)->Result<Vec<Room>,String>{
-        self.repo.get_all_room().await
-    }  
-    pub async fn get_room_by_id(&self,room_id:i32)->Result<Room,String>{
-        self.repo.get_room_by_id(room_id).await
+    // ห้องที่มีกฎการเข้าถึงจองได้เฉพาะผู้ใช้หรือสมาชิกของกลุ่มที่ระบุ
+    pub async fn ensure_bookable(&self, room_id: i32, user_id: i32) -> Result<(), AppError> {
+        if !self.repo.is_bookable_by(room_id, user_id).await? {
+            return Err(AppError::Forbidden(
+                "This room can only be booked by specific groups or users.".to_string(),
+            ));
+        }
+        Ok(())
     }
 
 
-    pub async fn get_all_active_rooms(&self) -> Result<Vec<Room>, String> {
-        self.repo.get_all_active_rooms().await
+    pub async fn get_all_active_rooms(
+        &self,
+        tenant: Tenant,
+        restricted_to: Option<i32>,
+        query: ListQuery,
+    ) -> Result<Page<Room>, AppError> {
+        self.list_rooms(tenant, restricted_to, query, true).await
+    }
+
+    async fn list_rooms(
+        &self,
+        tenant: Tenant,
+        restricted_to: Option<i32>,
+        query: ListQuery,
+        active_only: bool,
+    ) -> Result<Page<Room>, AppError> {
+        query.sort_field(RoomRepository::SORT_FIELDS, "id")?;
+        let offset = query.offset()?;
+        let (rooms, total) = self.repo.list_rooms(tenant, restricted_to, &query, offset, active_only).await?;
+        Ok(Page::new(rooms, total, query.limit(), offset))
     }
 
-    pub async fn update_room(&self,room_id:i32,request:UpdateRoomRequest)->Result<Room,String>{
+    pub async fn update_room(&self,room_id:i32,request:UpdateRoomRequest,audit:PendingAudit<'_, Room>)->Result<Room,AppError>{
         let changes = RoomChangeset{
             name:request.name,
             status:request.status,
             updated_at:Some(Utc::now().naive_utc()),
             deleted_at:None
         };
-        self.repo.update_room(room_id,changes).await
+        self.repo.update_room(room_id,changes,audit).await
     }
 
-    pub async fn delete_room(&self,room_id:i32)->Result<Room,String>{
+    pub async fn delete_room(&self,room_id:i32,audit:PendingAudit<'_, Room>)->Result<Room,AppError>{
         
-        self.repo.delete_room(room_id).await
+        self.repo.delete_room(room_id,audit).await
+    }
+
+    pub async fn restore_room(&self, room_id: i32, audit: PendingAudit<'_, Room>) -> Result<Room, AppError> {
+        self.repo.restore_room(room_id, audit).await
     }
     
 }
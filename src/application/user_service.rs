@@ -1,51 +1,120 @@
-use anyhow::Result;
+use std::sync::Arc;
 
-use crate::{domain::user::{LoginCredentials, NewUser, RegisterUserRequest, User}, infrastructure::user_repository::UserRepository};
+use chrono::Utc;
+
+use crate::{domain::audit::{AuditContext, PendingAudit}, domain::organization::Tenant, domain::pagination::{ListQuery, Page}, domain::user::{normalize_email, DeleteUserQuery, FutureBookingsPolicy, LoginCredentials, NewUser, ProfileChangeset, RegisterUserRequest, UpdateProfileRequest, User}, error::AppError, infrastructure::{auth_provider::AuthProvider, user_repository::UserRepository}};
 use bcrypt;
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct UserService {
     repo: UserRepository,
+    auth_providers: Vec<Arc<dyn AuthProvider>>, // ลองตามลำดับใน AUTH_PROVIDERS
 }
 
 impl UserService {
-    pub fn new(repo: UserRepository) -> Self {
-        UserService { repo }
+    pub fn new(repo: UserRepository, auth_providers: Vec<Arc<dyn AuthProvider>>) -> Self {
+        UserService { repo, auth_providers }
     }
 
-    pub async fn register_user(&self, request: RegisterUserRequest) -> Result<User, String> {
-        let hashed_password = bcrypt::hash(&request.password, bcrypt::DEFAULT_COST)
-            .map_err(|e| format!("Failed to hash password: {}", e))?;
+    // require_email: โหมดยืนยันอีเมล (EMAIL_VERIFICATION_REQUIRED) ต้องมีอีเมลและบัญชีเริ่มเป็น "รอยืนยัน"
+    // organization_id: องค์กรที่เลือกจาก `organization` (ตรวจแล้วว่าเปิดให้สมัครเอง)
+    pub async fn register_user(
+        &self,
+        request: RegisterUserRequest,
+        require_email: bool,
+        organization_id: i32,
+        audit: PendingAudit<'_, User>,
+    ) -> Result<User, AppError> {
+        let email = request.email.as_deref().and_then(normalize_email);
+        if require_email && email.is_none() {
+            return Err(AppError::Validation("An email address is required to register.".to_string()));
+        }
+        let hashed_password = bcrypt::hash(&request.password, bcrypt::DEFAULT_COST)?;
         let new_user = NewUser {
             username: &request.username,
-            password_hash: &hashed_password,
+            password_hash: Some(&hashed_password),
+            email: email.as_deref(),
+            email_verification_pending: require_email,
+            organization_id,
         };
 
-        self.repo.register_user(new_user).await
+        self.repo.register_user(new_user, audit).await
     }
 
-    pub async fn login_user(&self,credentials:LoginCredentials)->Result<i32,String>{
-        let user = self.repo.find_user_by_username_and_verify_password(credentials).await?;
-
-        Ok(user.id)
+    // ลอง AuthProvider ทีละตัวจนกว่าจะสำเร็จ
+    // Unauthorized หรือ Directory ติดต่อไม่ได้ (BadGateway) จะลองตัวถัดไป ข้อผิดพลาดอื่นหยุดทันที
+    // ถ้าไม่มีตัวไหนสำเร็จและมีตัวที่ติดต่อไม่ได้ คืน BadGateway แทน Unauthorized
+    pub async fn login_user(&self,credentials:LoginCredentials,audit:&AuditContext)->Result<i32,AppError>{
+        let mut unavailable = None;
+        for provider in &self.auth_providers {
+            match provider.authenticate(&credentials, audit).await {
+                Ok(user_id) => return Ok(user_id),
+                Err(AppError::Unauthorized(_)) => {}
+                Err(e @ AppError::BadGateway(_)) => unavailable = Some(e),
+                Err(e) => return Err(e),
+            }
+        }
+        Err(unavailable.unwrap_or_else(|| AppError::Unauthorized("Invalid username or password".to_string())))
     }
 
     // *** ฟังก์ชันใหม่สำหรับ Admin Management (Regular Users) ***
 
     // ฟังก์ชันดึง User ทั้งหมด (เรียกจาก UserRepository)
-    pub async fn get_all_users(&self) -> Result<Vec<User>, String> { // Result<Vec<User>, String>
-        self.repo.get_all_users().await // เรียก UserRepository ซึ่งเป็น async
+    pub async fn get_all_users(&self, tenant: Tenant, query: ListQuery) -> Result<Page<User>, AppError> {
+        query.sort_field(UserRepository::SORT_FIELDS, "created_at")?;
+        let offset = query.offset()?;
+        let (users, total) = self.repo.get_all_users(tenant, &query, offset).await?; // เรียก UserRepository ซึ่งเป็น async
+        Ok(Page::new(users, total, query.limit(), offset))
     }
 
-    pub async fn get_user_by_id(&self,user_id:i32)->Result<User,String>{
+    pub async fn get_user_by_id(&self,user_id:i32)->Result<User,AppError>{
         self.repo.get_user_by_id(user_id).await
     }
 
+    // ผู้ใช้ตาม ID รวมที่ถูก Soft Delete แล้ว (สำหรับ Admin ที่ตรวจองค์กรแล้ว)
+    pub async fn find_user(&self, user_id: i32) -> Result<User, AppError> {
+        self.repo
+            .find_user(user_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("User not found".to_string()))
+    }
+
+    // แก้ไขโปรไฟล์ของตัวเอง (PATCH /users/me)
+    pub async fn update_profile(
+        &self,
+        user_id: i32,
+        request: UpdateProfileRequest,
+        audit: PendingAudit<'_, User>,
+    ) -> Result<User, AppError> {
+        let current = self.repo.get_user_by_id(user_id).await?;
+        let changes = ProfileChangeset::new(request, current.email.as_deref(), Utc::now().naive_utc());
+        self.repo.update_profile(user_id, changes, audit).await
+    }
+
     // ฟังก์ชัน Soft Delete User (เรียกจาก UserRepository)
-    pub async fn delete_user(&self, user_id: i32) -> Result<(), String> { // Result<(), String>
-        let success = self.repo.soft_delete_user(user_id).await?; // เรียก UserRepository ซึ่งเป็น async
+    // การจองที่ยังไม่จบถูกยกเลิก โอน หรือเก็บไว้ตาม future_bookings (ค่าเริ่มต้น: ยกเลิก)
+    pub async fn delete_user(
+        &self,
+        user_id: i32,
+        query: DeleteUserQuery,
+        audit: PendingAudit<'_, User>,
+    ) -> Result<(), AppError> {
+        let policy = query.future_bookings.unwrap_or_default();
+        if query.transfer_to.is_some() && policy != FutureBookingsPolicy::Transfer {
+            return Err(AppError::Validation("`transfer_to` is only used with future_bookings=transfer.".to_string()));
+        }
+        if query.transfer_to == Some(user_id) {
+            return Err(AppError::Validation(
+                "`transfer_to` must be an active user other than the one being deleted.".to_string(),
+            ));
+        }
+        let success = self.repo.soft_delete_user(user_id, policy, query.transfer_to, audit).await?; // เรียก UserRepository ซึ่งเป็น async
         if !success {
-            return Err("User not found or already deleted".to_string()); // สามารถปรับข้อความ Error ให้ตรงกับ UserServiceError ได้
+            return Err(AppError::NotFound("User not found or already deleted".to_string()));
         }
         Ok(())
     }
+
+    pub async fn restore_user(&self, user_id: i32, audit: PendingAudit<'_, User>) -> Result<User, AppError> {
+        self.repo.restore_user(user_id, audit).await
+    }
 }
@@ -1,12 +1,19 @@
+use std::fmt;
+
 use chrono::NaiveDateTime;
-use diesel::{Insertable, Queryable};
+use diesel::{Insertable, Queryable, Selectable};
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
 
-use crate::infrastructure::schema::admins;
+use crate::domain::validation::{not_blank, username_chars, PASSWORD_MAX_LENGTH, PASSWORD_MIN_LENGTH, USERNAME_MAX_LENGTH, USERNAME_MIN_LENGTH};
+use crate::domain::user::REDACTED_HASH;
+use crate::infrastructure::schema::{admin_invitations, admins};
 
 // Admin: Entity ที่แทนข้อมูลผู้ใช้ในฐานข้อมูล (เมื่อดึงออกมาหรือบันทึกเสร็จแล้ว)
-// มี Field ครบทุกคอลัมน์ในตาราง users
-#[derive(Debug, Clone, Queryable, Serialize, Deserialize)]
+// มี Field ครบทุกคอลัมน์ในตาราง users (ไม่ derive Serialize เพื่อไม่ให้ password_hash หลุดออกไป ใช้ AdminResponse แทน)
+// Debug เขียนเองเพื่อไม่ให้ password_hash หลุดไปใน Log (เหมือน User)
+#[derive(Clone, Queryable)]
 #[diesel(table_name = admins)]
 pub struct Admin {
     pub id: i32,
@@ -14,15 +21,46 @@ pub struct Admin {
     pub password_hash: String, // <<-- รหัสผ่านที่ถูก Hash แล้ว
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime, // <<-- ถ้ามี updated_at ใน DB
-    pub deleted_at: Option<NaiveDateTime>
+    pub deleted_at: Option<NaiveDateTime>,
+    pub organization_id: Option<i32>, // None = Platform Admin (ดูแลทุกองค์กร)
+}
+
+impl fmt::Debug for Admin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Admin")
+            .field("id", &self.id)
+            .field("username", &self.username)
+            .field("password_hash", &REDACTED_HASH)
+            .field("created_at", &self.created_at)
+            .field("updated_at", &self.updated_at)
+            .field("deleted_at", &self.deleted_at)
+            .field("organization_id", &self.organization_id)
+            .finish()
+    }
 }
 
-// RegisterAdminRequest: Struct ที่ใช้รับข้อมูลจาก Client สำหรับการลงทะเบียน
-// มีรหัสผ่านดิบ (Plain Text) และไม่มี id เพราะ DB จะสร้างให้
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct RegisterAdminRequest {
+// AdminResponse: ข้อมูล Admin ที่ส่งกลับให้ Client (ไม่มี password_hash)
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct AdminResponse {
+    pub id: i32,
     pub username: String,
-    pub password: String, // <<-- รหัสผ่านดิบ (Plain Text) จาก Client
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub deleted_at: Option<NaiveDateTime>,
+    pub organization_id: Option<i32>,
+}
+
+impl From<Admin> for AdminResponse {
+    fn from(admin: Admin) -> Self {
+        AdminResponse {
+            id: admin.id,
+            username: admin.username,
+            created_at: admin.created_at,
+            updated_at: admin.updated_at,
+            deleted_at: admin.deleted_at,
+            organization_id: admin.organization_id,
+        }
+    }
 }
 
 // NewAdmin: Struct ที่ใช้สำหรับการ INSERT ข้อมูลใหม่ลงในฐานข้อมูล
@@ -32,6 +70,7 @@ pub struct RegisterAdminRequest {
 pub struct NewAdmin<'a> {
     pub username: &'a str,
     pub password_hash: &'a str, // <<-- รหัสผ่านที่ถูก Hash แล้ว
+    pub organization_id: Option<i32>,
 }
 
 // LoginCredentials: Struct ที่ใช้รับข้อมูลจาก Client สำหรับการ Login
@@ -40,3 +79,67 @@ pub struct LoginCredentials {
     pub username: String,
     pub password: String, // <<-- รหัสผ่านดิบ (Plain Text) จาก Client
 }
+
+// SetupAdminRequest: สร้าง Admin คนแรก (POST /admin/setup) ด้วย Setup Token ที่แสดงตอนเริ่มระบบ
+#[derive(Debug, Clone, Deserialize, ToSchema, Validate)]
+pub struct SetupAdminRequest {
+    #[validate(custom(function = "not_blank"))]
+    pub setup_token: String,
+    #[validate(length(min = USERNAME_MIN_LENGTH, max = USERNAME_MAX_LENGTH), custom(function = "username_chars"))]
+    pub username: String,
+    #[validate(length(min = PASSWORD_MIN_LENGTH, max = PASSWORD_MAX_LENGTH))]
+    pub password: String,
+}
+
+// CreateInvitationRequest: อายุของคำเชิญ (ชั่วโมง) ถ้าไม่ระบุใช้ค่าจาก ADMIN_INVITE_TTL_HOURS
+#[derive(Debug, Clone, Default, Deserialize, ToSchema, Validate)]
+pub struct CreateInvitationRequest {
+    #[validate(range(min = 1, max = 720))]
+    pub expires_in_hours: Option<i64>,
+    // องค์กรของ Admin ที่จะถูกสร้าง กำหนดได้เฉพาะ Platform Admin (ไม่ระบุ = Platform Admin)
+    // Admin ขององค์กรเชิญได้เฉพาะเข้าองค์กรตัวเอง
+    pub organization_id: Option<i32>,
+}
+
+// AcceptInvitationRequest: สร้างบัญชี Admin จากลิงก์คำเชิญ
+#[derive(Debug, Clone, Deserialize, ToSchema, Validate)]
+pub struct AcceptInvitationRequest {
+    #[validate(custom(function = "not_blank"))]
+    pub token: String,
+    #[validate(length(min = USERNAME_MIN_LENGTH, max = USERNAME_MAX_LENGTH), custom(function = "username_chars"))]
+    pub username: String,
+    #[validate(length(min = PASSWORD_MIN_LENGTH, max = PASSWORD_MAX_LENGTH))]
+    pub password: String,
+}
+
+// AdminInvitation: แถวในตาราง admin_invitations (ไม่เก็บตัว Token เก็บเฉพาะ jti)
+#[derive(Debug, Clone, Queryable, Selectable, Serialize, ToSchema)]
+#[diesel(table_name = admin_invitations)]
+pub struct AdminInvitation {
+    pub id: i32,
+    pub jti: String,
+    pub invited_by: i32,
+    pub expires_at: NaiveDateTime,
+    pub created_at: NaiveDateTime,
+    pub accepted_at: Option<NaiveDateTime>,
+    pub accepted_admin_id: Option<i32>,
+    pub revoked_at: Option<NaiveDateTime>,
+    pub organization_id: Option<i32>,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = admin_invitations)]
+pub struct NewAdminInvitation<'a> {
+    pub jti: &'a str,
+    pub invited_by: i32,
+    pub expires_at: NaiveDateTime,
+    pub organization_id: Option<i32>,
+}
+
+// InvitationResponse: ลิงก์คำเชิญที่ส่งให้ผู้ถูกเชิญ (Token แสดงครั้งเดียวตอนสร้าง)
+#[derive(Debug, Serialize, ToSchema)]
+pub struct InvitationResponse {
+    pub invitation: AdminInvitation,
+    pub token: String,
+    pub invite_url: String,
+}
@@ -0,0 +1,130 @@
+use chrono::NaiveDateTime;
+use diesel::{Insertable, Queryable, Selectable};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::domain::permission::Permission;
+use crate::domain::validation::not_blank;
+use crate::infrastructure::schema::api_keys;
+
+// ทุก Key ขึ้นต้นด้วย API_KEY_PREFIX เพื่อแยกจาก JWT และให้เครื่องมือสแกน Secret ตรวจพบได้
+pub const API_KEY_PREFIX: &str = "rbk_";
+pub const API_KEY_MAX_TTL_DAYS: i64 = 365;
+
+// ApiKey: แถวในตาราง api_keys (ไม่มีตัว Key เก็บเฉพาะ hash)
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = api_keys)]
+pub struct ApiKey {
+    pub id: i32,
+    pub principal_kind: String,
+    pub principal_id: i32,
+    pub name: String,
+    pub prefix: String,
+    pub key_hash: String,
+    pub scopes: String, // Permission คั่นด้วยช่องว่าง
+    pub expires_at: NaiveDateTime,
+    pub last_used_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub revoked_at: Option<NaiveDateTime>,
+}
+
+impl ApiKey {
+    pub fn scope_list(&self) -> Vec<Permission> {
+        self.scopes.split_whitespace().filter_map(Permission::parse).collect()
+    }
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = api_keys)]
+pub struct NewApiKey<'a> {
+    pub principal_kind: &'a str,
+    pub principal_id: i32,
+    pub name: &'a str,
+    pub prefix: &'a str,
+    pub key_hash: &'a str,
+    pub scopes: &'a str,
+    pub expires_at: NaiveDateTime,
+}
+
+// CreateApiKeyRequest: สร้าง API Key (scopes ต้องเป็น Permission ที่บัญชีมีอยู่)
+// expires_in_days ถ้าไม่ระบุใช้ค่าจาก API_KEY_DEFAULT_TTL_DAYS
+#[derive(Debug, Clone, Deserialize, ToSchema, Validate)]
+pub struct CreateApiKeyRequest {
+    #[validate(custom(function = "not_blank"), length(max = 100))]
+    pub name: String,
+    #[validate(length(min = 1, message = "must contain at least one permission"))]
+    pub scopes: Vec<Permission>,
+    #[validate(range(min = 1, max = API_KEY_MAX_TTL_DAYS))]
+    pub expires_in_days: Option<i64>,
+}
+
+// ApiKeyResponse: ข้อมูล API Key ที่แสดงได้ (prefix ใช้ระบุว่าเป็น Key ไหน)
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ApiKeyResponse {
+    pub id: i32,
+    pub name: String,
+    pub prefix: String,
+    pub scopes: Vec<Permission>,
+    pub expires_at: NaiveDateTime,
+    pub last_used_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub revoked_at: Option<NaiveDateTime>,
+}
+
+impl From<ApiKey> for ApiKeyResponse {
+    fn from(key: ApiKey) -> Self {
+        ApiKeyResponse {
+            scopes: key.scope_list(),
+            id: key.id,
+            name: key.name,
+            prefix: key.prefix,
+            expires_at: key.expires_at,
+            last_used_at: key.last_used_at,
+            created_at: key.created_at,
+            revoked_at: key.revoked_at,
+        }
+    }
+}
+
+// CreatedApiKeyResponse: ตัว Key แสดงครั้งเดียวตอนสร้าง
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct CreatedApiKeyResponse {
+    pub api_key: ApiKeyResponse,
+    pub key: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(scopes: &str) -> ApiKey {
+        let now = chrono::Utc::now().naive_utc();
+        ApiKey {
+            id: 1,
+            principal_kind: "user".to_string(),
+            principal_id: 1,
+            name: "Script".to_string(),
+            prefix: "rbk_12345678".to_string(),
+            key_hash: "hash".to_string(),
+            scopes: scopes.to_string(),
+            expires_at: now,
+            last_used_at: None,
+            created_at: now,
+            revoked_at: None,
+        }
+    }
+
+    #[test]
+    fn unknown_scopes_are_ignored() {
+        let scopes = key("bookings:write  rooms:fly reports:read").scope_list();
+        assert_eq!(scopes, vec![Permission::BookingsWrite, Permission::ReportsRead]);
+    }
+
+    #[test]
+    fn response_never_contains_the_hash() {
+        let response = serde_json::to_value(ApiKeyResponse::from(key("bookings:write"))).unwrap();
+        assert!(response.get("key_hash").is_none());
+        assert_eq!(response["scopes"], serde_json::json!(["bookings:write"]));
+    }
+}
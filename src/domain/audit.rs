@@ -0,0 +1,444 @@
+use chrono::{DateTime, NaiveDateTime, Utc};
+use diesel::{Insertable, Queryable, Selectable};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::domain::pagination::{ListQuery, SortDirection};
+use crate::domain::permission::PrincipalKind;
+use crate::domain::user::User;
+use crate::infrastructure::schema::audit_log;
+
+// prev_hash ของแถวแรกใน Audit Log
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+// AuditEntry: การเปลี่ยนแปลงข้อมูลหนึ่งครั้ง (แถวใน audit_log)
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = audit_log)]
+pub struct AuditEntry {
+    pub id: i32,
+    pub created_at: NaiveDateTime,
+    pub organization_id: Option<i32>,
+    pub actor_kind: String,
+    pub actor_id: Option<i32>,
+    pub impersonator_id: Option<i32>,
+    pub action: String,
+    pub entity_type: String,
+    pub entity_id: Option<String>,
+    pub before_json: Option<String>,
+    pub after_json: Option<String>,
+    pub request_id: Option<String>,
+    pub ip: Option<String>,
+    pub prev_hash: String,
+    pub hash: String,
+}
+
+impl AuditEntry {
+    // ค่าของแถวในรูปที่ใช้คำนวณ hash (ใช้ตอนตรวจสอบ Chain)
+    pub fn as_new(&self) -> NewAuditEntry<'_> {
+        NewAuditEntry {
+            created_at: self.created_at,
+            organization_id: self.organization_id,
+            actor_kind: &self.actor_kind,
+            actor_id: self.actor_id,
+            impersonator_id: self.impersonator_id,
+            action: &self.action,
+            entity_type: &self.entity_type,
+            entity_id: self.entity_id.as_deref(),
+            before_json: self.before_json.as_deref(),
+            after_json: self.after_json.as_deref(),
+            request_id: self.request_id.as_deref(),
+            ip: self.ip.as_deref(),
+            prev_hash: self.prev_hash.clone(),
+            hash: self.hash.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = audit_log)]
+pub struct NewAuditEntry<'a> {
+    pub created_at: NaiveDateTime,
+    pub organization_id: Option<i32>,
+    pub actor_kind: &'a str,
+    pub actor_id: Option<i32>,
+    pub impersonator_id: Option<i32>,
+    pub action: &'a str,
+    pub entity_type: &'a str,
+    pub entity_id: Option<&'a str>,
+    pub before_json: Option<&'a str>,
+    pub after_json: Option<&'a str>,
+    pub request_id: Option<&'a str>,
+    pub ip: Option<&'a str>,
+    pub prev_hash: String,
+    pub hash: String,
+}
+
+impl NewAuditEntry<'_> {
+    // SHA-256 (hex) ของ prev_hash และทุกคอลัมน์ยกเว้น id และ hash
+    // เรียงเป็น JSON array เพื่อให้ค่าที่มีตัวคั่นอยู่ข้างในแยกกันได้ชัดเจน
+    pub fn compute_hash(&self) -> String {
+        let content = serde_json::json!([
+            self.prev_hash,
+            self.created_at.format("%Y-%m-%dT%H:%M:%S%.6f").to_string(),
+            self.organization_id,
+            self.actor_kind,
+            self.actor_id,
+            self.impersonator_id,
+            self.action,
+            self.entity_type,
+            self.entity_id,
+            self.before_json,
+            self.after_json,
+            self.request_id,
+            self.ip,
+        ]);
+        hex::encode(Sha256::digest(content.to_string().as_bytes()))
+    }
+}
+
+// AuditEntryResponse: แถวของ Audit Log ที่ส่งให้ Client (before/after เป็น JSON object)
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct AuditEntryResponse {
+    pub id: i32,
+    pub created_at: NaiveDateTime,
+    pub organization_id: Option<i32>,
+    pub actor_kind: String, // user, admin หรือ anonymous (เช่น สมัครสมาชิก)
+    pub actor_id: Option<i32>,
+    pub impersonator_id: Option<i32>, // Admin ที่สวมสิทธิ์ผู้ใช้อยู่ตอนทำรายการ
+    pub action: String,               // เช่น booking.cancel, user.delete
+    pub entity_type: String,
+    pub entity_id: Option<String>,
+    #[schema(value_type = Option<Object>)]
+    pub before: Option<serde_json::Value>,
+    #[schema(value_type = Option<Object>)]
+    pub after: Option<serde_json::Value>,
+    pub request_id: Option<String>,
+    pub ip: Option<String>,
+    pub prev_hash: String,
+    pub hash: String,
+}
+
+impl From<AuditEntry> for AuditEntryResponse {
+    fn from(entry: AuditEntry) -> Self {
+        let parse = |json: Option<String>| json.and_then(|json| serde_json::from_str(&json).ok());
+        AuditEntryResponse {
+            id: entry.id,
+            created_at: entry.created_at,
+            organization_id: entry.organization_id,
+            actor_kind: entry.actor_kind,
+            actor_id: entry.actor_id,
+            impersonator_id: entry.impersonator_id,
+            action: entry.action,
+            entity_type: entry.entity_type,
+            entity_id: entry.entity_id,
+            before: parse(entry.before_json),
+            after: parse(entry.after_json),
+            request_id: entry.request_id,
+            ip: entry.ip,
+            prev_hash: entry.prev_hash,
+            hash: entry.hash,
+        }
+    }
+}
+
+// AuditActor: ผู้กระทำ (บัญชีที่ Login อยู่ หรือ anonymous สำหรับ Endpoint ที่ไม่ต้อง Login)
+#[derive(Debug, Clone)]
+pub struct AuditActor {
+    pub kind: &'static str,
+    pub id: Option<i32>,
+    pub impersonator_id: Option<i32>,
+    pub organization_id: Option<i32>, // None = Platform Admin หรือ anonymous
+}
+
+impl AuditActor {
+    pub fn anonymous() -> Self {
+        AuditActor {
+            kind: "anonymous",
+            id: None,
+            impersonator_id: None,
+            organization_id: None,
+        }
+    }
+
+    // บัญชีที่เพิ่งถูกสร้างหรือยืนยันตัวตนด้วย Token ใน Body (เช่น สมัครสมาชิก, รับคำเชิญ, รีเซ็ตรหัสผ่าน)
+    pub fn account(kind: PrincipalKind, id: i32, organization_id: Option<i32>) -> Self {
+        AuditActor {
+            kind: kind.as_str(),
+            id: Some(id),
+            impersonator_id: None,
+            organization_id,
+        }
+    }
+}
+
+// AuditContext: ข้อมูลของ Request ที่บันทึกคู่กับทุก AuditEvent
+#[derive(Debug, Clone)]
+pub struct AuditContext {
+    pub actor: AuditActor,
+    pub request_id: Option<String>,
+    pub ip: Option<String>,
+}
+
+impl AuditContext {
+    // AuditEvent ที่สร้างจากผลของการเปลี่ยนแปลง Repository เขียนใน Transaction เดียวกับการเปลี่ยนแปลง
+    pub fn pending<'a, T: ?Sized>(&'a self, event: impl FnOnce(&T) -> AuditEvent + Send + 'a) -> PendingAudit<'a, T> {
+        PendingAudit { context: self, event: Box::new(event) }
+    }
+}
+
+// PendingAudit: AuditEvent ที่รอผลของการเปลี่ยนแปลง ถ้าเขียน Audit Log ไม่สำเร็จ การเปลี่ยนแปลงถูก Rollback ด้วย
+pub struct PendingAudit<'a, T: ?Sized> {
+    context: &'a AuditContext,
+    event: Box<dyn FnOnce(&T) -> AuditEvent + Send + 'a>,
+}
+
+impl<'a, T: ?Sized> PendingAudit<'a, T> {
+    pub fn resolve(self, value: &T) -> (&'a AuditContext, AuditEvent) {
+        (self.context, (self.event)(value))
+    }
+}
+
+// AuditEvent: การเปลี่ยนแปลงหนึ่งครั้งที่บันทึกลง Audit Log
+// before/after คือสถานะของ Entity ก่อนและหลัง (None = ไม่มี เช่น สร้างใหม่หรือลบถาวร)
+#[derive(Debug, Clone)]
+pub struct AuditEvent {
+    pub action: &'static str,
+    pub entity_type: &'static str,
+    pub entity_id: Option<String>,
+    pub before: Option<serde_json::Value>,
+    pub after: Option<serde_json::Value>,
+    pub actor: Option<AuditActor>, // ผู้กระทำแทนค่าใน AuditContext (เช่น บัญชีที่เพิ่งสมัคร)
+}
+
+impl AuditEvent {
+    pub fn new(action: &'static str, entity_type: &'static str, entity_id: impl ToString) -> Self {
+        AuditEvent {
+            action,
+            entity_type,
+            entity_id: Some(entity_id.to_string()),
+            before: None,
+            after: None,
+            actor: None,
+        }
+    }
+
+    pub fn by(mut self, actor: AuditActor) -> Self {
+        self.actor = Some(actor);
+        self
+    }
+
+    pub fn before(mut self, value: &impl Serialize) -> Self {
+        self.before = serde_json::to_value(value).ok();
+        self
+    }
+
+    pub fn after(mut self, value: &impl Serialize) -> Self {
+        self.after = serde_json::to_value(value).ok();
+        self
+    }
+}
+
+// UserAuditSnapshot: สถานะของบัญชีผู้ใช้ที่เก็บใน Audit Log
+// ไม่มีข้อมูลติดต่อ (username, อีเมล, เบอร์โทร) เพราะ Audit Log ลบไม่ได้ แม้ผู้ใช้ขอลบข้อมูลส่วนตัว
+#[derive(Debug, Clone, Serialize)]
+pub struct UserAuditSnapshot {
+    pub id: i32,
+    pub organization_id: i32,
+    pub has_password: bool,
+    pub email_verified_at: Option<NaiveDateTime>,
+    pub email_verification_pending: bool,
+    pub deleted_at: Option<NaiveDateTime>,
+    pub erased_at: Option<NaiveDateTime>,
+}
+
+impl From<&User> for UserAuditSnapshot {
+    fn from(user: &User) -> Self {
+        UserAuditSnapshot {
+            id: user.id,
+            organization_id: user.organization_id,
+            has_password: user.password_hash.is_some(),
+            email_verified_at: user.email_verified_at,
+            email_verification_pending: user.email_verification_pending,
+            deleted_at: user.deleted_at,
+            erased_at: user.erased_at,
+        }
+    }
+}
+
+impl AuditEvent {
+    // ผู้ใช้ที่ถูกสร้างครั้งแรกตอน Login ผ่าน OIDC หรือ LDAP (ผู้กระทำคือบัญชีใหม่ ไม่ใช่ anonymous)
+    pub fn user_provisioned(user: &User) -> Self {
+        AuditEvent::new("user.provision", "user", user.id)
+            .after(&UserAuditSnapshot::from(user))
+            .by(AuditActor::account(PrincipalKind::User, user.id, Some(user.organization_id)))
+    }
+}
+
+// AuditQuery: ตัวกรองของ GET /admin/audit (ล่าสุดก่อนเป็นค่าเริ่มต้น)
+#[derive(Debug, Clone, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AuditQuery {
+    pub actor_kind: Option<String>,
+    pub actor_id: Option<i32>,
+    pub action: Option<String>, // ตรงทั้งคำ (booking.cancel) หรือขึ้นต้นด้วย prefix ที่ลงท้ายด้วยจุด (booking.)
+    pub entity_type: Option<String>,
+    pub entity_id: Option<String>,
+    pub request_id: Option<String>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub limit: Option<i64>,
+    pub cursor: Option<String>,
+    pub page: Option<i64>,
+    pub order: Option<SortDirection>,
+}
+
+impl AuditQuery {
+    // การแบ่งหน้าและเรียงลำดับใช้กติกาเดียวกับ ListQuery
+    pub fn paging(&self) -> ListQuery {
+        ListQuery {
+            limit: self.limit,
+            cursor: self.cursor.clone(),
+            page: self.page,
+            order: self.order,
+            ..Default::default()
+        }
+    }
+}
+
+// AuditVerification: ผลการตรวจสอบ Hash Chain ทั้งหมด
+// last_hash ใช้เทียบกับค่าที่เก็บไว้นอกระบบ (การลบแถวท้ายสุดตรวจจาก Chain อย่างเดียวไม่ได้)
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct AuditVerification {
+    pub valid: bool,
+    pub checked: i64,
+    pub last_id: Option<i32>,
+    pub last_hash: Option<String>,
+    pub broken_at: Option<i32>, // id ของแถวแรกที่ไม่ตรง
+    pub reason: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry() -> NewAuditEntry<'static> {
+        NewAuditEntry {
+            created_at: NaiveDateTime::parse_from_str("2026-01-02 03:04:05.123456", "%Y-%m-%d %H:%M:%S%.f").unwrap(),
+            organization_id: Some(1),
+            actor_kind: "admin",
+            actor_id: Some(2),
+            impersonator_id: None,
+            action: "room.update",
+            entity_type: "room",
+            entity_id: Some("3"),
+            before_json: Some(r#"{"name":"A"}"#),
+            after_json: Some(r#"{"name":"B"}"#),
+            request_id: Some("req-1"),
+            ip: Some("127.0.0.1"),
+            prev_hash: GENESIS_HASH.to_string(),
+            hash: String::new(),
+        }
+    }
+
+    fn user() -> User {
+        let now = Utc::now().naive_utc();
+        User {
+            id: 7,
+            username: "alice".to_string(),
+            password_hash: None,
+            created_at: now,
+            updated_at: now,
+            deleted_at: None,
+            display_name: None,
+            directory_groups: None,
+            email: Some("alice@example.com".to_string()),
+            phone: None,
+            department: None,
+            timezone: None,
+            language: None,
+            email_verified_at: None,
+            email_verification_pending: false,
+            erased_at: None,
+            organization_id: 4,
+        }
+    }
+
+    #[test]
+    fn hash_covers_every_column_and_the_previous_hash() {
+        let original = entry().compute_hash();
+        assert_eq!(original, entry().compute_hash());
+        assert_eq!(original.len(), 64);
+
+        let changes: Vec<fn(&mut NewAuditEntry<'static>)> = vec![
+            |e| e.prev_hash = "f".repeat(64),
+            |e| e.created_at += chrono::Duration::microseconds(1),
+            |e| e.organization_id = None,
+            |e| e.actor_kind = "user",
+            |e| e.actor_id = Some(3),
+            |e| e.impersonator_id = Some(2),
+            |e| e.action = "room.delete",
+            |e| e.entity_id = Some("4"),
+            |e| e.before_json = None,
+            |e| e.after_json = Some(r#"{"name":"C"}"#),
+            |e| e.request_id = None,
+            |e| e.ip = Some("10.0.0.1"),
+        ];
+        for change in changes {
+            let mut modified = entry();
+            change(&mut modified);
+            assert_ne!(modified.compute_hash(), original);
+        }
+        // ค่าที่มีตัวคั่นอยู่ข้างในไม่ทำให้สองแถวได้ hash เดียวกัน
+        let mut shifted = entry();
+        shifted.entity_type = "room\",\"3";
+        shifted.entity_id = None;
+        assert_ne!(shifted.compute_hash(), original);
+    }
+
+    #[test]
+    fn stored_entries_hash_the_same_as_when_written() {
+        let mut new = entry();
+        new.hash = new.compute_hash();
+        let stored = AuditEntry {
+            id: 1,
+            created_at: new.created_at,
+            organization_id: new.organization_id,
+            actor_kind: new.actor_kind.to_string(),
+            actor_id: new.actor_id,
+            impersonator_id: new.impersonator_id,
+            action: new.action.to_string(),
+            entity_type: new.entity_type.to_string(),
+            entity_id: new.entity_id.map(str::to_string),
+            before_json: new.before_json.map(str::to_string),
+            after_json: new.after_json.map(str::to_string),
+            request_id: new.request_id.map(str::to_string),
+            ip: new.ip.map(str::to_string),
+            prev_hash: new.prev_hash.clone(),
+            hash: new.hash.clone(),
+        };
+        assert_eq!(stored.as_new().compute_hash(), stored.hash);
+    }
+
+    #[test]
+    fn pending_events_are_built_from_the_changed_value() {
+        let context = AuditContext { actor: AuditActor::anonymous(), request_id: Some("req-1".to_string()), ip: None };
+        let pending = context.pending(|name: &str| AuditEvent::new("room.create", "room", 3).after(&name));
+        let (resolved, event) = pending.resolve("Hall");
+        assert_eq!(resolved.request_id.as_deref(), Some("req-1"));
+        assert_eq!(event.after, Some(serde_json::json!("Hall")));
+        assert!(event.actor.is_none());
+    }
+
+    #[test]
+    fn provisioned_users_are_their_own_actor_and_contact_details_are_left_out() {
+        let event = AuditEvent::user_provisioned(&user());
+        assert_eq!(event.action, "user.provision");
+        assert_eq!(event.entity_id.as_deref(), Some("7"));
+        let actor = event.actor.unwrap();
+        assert_eq!((actor.kind, actor.id, actor.organization_id), ("user", Some(7), Some(4)));
+        let after = event.after.unwrap().to_string();
+        assert!(after.contains("\"has_password\":false"));
+        assert!(!after.contains("alice"));
+    }
+}
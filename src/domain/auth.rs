@@ -1,4 +1,11 @@
+use chrono::NaiveDateTime;
+use diesel::{Insertable, Queryable, Selectable};
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::domain::validation::not_blank;
+use crate::infrastructure::schema::refresh_tokens;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct LoginResponse {
@@ -6,4 +13,62 @@ pub struct LoginResponse {
     pub token: String,      // เพิ่ม token ใน response
     pub role: String,       // เพิ่ม role ใน response
     pub expires_in: i64,    // เวลาหมดอายุ (วินาที)
-}
\ No newline at end of file
+}
+
+// RefreshToken: แถวในตาราง refresh_tokens (token จริงไม่ถูกเก็บ เก็บเฉพาะ hash)
+// - used_at: ถูกใช้ refresh ไปแล้ว (rotate) ถ้าถูกส่งมาอีกถือว่าเป็นการใช้ซ้ำ
+// - revoked_at: ถูกเพิกถอน (logout หรือทั้ง family ถูกเพิกถอนเพราะตรวจพบการใช้ซ้ำ)
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = refresh_tokens)]
+pub struct RefreshToken {
+    pub id: i32,
+    pub token_hash: String,
+    pub family_id: String,
+    pub subject_id: i32,
+    pub role: String,
+    pub access_jti: String,
+    pub access_expires_at: NaiveDateTime,
+    pub expires_at: NaiveDateTime,
+    pub created_at: NaiveDateTime,
+    pub used_at: Option<NaiveDateTime>,
+    pub revoked_at: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = refresh_tokens)]
+pub struct NewRefreshToken<'a> {
+    pub token_hash: &'a str,
+    pub family_id: &'a str,
+    pub subject_id: i32,
+    pub role: &'a str,
+    pub access_jti: &'a str,
+    pub access_expires_at: NaiveDateTime,
+    pub expires_at: NaiveDateTime,
+}
+
+// RefreshTokenRequest: Body ของ POST /auth/refresh และ POST /auth/logout
+#[derive(Debug, Clone, Deserialize, ToSchema, Validate)]
+pub struct RefreshTokenRequest {
+    #[validate(custom(function = "not_blank"))]
+    pub refresh_token: String,
+}
+
+// TokenPair: Access Token + Refresh Token ชุดใหม่ที่ออกให้ตอน login หรือ refresh
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct TokenPair {
+    pub token: String,
+    pub refresh_token: String,
+    pub role: String,
+    pub expires_in: i64,         // อายุ Access Token (วินาที)
+    pub refresh_expires_in: i64, // อายุ Refresh Token (วินาที)
+}
+
+// SessionResponse: Body ที่ตอบกลับเมื่อ login/refresh แบบ Cookie Session
+// Token อยู่ใน HttpOnly Cookie ส่วน csrf_token ต้องส่งกลับมาใน Header `X-CSRF-Token`
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SessionResponse {
+    pub role: String,
+    pub expires_in: i64,
+    pub refresh_expires_in: i64,
+    pub csrf_token: String,
+}
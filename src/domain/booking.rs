@@ -3,8 +3,10 @@ use crate::infrastructure::schema::bookings;
 use chrono::{DateTime, NaiveDateTime, Utc};
 use diesel::prelude::*;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::{Validate, ValidationError};
 
-#[derive(Debug, Clone, Serialize, Deserialize, Queryable, Selectable)]
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable, Selectable, ToSchema)]
 #[diesel(table_name = bookings)]
 pub struct Booking {
     pub id: i32,
@@ -31,11 +33,27 @@ pub struct NewBooking {
      pub deleted_at: Option<NaiveDateTime>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+// end_time ต้องอยู่หลัง start_time (ตรวจสอบระดับ struct ผ่าน validate_booking_window)
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, Validate)]
+#[validate(schema(function = "validate_booking_window", skip_on_field_errors = false))]
 pub struct CreateBookingRequest {
+    #[validate(range(min = 1))]
     pub room_id: i32,
     pub start_time: DateTime<Utc>,
     pub end_time: DateTime<Utc>,
+    // จองแทนผู้ใช้คนอื่น (ต้องมี Permission `bookings:manage`) ถ้าไม่ระบุจะจองให้ผู้ที่ login
+    #[serde(default)]
+    #[validate(range(min = 1))]
+    pub user_id: Option<i32>,
+}
+
+fn validate_booking_window(request: &CreateBookingRequest) -> Result<(), ValidationError> {
+    if request.end_time <= request.start_time {
+        let mut error = ValidationError::new("end_before_start").with_message("must be after start_time".into());
+        error.add_param("field".into(), &"end_time");
+        return Err(error);
+    }
+    Ok(())
 }
 
 #[derive(Debug, Deserialize)]
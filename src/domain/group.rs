@@ -0,0 +1,98 @@
+use chrono::NaiveDateTime;
+use diesel::{AsChangeset, Insertable, Queryable, Selectable};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::domain::validation::not_blank;
+use crate::infrastructure::schema::groups;
+
+// Group: กลุ่มผู้ใช้ภายในองค์กร ใช้กำหนดว่าใครจองห้องที่มีกฎการเข้าถึงได้
+#[derive(Debug, Clone, Queryable, Selectable, Serialize, ToSchema)]
+#[diesel(table_name = groups)]
+pub struct Group {
+    pub id: i32,
+    pub organization_id: i32,
+    pub name: String,
+    pub description: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = groups)]
+pub struct NewGroup<'a> {
+    pub organization_id: i32,
+    pub name: &'a str,
+    pub description: Option<&'a str>,
+}
+
+// CreateGroupRequest: กลุ่มใหม่อยู่ในองค์กรของผู้สร้าง
+#[derive(Debug, Clone, Deserialize, ToSchema, Validate)]
+pub struct CreateGroupRequest {
+    #[validate(length(max = 100), custom(function = "not_blank"))]
+    pub name: String,
+    #[validate(length(max = 500))]
+    pub description: Option<String>,
+    pub organization_id: Option<i32>, // ใช้ได้เฉพาะ Platform Admin (ไม่ระบุ = องค์กรเริ่มต้น)
+}
+
+// UpdateGroupRequest: แก้ไขเฉพาะ Field ที่ส่งมา (ย้ายองค์กรไม่ได้)
+#[derive(Debug, Clone, Deserialize, ToSchema, Validate)]
+pub struct UpdateGroupRequest {
+    #[validate(length(max = 100), custom(function = "not_blank"))]
+    pub name: Option<String>,
+    #[validate(length(max = 500))]
+    pub description: Option<String>,
+}
+
+#[derive(Debug, AsChangeset)]
+#[diesel(table_name = groups)]
+pub struct GroupChangeset {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub updated_at: NaiveDateTime,
+}
+
+// RoomAccessRequest: ผู้ที่จองห้องได้ (แทนที่กฎเดิมทั้งหมด ส่งทั้งสองรายการเป็น [] เพื่อเปิดให้ทุกคน)
+#[derive(Debug, Clone, Deserialize, ToSchema, Validate)]
+pub struct RoomAccessRequest {
+    #[validate(length(max = 100))]
+    #[serde(default)]
+    pub group_ids: Vec<i32>,
+    #[validate(length(max = 500))]
+    #[serde(default)]
+    pub user_ids: Vec<i32>,
+}
+
+// RoomAccessResponse: กฎการเข้าถึงของห้อง restricted = false คือทุกคนที่เห็นห้องจองได้
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RoomAccessResponse {
+    pub room_id: i32,
+    pub restricted: bool,
+    pub group_ids: Vec<i32>,
+    pub user_ids: Vec<i32>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn group_names_must_not_be_blank() {
+        let request = |name: &str| CreateGroupRequest { name: name.to_string(), description: None, organization_id: None };
+        assert!(request("Lab team").validate().is_ok());
+        assert!(request("  ").validate().is_err());
+        assert!(request(&"x".repeat(101)).validate().is_err());
+        let update = UpdateGroupRequest { name: Some(String::new()), description: None };
+        assert!(update.validate().is_err());
+    }
+
+    #[test]
+    fn access_lists_default_to_empty() {
+        let request: RoomAccessRequest = serde_json::from_str(r#"{ "group_ids": [1] }"#).unwrap();
+        assert_eq!((request.group_ids, request.user_ids), (vec![1], vec![]));
+        let too_many = RoomAccessRequest { group_ids: (0..101).collect(), user_ids: vec![] };
+        assert!(too_many.validate().is_err());
+    }
+}
@@ -0,0 +1,70 @@
+use chrono::NaiveDateTime;
+use diesel::{Insertable, Queryable, Selectable};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::domain::validation::not_blank;
+use crate::infrastructure::schema::{impersonation_actions, impersonations};
+
+// Impersonation: การสวมสิทธิ์ผู้ใช้หนึ่งครั้ง (Admin ดูได้ที่ GET /admin/impersonations)
+#[derive(Debug, Clone, Queryable, Selectable, Serialize, ToSchema)]
+#[diesel(table_name = impersonations)]
+pub struct Impersonation {
+    pub id: i32,
+    pub admin_id: i32,
+    pub user_id: i32,
+    pub reason: String,
+    #[serde(skip)]
+    pub jti: String,
+    pub created_at: NaiveDateTime,
+    pub expires_at: NaiveDateTime,
+    pub ended_at: Option<NaiveDateTime>, // จบก่อนหมดอายุ (Admin จบเอง หรือ session ของผู้ใช้ถูกเพิกถอน)
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = impersonations)]
+pub struct NewImpersonation<'a> {
+    pub admin_id: i32,
+    pub user_id: i32,
+    pub reason: &'a str,
+    pub jti: &'a str,
+    pub expires_at: NaiveDateTime,
+}
+
+// ImpersonationAction: Request หนึ่งครั้งที่ส่งมาด้วย Token ของการสวมสิทธิ์
+#[derive(Debug, Clone, Queryable, Selectable, Serialize, ToSchema)]
+#[diesel(table_name = impersonation_actions)]
+pub struct ImpersonationAction {
+    pub id: i32,
+    pub impersonation_id: i32,
+    pub method: String,
+    pub path: String,
+    pub status: i32,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = impersonation_actions)]
+pub struct NewImpersonationAction<'a> {
+    pub impersonation_id: i32,
+    pub method: &'a str,
+    pub path: &'a str,
+    pub status: i32,
+}
+
+// ImpersonateRequest: เหตุผลที่ต้องสวมสิทธิ์ (บันทึกไว้ตรวจสอบภายหลัง)
+#[derive(Debug, Clone, Deserialize, ToSchema, Validate)]
+pub struct ImpersonateRequest {
+    #[validate(length(max = 500), custom(function = "not_blank"))]
+    pub reason: String,
+}
+
+// ImpersonationResponse: Access Token ที่ทำงานในนามผู้ใช้ (ไม่มี Refresh Token ต่ออายุไม่ได้)
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ImpersonationResponse {
+    pub impersonation_id: i32,
+    pub user_id: i32,
+    pub token: String,
+    pub expires_in: i64, // อายุ Token (วินาที)
+}
@@ -0,0 +1,134 @@
+use chrono::{Duration, NaiveDateTime};
+use diesel::{Insertable, Queryable, Selectable};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::infrastructure::schema::{login_events, login_throttles};
+
+pub const SCOPE_USERNAME: &str = "username";
+pub const SCOPE_IP: &str = "ip";
+
+pub const DEFAULT_EVENT_LIMIT: i64 = 50;
+pub const MAX_EVENT_LIMIT: i64 = 500;
+
+// LockoutPolicy: กฎการล็อกสำหรับแต่ละ scope
+// - Login ผิดครบ threshold ครั้ง จะถูกล็อก base_delay และเพิ่มเป็นสองเท่าทุกครั้งที่ผิดต่อ (สูงสุด max_delay)
+// - ถ้าไม่มีการ Login ผิดภายใน window (นับจากครั้งล่าสุดหรือเวลาที่ปลดล็อก) ตัวนับจะเริ่มใหม่
+#[derive(Debug, Clone, Copy)]
+pub struct LockoutPolicy {
+    pub threshold: i32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    pub window: Duration,
+}
+
+impl LockoutPolicy {
+    pub fn lock_duration(&self, failed_count: i32) -> Option<Duration> {
+        if failed_count < self.threshold {
+            return None;
+        }
+        let exponent = (failed_count - self.threshold).min(30) as u32;
+        let seconds = self.base_delay.num_seconds().saturating_mul(1_i64 << exponent);
+        Some(Duration::seconds(seconds).min(self.max_delay))
+    }
+}
+
+// LoginThrottle: แถวในตาราง login_throttles (สถานะการล็อกของ username หรือ IP)
+#[derive(Debug, Clone, Queryable, Selectable, Serialize, ToSchema)]
+#[diesel(table_name = login_throttles)]
+pub struct LoginThrottle {
+    pub id: i32,
+    pub scope: String,
+    pub throttle_key: String,
+    pub failed_count: i32,
+    pub last_failed_at: NaiveDateTime,
+    pub locked_until: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = login_throttles)]
+pub struct NewLoginThrottle<'a> {
+    pub scope: &'a str,
+    pub throttle_key: &'a str,
+    pub failed_count: i32,
+    pub last_failed_at: NaiveDateTime,
+    pub locked_until: Option<NaiveDateTime>,
+}
+
+// LoginEvent: ประวัติการ Login หนึ่งครั้ง
+#[derive(Debug, Clone, Queryable, Selectable, Serialize, ToSchema)]
+#[diesel(table_name = login_events)]
+pub struct LoginEvent {
+    pub id: i32,
+    pub account_kind: String,
+    pub username: String,
+    pub ip: String,
+    pub success: bool,
+    pub reason: Option<String>,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = login_events)]
+pub struct NewLoginEvent<'a> {
+    pub account_kind: &'a str,
+    pub username: &'a str,
+    pub ip: &'a str,
+    pub success: bool,
+    pub reason: Option<&'a str>,
+}
+
+// LoginEventQuery: ตัวกรองประวัติการ Login (ล่าสุดก่อน)
+#[derive(Debug, Clone, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct LoginEventQuery {
+    pub username: Option<String>,
+    pub ip: Option<String>,
+    pub success: Option<bool>,
+    pub limit: Option<i64>,
+}
+
+impl LoginEventQuery {
+    pub fn limit(&self) -> i64 {
+        self.limit.unwrap_or(DEFAULT_EVENT_LIMIT).clamp(1, MAX_EVENT_LIMIT)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> LockoutPolicy {
+        LockoutPolicy {
+            threshold: 3,
+            base_delay: Duration::seconds(30),
+            max_delay: Duration::minutes(10),
+            window: Duration::minutes(15),
+        }
+    }
+
+    #[test]
+    fn no_lock_below_threshold() {
+        assert_eq!(policy().lock_duration(0), None);
+        assert_eq!(policy().lock_duration(2), None);
+    }
+
+    #[test]
+    fn delay_doubles_after_threshold_up_to_max() {
+        let policy = policy();
+        assert_eq!(policy.lock_duration(3), Some(Duration::seconds(30)));
+        assert_eq!(policy.lock_duration(4), Some(Duration::seconds(60)));
+        assert_eq!(policy.lock_duration(7), Some(Duration::seconds(480)));
+        assert_eq!(policy.lock_duration(8), Some(Duration::minutes(10)));
+        // ตัวนับที่สูงมากต้องไม่ overflow
+        assert_eq!(policy.lock_duration(i32::MAX), Some(Duration::minutes(10)));
+    }
+
+    #[test]
+    fn event_limit_is_clamped() {
+        let query = |limit| LoginEventQuery { limit, ..Default::default() };
+        assert_eq!(query(None).limit(), DEFAULT_EVENT_LIMIT);
+        assert_eq!(query(Some(0)).limit(), 1);
+        assert_eq!(query(Some(10_000)).limit(), MAX_EVENT_LIMIT);
+    }
+}
@@ -0,0 +1,149 @@
+use chrono::NaiveDateTime;
+use diesel::{Insertable, Queryable, Selectable};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::domain::auth::{SessionResponse, TokenPair};
+use crate::domain::validation::not_blank;
+use crate::infrastructure::schema::{mfa_challenges, mfa_recovery_codes, mfa_totp};
+
+// TotpFactor: แถวในตาราง mfa_totp
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = mfa_totp)]
+pub struct TotpFactor {
+    pub id: i32,
+    pub principal_kind: String,
+    pub principal_id: i32,
+    pub secret: String, // Base32
+    pub last_used_step: Option<i64>,
+    pub confirmed_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = mfa_totp)]
+pub struct NewTotpFactor<'a> {
+    pub principal_kind: &'a str,
+    pub principal_id: i32,
+    pub secret: &'a str,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = mfa_recovery_codes)]
+pub struct NewRecoveryCode<'a> {
+    pub principal_kind: &'a str,
+    pub principal_id: i32,
+    pub code_hash: &'a str,
+}
+
+// MfaChallenge: แถวในตาราง mfa_challenges
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = mfa_challenges)]
+pub struct MfaChallenge {
+    pub id: i32,
+    pub token_hash: String,
+    pub principal_kind: String,
+    pub principal_id: i32,
+    pub enrollment: bool,
+    pub attempts: i32,
+    pub expires_at: NaiveDateTime,
+    pub created_at: NaiveDateTime,
+    pub completed_at: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = mfa_challenges)]
+pub struct NewMfaChallenge<'a> {
+    pub token_hash: &'a str,
+    pub principal_kind: &'a str,
+    pub principal_id: i32,
+    pub enrollment: bool,
+    pub expires_at: NaiveDateTime,
+}
+
+// MfaChallengeResponse: ตอบกลับจาก Login เมื่อบัญชีต้องยืนยันรหัส 2FA ก่อนได้รับ Token
+// enrollment_required: ยังไม่มี Authenticator ต้องลงทะเบียนผ่าน POST /auth/mfa/enroll ก่อน
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct MfaChallengeResponse {
+    pub mfa_required: bool,
+    pub enrollment_required: bool,
+    pub challenge_token: String,
+    pub expires_in: i64, // วินาที
+}
+
+// TotpEnrollmentResponse: Secret สำหรับเพิ่มใน Authenticator App (otpauth_uri ใช้สร้าง QR Code)
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct TotpEnrollmentResponse {
+    pub secret: String,
+    pub otpauth_uri: String,
+}
+
+// RecoveryCodesResponse: Recovery Code ชุดใหม่ (แสดงครั้งเดียว)
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RecoveryCodesResponse {
+    pub recovery_codes: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct MfaStatusResponse {
+    pub enabled: bool,
+    pub required: bool, // Role ของบัญชีกำหนดให้ต้องใช้ 2FA
+    pub pending_enrollment: bool,
+    pub recovery_codes_remaining: i64,
+}
+
+// MfaEnrollRequest: ลงทะเบียน Authenticator ระหว่าง Login (ใช้ Challenge Token แทน Access Token)
+#[derive(Debug, Clone, Deserialize, ToSchema, Validate)]
+pub struct MfaEnrollRequest {
+    #[validate(custom(function = "not_blank"))]
+    pub challenge_token: String,
+}
+
+// MfaVerifyRequest: Login ขั้นที่สอง ส่ง `code` จาก Authenticator หรือ `recovery_code` อย่างใดอย่างหนึ่ง
+#[derive(Debug, Clone, Deserialize, ToSchema, Validate)]
+pub struct MfaVerifyRequest {
+    #[validate(custom(function = "not_blank"))]
+    pub challenge_token: String,
+    pub code: Option<String>,
+    pub recovery_code: Option<String>,
+}
+
+// TotpCodeRequest: ยืนยันตัวตนด้วยรหัส 2FA ก่อนปิด 2FA หรือสร้าง Recovery Code ใหม่
+#[derive(Debug, Clone, Deserialize, ToSchema, Validate)]
+pub struct TotpCodeRequest {
+    pub code: Option<String>,
+    pub recovery_code: Option<String>,
+}
+
+// TotpConfirmRequest: ยืนยันการลงทะเบียนด้วยรหัสแรกจาก Authenticator
+#[derive(Debug, Clone, Deserialize, ToSchema, Validate)]
+pub struct TotpConfirmRequest {
+    #[validate(custom(function = "not_blank"))]
+    pub code: String,
+}
+
+// MfaPolicyRequest: กำหนดว่า Role ต้องใช้ 2FA หรือไม่
+#[derive(Debug, Clone, Deserialize, ToSchema, Validate)]
+pub struct MfaPolicyRequest {
+    pub required: bool,
+}
+
+// MfaTokensResponse: Token ที่ได้หลังยืนยันรหัส 2FA
+// recovery_codes มีเฉพาะเมื่อเพิ่งลงทะเบียน 2FA ระหว่าง Login (แสดงครั้งเดียว)
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct MfaTokensResponse {
+    #[serde(flatten)]
+    pub tokens: TokenPair,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recovery_codes: Option<Vec<String>>,
+}
+
+// MfaSessionResponse: เหมือน MfaTokensResponse สำหรับ Cookie Session (`?mode=cookie`)
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct MfaSessionResponse {
+    #[serde(flatten)]
+    pub session: SessionResponse,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recovery_codes: Option<Vec<String>>,
+}
@@ -3,4 +3,18 @@ pub mod user;
 pub mod admin;
 pub mod booking;
 pub mod booking_status;
-pub mod auth;
\ No newline at end of file
+pub mod auth;
+pub mod report;
+pub mod pagination;
+pub mod validation;
+pub mod permission;
+pub mod password;
+pub mod login_throttle;
+pub mod mfa;
+pub mod api_key;
+pub mod oidc;
+pub mod privacy;
+pub mod organization;
+pub mod group;
+pub mod impersonation;
+pub mod audit;
\ No newline at end of file
@@ -0,0 +1,75 @@
+use chrono::NaiveDateTime;
+use diesel::{Insertable, Queryable, Selectable};
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+use crate::infrastructure::schema::{external_identities, oidc_login_states};
+
+// แหล่งที่มาของ Role ที่ซิงก์จากกลุ่มใน ID Token (ดู role_assignments.source)
+pub const OIDC_ROLE_SOURCE: &str = "oidc";
+
+// OidcLoginState: Login ผ่าน OIDC ที่รอ Callback จาก Identity Provider
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = oidc_login_states)]
+pub struct OidcLoginState {
+    pub id: i32,
+    pub state_hash: String,
+    pub nonce: String,
+    pub code_verifier: String,
+    pub mode: String, // "bearer" หรือ "cookie" ตาม ?mode= ตอนเริ่ม Login
+    pub expires_at: NaiveDateTime,
+    pub created_at: NaiveDateTime,
+    pub used_at: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = oidc_login_states)]
+pub struct NewOidcLoginState<'a> {
+    pub state_hash: &'a str,
+    pub nonce: &'a str,
+    pub code_verifier: &'a str,
+    pub mode: &'a str,
+    pub expires_at: NaiveDateTime,
+}
+
+// ExternalIdentity: ผู้ใช้ที่ผูกกับบัญชีของ Identity Provider (issuer + sub) หรือ LDAP (ดู LDAP_ISSUER)
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = external_identities)]
+pub struct ExternalIdentity {
+    pub id: i32,
+    pub user_id: i32,
+    pub issuer: String,
+    pub subject: String,
+    pub created_at: NaiveDateTime,
+    pub last_login_at: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = external_identities)]
+pub struct NewExternalIdentity<'a> {
+    pub user_id: i32,
+    pub issuer: &'a str,
+    pub subject: &'a str,
+    pub last_login_at: NaiveDateTime,
+}
+
+// OidcIdentity: ข้อมูลผู้ใช้ที่ได้จาก ID Token ที่ตรวจสอบแล้ว
+#[derive(Debug, Clone)]
+pub struct OidcIdentity {
+    pub subject: String,
+    pub username: String,
+    pub organization: String, // slug ขององค์กรที่ใช้ถ้าต้องสร้างผู้ใช้ใหม่
+    pub display_name: Option<String>,
+    pub groups: Vec<String>,
+}
+
+// OidcCallbackQuery: Query ที่ Identity Provider ส่งกลับมาที่ Redirect URI
+// สำเร็จ: code + state, ไม่สำเร็จ: error (+ error_description)
+#[derive(Debug, Clone, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct OidcCallbackQuery {
+    pub code: Option<String>,
+    pub state: Option<String>,
+    pub error: Option<String>,
+    pub error_description: Option<String>,
+}
@@ -0,0 +1,190 @@
+use chrono::NaiveDateTime;
+use diesel::{AsChangeset, Insertable, Queryable, Selectable};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::domain::permission::PrincipalKind;
+use crate::domain::validation::{not_blank, slug_chars};
+use crate::error::AppError;
+use crate::infrastructure::schema::organizations;
+
+// องค์กรเริ่มต้นที่สร้างใน Migration (ผู้ใช้ที่สมัครโดยไม่ระบุองค์กร และผู้ใช้จาก OIDC/LDAP ถ้าไม่ได้ตั้งค่า)
+pub const DEFAULT_ORGANIZATION_ID: i32 = 1;
+pub const DEFAULT_ORGANIZATION_SLUG: &str = "default";
+
+// ExternalOrganization: องค์กรที่ผู้ใช้ใหม่จาก OIDC/LDAP ถูกสร้างไว้
+// Fixed = ทุกคนอยู่องค์กรเดียว (slug), Claim = อ่าน slug จาก claim/attribute ของผู้ใช้แต่ละคน
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExternalOrganization {
+    Fixed(String),
+    Claim(String),
+}
+
+impl ExternalOrganization {
+    // ตั้ง claim ไว้ใช้ claim ก่อน ไม่เช่นนั้นใช้ slug ที่กำหนด (ค่าเริ่มต้นคือองค์กรเริ่มต้น)
+    pub fn from_settings(slug: Option<String>, claim: Option<String>) -> Self {
+        match (claim.filter(|c| !c.trim().is_empty()), slug.filter(|s| !s.trim().is_empty())) {
+            (Some(claim), _) => ExternalOrganization::Claim(claim.trim().to_string()),
+            (None, Some(slug)) => ExternalOrganization::Fixed(slug.trim().to_string()),
+            (None, None) => ExternalOrganization::Fixed(DEFAULT_ORGANIZATION_SLUG.to_string()),
+        }
+    }
+
+    // slug ขององค์กรสำหรับผู้ใช้คนนี้ (claim_value = ค่าแรกของ claim/attribute ที่ตั้งไว้)
+    // ไม่มีค่าใน claim ถือว่าไม่รู้องค์กร ไม่ย้อนไปใช้องค์กรเริ่มต้น (กันผู้ใช้เข้าผิด Tenant)
+    pub fn slug<'a>(&'a self, claim_value: Option<&'a str>) -> Result<&'a str, AppError> {
+        match self {
+            ExternalOrganization::Fixed(slug) => Ok(slug),
+            ExternalOrganization::Claim(name) => claim_value
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .ok_or_else(|| AppError::Forbidden(format!("The account has no '{}' value to choose its organization.", name))),
+        }
+    }
+
+    // ชื่อ claim/attribute ที่ต้องอ่าน (None = ไม่ต้องอ่าน)
+    pub fn claim(&self) -> Option<&str> {
+        match self {
+            ExternalOrganization::Fixed(_) => None,
+            ExternalOrganization::Claim(name) => Some(name),
+        }
+    }
+}
+
+// Tenant: ขอบเขตข้อมูลของผู้เรียก ทุก Query ของ Admin ถูกกรองด้วยค่านี้
+// All = Platform Admin (Admin ที่ไม่สังกัดองค์กร) เห็นข้อมูลทุกองค์กร
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tenant {
+    All,
+    Organization(i32),
+}
+
+impl Tenant {
+    // สร้างจาก claim `org` ของ Token (หรือองค์กรของเจ้าของ API Key)
+    // บัญชีผู้ใช้ต้องมีองค์กรเสมอ ส่วน Admin ที่ไม่มีองค์กรคือ Platform Admin
+    pub fn for_principal(kind: PrincipalKind, organization_id: Option<i32>) -> Result<Self, AppError> {
+        match (kind, organization_id) {
+            (_, Some(id)) => Ok(Tenant::Organization(id)),
+            (PrincipalKind::Admin, None) => Ok(Tenant::All),
+            (PrincipalKind::User, None) => Err(AppError::Unauthorized(
+                "Token has no organization; sign in again.".to_string(),
+            )),
+        }
+    }
+
+    // None = ไม่กรอง (Platform Admin)
+    pub fn organization_id(self) -> Option<i32> {
+        match self {
+            Tenant::All => None,
+            Tenant::Organization(id) => Some(id),
+        }
+    }
+}
+
+// Organization: แถวในตาราง organizations
+#[derive(Debug, Clone, Queryable, Selectable, Serialize, ToSchema)]
+#[diesel(table_name = organizations)]
+pub struct Organization {
+    pub id: i32,
+    pub name: String,
+    pub slug: String,
+    pub self_registration: bool, // สมัครเข้าองค์กรนี้ผ่าน POST /register ได้เอง
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = organizations)]
+pub struct NewOrganization<'a> {
+    pub name: &'a str,
+    pub slug: &'a str,
+    pub self_registration: bool,
+}
+
+// CreateOrganizationRequest: สร้างองค์กรใหม่ (Platform Admin เท่านั้น)
+#[derive(Debug, Clone, Deserialize, ToSchema, Validate)]
+pub struct CreateOrganizationRequest {
+    #[validate(length(max = 255), custom(function = "not_blank"))]
+    pub name: String,
+    #[validate(length(min = 2, max = 64), custom(function = "slug_chars"))]
+    pub slug: String,
+    pub self_registration: Option<bool>, // ค่าเริ่มต้น false
+}
+
+// UpdateOrganizationRequest: แก้ไขเฉพาะ Field ที่ส่งมา (slug เปลี่ยนไม่ได้)
+#[derive(Debug, Clone, Deserialize, ToSchema, Validate)]
+pub struct UpdateOrganizationRequest {
+    #[validate(length(max = 255), custom(function = "not_blank"))]
+    pub name: Option<String>,
+    pub self_registration: Option<bool>,
+}
+
+#[derive(Debug, AsChangeset)]
+#[diesel(table_name = organizations)]
+pub struct OrganizationChangeset {
+    pub name: Option<String>,
+    pub self_registration: Option<bool>,
+    pub updated_at: NaiveDateTime,
+}
+
+// SetOrganizationRequest: ย้ายผู้ใช้ไปองค์กรอื่น (Platform Admin เท่านั้น)
+#[derive(Debug, Clone, Deserialize, ToSchema, Validate)]
+pub struct SetOrganizationRequest {
+    pub organization_id: i32,
+}
+
+// RoomSharesRequest: องค์กรอื่นที่เห็นและจองห้องนี้ได้ (แทนที่รายการเดิมทั้งหมด ส่ง [] เพื่อเลิกแชร์)
+#[derive(Debug, Clone, Deserialize, ToSchema, Validate)]
+pub struct RoomSharesRequest {
+    #[validate(length(max = 100))]
+    pub organization_ids: Vec<i32>,
+}
+
+// RoomSharesResponse: องค์กรที่ห้องถูกแชร์ให้ (ไม่รวมองค์กรเจ้าของ)
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RoomSharesResponse {
+    pub room_id: i32,
+    pub organization_id: i32, // องค์กรเจ้าของห้อง
+    pub shared_with: Vec<i32>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn external_organization_prefers_the_claim() {
+        let default = ExternalOrganization::from_settings(None, Some(" ".to_string()));
+        assert_eq!(default, ExternalOrganization::Fixed(DEFAULT_ORGANIZATION_SLUG.to_string()));
+        let fixed = ExternalOrganization::from_settings(Some("acme".to_string()), None);
+        assert_eq!(fixed.slug(Some("other")).unwrap(), "acme");
+        let claim = ExternalOrganization::from_settings(Some("acme".to_string()), Some("org".to_string()));
+        assert_eq!(claim.claim(), Some("org"));
+        assert_eq!(claim.slug(Some("globex")).unwrap(), "globex");
+    }
+
+    #[test]
+    fn missing_claim_value_is_rejected() {
+        let claim = ExternalOrganization::Claim("org".to_string());
+        assert!(matches!(claim.slug(None), Err(AppError::Forbidden(_))));
+        assert!(matches!(claim.slug(Some("  ")), Err(AppError::Forbidden(_))));
+    }
+
+    #[test]
+    fn organization_slugs_are_validated() {
+        let request = |slug: &str| CreateOrganizationRequest { name: "Acme".to_string(), slug: slug.to_string(), self_registration: None };
+        assert!(request("acme-2").validate().is_ok());
+        for slug in ["a", "Acme", "acme corp", "acme_corp"] {
+            assert!(request(slug).validate().is_err(), "{slug}");
+        }
+    }
+
+    #[test]
+    fn tenant_of_principal() {
+        assert_eq!(Tenant::for_principal(PrincipalKind::User, Some(3)).unwrap(), Tenant::Organization(3));
+        assert_eq!(Tenant::for_principal(PrincipalKind::Admin, None).unwrap(), Tenant::All);
+        assert!(Tenant::for_principal(PrincipalKind::User, None).is_err());
+        assert_eq!(Tenant::All.organization_id(), None);
+    }
+}
@@ -0,0 +1,174 @@
+// src/domain/pagination.rs
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::error::AppError;
+
+pub const DEFAULT_PAGE_SIZE: i64 = 20;
+pub const MAX_PAGE_SIZE: i64 = 100;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SortDirection {
+    Asc,
+    #[default]
+    Desc,
+}
+
+// ListQuery: Query parameters ที่ใช้ร่วมกันในทุก list endpoint
+// - การแบ่งหน้า: ใช้ `cursor` (ค่าจาก next_cursor ของหน้าก่อน) หรือ `page` (เริ่มที่ 1) คู่กับ `limit`
+//   cursor คือ offset ของรายการถัดไป ไม่ใช่ keyset: ถ้ามีการเพิ่ม/ลบรายการระหว่างดึงแต่ละหน้า รายการอาจซ้ำหรือตกหล่น
+// - การเรียงลำดับ: `sort` (ชื่อ field) และ `order` (asc/desc)
+// - ตัวกรอง: แต่ละ endpoint ใช้เฉพาะตัวกรองที่เกี่ยวข้อง ตัวที่ไม่เกี่ยวข้องจะถูกละเว้น
+// - include_deleted=true: รวมรายการที่ถูก Soft Delete (ผู้ใช้ ห้อง และการจอง)
+#[derive(Debug, Clone, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ListQuery {
+    pub limit: Option<i64>,
+    pub cursor: Option<String>,
+    pub page: Option<i64>,
+    pub sort: Option<String>,
+    pub order: Option<SortDirection>,
+    pub room_id: Option<i32>,
+    pub user_id: Option<i32>,
+    pub status: Option<String>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub include_deleted: Option<bool>,
+}
+
+impl ListQuery {
+    pub fn limit(&self) -> i64 {
+        self.limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE)
+    }
+
+    // cursor มีความสำคัญกว่า page ถ้าส่งมาทั้งคู่
+    pub fn offset(&self) -> Result<i64, AppError> {
+        if let Some(cursor) = &self.cursor {
+            return cursor
+                .parse::<i64>()
+                .ok()
+                .filter(|offset| *offset >= 0)
+                .ok_or_else(|| AppError::Validation("Invalid cursor".to_string()));
+        }
+        match self.page {
+            Some(page) if page < 1 => Err(AppError::Validation("`page` must be 1 or greater".to_string())),
+            // page ที่ใหญ่มากทำให้ offset เกินขอบเขตของ i64
+            Some(page) => (page - 1)
+                .checked_mul(self.limit())
+                .ok_or_else(|| AppError::Validation("`page` is too large".to_string())),
+            None => Ok(0),
+        }
+    }
+
+    pub fn include_deleted(&self) -> bool {
+        self.include_deleted.unwrap_or(false)
+    }
+
+    pub fn direction(&self) -> SortDirection {
+        self.order.unwrap_or_default()
+    }
+
+    // ตรวจสอบว่า `sort` เป็น field ที่ endpoint นั้นอนุญาต แล้วคืนชื่อ field ที่จะใช้เรียง
+    pub fn sort_field<'a>(&'a self, allowed: &[&'a str], default: &'a str) -> Result<&'a str, AppError> {
+        match self.sort.as_deref() {
+            None => Ok(default),
+            Some(field) if allowed.contains(&field) => Ok(field),
+            Some(field) => Err(AppError::Validation(format!(
+                "Invalid sort field '{}'. Allowed: {}",
+                field,
+                allowed.join(", ")
+            ))),
+        }
+    }
+}
+
+// Page: Envelope มาตรฐานสำหรับผลลัพธ์แบบแบ่งหน้า
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub total: i64,
+    pub limit: i64,
+    // offset ของรายการถัดไป ส่งกลับมาเป็น `cursor` (ไม่ใช่ keyset: การเพิ่ม/ลบรายการระหว่างดึงทำให้หน้าเลื่อนได้)
+    pub next_cursor: Option<String>,
+}
+
+impl<T> Page<T> {
+    pub fn new(items: Vec<T>, total: i64, limit: i64, offset: i64) -> Self {
+        let next_offset = offset + items.len() as i64;
+        let next_cursor = (next_offset < total).then(|| next_offset.to_string());
+        Page {
+            items,
+            total,
+            limit,
+            next_cursor,
+        }
+    }
+
+    // แปลงรายการในหน้า (เช่น Entity -> Response)
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> Page<U> {
+        Page {
+            items: self.items.into_iter().map(f).collect(),
+            total: self.total,
+            limit: self.limit,
+            next_cursor: self.next_cursor,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn query(page: Option<i64>, limit: Option<i64>, cursor: Option<&str>) -> ListQuery {
+        ListQuery {
+            page,
+            limit,
+            cursor: cursor.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn limit_is_clamped() {
+        assert_eq!(query(None, None, None).limit(), DEFAULT_PAGE_SIZE);
+        assert_eq!(query(None, Some(0), None).limit(), 1);
+        assert_eq!(query(None, Some(1000), None).limit(), MAX_PAGE_SIZE);
+    }
+
+    #[test]
+    fn page_offset() {
+        assert_eq!(query(None, None, None).offset().unwrap(), 0);
+        assert_eq!(query(Some(3), Some(10), None).offset().unwrap(), 20);
+        assert!(matches!(query(Some(0), None, None).offset(), Err(AppError::Validation(_))));
+    }
+
+    #[test]
+    fn huge_page_is_rejected_instead_of_overflowing() {
+        assert!(matches!(query(Some(i64::MAX), Some(100), None).offset(), Err(AppError::Validation(_))));
+    }
+
+    #[test]
+    fn cursor_takes_precedence_over_page() {
+        assert_eq!(query(Some(5), None, Some("7")).offset().unwrap(), 7);
+        assert!(matches!(query(None, None, Some("-1")).offset(), Err(AppError::Validation(_))));
+        assert!(matches!(query(None, None, Some("abc")).offset(), Err(AppError::Validation(_))));
+    }
+
+    #[test]
+    fn next_cursor_is_offset_of_next_item() {
+        let page = Page::new(vec![1, 2], 5, 2, 2);
+        assert_eq!(page.next_cursor.as_deref(), Some("4"));
+        let last = Page::new(vec![5], 5, 2, 4);
+        assert_eq!(last.next_cursor, None);
+    }
+
+    #[test]
+    fn sort_field_must_be_allowed() {
+        let mut list = query(None, None, None);
+        assert_eq!(list.sort_field(&["name", "created_at"], "created_at").unwrap(), "created_at");
+        list.sort = Some("password_hash".to_string());
+        assert!(matches!(list.sort_field(&["name"], "name"), Err(AppError::Validation(_))));
+    }
+}
@@ -0,0 +1,41 @@
+use chrono::NaiveDateTime;
+use diesel::Insertable;
+use serde::Deserialize;
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::domain::validation::{not_blank, PASSWORD_MAX_LENGTH, PASSWORD_MIN_LENGTH};
+use crate::infrastructure::schema::password_reset_tokens;
+
+// ChangePasswordRequest: เปลี่ยนรหัสผ่านของตัวเอง (ต้องยืนยันรหัสผ่านปัจจุบัน)
+#[derive(Debug, Clone, Deserialize, ToSchema, Validate)]
+pub struct ChangePasswordRequest {
+    #[validate(custom(function = "not_blank"))]
+    pub current_password: String,
+    #[validate(length(min = PASSWORD_MIN_LENGTH, max = PASSWORD_MAX_LENGTH))]
+    pub new_password: String,
+}
+
+// PasswordResetRequest: ขอลิงก์รีเซ็ตรหัสผ่าน (ส่งผ่าน Notifier)
+#[derive(Debug, Clone, Deserialize, ToSchema, Validate)]
+pub struct PasswordResetRequest {
+    #[validate(custom(function = "not_blank"))]
+    pub username: String,
+}
+
+// PasswordResetConfirmRequest: ตั้งรหัสผ่านใหม่ด้วย Token จากลิงก์รีเซ็ต
+#[derive(Debug, Clone, Deserialize, ToSchema, Validate)]
+pub struct PasswordResetConfirmRequest {
+    #[validate(custom(function = "not_blank"))]
+    pub token: String,
+    #[validate(length(min = PASSWORD_MIN_LENGTH, max = PASSWORD_MAX_LENGTH))]
+    pub new_password: String,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = password_reset_tokens)]
+pub struct NewPasswordResetToken<'a> {
+    pub user_id: i32,
+    pub token_hash: &'a str,
+    pub expires_at: NaiveDateTime,
+}
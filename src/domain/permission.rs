@@ -0,0 +1,239 @@
+// src/domain/permission.rs
+
+use diesel::Insertable;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::domain::validation::not_blank;
+use crate::infrastructure::schema::role_assignments;
+
+// Permission: สิทธิ์ที่ Handler ต้องการ (เก็บใน role_permissions เป็น string เช่น "rooms:write")
+// Role กำหนดชุดของ Permission และผู้ใช้ได้รับ Permission ผ่าน Role ที่ถืออยู่
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize, ToSchema)]
+pub enum Permission {
+    #[serde(rename = "rooms:write")]
+    RoomsWrite, // เพิ่ม/แก้ไข/ลบห้อง
+    #[serde(rename = "bookings:write")]
+    BookingsWrite, // จอง/ดู/ยกเลิกการจองของตัวเอง
+    #[serde(rename = "bookings:read_all")]
+    BookingsReadAll, // ดูการจองทั้งหมด
+    #[serde(rename = "bookings:manage")]
+    BookingsManage, // ยกเลิก/บันทึก no-show/จองแทนผู้ใช้คนอื่น
+    #[serde(rename = "reports:read")]
+    ReportsRead,
+    #[serde(rename = "users:read")]
+    UsersRead,
+    #[serde(rename = "users:write")]
+    UsersWrite, // ลบผู้ใช้
+    #[serde(rename = "roles:manage")]
+    RolesManage, // กำหนด Role ให้ผู้ใช้
+    #[serde(rename = "admins:manage")]
+    AdminsManage, // เชิญ Admin ใหม่
+    #[serde(rename = "audit:read")]
+    AuditRead, // ดู Audit Log
+}
+
+impl Permission {
+    pub const ALL: [Permission; 10] = [
+        Permission::RoomsWrite,
+        Permission::BookingsWrite,
+        Permission::BookingsReadAll,
+        Permission::BookingsManage,
+        Permission::ReportsRead,
+        Permission::UsersRead,
+        Permission::UsersWrite,
+        Permission::RolesManage,
+        Permission::AdminsManage,
+        Permission::AuditRead,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Permission::RoomsWrite => "rooms:write",
+            Permission::BookingsWrite => "bookings:write",
+            Permission::BookingsReadAll => "bookings:read_all",
+            Permission::BookingsManage => "bookings:manage",
+            Permission::ReportsRead => "reports:read",
+            Permission::UsersRead => "users:read",
+            Permission::UsersWrite => "users:write",
+            Permission::RolesManage => "roles:manage",
+            Permission::AdminsManage => "admins:manage",
+            Permission::AuditRead => "audit:read",
+        }
+    }
+
+    // แปลงจาก string ใน DB (Permission ที่ไม่รู้จักจะถูกละเว้น)
+    pub fn parse(value: &str) -> Option<Self> {
+        Permission::ALL.into_iter().find(|p| p.as_str() == value)
+    }
+}
+
+// RequiredPermission: Marker type สำหรับระบุ Permission ใน `RequirePermission<P>`
+// เช่น `RequirePermission<perm::RoomsWrite>`
+pub trait RequiredPermission: Send + Sync + 'static {
+    const PERMISSION: Permission;
+}
+
+pub mod perm {
+    use super::{Permission, RequiredPermission};
+
+    macro_rules! permission_markers {
+        ($($name:ident),* $(,)?) => {
+            $(
+                pub struct $name;
+
+                impl RequiredPermission for $name {
+                    const PERMISSION: Permission = Permission::$name;
+                }
+            )*
+        };
+    }
+
+    permission_markers!(
+        RoomsWrite,
+        BookingsWrite,
+        BookingsReadAll,
+        BookingsManage,
+        ReportsRead,
+        UsersRead,
+        UsersWrite,
+        RolesManage,
+        AdminsManage,
+        AuditRead,
+    );
+}
+
+// ประเภทบัญชีของผู้ที่ login (ตรงกับ `role` ใน JWT และเป็น Role พื้นฐานที่ได้รับเสมอ)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum PrincipalKind {
+    User,
+    Admin,
+}
+
+impl PrincipalKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PrincipalKind::User => "user",
+            PrincipalKind::Admin => "admin",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "user" => Some(PrincipalKind::User),
+            "admin" => Some(PrincipalKind::Admin),
+            _ => None,
+        }
+    }
+}
+
+// Role พร้อม Permission ทั้งหมดของ Role นั้น (GET /admin/roles)
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RoleResponse {
+    pub name: String,
+    pub description: String,
+    pub permissions: Vec<Permission>,
+    pub require_mfa: bool, // บัญชีที่มี Role นี้ต้องใช้ 2FA ตอน Login
+}
+
+// Role เพิ่มเติมที่ Admin กำหนดให้บัญชี (นอกเหนือจาก Role พื้นฐานตามประเภทบัญชี)
+#[derive(Debug, Insertable)]
+#[diesel(table_name = role_assignments)]
+pub struct NewRoleAssignment<'a> {
+    pub principal_kind: &'a str,
+    pub principal_id: i32,
+    pub role_name: &'a str,
+}
+
+#[derive(Debug, Clone, Deserialize, ToSchema, Validate)]
+pub struct AssignRoleRequest {
+    #[validate(custom(function = "not_blank"))]
+    pub role: String,
+}
+
+// Role และ Permission ที่มีผลของผู้ใช้หนึ่งคน
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct UserRolesResponse {
+    pub user_id: i32,
+    pub base_role: String,
+    pub roles: Vec<String>,
+    pub permissions: Vec<Permission>,
+}
+
+// GroupRoleMap: Role ในระบบที่ได้จากกลุ่มในระบบยืนยันตัวตนภายนอก (OIDC/LDAP)
+// ตั้งค่าในรูปแบบ `group=role,group=role` (กลุ่มเดียวกันกำหนดได้หลาย Role)
+#[derive(Debug, Clone, Default)]
+pub struct GroupRoleMap(Vec<(String, String)>);
+
+impl GroupRoleMap {
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut mappings = Vec::new();
+        for entry in spec.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let invalid = || format!("Invalid group role mapping '{}': expected group=role", entry);
+            let (group, role) = entry.split_once('=').ok_or_else(invalid)?;
+            let (group, role) = (group.trim(), role.trim());
+            if group.is_empty() || role.is_empty() {
+                return Err(invalid());
+            }
+            mappings.push((group.to_string(), role.to_string()));
+        }
+        Ok(GroupRoleMap(mappings))
+    }
+
+    // Role ที่ตรงกับกลุ่มของผู้ใช้ (ไม่รวม Role พื้นฐาน "user" ซึ่งได้รับเสมออยู่แล้ว)
+    pub fn roles_for(&self, groups: &[String]) -> Vec<String> {
+        let mut roles: Vec<String> = self
+            .0
+            .iter()
+            .filter(|(group, _)| groups.contains(group))
+            .map(|(_, role)| role.clone())
+            .filter(|role| role != PrincipalKind::User.as_str())
+            .collect();
+        roles.sort();
+        roles.dedup();
+        roles
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn permission_names_round_trip() {
+        for permission in Permission::ALL {
+            assert_eq!(Permission::parse(permission.as_str()), Some(permission));
+            // ชื่อใน JSON ต้องตรงกับชื่อที่เก็บใน role_permissions
+            assert_eq!(serde_json::to_value(permission).unwrap(), permission.as_str());
+        }
+        assert_eq!(Permission::parse("rooms:delete"), None);
+    }
+
+    #[test]
+    fn group_role_map_parses_pairs() {
+        let map = GroupRoleMap::parse(" staff = room_manager , audit=auditor,staff=auditor ,").unwrap();
+        let groups = vec!["staff".to_string()];
+        assert_eq!(map.roles_for(&groups), vec!["auditor".to_string(), "room_manager".to_string()]);
+        assert!(map.roles_for(&["nobody".to_string()]).is_empty());
+        assert!(GroupRoleMap::parse("").unwrap().roles_for(&groups).is_empty());
+    }
+
+    #[test]
+    fn group_role_map_rejects_bad_entries_and_skips_base_role() {
+        assert!(GroupRoleMap::parse("staff").is_err());
+        assert!(GroupRoleMap::parse("=auditor").is_err());
+        assert!(GroupRoleMap::parse("staff=").is_err());
+        let map = GroupRoleMap::parse("staff=user").unwrap();
+        assert!(map.roles_for(&["staff".to_string()]).is_empty());
+    }
+
+    #[test]
+    fn principal_kinds_round_trip() {
+        for kind in [PrincipalKind::User, PrincipalKind::Admin] {
+            assert_eq!(PrincipalKind::parse(kind.as_str()), Some(kind));
+        }
+        assert_eq!(PrincipalKind::parse("root"), None);
+    }
+}
@@ -0,0 +1,89 @@
+use chrono::NaiveDateTime;
+use diesel::{Insertable, Queryable, Selectable};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::domain::booking::Booking;
+use crate::domain::login_throttle::LoginEvent;
+use crate::domain::user::UserResponse;
+use crate::domain::validation::not_blank;
+use crate::infrastructure::schema::erasure_requests;
+
+// username ของผู้ใช้ที่ถูกลบข้อมูลแล้ว ('#' ไม่ผ่าน username_chars จึงไม่ชนกับผู้ใช้ที่สมัครใหม่)
+pub fn erased_username(user_id: i32) -> String {
+    format!("erased#{user_id}")
+}
+
+// UserDataExport: ข้อมูลส่วนตัวทั้งหมดของผู้ใช้ (GET /users/me/export)
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct UserDataExport {
+    pub exported_at: NaiveDateTime,
+    pub profile: UserResponse,
+    pub roles: Vec<String>, // Role ที่ได้รับเพิ่ม (ไม่รวม Role พื้นฐาน user)
+    pub bookings: Vec<Booking>, // ทุกการจอง รวมที่ถูกยกเลิก
+    pub login_events: Vec<LoginEvent>,
+}
+
+// ErasureRequest: บันทึกการลบข้อมูลส่วนตัวหนึ่งครั้ง (Admin ดูได้ที่ GET /admin/erasure-requests)
+#[derive(Debug, Clone, Queryable, Selectable, Serialize, ToSchema)]
+#[diesel(table_name = erasure_requests)]
+pub struct ErasureRequest {
+    pub id: i32,
+    pub user_id: i32,
+    pub requested_by_kind: String, // 'user' = ผู้ใช้ขอเอง, 'admin' = Admin ดำเนินการ
+    pub requested_by_id: i32,
+    pub reason: Option<String>,
+    pub bookings_cancelled: i32, // การจองที่ยังไม่จบซึ่งถูกยกเลิก
+    pub bookings_retained: i32, // การจองเดิมที่เก็บไว้สำหรับรายงาน (ไม่ระบุตัวตนแล้ว)
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = erasure_requests)]
+pub struct NewErasureRequest<'a> {
+    pub user_id: i32,
+    pub requested_by_kind: &'a str,
+    pub requested_by_id: i32,
+    pub reason: Option<&'a str>,
+    pub bookings_cancelled: i32,
+    pub bookings_retained: i32,
+}
+
+// EraseAccountRequest: ผู้ใช้ขอลบข้อมูลของตัวเอง ต้องพิมพ์ username ซ้ำเพื่อยืนยัน
+#[derive(Debug, Clone, Deserialize, ToSchema, Validate)]
+pub struct EraseAccountRequest {
+    #[validate(custom(function = "not_blank"))]
+    pub confirm_username: String,
+    #[validate(length(max = 500))]
+    pub reason: Option<String>,
+}
+
+// EraseUserRequest: Admin ลบข้อมูลของผู้ใช้ (เช่น ตามคำขอที่ได้รับทางอื่น)
+#[derive(Debug, Clone, Default, Deserialize, ToSchema, Validate)]
+pub struct EraseUserRequest {
+    #[validate(length(max = 500))]
+    pub reason: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::validation::username_chars;
+
+    #[test]
+    fn erased_usernames_cannot_be_registered() {
+        assert_eq!(erased_username(42), "erased#42");
+        assert_ne!(erased_username(1), erased_username(11));
+        assert!(username_chars(&erased_username(42)).is_err());
+    }
+
+    #[test]
+    fn erasure_requests_are_validated() {
+        let confirm = |name: &str, reason: Option<String>| EraseAccountRequest { confirm_username: name.to_string(), reason };
+        assert!(confirm("peter", None).validate().is_ok());
+        assert!(confirm("  ", None).validate().is_err());
+        assert!(confirm("peter", Some("x".repeat(501))).validate().is_err());
+        assert!(EraseUserRequest { reason: Some("x".repeat(500)) }.validate().is_ok());
+    }
+}
@@ -0,0 +1,111 @@
+// src/domain/report.rs
+use chrono::NaiveDate;
+use diesel::QueryableByName;
+use diesel::sql_types::{BigInt, Double, Integer, Nullable, Text};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+// ReportPeriod: ช่วงเวลาที่ใช้จัดกลุ่มข้อมูลในรายงาน
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportPeriod {
+    #[default]
+    Day,
+    Week,
+    Month,
+}
+
+// ReportQuery: Query parameters ที่ใช้ร่วมกันในทุก report endpoint
+// from/to เป็นวันที่ (YYYY-MM-DD) โดย `to` นับรวมวันสุดท้ายด้วย
+#[derive(Debug, Clone, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ReportQuery {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    pub period: Option<ReportPeriod>,
+    pub room_id: Option<i32>,
+    pub limit: Option<i64>,
+}
+
+// RoomUtilization: ชั่วโมงที่ถูกจองเทียบกับชั่วโมงเปิดให้บริการ ต่อห้อง ต่อช่วงเวลา
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RoomUtilization {
+    pub room_id: i32,
+    pub room_name: String,
+    pub period_start: NaiveDate,
+    pub bookings: i64,
+    pub booked_hours: f64,
+    pub open_hours: f64,
+    pub utilization: f64, // booked_hours / open_hours (0.0 - 1.0)
+}
+
+// PeakHourCell: หนึ่งช่องของ heatmap (วันในสัปดาห์ x ชั่วโมง)
+// weekday: 0 = Sunday ... 6 = Saturday (ตาม strftime('%w') ของ SQLite)
+#[derive(Debug, Clone, Serialize, QueryableByName, ToSchema)]
+pub struct PeakHourCell {
+    #[diesel(sql_type = Integer)]
+    pub weekday: i32,
+    #[diesel(sql_type = Integer)]
+    pub hour: i32,
+    #[diesel(sql_type = BigInt)]
+    pub bookings: i64,
+}
+
+// BookingRates: อัตราการยกเลิกและการไม่มาใช้ห้อง (no-show) ต่อห้อง ต่อช่วงเวลา
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct BookingRates {
+    pub room_id: i32,
+    pub room_name: String,
+    pub period_start: NaiveDate,
+    pub total: i64,
+    pub cancelled: i64,
+    pub no_show: i64,
+    pub cancellation_rate: f64,
+    pub no_show_rate: f64,
+}
+
+// TopBooker: ผู้ใช้ที่จองมากที่สุดในช่วงเวลาที่เลือก
+#[derive(Debug, Clone, Serialize, QueryableByName, ToSchema)]
+pub struct TopBooker {
+    #[diesel(sql_type = Integer)]
+    pub user_id: i32,
+    #[diesel(sql_type = Text)]
+    pub username: String,
+    #[diesel(sql_type = BigInt)]
+    pub bookings: i64,
+    #[diesel(sql_type = Double)]
+    pub booked_hours: f64,
+}
+
+// UtilizationRow: ผลลัพธ์ดิบจาก SQL ก่อนคำนวณ open_hours
+// period_start เป็น NULL สำหรับห้องที่ไม่มีการจองเลยในช่วงที่เลือก (มาจาก LEFT JOIN)
+#[derive(Debug, Clone, QueryableByName)]
+pub struct UtilizationRow {
+    #[diesel(sql_type = Integer)]
+    pub room_id: i32,
+    #[diesel(sql_type = Text)]
+    pub room_name: String,
+    #[diesel(sql_type = Nullable<Text>)]
+    pub period_start: Option<String>,
+    #[diesel(sql_type = BigInt)]
+    pub bookings: i64,
+    #[diesel(sql_type = Double)]
+    pub booked_hours: f64,
+}
+
+// BookingRatesRow: ผลลัพธ์ดิบจาก SQL สำหรับ BookingRates
+#[derive(Debug, Clone, QueryableByName)]
+pub struct BookingRatesRow {
+    #[diesel(sql_type = Integer)]
+    pub room_id: i32,
+    #[diesel(sql_type = Text)]
+    pub room_name: String,
+    #[diesel(sql_type = Text)]
+    pub period_start: String,
+    #[diesel(sql_type = BigInt)]
+    pub total: i64,
+    #[diesel(sql_type = BigInt)]
+    pub cancelled: i64,
+    #[diesel(sql_type = BigInt)]
+    pub no_show: i64,
+}
@@ -1,11 +1,14 @@
 use chrono::NaiveDateTime;
 use diesel::{prelude::AsChangeset, Insertable, Queryable, Selectable};
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
 
+use crate::domain::validation::{not_blank, room_status};
 use crate::infrastructure::schema::rooms;
 // Room: Entity ที่แทนข้อมูลผู้ใช้ในฐานข้อมูล (เมื่อดึงออกมาหรือบันทึกเสร็จแล้ว)
 // มี Field ครบทุกคอลัมน์ในตาราง users
-#[derive(Debug, Clone, Serialize, Deserialize, Queryable, Selectable)]
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable, Selectable, ToSchema)]
 #[diesel(table_name = rooms)]
 pub struct Room {
     pub id: i32,
@@ -14,14 +17,18 @@ pub struct Room {
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime, 
     pub deleted_at: Option<NaiveDateTime>,
+    pub organization_id: i32, // องค์กรเจ้าของห้อง
 }
 
 // AddURoomRequest: Struct ที่ใช้รับข้อมูลจาก Client สำหรับการลงทะเบียน
 // มี สถานะไม่มี id เพราะ DB จะสร้างให้
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, Validate)]
 pub struct AddRoomRequest {
+    #[validate(length(max = 100), custom(function = "not_blank"))]
     pub name: String,
-    pub status: String, // <<-- สถานะ จาก client
+    #[validate(custom(function = "room_status"))]
+    pub status: String, // <<-- สถานะ จาก client (available, booked, maintenance)
+    pub organization_id: Option<i32>, // ใช้ได้เฉพาะ Platform Admin (ไม่ระบุ = องค์กรเริ่มต้น) Admin ขององค์กรสร้างได้ในองค์กรตัวเองเท่านั้น
 }
 
 // NewRoom: Struct ที่ใช้สำหรับการ INSERT ข้อมูลใหม่ลงในฐานข้อมูล
@@ -31,6 +38,7 @@ pub struct AddRoomRequest {
 pub struct NewRoom<'a> {
     pub name: &'a str,
     pub status: &'a str, // <<-- สถานะห้อง
+    pub organization_id: i32,
 }
 
 
@@ -45,8 +53,10 @@ pub struct RoomChangeset {
     pub deleted_at: Option<NaiveDateTime>, // ถ้าต้องการ update deleted_at
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, Validate)]
 pub struct UpdateRoomRequest{
+    #[validate(length(max = 100), custom(function = "not_blank"))]
     pub name:Option<String>,
+    #[validate(custom(function = "room_status"))]
     pub status:Option<String>
 }
\ No newline at end of file
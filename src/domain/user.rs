@@ -1,28 +1,206 @@
+use std::fmt;
+
 use chrono::NaiveDateTime;
-use diesel::{Insertable, Queryable};
+use diesel::{AsChangeset, Insertable, Queryable};
 use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
 
+use crate::domain::validation::{
+    language_tag, not_blank, optional_email, phone_number, timezone_name, username_chars, PASSWORD_MAX_LENGTH, PASSWORD_MIN_LENGTH, USERNAME_MAX_LENGTH,
+    USERNAME_MIN_LENGTH,
+};
 use crate::infrastructure::schema::users;
 
+// ค่าที่แสดงแทน password_hash ใน Debug
+pub const REDACTED_HASH: &str = "[REDACTED]";
+
 // User: Entity ที่แทนข้อมูลผู้ใช้ในฐานข้อมูล (เมื่อดึงออกมาหรือบันทึกเสร็จแล้ว)
-// มี Field ครบทุกคอลัมน์ในตาราง users
-#[derive(Debug, Clone, Queryable, Serialize, Deserialize)]
+// มี Field ครบทุกคอลัมน์ในตาราง users (ไม่ derive Serialize เพื่อไม่ให้ password_hash หลุดออกไปใน Response ใช้ UserResponse แทน)
+// Debug เขียนเองเพื่อไม่ให้ password_hash หลุดไปใน Log เมื่อพิมพ์ด้วย {:?}
+#[derive(Clone, Queryable)]
 #[diesel(table_name = users)]
 pub struct User {
     pub id: i32,
     pub username: String,
-    pub password_hash: String, // <<-- รหัสผ่านที่ถูก Hash แล้ว
+    pub password_hash: Option<String>, // <<-- รหัสผ่านที่ถูก Hash แล้ว (None = Login ผ่าน OIDC/LDAP เท่านั้น)
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime, // <<-- ถ้ามี updated_at ใน DB
-    pub deleted_at: Option<NaiveDateTime>
+    pub deleted_at: Option<NaiveDateTime>,
+    pub display_name: Option<String>, // ชื่อที่แสดง (ซิงก์จาก OIDC/LDAP ทุกครั้งที่ Login)
+    pub directory_groups: Option<String>, // กลุ่มจาก OIDC/LDAP (เก็บเป็น JSON array, None = ผู้ใช้ในระบบ)
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    pub department: Option<String>,
+    pub timezone: Option<String>, // ชื่อ Time Zone ของ IANA เช่น Asia/Bangkok
+    pub language: Option<String>, // Language Tag เช่น th, en-US
+    pub email_verified_at: Option<NaiveDateTime>,
+    pub email_verification_pending: bool, // true = จองห้องไม่ได้จนกว่าจะยืนยันอีเมลหรือ Admin อนุมัติแทน
+    pub erased_at: Option<NaiveDateTime>, // ข้อมูลส่วนตัวถูกลบถาวร (username เหลือเพียง erased#<id>)
+    pub organization_id: i32,
+}
+
+impl fmt::Debug for User {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("User")
+            .field("id", &self.id)
+            .field("username", &self.username)
+            .field("password_hash", &self.password_hash.as_ref().map(|_| REDACTED_HASH))
+            .field("created_at", &self.created_at)
+            .field("updated_at", &self.updated_at)
+            .field("deleted_at", &self.deleted_at)
+            .field("display_name", &self.display_name)
+            .field("directory_groups", &self.directory_groups)
+            .field("email", &self.email)
+            .field("phone", &self.phone)
+            .field("department", &self.department)
+            .field("timezone", &self.timezone)
+            .field("language", &self.language)
+            .field("email_verified_at", &self.email_verified_at)
+            .field("email_verification_pending", &self.email_verification_pending)
+            .field("erased_at", &self.erased_at)
+            .field("organization_id", &self.organization_id)
+            .finish()
+    }
+}
+
+// UserResponse: ข้อมูลผู้ใช้ที่ส่งกลับให้ Client (ไม่มี password_hash)
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct UserResponse {
+    pub id: i32,
+    pub username: String,
+    pub organization_id: i32,
+    pub display_name: Option<String>,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    pub department: Option<String>,
+    pub timezone: Option<String>,
+    pub language: Option<String>,
+    pub has_password: bool, // false = Login ผ่าน OIDC/LDAP เท่านั้น
+    pub email_verified_at: Option<NaiveDateTime>,
+    pub email_verification_pending: bool,
+    pub directory_groups: Option<Vec<String>>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub deleted_at: Option<NaiveDateTime>,
+    pub erased_at: Option<NaiveDateTime>,
+}
+
+impl From<User> for UserResponse {
+    fn from(user: User) -> Self {
+        UserResponse {
+            id: user.id,
+            username: user.username,
+            organization_id: user.organization_id,
+            display_name: user.display_name,
+            email: user.email,
+            phone: user.phone,
+            department: user.department,
+            timezone: user.timezone,
+            language: user.language,
+            has_password: user.password_hash.is_some(),
+            email_verified_at: user.email_verified_at,
+            email_verification_pending: user.email_verification_pending,
+            directory_groups: user
+                .directory_groups
+                .map(|json| serde_json::from_str(&json).unwrap_or_default()),
+            created_at: user.created_at,
+            updated_at: user.updated_at,
+            deleted_at: user.deleted_at,
+            erased_at: user.erased_at,
+        }
+    }
+}
+
+// UpdateProfileRequest: แก้ไขโปรไฟล์ของตัวเอง (PATCH /users/me)
+// Field ที่ไม่ส่งมาจะไม่เปลี่ยน ส่งเป็น "" เพื่อล้างค่า
+#[derive(Debug, Clone, Deserialize, ToSchema, Validate)]
+pub struct UpdateProfileRequest {
+    #[validate(length(max = 100))]
+    pub display_name: Option<String>,
+    #[validate(length(max = 255), custom(function = "optional_email"))]
+    pub email: Option<String>,
+    #[validate(length(max = 32), custom(function = "phone_number"))]
+    pub phone: Option<String>,
+    #[validate(length(max = 100))]
+    pub department: Option<String>,
+    #[validate(custom(function = "timezone_name"))]
+    pub timezone: Option<String>,
+    #[validate(custom(function = "language_tag"))]
+    pub language: Option<String>,
+}
+
+impl UpdateProfileRequest {
+    // ชื่อ Field ที่ส่งมาแก้ไข (บันทึกใน Audit Log แทนค่าจริงซึ่งเป็นข้อมูลส่วนตัว)
+    pub fn changed_fields(&self) -> Vec<&'static str> {
+        [
+            ("display_name", self.display_name.is_some()),
+            ("email", self.email.is_some()),
+            ("phone", self.phone.is_some()),
+            ("department", self.department.is_some()),
+            ("timezone", self.timezone.is_some()),
+            ("language", self.language.is_some()),
+        ]
+        .into_iter()
+        .filter_map(|(field, changed)| changed.then_some(field))
+        .collect()
+    }
+}
+
+// ProfileChangeset: Struct สำหรับ UPDATE โปรไฟล์ (None = ไม่เปลี่ยน, Some(None) = ล้างค่า)
+#[derive(Debug, Clone, AsChangeset)]
+#[diesel(table_name = users)]
+pub struct ProfileChangeset {
+    pub display_name: Option<Option<String>>,
+    pub email: Option<Option<String>>,
+    pub phone: Option<Option<String>>,
+    pub department: Option<Option<String>>,
+    pub timezone: Option<Option<String>>,
+    pub language: Option<Option<String>>,
+    pub email_verified_at: Option<Option<NaiveDateTime>>, // ล้างเมื่ออีเมลเปลี่ยน
+    pub updated_at: NaiveDateTime,
+}
+
+// ค่าที่ส่งมาเป็น "" (หรือมีแต่ช่องว่าง) หมายถึงล้างค่า
+fn clearable(value: Option<String>) -> Option<Option<String>> {
+    value.map(|v| Some(v.trim().to_string()).filter(|v| !v.is_empty()))
+}
+
+// อีเมลเก็บเป็นตัวพิมพ์เล็กเสมอ
+pub fn normalize_email(email: &str) -> Option<String> {
+    Some(email.trim().to_lowercase()).filter(|e| !e.is_empty())
+}
+
+impl ProfileChangeset {
+    // current_email: อีเมลก่อนแก้ไข (ถ้าเปลี่ยน ต้องยืนยันอีเมลใหม่)
+    pub fn new(request: UpdateProfileRequest, current_email: Option<&str>, now: NaiveDateTime) -> Self {
+        let email = request.email.as_deref().map(normalize_email);
+        let email_changed = email.as_ref().is_some_and(|email| email.as_deref() != current_email);
+        ProfileChangeset {
+            display_name: clearable(request.display_name),
+            email,
+            phone: clearable(request.phone),
+            department: clearable(request.department),
+            timezone: clearable(request.timezone),
+            language: clearable(request.language),
+            email_verified_at: email_changed.then_some(None),
+            updated_at: now,
+        }
+    }
 }
 
 // RegisterUserRequest: Struct ที่ใช้รับข้อมูลจาก Client สำหรับการลงทะเบียน
 // มีรหัสผ่านดิบ (Plain Text) และไม่มี id เพราะ DB จะสร้างให้
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, Validate)]
 pub struct RegisterUserRequest {
+    #[validate(length(min = USERNAME_MIN_LENGTH, max = USERNAME_MAX_LENGTH), custom(function = "username_chars"))]
     pub username: String,
+    #[validate(length(min = PASSWORD_MIN_LENGTH, max = PASSWORD_MAX_LENGTH))]
     pub password: String, // <<-- รหัสผ่านดิบ (Plain Text) จาก Client
+    #[validate(length(max = 255), custom(function = "optional_email"))]
+    pub email: Option<String>, // จำเป็นเมื่อเปิด EMAIL_VERIFICATION_REQUIRED
+    #[validate(length(max = 64))]
+    pub organization: Option<String>, // slug ขององค์กรที่เปิดให้สมัครเอง (ไม่ระบุ = องค์กรเริ่มต้น)
 }
 
 // NewUser: Struct ที่ใช้สำหรับการ INSERT ข้อมูลใหม่ลงในฐานข้อมูล
@@ -31,7 +209,17 @@ pub struct RegisterUserRequest {
 #[diesel(table_name = users)]
 pub struct NewUser<'a> {
     pub username: &'a str,
-    pub password_hash: &'a str, 
+    pub password_hash: Option<&'a str>,
+    pub email: Option<&'a str>,
+    pub email_verification_pending: bool,
+    pub organization_id: i32,
+}
+
+// VerifyEmailRequest: ยืนยันอีเมลด้วย Token จากลิงก์ที่ส่งทาง Notifier
+#[derive(Debug, Clone, Deserialize, ToSchema, Validate)]
+pub struct VerifyEmailRequest {
+    #[validate(custom(function = "not_blank"))]
+    pub token: String,
 }
 
 // LoginCredentials: Struct ที่ใช้รับข้อมูลจาก Client สำหรับการ Login
@@ -40,3 +228,70 @@ pub struct LoginCredentials {
     pub username: String,
     pub password: String, // <<-- รหัสผ่านดิบ (Plain Text) จาก Client
 }
+
+// FutureBookingsPolicy: การจองที่ยังไม่จบของผู้ใช้ที่ถูกลบ
+// cancel (ค่าเริ่มต้น) ยกเลิกและคืนห้อง, transfer โอนให้ผู้ใช้ใน transfer_to, keep เก็บไว้ตามเดิม
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum FutureBookingsPolicy {
+    #[default]
+    Cancel,
+    Transfer,
+    Keep,
+}
+
+// DeleteUserQuery: Query parameters ของ DELETE /admin/users/:user_id
+#[derive(Debug, Clone, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DeleteUserQuery {
+    pub future_bookings: Option<FutureBookingsPolicy>,
+    pub transfer_to: Option<i32>, // ต้องระบุเมื่อ future_bookings=transfer
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user(password_hash: Option<&str>) -> User {
+        let now = chrono::Utc::now().naive_utc();
+        User {
+            id: 7,
+            username: "alice".to_string(),
+            password_hash: password_hash.map(str::to_string),
+            created_at: now,
+            updated_at: now,
+            deleted_at: None,
+            display_name: None,
+            directory_groups: Some(r#"["staff"]"#.to_string()),
+            email: Some("alice@example.com".to_string()),
+            phone: None,
+            department: None,
+            timezone: None,
+            language: None,
+            email_verified_at: None,
+            email_verification_pending: false,
+            erased_at: None,
+            organization_id: 1,
+        }
+    }
+
+    #[test]
+    fn debug_redacts_the_password_hash() {
+        let hash = "$2b$12$abcdefghijklmnopqrstuvwxyz0123456789";
+        let printed = format!("{:?}", user(Some(hash)));
+        assert!(!printed.contains(hash));
+        assert!(printed.contains(REDACTED_HASH));
+        assert!(printed.contains("alice"));
+        assert!(format!("{:?}", user(None)).contains("password_hash: None"));
+    }
+
+    #[test]
+    fn response_hides_the_hash_and_decodes_groups() {
+        let response = UserResponse::from(user(Some("hash")));
+        assert!(response.has_password);
+        assert_eq!(response.directory_groups, Some(vec!["staff".to_string()]));
+        let json = serde_json::to_value(&response).unwrap();
+        assert!(json.get("password_hash").is_none());
+    }
+}
@@ -0,0 +1,97 @@
+// src/domain/validation.rs
+// กฎตรวจสอบข้อมูลแบบกำหนดเองที่ใช้ร่วมกันใน Request ของ Domain (ใช้ผ่าน #[validate(custom(...))])
+use std::str::FromStr;
+
+use validator::{ValidateEmail, ValidationError};
+
+// สถานะห้องที่ Client กำหนดได้
+pub const ROOM_STATUSES: &[&str] = &["available", "booked", "maintenance"];
+
+pub const USERNAME_MIN_LENGTH: u64 = 3;
+pub const USERNAME_MAX_LENGTH: u64 = 50;
+pub const PASSWORD_MIN_LENGTH: u64 = 8;
+pub const PASSWORD_MAX_LENGTH: u64 = 128;
+
+// ห้ามเป็นค่าว่างหรือมีแต่ช่องว่าง
+pub fn not_blank(value: &str) -> Result<(), ValidationError> {
+    if value.trim().is_empty() {
+        return Err(ValidationError::new("blank").with_message("must not be blank".into()));
+    }
+    Ok(())
+}
+
+// username: ตัวอักษร ตัวเลข และ . _ - เท่านั้น
+pub fn username_chars(value: &str) -> Result<(), ValidationError> {
+    if !value.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-')) {
+        return Err(ValidationError::new("invalid_characters")
+            .with_message("may only contain letters, digits, '.', '_' and '-'".into()));
+    }
+    Ok(())
+}
+
+// slug ขององค์กร: ตัวพิมพ์เล็ก ตัวเลข และ - เท่านั้น
+pub fn slug_chars(value: &str) -> Result<(), ValidationError> {
+    if !value.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-') {
+        return Err(ValidationError::new("invalid_characters")
+            .with_message("may only contain lowercase letters, digits and '-'".into()));
+    }
+    Ok(())
+}
+
+// username ที่มาจากระบบภายนอก (OIDC/LDAP) ต้องผ่านกฎเดียวกับตอนสมัคร
+pub fn is_valid_username(value: &str) -> bool {
+    let length = value.chars().count() as u64;
+    (USERNAME_MIN_LENGTH..=USERNAME_MAX_LENGTH).contains(&length) && username_chars(value).is_ok()
+}
+
+// ค่าว่างใช้ล้างค่าได้ (ดู UpdateProfileRequest)
+pub fn optional_email(value: &str) -> Result<(), ValidationError> {
+    if !value.trim().is_empty() && !value.trim().validate_email() {
+        return Err(ValidationError::new("email").with_message("must be a valid email address".into()));
+    }
+    Ok(())
+}
+
+// เบอร์โทร: ตัวเลข ช่องว่าง และ + - ( ) เท่านั้น
+pub fn phone_number(value: &str) -> Result<(), ValidationError> {
+    if !value.chars().all(|c| c.is_ascii_digit() || matches!(c, ' ' | '+' | '-' | '(' | ')')) {
+        return Err(ValidationError::new("invalid_characters")
+            .with_message("may only contain digits, spaces, '+', '-', '(' and ')'".into()));
+    }
+    Ok(())
+}
+
+// ชื่อ Time Zone ในฐานข้อมูลของ IANA เช่น Asia/Bangkok
+pub fn timezone_name(value: &str) -> Result<(), ValidationError> {
+    if !value.trim().is_empty() && chrono_tz::Tz::from_str(value.trim()).is_err() {
+        return Err(ValidationError::new("timezone")
+            .with_message("must be an IANA time zone name such as Asia/Bangkok".into()));
+    }
+    Ok(())
+}
+
+// Language Tag แบบ BCP 47 อย่างง่าย เช่น th, en-US, zh-Hant-TW
+pub fn language_tag(value: &str) -> Result<(), ValidationError> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Ok(());
+    }
+    let mut parts = value.split('-');
+    let primary_ok = parts
+        .next()
+        .is_some_and(|p| (2..=3).contains(&p.len()) && p.chars().all(|c| c.is_ascii_alphabetic()));
+    let rest_ok = parts.all(|p| (1..=8).contains(&p.len()) && p.chars().all(|c| c.is_ascii_alphanumeric()));
+    if value.len() > 35 || !primary_ok || !rest_ok {
+        return Err(ValidationError::new("language")
+            .with_message("must be a language tag such as th or en-US".into()));
+    }
+    Ok(())
+}
+
+pub fn room_status(value: &str) -> Result<(), ValidationError> {
+    if !ROOM_STATUSES.contains(&value) {
+        return Err(ValidationError::new("one_of")
+            .with_message(format!("must be one of: {}", ROOM_STATUSES.join(", ")).into()));
+    }
+    Ok(())
+}
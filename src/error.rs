@@ -0,0 +1,303 @@
+// src/error.rs
+
+use axum::{
+    http::{header, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+// AppError: Error กลางที่ใช้ร่วมกันทุก Layer (Repository -> Service -> Handler)
+// แต่ละ variant ถูกแปลงเป็น HTTP status และ body แบบ RFC 7807 (application/problem+json)
+// ข้อความใน Internal จะถูกบันทึกลง log เท่านั้น ไม่ถูกส่งกลับไปให้ Client
+// InvalidFields ใช้กับ Request Body ที่ไม่ผ่านการตรวจสอบ โดยส่ง error ของทุก field กลับไปพร้อมกัน
+// TooManyRequests มีจำนวนวินาทีที่ต้องรอ ส่งกลับใน Header Retry-After
+// BadGateway ใช้เมื่อบริการภายนอก (เช่น Identity Provider) ตอบผิดพลาดหรือติดต่อไม่ได้
+// BadRequest ใช้กับ Body ที่ไม่ใช่ JSON ที่ถูกต้อง และ UnsupportedMediaType เมื่อไม่ได้ส่งเป็น application/json
+#[derive(Debug)]
+pub enum AppError {
+    NotFound(String),
+    Conflict(String),
+    Validation(String),
+    BadRequest(String),
+    UnsupportedMediaType(String),
+    InvalidFields(Vec<FieldError>),
+    Unauthorized(String),
+    Forbidden(String),
+    TooManyRequests(String, u64),
+    BadGateway(String),
+    Internal(String),
+}
+
+// ProblemDetails: Body ตาม RFC 7807 พร้อม `code` ที่คงที่สำหรับให้ Client ใช้ตรวจสอบ
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ProblemDetails {
+    #[serde(rename = "type")]
+    pub problem_type: String,
+    pub title: &'static str,
+    pub status: u16,
+    pub detail: String,
+    pub code: &'static str,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<FieldError>,
+}
+
+// FieldError: Error ของ field เดียวใน Request Body
+// `code` มาจากชื่อกฎที่ไม่ผ่าน (เช่น length, range, end_before_start)
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct FieldError {
+    pub field: String,
+    pub code: String,
+    pub message: String,
+}
+
+impl AppError {
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            AppError::NotFound(_) => StatusCode::NOT_FOUND,
+            AppError::Conflict(_) => StatusCode::CONFLICT,
+            AppError::Validation(_) | AppError::InvalidFields(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            AppError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            AppError::UnsupportedMediaType(_) => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            AppError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            AppError::Forbidden(_) => StatusCode::FORBIDDEN,
+            AppError::TooManyRequests(..) => StatusCode::TOO_MANY_REQUESTS,
+            AppError::BadGateway(_) => StatusCode::BAD_GATEWAY,
+            AppError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    // Error code ที่คงที่ ห้ามเปลี่ยนค่าเพราะ Client อาจใช้อ้างอิง
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::NotFound(_) => "not_found",
+            AppError::Conflict(_) => "conflict",
+            AppError::Validation(_) | AppError::InvalidFields(_) => "validation_failed",
+            AppError::BadRequest(_) => "bad_request",
+            AppError::UnsupportedMediaType(_) => "unsupported_media_type",
+            AppError::Unauthorized(_) => "unauthorized",
+            AppError::Forbidden(_) => "forbidden",
+            AppError::TooManyRequests(..) => "too_many_requests",
+            AppError::BadGateway(_) => "bad_gateway",
+            AppError::Internal(_) => "internal_error",
+        }
+    }
+
+    fn title(&self) -> &'static str {
+        match self {
+            AppError::NotFound(_) => "Not Found",
+            AppError::Conflict(_) => "Conflict",
+            AppError::Validation(_) | AppError::InvalidFields(_) => "Validation Failed",
+            AppError::BadRequest(_) => "Bad Request",
+            AppError::UnsupportedMediaType(_) => "Unsupported Media Type",
+            AppError::Unauthorized(_) => "Unauthorized",
+            AppError::Forbidden(_) => "Forbidden",
+            AppError::TooManyRequests(..) => "Too Many Requests",
+            AppError::BadGateway(_) => "Bad Gateway",
+            AppError::Internal(_) => "Internal Server Error",
+        }
+    }
+
+    pub fn to_problem(&self) -> ProblemDetails {
+        let detail = match self {
+            AppError::Internal(_) => "An internal error occurred.".to_string(),
+            AppError::InvalidFields(errors) => format!("Request body has {} validation error(s).", errors.len()),
+            AppError::NotFound(msg)
+            | AppError::Conflict(msg)
+            | AppError::Validation(msg)
+            | AppError::BadRequest(msg)
+            | AppError::UnsupportedMediaType(msg)
+            | AppError::Unauthorized(msg)
+            | AppError::Forbidden(msg)
+            | AppError::TooManyRequests(msg, _)
+            | AppError::BadGateway(msg) => msg.clone(),
+        };
+        ProblemDetails {
+            problem_type: format!("/problems/{}", self.code()),
+            title: self.title(),
+            status: self.status_code().as_u16(),
+            detail,
+            code: self.code(),
+            errors: match self {
+                AppError::InvalidFields(errors) => errors.clone(),
+                _ => Vec::new(),
+            },
+        }
+    }
+}
+
+impl std::fmt::Display for AppError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AppError::NotFound(msg) => write!(f, "Not found: {}", msg),
+            AppError::Conflict(msg) => write!(f, "Conflict: {}", msg),
+            AppError::Validation(msg) => write!(f, "Validation failed: {}", msg),
+            AppError::BadRequest(msg) => write!(f, "Bad request: {}", msg),
+            AppError::UnsupportedMediaType(msg) => write!(f, "Unsupported media type: {}", msg),
+            AppError::InvalidFields(errors) => {
+                let fields: Vec<&str> = errors.iter().map(|e| e.field.as_str()).collect();
+                write!(f, "Validation failed: {}", fields.join(", "))
+            }
+            AppError::Unauthorized(msg) => write!(f, "Unauthorized: {}", msg),
+            AppError::Forbidden(msg) => write!(f, "Forbidden: {}", msg),
+            AppError::TooManyRequests(msg, _) => write!(f, "Too many requests: {}", msg),
+            AppError::BadGateway(msg) => write!(f, "Bad gateway: {}", msg),
+            AppError::Internal(msg) => write!(f, "Internal error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl IntoResponse for AppError {
+    fn into_response(self) -> Response {
+        if let AppError::Internal(msg) = &self {
+            tracing::error!(error = %msg, "internal error");
+        }
+        let status = self.status_code();
+        let mut response = (
+            status,
+            [(header::CONTENT_TYPE, "application/problem+json")],
+            Json(self.to_problem()),
+        )
+            .into_response();
+        if let AppError::TooManyRequests(_, retry_after) = &self {
+            response.headers_mut().insert(header::RETRY_AFTER, HeaderValue::from(*retry_after));
+        }
+        response
+    }
+}
+
+impl From<diesel::result::Error> for AppError {
+    fn from(error: diesel::result::Error) -> Self {
+        use diesel::result::{DatabaseErrorKind, Error};
+        match error {
+            Error::NotFound => AppError::NotFound("Resource not found".to_string()),
+            Error::DatabaseError(DatabaseErrorKind::UniqueViolation, _) => {
+                AppError::Conflict("Resource already exists".to_string())
+            }
+            e => AppError::Internal(format!("Database error: {}", e)),
+        }
+    }
+}
+
+impl From<r2d2::Error> for AppError {
+    fn from(error: r2d2::Error) -> Self {
+        AppError::Internal(format!("Failed to get DB connection: {}", error))
+    }
+}
+
+impl From<bcrypt::BcryptError> for AppError {
+    fn from(error: bcrypt::BcryptError) -> Self {
+        AppError::Internal(format!("Password hashing error: {}", error))
+    }
+}
+
+// แปลง ValidationErrors ของ validator ให้เป็นรายการ FieldError แบบแบน
+// field ที่ซ้อนกันใช้ชื่อแบบ `parent.child` และรายการใช้ `items[0]`
+// error ระดับ struct (schema) จะใช้ชื่อ field จาก param `field` ถ้ามี
+impl From<validator::ValidationErrors> for AppError {
+    fn from(errors: validator::ValidationErrors) -> Self {
+        let mut fields = Vec::new();
+        collect_field_errors(None, &errors, &mut fields);
+        fields.sort_by(|a, b| a.field.cmp(&b.field));
+        AppError::InvalidFields(fields)
+    }
+}
+
+fn collect_field_errors(prefix: Option<&str>, errors: &validator::ValidationErrors, out: &mut Vec<FieldError>) {
+    use validator::ValidationErrorsKind;
+
+    let path = |name: &str| match prefix {
+        Some(prefix) => format!("{}.{}", prefix, name),
+        None => name.to_string(),
+    };
+    for (name, kind) in errors.errors() {
+        match kind {
+            ValidationErrorsKind::Field(field_errors) => {
+                for error in field_errors {
+                    let field = match error.params.get("field").and_then(|v| v.as_str()) {
+                        Some(field) if name == "__all__" => path(field),
+                        _ => path(name),
+                    };
+                    out.push(FieldError {
+                        field,
+                        code: error.code.to_string(),
+                        message: error
+                            .message
+                            .as_ref()
+                            .map(|m| m.to_string())
+                            .unwrap_or_else(|| default_message(error)),
+                    });
+                }
+            }
+            ValidationErrorsKind::Struct(nested) => collect_field_errors(Some(&path(name)), nested, out),
+            ValidationErrorsKind::List(items) => {
+                for (index, nested) in items {
+                    collect_field_errors(Some(&format!("{}[{}]", path(name), index)), nested, out);
+                }
+            }
+        }
+    }
+}
+
+// ข้อความเริ่มต้นสำหรับกฎมาตรฐานที่ไม่ได้กำหนด message ไว้ (length, range)
+fn default_message(error: &validator::ValidationError) -> String {
+    let param = |name: &str| error.params.get(name).map(|v| v.to_string());
+    match (error.code.as_ref(), param("min"), param("max")) {
+        ("length", Some(min), Some(max)) => format!("must be between {} and {} characters", min, max),
+        ("length", Some(min), None) => format!("must be at least {} characters", min),
+        ("length", None, Some(max)) => format!("must be at most {} characters", max),
+        ("range", Some(min), Some(max)) => format!("must be between {} and {}", min, max),
+        ("range", Some(min), None) => format!("must be at least {}", min),
+        ("range", None, Some(max)) => format!("must be at most {}", max),
+        (code, _, _) => format!("is invalid ({})", code),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn internal_details_are_not_sent_to_client() {
+        let problem = AppError::Internal("Database error: disk I/O at /var/db".to_string()).to_problem();
+        assert_eq!(problem.status, 500);
+        assert_eq!(problem.code, "internal_error");
+        assert_eq!(problem.detail, "An internal error occurred.");
+    }
+
+    #[test]
+    fn problem_response_has_content_type_and_retry_after() {
+        let response = AppError::TooManyRequests("Slow down".to_string(), 42).into_response();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "application/problem+json");
+        assert_eq!(response.headers()[header::RETRY_AFTER], "42");
+    }
+
+    #[test]
+    fn diesel_errors_map_to_stable_codes() {
+        use diesel::result::{DatabaseErrorKind, Error};
+        assert_eq!(AppError::from(Error::NotFound).code(), "not_found");
+        let unique = Error::DatabaseError(DatabaseErrorKind::UniqueViolation, Box::new("users.username".to_string()));
+        assert_eq!(AppError::from(unique).code(), "conflict");
+        assert_eq!(AppError::from(Error::RollbackTransaction).code(), "internal_error");
+    }
+
+    #[test]
+    fn field_errors_are_flattened_and_sorted() {
+        let mut errors = validator::ValidationErrors::new();
+        errors.add("username", validator::ValidationError::new("length").with_message("too short".into()));
+        let mut range = validator::ValidationError::new("range");
+        range.add_param("min".into(), &1);
+        errors.add("room_id", range);
+        let AppError::InvalidFields(fields) = AppError::from(errors) else {
+            panic!("expected InvalidFields");
+        };
+        assert_eq!(fields[0].field, "room_id");
+        assert_eq!(fields[0].message, "must be at least 1");
+        assert_eq!(fields[1].field, "username");
+        assert_eq!(fields[1].code, "length");
+    }
+}
@@ -1,8 +1,13 @@
-use super::schema::admins;
-use crate::domain::admin::Admin;
+use super::audit_repository::AuditRepository;
+use super::schema::{admin_invitations, admins, organizations};
+use crate::domain::audit::PendingAudit;
+use crate::domain::organization::Tenant;
+use crate::domain::admin::{Admin, AdminInvitation, NewAdminInvitation};
 use crate::domain::admin::LoginCredentials;
 use crate::domain::admin::NewAdmin;
+use crate::error::AppError;
 
+use chrono::NaiveDateTime;
 use diesel::SqliteConnection;
 use diesel::prelude::*;
 use diesel::r2d2::ConnectionManager;
@@ -18,67 +23,179 @@ impl AdminRepository {
         AdminRepository { pool }
     }
 
-    pub async fn register_admin(&self, conn: &mut SqliteConnection,new_admin_data: NewAdmin<'_>) -> Result<Admin, String> {
-        
-
-        // ตรวจสอบว่า username ซ้ำหรือไม่ (เป็น Good Practice)
-        let existing_admin_result = admins::table
-            .filter(admins::username.eq(&new_admin_data.username))
-            .first::<Admin>(conn);
-
-        match existing_admin_result {
-            Ok(_) => {
-                // ถ้า User ถูกพบ แสดงว่า username ซ้ำ
-                return Err("Username already taken".to_string());
-            }
-            Err(diesel::result::Error::NotFound) => {
-                // ถ้าไม่พบผู้ใช้ ถือว่าถูกต้อง สามารถดำเนินการต่อได้
-                // ไม่ต้องทำอะไรตรงนี้
-            }
-            Err(e) => {
-                // จัดการกับ Error อื่นๆ ที่ไม่ใช่ NotFound
-                return Err(format!("Database error during admin check: {}", e));
-            }
-        }
-
-        diesel::insert_into(admins::table)
-            .values(&new_admin_data)
-            .execute(conn)
-            .map_err(|e| format!("Failed to insert user into DB: {}", e))?;
-
-        let inserted_admin = admins::table
-            .filter(admins::username.eq(&new_admin_data.username))
-            .first::<Admin>(conn)
-            .map_err(|e| format!("Failed to retrieve newly inserted user: {}", e))?;
-
-        Ok(inserted_admin)
-    }
     // ฟังก์ชันสำหรับค้นหาผู้ใช้ด้วย Username และตรวจสอบรหัสผ่าน›
     pub async fn find_admin_by_username_and_verify_password(
         &self,
         login_credentials: LoginCredentials,
-    ) -> Result<Admin, String> {
-        let mut conn = self
-            .pool
-            .get()
-            .map_err(|e| format!("Failed to get DB connection: {}", e))?;
+    ) -> Result<Admin, AppError> {
+        let mut conn = self.pool.get()?;
+
+        let invalid_credentials = || AppError::Unauthorized("Invalid username or password".to_string());
 
         let admin = admins::table
             .filter(admins::username.eq(&login_credentials.username))
             .first::<Admin>(&mut conn)
-            .map_err(|e| match e {
-                diesel::result::Error::NotFound => "Invalid username or password".to_string(),
-                _ => format!("Database error while fetching user: {}", e),
-            })?;
+            .optional()?
+            .ok_or_else(invalid_credentials)?;
 
         // ตรวจสอบรหัสผ่าน (Plain Text) กับ password_hash ที่เก็บใน DB
-        let password_matches = bcrypt::verify(&login_credentials.password, &admin.password_hash)
-            .map_err(|e| format!("Password verification internal error: {}", e))?;
+        let password_matches = bcrypt::verify(&login_credentials.password, &admin.password_hash)?;
 
         if password_matches {
             Ok(admin)
         } else {
-            Err("Invalid username or password".to_string())
+            Err(invalid_credentials())
         }
     }
+
+    pub async fn count_admins(&self) -> Result<i64, AppError> {
+        let mut conn = self.pool.get()?;
+        let count = admins::table.count().get_result::<i64>(&mut conn)?;
+        Ok(count)
+    }
+
+    // สร้าง Admin คนแรก: ตรวจสอบและ INSERT ใน transaction เดียวกัน เพื่อไม่ให้สร้างซ้ำได้เมื่อมีคำขอพร้อมกัน
+    pub async fn create_first_admin(&self, new_admin: NewAdmin<'_>, audit: PendingAudit<'_, Admin>) -> Result<Admin, AppError> {
+        let mut conn = self.pool.get()?;
+        conn.immediate_transaction(|conn| {
+            let existing = admins::table.count().get_result::<i64>(conn)?;
+            if existing > 0 {
+                return Err(AppError::Conflict("An admin account already exists".to_string()));
+            }
+            let admin = insert_admin(conn, &new_admin)?;
+            AuditRepository::append(conn, audit, &admin)?;
+            Ok(admin)
+        })
+    }
+
+    pub async fn insert_invitation(
+        &self,
+        invitation: NewAdminInvitation<'_>,
+        audit: PendingAudit<'_, AdminInvitation>,
+    ) -> Result<AdminInvitation, AppError> {
+        let mut conn = self.pool.get()?;
+        conn.immediate_transaction(|conn| {
+            if let Some(organization_id) = invitation.organization_id {
+                let exists = organizations::table
+                    .find(organization_id)
+                    .count()
+                    .get_result::<i64>(conn)?
+                    > 0;
+                if !exists {
+                    return Err(AppError::Validation("`organization_id` does not exist.".to_string()));
+                }
+            }
+            diesel::insert_into(admin_invitations::table)
+                .values(&invitation)
+                .execute(conn)?;
+            let inserted = admin_invitations::table
+                .filter(admin_invitations::jti.eq(invitation.jti))
+                .select(AdminInvitation::as_select())
+                .first(conn)?;
+            AuditRepository::append(conn, audit, &inserted)?;
+            Ok(inserted)
+        })
+    }
+
+    // คำเชิญที่ยังใช้ได้ (ยังไม่ถูกใช้ ไม่ถูกเพิกถอน และยังไม่หมดอายุ) เฉพาะคำเชิญเข้าองค์กรของผู้เรียก
+    pub async fn list_pending_invitations(&self, tenant: Tenant, now: NaiveDateTime) -> Result<Vec<AdminInvitation>, AppError> {
+        let mut conn = self.pool.get()?;
+        let mut q = admin_invitations::table.into_boxed();
+        if let Some(org) = tenant.organization_id() {
+            q = q.filter(admin_invitations::organization_id.eq(org));
+        }
+        let invitations = q
+            .filter(admin_invitations::accepted_at.is_null())
+            .filter(admin_invitations::revoked_at.is_null())
+            .filter(admin_invitations::expires_at.gt(now))
+            .order(admin_invitations::created_at.desc())
+            .select(AdminInvitation::as_select())
+            .load(&mut conn)?;
+        Ok(invitations)
+    }
+
+    // คืนค่า false ถ้าไม่พบคำเชิญ หรือคำเชิญถูกใช้/เพิกถอนไปแล้ว
+    pub async fn revoke_invitation(
+        &self,
+        tenant: Tenant,
+        id: i32,
+        now: NaiveDateTime,
+        audit: PendingAudit<'_, ()>,
+    ) -> Result<bool, AppError> {
+        let mut conn = self.pool.get()?;
+        conn.immediate_transaction(|conn| {
+            let pending = admin_invitations::table
+                .filter(admin_invitations::id.eq(id))
+                .filter(admin_invitations::accepted_at.is_null())
+                .filter(admin_invitations::revoked_at.is_null());
+            let affected = match tenant.organization_id() {
+                Some(org) => diesel::update(pending.filter(admin_invitations::organization_id.eq(org)))
+                    .set(admin_invitations::revoked_at.eq(now))
+                    .execute(conn)?,
+                None => diesel::update(pending)
+                    .set(admin_invitations::revoked_at.eq(now))
+                    .execute(conn)?,
+            };
+            if affected == 0 {
+                return Ok(false);
+            }
+            AuditRepository::append(conn, audit, &())?;
+            Ok(true)
+        })
+    }
+
+    // ใช้คำเชิญ: ทำเครื่องหมายว่าใช้แล้วและสร้าง Admin ใน transaction เดียวกัน (คำเชิญใช้ได้ครั้งเดียว)
+    pub async fn accept_invitation(
+        &self,
+        jti: &str,
+        new_admin: NewAdmin<'_>,
+        now: NaiveDateTime,
+        audit: PendingAudit<'_, Admin>,
+    ) -> Result<Admin, AppError> {
+        let mut conn = self.pool.get()?;
+        conn.immediate_transaction(|conn| {
+            let claimed = diesel::update(
+                admin_invitations::table
+                    .filter(admin_invitations::jti.eq(jti))
+                    .filter(admin_invitations::accepted_at.is_null())
+                    .filter(admin_invitations::revoked_at.is_null())
+                    .filter(admin_invitations::expires_at.gt(now)),
+            )
+            .set(admin_invitations::accepted_at.eq(now))
+            .execute(conn)?;
+            if claimed == 0 {
+                return Err(AppError::Unauthorized(
+                    "Invitation is invalid, expired, revoked or already used.".to_string(),
+                ));
+            }
+
+            // Admin ใหม่อยู่ในองค์กรเดียวกับคำเชิญ (None = Platform Admin)
+            let organization_id = admin_invitations::table
+                .filter(admin_invitations::jti.eq(jti))
+                .select(admin_invitations::organization_id)
+                .first::<Option<i32>>(conn)?;
+            let admin = insert_admin(conn, &NewAdmin { organization_id, ..new_admin })?;
+            diesel::update(admin_invitations::table.filter(admin_invitations::jti.eq(jti)))
+                .set(admin_invitations::accepted_admin_id.eq(admin.id))
+                .execute(conn)?;
+            AuditRepository::append(conn, audit, &admin)?;
+            Ok(admin)
+        })
+    }
+}
+
+fn insert_admin(conn: &mut SqliteConnection, new_admin: &NewAdmin<'_>) -> Result<Admin, AppError> {
+    diesel::insert_into(admins::table)
+        .values(new_admin)
+        .execute(conn)
+        .map_err(|e| match e {
+            diesel::result::Error::DatabaseError(diesel::result::DatabaseErrorKind::UniqueViolation, _) => {
+                AppError::Conflict("Username already taken".to_string())
+            }
+            e => e.into(),
+        })?;
+    let admin = admins::table
+        .filter(admins::username.eq(new_admin.username))
+        .first::<Admin>(conn)?;
+    Ok(admin)
 }
@@ -0,0 +1,121 @@
+// src/infrastructure/api_key_repository.rs
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use diesel::SqliteConnection;
+
+use crate::domain::api_key::{ApiKey, NewApiKey};
+use crate::domain::audit::PendingAudit;
+use crate::error::AppError;
+
+use super::audit_repository::AuditRepository;
+use super::schema::{admins, api_keys, users};
+
+#[derive(Debug, Clone)]
+pub struct ApiKeyRepository {
+    pool: Pool<ConnectionManager<SqliteConnection>>,
+}
+
+impl ApiKeyRepository {
+    pub fn new(pool: Pool<ConnectionManager<SqliteConnection>>) -> Self {
+        ApiKeyRepository { pool }
+    }
+
+    pub async fn insert(&self, key: NewApiKey<'_>, audit: PendingAudit<'_, ApiKey>) -> Result<ApiKey, AppError> {
+        let mut conn = self.pool.get()?;
+        conn.transaction(|conn| {
+            diesel::insert_into(api_keys::table)
+                .values(&key)
+                .execute(conn)?;
+            let inserted = api_keys::table
+                .filter(api_keys::prefix.eq(key.prefix))
+                .select(ApiKey::as_select())
+                .first(conn)?;
+            AuditRepository::append(conn, audit, &inserted)?;
+            Ok(inserted)
+        })
+    }
+
+    // Key ทั้งหมดของบัญชี (รวมที่หมดอายุ/ถูกเพิกถอนแล้ว) ล่าสุดก่อน
+    pub async fn list_for(&self, principal_kind: &str, principal_id: i32) -> Result<Vec<ApiKey>, AppError> {
+        let mut conn = self.pool.get()?;
+        let keys = api_keys::table
+            .filter(api_keys::principal_kind.eq(principal_kind))
+            .filter(api_keys::principal_id.eq(principal_id))
+            .order(api_keys::id.desc())
+            .select(ApiKey::as_select())
+            .load(&mut conn)?;
+        Ok(keys)
+    }
+
+    // เพิกถอน Key ของบัญชี คืนค่า false ถ้าไม่พบหรือถูกเพิกถอนไปแล้ว
+    pub async fn revoke(
+        &self,
+        principal_kind: &str,
+        principal_id: i32,
+        key_id: i32,
+        now: NaiveDateTime,
+        audit: PendingAudit<'_, ()>,
+    ) -> Result<bool, AppError> {
+        let mut conn = self.pool.get()?;
+        conn.immediate_transaction(|conn| {
+            let affected = diesel::update(
+                api_keys::table
+                    .find(key_id)
+                    .filter(api_keys::principal_kind.eq(principal_kind))
+                    .filter(api_keys::principal_id.eq(principal_id))
+                    .filter(api_keys::revoked_at.is_null()),
+            )
+            .set(api_keys::revoked_at.eq(now))
+            .execute(conn)?;
+            if affected == 0 {
+                return Ok(false);
+            }
+            AuditRepository::append(conn, audit, &())?;
+            Ok(true)
+        })
+    }
+
+    // Key ที่ใช้ได้ (ยังไม่หมดอายุ ไม่ถูกเพิกถอน และบัญชีเจ้าของยังไม่ถูกลบ)
+    pub async fn find_active_by_hash(&self, key_hash: &str, now: NaiveDateTime) -> Result<Option<ApiKey>, AppError> {
+        let mut conn = self.pool.get()?;
+        let Some(key) = api_keys::table
+            .filter(api_keys::key_hash.eq(key_hash))
+            .filter(api_keys::revoked_at.is_null())
+            .filter(api_keys::expires_at.gt(now))
+            .select(ApiKey::as_select())
+            .first(&mut conn)
+            .optional()?
+        else {
+            return Ok(None);
+        };
+
+        let owner_active = match key.principal_kind.as_str() {
+            "admin" => admins::table
+                .find(key.principal_id)
+                .filter(admins::deleted_at.is_null())
+                .count()
+                .get_result::<i64>(&mut conn)?,
+            _ => users::table
+                .find(key.principal_id)
+                .filter(users::deleted_at.is_null())
+                .count()
+                .get_result::<i64>(&mut conn)?,
+        };
+        Ok((owner_active > 0).then_some(key))
+    }
+
+    // บันทึกเวลาที่ใช้ล่าสุด (อัปเดตเมื่อค่าเดิมเก่ากว่า `stale_before` เพื่อลดการเขียน DB ทุก Request)
+    pub async fn touch(&self, key_id: i32, now: NaiveDateTime, stale_before: NaiveDateTime) -> Result<(), AppError> {
+        let mut conn = self.pool.get()?;
+        diesel::update(
+            api_keys::table
+                .find(key_id)
+                .filter(api_keys::last_used_at.is_null().or(api_keys::last_used_at.lt(stale_before))),
+        )
+        .set(api_keys::last_used_at.eq(now))
+        .execute(&mut conn)?;
+        Ok(())
+    }
+}
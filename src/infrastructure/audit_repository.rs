@@ -0,0 +1,168 @@
+// src/infrastructure/audit_repository.rs
+
+use chrono::{SubsecRound, Utc};
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use diesel::SqliteConnection;
+
+use crate::domain::audit::{AuditEntry, AuditQuery, AuditVerification, NewAuditEntry, PendingAudit, GENESIS_HASH};
+use crate::domain::organization::Tenant;
+use crate::domain::pagination::SortDirection;
+use crate::error::AppError;
+
+use super::schema::audit_log;
+
+// จำนวนแถวที่โหลดต่อรอบตอนตรวจสอบ Chain
+const VERIFY_BATCH_SIZE: i64 = 1000;
+
+#[derive(Debug, Clone)]
+pub struct AuditRepository {
+    pool: Pool<ConnectionManager<SqliteConnection>>,
+}
+
+impl AuditRepository {
+    pub fn new(pool: Pool<ConnectionManager<SqliteConnection>>) -> Self {
+        AuditRepository { pool }
+    }
+
+    // เพิ่มแถวต่อท้าย Chain ภายใน Transaction ของการเปลี่ยนแปลง (เรียกหลังเขียนข้อมูลแล้ว หรือใน immediate_transaction
+    // เพื่อให้ถือ Write Lock อยู่ สอง Request จึงอ้าง prev_hash เดียวกันไม่ได้)
+    pub fn append<T: ?Sized>(
+        conn: &mut SqliteConnection,
+        pending: PendingAudit<'_, T>,
+        value: &T,
+    ) -> Result<(), diesel::result::Error> {
+        let (context, event) = pending.resolve(value);
+        let actor = event.actor.as_ref().unwrap_or(&context.actor);
+        let before = event.before.as_ref().map(|value| value.to_string());
+        let after = event.after.as_ref().map(|value| value.to_string());
+        let mut entry = NewAuditEntry {
+            // เก็บถึงระดับไมโครวินาทีเพื่อให้ค่าที่อ่านกลับมาคำนวณ hash ได้ตรงกัน
+            created_at: Utc::now().naive_utc().trunc_subsecs(6),
+            organization_id: actor.organization_id,
+            actor_kind: actor.kind,
+            actor_id: actor.id,
+            impersonator_id: actor.impersonator_id,
+            action: event.action,
+            entity_type: event.entity_type,
+            entity_id: event.entity_id.as_deref(),
+            before_json: before.as_deref(),
+            after_json: after.as_deref(),
+            request_id: context.request_id.as_deref(),
+            ip: context.ip.as_deref(),
+            prev_hash: String::new(),
+            hash: String::new(),
+        };
+        entry.prev_hash = audit_log::table
+            .order(audit_log::id.desc())
+            .select(audit_log::hash)
+            .first::<String>(conn)
+            .optional()?
+            .unwrap_or_else(|| GENESIS_HASH.to_string());
+        entry.hash = entry.compute_hash();
+        diesel::insert_into(audit_log::table).values(&entry).execute(conn)?;
+        Ok(())
+    }
+
+    pub async fn list(
+        &self,
+        tenant: Tenant,
+        query: &AuditQuery,
+        offset: i64,
+        limit: i64,
+    ) -> Result<(Vec<AuditEntry>, i64), AppError> {
+        let mut conn = self.pool.get()?;
+        let filtered = || {
+            let mut q = audit_log::table.into_boxed();
+            if let Some(org) = tenant.organization_id() {
+                q = q.filter(audit_log::organization_id.eq(org));
+            }
+            if let Some(actor_kind) = &query.actor_kind {
+                q = q.filter(audit_log::actor_kind.eq(actor_kind));
+            }
+            if let Some(actor_id) = query.actor_id {
+                q = q.filter(audit_log::actor_id.eq(actor_id));
+            }
+            if let Some(action) = &query.action {
+                q = match action.strip_suffix('.') {
+                    Some(prefix) => q.filter(audit_log::action.like(format!("{}.%", prefix))),
+                    None => q.filter(audit_log::action.eq(action)),
+                };
+            }
+            if let Some(entity_type) = &query.entity_type {
+                q = q.filter(audit_log::entity_type.eq(entity_type));
+            }
+            if let Some(entity_id) = &query.entity_id {
+                q = q.filter(audit_log::entity_id.eq(entity_id));
+            }
+            if let Some(request_id) = &query.request_id {
+                q = q.filter(audit_log::request_id.eq(request_id));
+            }
+            if let Some(from) = query.from {
+                q = q.filter(audit_log::created_at.ge(from.naive_utc()));
+            }
+            if let Some(to) = query.to {
+                q = q.filter(audit_log::created_at.lt(to.naive_utc()));
+            }
+            q
+        };
+        let total = filtered().count().get_result::<i64>(&mut conn)?;
+
+        let q = match query.order.unwrap_or_default() {
+            SortDirection::Asc => filtered().order(audit_log::id.asc()),
+            SortDirection::Desc => filtered().order(audit_log::id.desc()),
+        };
+        let items = q
+            .limit(limit)
+            .offset(offset)
+            .select(AuditEntry::as_select())
+            .load(&mut conn)?;
+        Ok((items, total))
+    }
+
+    // ตรวจทุกแถวตามลำดับ id: prev_hash ต้องเท่ากับ hash ของแถวก่อนหน้า และ hash ต้องคำนวณได้ตรงกับข้อมูลในแถว
+    pub async fn verify(&self) -> Result<AuditVerification, AppError> {
+        let mut conn = self.pool.get()?;
+        let mut result = AuditVerification {
+            valid: true,
+            checked: 0,
+            last_id: None,
+            last_hash: None,
+            broken_at: None,
+            reason: None,
+        };
+        let mut expected_prev = GENESIS_HASH.to_string();
+        let mut after_id = 0;
+        loop {
+            let batch = audit_log::table
+                .filter(audit_log::id.gt(after_id))
+                .order(audit_log::id.asc())
+                .limit(VERIFY_BATCH_SIZE)
+                .select(AuditEntry::as_select())
+                .load(&mut conn)?;
+            if batch.is_empty() {
+                return Ok(result);
+            }
+            for entry in batch {
+                let reason = if entry.prev_hash != expected_prev {
+                    Some("prev_hash does not match the previous entry (an entry was removed or reordered)")
+                } else if entry.as_new().compute_hash() != entry.hash {
+                    Some("hash does not match the entry contents (the entry was modified)")
+                } else {
+                    None
+                };
+                if let Some(reason) = reason {
+                    result.valid = false;
+                    result.broken_at = Some(entry.id);
+                    result.reason = Some(reason.to_string());
+                    return Ok(result);
+                }
+                result.checked += 1;
+                result.last_id = Some(entry.id);
+                after_id = entry.id;
+                expected_prev = entry.hash.clone();
+                result.last_hash = Some(entry.hash);
+            }
+        }
+    }
+}
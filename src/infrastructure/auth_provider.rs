@@ -0,0 +1,248 @@
+// src/infrastructure/auth_provider.rs
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use ldap3::{ldap_escape, Ldap, LdapConnAsync, LdapConnSettings, LdapError, Scope, SearchEntry};
+
+use crate::domain::audit::{AuditContext, AuditEvent};
+use crate::domain::organization::ExternalOrganization;
+use crate::domain::permission::{GroupRoleMap, PrincipalKind};
+use crate::domain::user::LoginCredentials;
+use crate::domain::validation::is_valid_username;
+use crate::error::AppError;
+
+use super::role_repository::RoleRepository;
+use super::user_repository::UserRepository;
+
+// issuer ของผู้ใช้ที่มาจาก LDAP ใน external_identities (subject = DN ของผู้ใช้)
+pub const LDAP_ISSUER: &str = "ldap";
+// แหล่งที่มาของ Role ที่ซิงก์จากกลุ่มใน LDAP (ดู role_assignments.source)
+pub const LDAP_ROLE_SOURCE: &str = "ldap";
+
+// result code 49 (invalidCredentials) ตาม RFC 4511
+const LDAP_INVALID_CREDENTIALS: u32 = 49;
+
+fn invalid_credentials() -> AppError {
+    AppError::Unauthorized("Invalid username or password".to_string())
+}
+
+// AuthProvider: วิธีตรวจสอบ username/password ตอน Login (เปลี่ยนหรือเพิ่มได้โดย implement trait นี้)
+// คืนค่า id ของผู้ใช้ในระบบ หรือ Unauthorized เมื่อ username/password ไม่ถูกต้อง
+// audit ใช้บันทึกผู้ใช้ที่ Provider สร้างขึ้นใหม่ระหว่าง Login
+#[async_trait]
+pub trait AuthProvider: Send + Sync {
+    async fn authenticate(&self, credentials: &LoginCredentials, audit: &AuditContext) -> Result<i32, AppError>;
+}
+
+// PasswordAuthProvider: ตรวจรหัสผ่าน (bcrypt) ที่เก็บในตาราง users
+#[derive(Debug, Clone)]
+pub struct PasswordAuthProvider {
+    repo: UserRepository,
+}
+
+impl PasswordAuthProvider {
+    pub fn new(repo: UserRepository) -> Self {
+        PasswordAuthProvider { repo }
+    }
+}
+
+#[async_trait]
+impl AuthProvider for PasswordAuthProvider {
+    async fn authenticate(&self, credentials: &LoginCredentials, _audit: &AuditContext) -> Result<i32, AppError> {
+        let user = self.repo.find_user_by_username_and_verify_password(credentials).await?;
+        Ok(user.id)
+    }
+}
+
+// LdapConfig: ค่าตั้งค่าของ LDAP (อ่านจาก Environment ใน main.rs)
+#[derive(Debug, Clone)]
+pub struct LdapConfig {
+    pub url: String,                   // เช่น ldap://ldap.example.com:389 หรือ ldaps://...
+    pub starttls: bool,
+    pub bind_dn: Option<String>,       // บัญชีสำหรับค้นหาผู้ใช้ (None = ค้นหาแบบ anonymous)
+    pub bind_password: Option<String>,
+    pub base_dn: String,
+    pub user_filter: String,           // {username} ถูกแทนด้วย username ที่ escape แล้ว
+    pub username_attribute: String,
+    pub display_name_attribute: String,
+    pub group_attribute: String,       // attribute ที่มีกลุ่มของผู้ใช้ (เช่น memberOf)
+    pub group_roles: GroupRoleMap,     // กลุ่มใน LDAP -> Role ในระบบ
+    pub organization: ExternalOrganization, // องค์กรของผู้ใช้ใหม่ (slug คงที่ หรือจาก attribute)
+    pub timeout_seconds: u64,
+}
+
+// DirectoryUser: ข้อมูลผู้ใช้จาก LDAP หลัง bind ด้วยรหัสผ่านของผู้ใช้สำเร็จ
+#[derive(Debug, Clone)]
+struct DirectoryUser {
+    dn: String,
+    username: String,
+    display_name: Option<String>,
+    groups: Vec<String>,
+    organization: Option<String>, // ค่าแรกของ attribute องค์กร (ถ้าตั้งค่าไว้)
+}
+
+fn directory_error(e: LdapError) -> AppError {
+    AppError::BadGateway(format!("Directory request failed: {}", e))
+}
+
+// ค่าของ attribute (ชื่อ attribute ใน LDAP ไม่สนตัวพิมพ์เล็ก/ใหญ่)
+fn attribute_values<'a>(entry: &'a SearchEntry, name: &str) -> &'a [String] {
+    entry
+        .attrs
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|(_, values)| values.as_slice())
+        .unwrap_or(&[])
+}
+
+// ชื่อกลุ่มจากค่าใน memberOf: ใช้ค่าของ RDN แรก (cn=staff,ou=groups,... -> staff)
+fn group_name(value: &str) -> String {
+    let rdn = value.split(',').next().unwrap_or(value);
+    match rdn.split_once('=') {
+        Some((_, name)) if value.contains(',') => name.trim().to_string(),
+        _ => value.trim().to_string(),
+    }
+}
+
+// LdapAuthProvider: Login ด้วยบัญชีใน LDAP directory ขององค์กร
+// 1. bind ด้วยบัญชีค้นหา (หรือ anonymous) แล้วค้นหา DN ของผู้ใช้ด้วย user_filter
+// 2. bind ด้วย DN ของผู้ใช้และรหัสผ่านที่กรอก
+// 3. หา/สร้างผู้ใช้ในระบบ (ไม่มีรหัสผ่าน ในองค์กรตาม LdapConfig::organization) ซิงก์ชื่อที่แสดง กลุ่ม และ Role ตามกลุ่ม
+#[derive(Clone)]
+pub struct LdapAuthProvider {
+    config: LdapConfig,
+    users: UserRepository,
+    roles: RoleRepository,
+}
+
+impl LdapAuthProvider {
+    pub fn new(config: LdapConfig, users: UserRepository, roles: RoleRepository) -> Self {
+        LdapAuthProvider { config, users, roles }
+    }
+
+    fn timeout(&self) -> Duration {
+        Duration::from_secs(self.config.timeout_seconds)
+    }
+
+    async fn connect(&self) -> Result<Ldap, AppError> {
+        let settings = LdapConnSettings::new()
+            .set_conn_timeout(self.timeout())
+            .set_starttls(self.config.starttls);
+        let (conn, ldap) = LdapConnAsync::with_settings(settings, &self.config.url)
+            .await
+            .map_err(directory_error)?;
+        ldap3::drive!(conn);
+        Ok(ldap)
+    }
+
+    async fn verify(&self, ldap: &mut Ldap, username: &str, password: &str) -> Result<DirectoryUser, AppError> {
+        let timeout = self.timeout();
+        if let Some(bind_dn) = &self.config.bind_dn {
+            ldap.with_timeout(timeout)
+                .simple_bind(bind_dn, self.config.bind_password.as_deref().unwrap_or_default())
+                .await
+                .and_then(|r| r.success())
+                .map_err(directory_error)?;
+        }
+
+        let filter = self.config.user_filter.replace("{username}", &ldap_escape(username));
+        let mut attributes = vec![
+            self.config.username_attribute.as_str(),
+            self.config.display_name_attribute.as_str(),
+            self.config.group_attribute.as_str(),
+        ];
+        attributes.extend(self.config.organization.claim());
+        let (entries, _) = ldap
+            .with_timeout(timeout)
+            .search(&self.config.base_dn, Scope::Subtree, &filter, attributes)
+            .await
+            .and_then(|r| r.success())
+            .map_err(directory_error)?;
+        // ไม่พบหรือพบมากกว่าหนึ่งรายการ ถือว่า username ไม่ถูกต้อง
+        let [entry] = <[_; 1]>::try_from(entries).map_err(|_| invalid_credentials())?;
+        let entry = SearchEntry::construct(entry);
+
+        let result = ldap
+            .with_timeout(timeout)
+            .simple_bind(&entry.dn, password)
+            .await
+            .map_err(directory_error)?;
+        match result.rc {
+            0 => {}
+            LDAP_INVALID_CREDENTIALS => return Err(invalid_credentials()),
+            _ => return Err(directory_error(LdapError::from(result))),
+        }
+
+        let username = attribute_values(&entry, &self.config.username_attribute)
+            .first()
+            .cloned()
+            .unwrap_or_else(|| username.to_string());
+        let mut groups: Vec<String> = attribute_values(&entry, &self.config.group_attribute)
+            .iter()
+            .map(|value| group_name(value))
+            .filter(|name| !name.is_empty())
+            .collect();
+        groups.sort();
+        groups.dedup();
+        Ok(DirectoryUser {
+            dn: entry.dn.to_lowercase(),
+            username,
+            display_name: attribute_values(&entry, &self.config.display_name_attribute).first().cloned(),
+            groups,
+            organization: self
+                .config
+                .organization
+                .claim()
+                .and_then(|name| attribute_values(&entry, name).first().cloned()),
+        })
+    }
+}
+
+#[async_trait]
+impl AuthProvider for LdapAuthProvider {
+    async fn authenticate(&self, credentials: &LoginCredentials, audit: &AuditContext) -> Result<i32, AppError> {
+        // รหัสผ่านว่างทำให้ LDAP ถือเป็น unauthenticated bind ซึ่ง "สำเร็จ" เสมอ (RFC 4513)
+        if credentials.password.is_empty() || credentials.username.trim().is_empty() {
+            return Err(invalid_credentials());
+        }
+
+        let mut ldap = self.connect().await?;
+        let verified = self.verify(&mut ldap, &credentials.username, &credentials.password).await;
+        let _ = ldap.unbind().await;
+        let directory_user = verified?;
+
+        if !is_valid_username(&directory_user.username) {
+            return Err(AppError::Forbidden(format!(
+                "'{}' from the directory is not a valid username.",
+                directory_user.username
+            )));
+        }
+
+        let organization = self.config.organization.slug(directory_user.organization.as_deref())?;
+        let user_id = self
+            .users
+            .provision_external_user(
+                LDAP_ISSUER,
+                &directory_user.dn,
+                &directory_user.username,
+                organization,
+                Utc::now().naive_utc(),
+                audit.pending(AuditEvent::user_provisioned),
+            )
+            .await?;
+        self.users
+            .sync_directory_profile(user_id, directory_user.display_name.as_deref(), &directory_user.groups)
+            .await?;
+        self.roles
+            .sync_roles(
+                PrincipalKind::User.as_str(),
+                user_id,
+                LDAP_ROLE_SOURCE,
+                &self.config.group_roles.roles_for(&directory_user.groups),
+            )
+            .await?;
+        Ok(user_id)
+    }
+}
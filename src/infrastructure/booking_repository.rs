@@ -1,21 +1,147 @@
+use crate::domain::audit::PendingAudit;
 use crate::domain::booking::{Booking, InternalCreateBookingRequest, NewBooking};
+use crate::domain::organization::Tenant;
+use crate::domain::pagination::ListQuery;
+use crate::infrastructure::audit_repository::AuditRepository;
+use crate::infrastructure::pagination::sort_by_column;
 use crate::infrastructure::room_repository::RoomRepository;
-use crate::infrastructure::schema::bookings;
+use crate::infrastructure::schema::{bookings, rooms, users};
+use crate::error::AppError;
 use diesel::prelude::*;
-use diesel::sqlite::SqliteConnection;
-use chrono::Utc;
+use diesel::sql_types::Bool;
+use diesel::sqlite::{Sqlite, SqliteConnection};
+use chrono::{NaiveDateTime, Utc};
 #[derive(Clone)]
 pub struct BookingRepository;
 
-    impl BookingRepository {
+type BookingScope = Box<dyn BoxableExpression<bookings::table, Sqlite, SqlType = Bool>>;
+
+// การจองของผู้ใช้ในองค์กร หรือการจองห้องขององค์กร (รวมผู้ใช้องค์กรอื่นที่จองห้องที่แชร์ไว้)
+// None = ไม่จำกัด (Platform Admin)
+pub(crate) fn tenant_scope(tenant: Tenant) -> Option<BookingScope> {
+    let org = tenant.organization_id()?;
+    Some(Box::new(
+        bookings::user_id
+            .eq_any(users::table.filter(users::organization_id.eq(org)).select(users::id))
+            .or(bookings::room_id.eq_any(rooms::table.filter(rooms::organization_id.eq(org)).select(rooms::id))),
+    ))
+}
+
+// การจอง booking_id ภายในขอบเขตของ tenant (ใช้ทั้งตอนอ่านและใน WHERE ของการแก้ไข)
+fn tenant_booking(tenant: Tenant, booking_id: i32) -> bookings::BoxedQuery<'static, Sqlite> {
+    let mut q = bookings::table.filter(bookings::id.eq(booking_id)).into_boxed();
+    if let Some(scope) = tenant_scope(tenant) {
+        q = q.filter(scope);
+    }
+    q
+}
+
+// Query การจองที่ยังไม่ถูกลบ (หรือทั้งหมดเมื่อ include_deleted) พร้อมตัวกรองจาก ListQuery
+// (room_id, user_id, status, from, to) from/to เทียบกับ start_time
+fn filtered_bookings(tenant: Tenant, query: &ListQuery) -> bookings::BoxedQuery<'_, Sqlite> {
+    let mut q = bookings::table.into_boxed();
+    if let Some(scope) = tenant_scope(tenant) {
+        q = q.filter(scope);
+    }
+    if !query.include_deleted() {
+        q = q.filter(bookings::deleted_at.is_null());
+    }
+    if let Some(room_id) = query.room_id {
+        q = q.filter(bookings::room_id.eq(room_id));
+    }
+    if let Some(user_id) = query.user_id {
+        q = q.filter(bookings::user_id.eq(user_id));
+    }
+    if let Some(status) = &query.status {
+        q = q.filter(bookings::status.eq(status));
+    }
+    if let Some(from) = query.from {
+        q = q.filter(bookings::start_time.ge(from.naive_utc()));
+    }
+    if let Some(to) = query.to {
+        q = q.filter(bookings::start_time.lt(to.naive_utc()));
+    }
+    q
+}
+
+// ห้องต้องไม่มีการจอง active อื่นที่ช่วงเวลาทับ [start, end) ใช้ทั้งตอนสร้างและกู้คืนการจอง
+fn ensure_room_free(
+    conn: &mut SqliteConnection,
+    room_id: i32,
+    start: NaiveDateTime,
+    end: NaiveDateTime,
+    except_id: Option<i32>,
+) -> Result<(), AppError> {
+    let mut q = bookings::table
+        .filter(bookings::room_id.eq(room_id))
+        .filter(bookings::deleted_at.is_null())
+        .filter(bookings::status.eq("active"))
+        .filter(bookings::start_time.lt(end))
+        .filter(bookings::end_time.gt(start))
+        .into_boxed();
+    if let Some(except_id) = except_id {
+        q = q.filter(bookings::id.ne(except_id));
+    }
+    let overlapping = q.count().get_result::<i64>(conn)?;
+    if overlapping > 0 {
+        return Err(AppError::Conflict("The room is already booked for an overlapping time.".to_string()));
+    }
+    Ok(())
+}
+
+impl BookingRepository {
+    pub const SORT_FIELDS: &'static [&'static str] =
+        &["id", "room_id", "user_id", "start_time", "end_time", "status", "created_at"];
+
+    // ดึงการจองตามตัวกรอง เรียงลำดับ และแบ่งหน้า คืนค่า (รายการในหน้านี้, จำนวนทั้งหมด)
+    pub fn list_bookings(
+        conn: &mut SqliteConnection,
+        tenant: Tenant,
+        query: &ListQuery,
+        offset: i64,
+    ) -> Result<(Vec<Booking>, i64), diesel::result::Error> {
+        let total = filtered_bookings(tenant, query).count().get_result::<i64>(conn)?;
+
+        let q = filtered_bookings(tenant, query);
+        let direction = query.direction();
+        let q = match query.sort.as_deref().unwrap_or("created_at") {
+            "id" => sort_by_column!(q, bookings::id, direction),
+            "room_id" => sort_by_column!(q, bookings::room_id, direction),
+            "user_id" => sort_by_column!(q, bookings::user_id, direction),
+            "start_time" => sort_by_column!(q, bookings::start_time, direction),
+            "end_time" => sort_by_column!(q, bookings::end_time, direction),
+            "status" => sort_by_column!(q, bookings::status, direction),
+            _ => sort_by_column!(q, bookings::created_at, direction),
+        };
+
+        let items = q
+            .then_order_by(bookings::id.asc())
+            .limit(query.limit())
+            .offset(offset)
+            .select(Booking::as_select())
+            .load(conn)?;
+
+        Ok((items, total))
+    }
+
     pub fn create_booking(
         conn: &mut SqliteConnection, // A mutable reference to the SQLite database connection.
         request: InternalCreateBookingRequest,
-    ) -> Result<Booking, diesel::result::Error> {
+        audit: PendingAudit<'_, Booking>,
+    ) -> Result<Booking, AppError> {
         // --- Begin Database Transaction ---
         // All operations within this block are treated as a single atomic unit.
         // If any step fails, the entire transaction will be rolled back.
-        conn.transaction(|transaction_conn| {
+        // IMMEDIATE takes the write lock first, so two overlapping requests cannot both pass the check.
+        conn.immediate_transaction(|transaction_conn| {
+            ensure_room_free(
+                transaction_conn,
+                request.room_id,
+                request.start_time.naive_utc(),
+                request.end_time.naive_utc(),
+                None,
+            )?;
+
             // Create a new booking record with the provided details and current timestamps.
             let new_booking = NewBooking {
                 room_id: request.room_id,
@@ -42,94 +168,131 @@ pub struct BookingRepository;
             // --- Update Room Status within the same transaction ---
             // Call the synchronous room status update function, ensuring it uses the
             // same transaction connection for atomicity.
+            // NotFound here means the room does not exist.
             RoomRepository::update_room_status_sync(
                 transaction_conn, // Pass the transaction's connection.
                 request.room_id,
                 "booked", // Set the room status to "booked".
-            )?;
+            )
+            .map_err(|e| match e {
+                diesel::result::Error::NotFound => AppError::NotFound("Room not found".to_string()),
+                e => e.into(),
+            })?;
             // --- End Room Status Update ---
 
+            // The audit entry is part of the same transaction: if it fails, the booking is rolled back.
+            AuditRepository::append(transaction_conn, audit, &created_booking)?;
+
             Ok(created_booking) // Return the successfully created booking.
         }) // The transaction will commit here if all operations succeed, or rollback on error.
     }
 
-    // ยกเลิกการจอง (Soft Delete)
+    // ยกเลิกการจอง (Soft Delete) คืนค่า false ถ้าไม่พบการจองในขอบเขตของ tenant หรือไม่ใช่ของ owner_id
     pub fn cancel_booking(
         conn: &mut SqliteConnection,
+        tenant: Tenant,
         booking_id: i32,
-        user_id: i32,
+        owner_id: Option<i32>,
+        audit: PendingAudit<'_, Booking>,
     ) -> Result<bool, diesel::result::Error> {
         use crate::infrastructure::schema::bookings;
 
-        let affected_rows = diesel::update(
-            bookings::table
-                .filter(bookings::id.eq(booking_id))
-                .filter(bookings::user_id.eq(user_id))
+        conn.transaction(|conn| {
+            let mut update = diesel::update(bookings::table)
+                .filter(bookings::id.eq_any(tenant_booking(tenant, booking_id).select(bookings::id)))
                 .filter(bookings::deleted_at.is_null())
-        )
-        .set((
-            bookings::status.eq("cancelled"),
-            bookings::deleted_at.eq(Some(Utc::now().naive_utc())),
-            bookings::updated_at.eq(Utc::now().naive_utc()),
-        ))
-        .execute(conn)?;
+                .set((
+                    bookings::status.eq("cancelled"),
+                    bookings::deleted_at.eq(Some(Utc::now().naive_utc())),
+                    bookings::updated_at.eq(Utc::now().naive_utc()),
+                ))
+                .into_boxed();
+            // owner_id = None: ไม่จำกัดเจ้าของการจอง
+            if let Some(owner_id) = owner_id {
+                update = update.filter(bookings::user_id.eq(owner_id));
+            }
 
-        Ok(affected_rows > 0)
+            if update.execute(conn)? == 0 {
+                return Ok(false);
+            }
+            let cancelled = bookings::table.find(booking_id).select(Booking::as_select()).first(conn)?;
+            AuditRepository::append(conn, audit, &cancelled)?;
+            Ok(true)
+        })
     }
 
-    // ดึงการจองทั้งหมดของผู้ใช้
-    pub fn get_user_bookings(
+    // ดึงการจองตาม ID
+    pub fn get_booking_by_id(
         conn: &mut SqliteConnection,
-        user_id: i32,
-    ) -> Result<Vec<Booking>, diesel::result::Error> {
+        booking_id: i32,
+    ) -> Result<Option<Booking>, diesel::result::Error> {
         use crate::infrastructure::schema::bookings;
 
         bookings::table
-            .filter(bookings::user_id.eq(user_id))
+            .filter(bookings::id.eq(booking_id))
             .filter(bookings::deleted_at.is_null())
-            .order(bookings::created_at.desc())
             .select(Booking::as_select())
-            .load(conn)
+            .first(conn)
+            .optional()
     }
 
-    // ดึงการจองทั้งหมด (สำหรับ Admin)
-    pub fn get_all_bookings(
+    // ดึงการจองตาม ID รวมที่ถูกยกเลิกแล้ว (ใช้เก็บสถานะก่อน/หลังลง Audit Log)
+    pub fn find_booking(
         conn: &mut SqliteConnection,
-    ) -> Result<Vec<Booking>, diesel::result::Error> {
+        booking_id: i32,
+    ) -> Result<Option<Booking>, diesel::result::Error> {
         use crate::infrastructure::schema::bookings;
 
         bookings::table
-            .filter(bookings::deleted_at.is_null())
-            .order(bookings::created_at.desc())
+            .find(booking_id)
             .select(Booking::as_select())
-            .load(conn)
+            .first(conn)
+            .optional()
     }
 
-    // ดึงการจองตาม ID
-    pub fn get_booking_by_id(
+    // บันทึกว่าผู้จองไม่มาใช้ห้อง (no-show) และคืนสถานะห้องเป็น available
+    pub fn mark_no_show(
         conn: &mut SqliteConnection,
+        tenant: Tenant,
         booking_id: i32,
-    ) -> Result<Option<Booking>, diesel::result::Error> {
-        use crate::infrastructure::schema::bookings;
+        audit: PendingAudit<'_, Booking>,
+    ) -> Result<Booking, diesel::result::Error> {
+        conn.transaction(|transaction_conn| {
+            let booking = tenant_booking(tenant, booking_id)
+                .filter(bookings::deleted_at.is_null())
+                .select(Booking::as_select())
+                .first(transaction_conn)?;
 
-        bookings::table
-            .filter(bookings::id.eq(booking_id))
-            .filter(bookings::deleted_at.is_null())
-            .select(Booking::as_select())
-            .first(conn)
-            .optional()
+            diesel::update(bookings::table)
+                .filter(bookings::id.eq_any(tenant_booking(tenant, booking_id).select(bookings::id)))
+                .set((
+                    bookings::status.eq("no_show"),
+                    bookings::updated_at.eq(Utc::now().naive_utc()),
+                ))
+                .execute(transaction_conn)?;
+
+            RoomRepository::update_room_status_sync(transaction_conn, booking.room_id, "available")?;
+
+            let updated = bookings::table
+                .filter(bookings::id.eq(booking_id))
+                .select(Booking::as_select())
+                .first(transaction_conn)?;
+            AuditRepository::append(transaction_conn, audit, &updated)?;
+            Ok(updated)
+        })
     }
 
     pub fn delete_booking(
     conn: &mut SqliteConnection,
+    tenant: Tenant,
     booking_id: i32,
+    audit: PendingAudit<'_, Booking>,
 ) -> Result<Booking, diesel::result::Error> {
     use crate::infrastructure::schema::bookings;
 
     conn.transaction(|transaction_conn| {
    
-        let booking_to_delete = bookings::table
-            .filter(bookings::id.eq(booking_id))
+        let booking_to_delete = tenant_booking(tenant, booking_id)
             .filter(bookings::deleted_at.is_null())
             .select(Booking::as_select())
             .first(transaction_conn)?;
@@ -137,7 +300,8 @@ pub struct BookingRepository;
         let room_id = booking_to_delete.room_id;
 
     
-        let updated_rows = diesel::update(bookings::table.filter(bookings::id.eq(booking_id)))
+        let updated_rows = diesel::update(bookings::table)
+            .filter(bookings::id.eq_any(tenant_booking(tenant, booking_id).select(bookings::id)))
             .set((
                 bookings::deleted_at.eq(Some(Utc::now().naive_utc())),
                 bookings::status.eq("cancelled"), 
@@ -158,10 +322,113 @@ pub struct BookingRepository;
         )?;
 
        
-        bookings::table
+        let deleted = bookings::table
             .filter(bookings::id.eq(booking_id))
             .select(Booking::as_select())
-            .first(transaction_conn)
+            .first(transaction_conn)?;
+        AuditRepository::append(transaction_conn, audit, &deleted)?;
+        Ok(deleted)
     })
 }
+
+    // การจองที่ยังไม่จบของผู้ใช้ (สถานะ active และ end_time ยังไม่ผ่าน) ใช้ตอนลบผู้ใช้
+    fn unfinished_bookings_of(conn: &mut SqliteConnection, user_id: i32, now: NaiveDateTime) -> QueryResult<Vec<Booking>> {
+        bookings::table
+            .filter(bookings::user_id.eq(user_id))
+            .filter(bookings::deleted_at.is_null())
+            .filter(bookings::status.eq("active"))
+            .filter(bookings::end_time.gt(now))
+            .select(Booking::as_select())
+            .load(conn)
+    }
+
+    // ยกเลิกการจองที่ยังไม่จบของผู้ใช้และคืนสถานะห้องเป็น available คืนจำนวนที่ยกเลิก
+    // (เรียกภายใน Transaction ของผู้เรียก)
+    pub fn cancel_unfinished_bookings(
+        conn: &mut SqliteConnection,
+        user_id: i32,
+        now: NaiveDateTime,
+    ) -> QueryResult<usize> {
+        let unfinished = Self::unfinished_bookings_of(conn, user_id, now)?;
+        for booking in &unfinished {
+            diesel::update(bookings::table.find(booking.id))
+                .set((
+                    bookings::status.eq("cancelled"),
+                    bookings::deleted_at.eq(Some(now)),
+                    bookings::updated_at.eq(now),
+                ))
+                .execute(conn)?;
+            RoomRepository::update_room_status_sync(conn, booking.room_id, "available")?;
+        }
+        Ok(unfinished.len())
+    }
+
+    // โอนการจองที่ยังไม่จบของผู้ใช้ให้ผู้ใช้อีกคน คืนจำนวนที่โอน (เรียกภายใน Transaction ของผู้เรียก)
+    pub fn transfer_unfinished_bookings(
+        conn: &mut SqliteConnection,
+        from_user_id: i32,
+        to_user_id: i32,
+        now: NaiveDateTime,
+    ) -> QueryResult<usize> {
+        let ids: Vec<i32> = Self::unfinished_bookings_of(conn, from_user_id, now)?
+            .into_iter()
+            .map(|booking| booking.id)
+            .collect();
+        diesel::update(bookings::table.filter(bookings::id.eq_any(&ids)))
+            .set((bookings::user_id.eq(to_user_id), bookings::updated_at.eq(now)))
+            .execute(conn)
+    }
+
+    // กู้คืนการจองที่ถูกยกเลิก (Soft Delete) กลับเป็น active และตั้งสถานะห้องเป็น booked
+    // ต้องยังไม่จบ และทั้งห้องและผู้จองต้องยังไม่ถูกลบ
+    pub fn restore_booking(
+        conn: &mut SqliteConnection,
+        tenant: Tenant,
+        booking_id: i32,
+        audit: PendingAudit<'_, Booking>,
+    ) -> Result<Booking, AppError> {
+        conn.immediate_transaction(|conn| {
+            let booking = tenant_booking(tenant, booking_id)
+                .filter(bookings::deleted_at.is_not_null())
+                .select(Booking::as_select())
+                .first(conn)
+                .optional()?
+                .ok_or_else(|| AppError::NotFound("Booking not found or not deleted".to_string()))?;
+
+            let now = Utc::now().naive_utc();
+            if booking.end_time <= now {
+                return Err(AppError::Conflict("Bookings that have already ended cannot be restored.".to_string()));
+            }
+            let room_deleted = rooms::table
+                .find(booking.room_id)
+                .select(rooms::deleted_at.is_not_null())
+                .first::<bool>(conn)?;
+            if room_deleted {
+                return Err(AppError::Conflict("The room of this booking is deleted; restore the room first.".to_string()));
+            }
+            let user_deleted = users::table
+                .find(booking.user_id)
+                .select(users::deleted_at.is_not_null())
+                .first::<bool>(conn)?;
+            if user_deleted {
+                return Err(AppError::Conflict("The user of this booking is deleted; restore the user first.".to_string()));
+            }
+            // ช่วงเวลาเดิมอาจถูกจองไปแล้วหลังยกเลิก
+            ensure_room_free(conn, booking.room_id, booking.start_time, booking.end_time, Some(booking.id))?;
+
+            diesel::update(bookings::table)
+                .filter(bookings::id.eq_any(tenant_booking(tenant, booking_id).select(bookings::id)))
+                .set((
+                    bookings::status.eq("active"),
+                    bookings::deleted_at.eq(None::<NaiveDateTime>),
+                    bookings::updated_at.eq(now),
+                ))
+                .execute(conn)?;
+            RoomRepository::update_room_status_sync(conn, booking.room_id, "booked")?;
+
+            let restored = bookings::table.find(booking_id).select(Booking::as_select()).first(conn)?;
+            AuditRepository::append(conn, audit, &restored)?;
+            Ok(restored)
+        })
+    }
 }
@@ -1,13 +1,32 @@
-use diesel::r2d2::{ConnectionManager, Pool};
+use diesel::connection::SimpleConnection;
+use diesel::r2d2::{ConnectionManager, CustomizeConnection, Pool};
 use diesel::SqliteConnection; // <<-- ใช้ SqliteConnection
 use std::env;
 
 pub type DbPool = Pool<ConnectionManager<SqliteConnection>>;
 
+// ตั้งค่าทุก Connection ที่ Pool เปิด: รอ Lock แทนที่จะคืน SQLITE_BUSY ทันทีเมื่อเขียนพร้อมกัน,
+// ใช้ WAL ให้อ่านได้ระหว่างมีการเขียน และเปิดการตรวจ Foreign Key (SQLite ปิดไว้เป็นค่าเริ่มต้น)
+#[derive(Debug)]
+struct SqlitePragmas;
+
+impl CustomizeConnection<SqliteConnection, diesel::r2d2::Error> for SqlitePragmas {
+    fn on_acquire(&self, conn: &mut SqliteConnection) -> Result<(), diesel::r2d2::Error> {
+        conn.batch_execute("PRAGMA busy_timeout = 5000; PRAGMA journal_mode = WAL; PRAGMA foreign_keys = ON;")
+            .map_err(diesel::r2d2::Error::QueryError)
+    }
+}
+
 pub fn establish_connection_pool() -> DbPool {
     let database_url = env::var("DATABASE_URL").expect("DATABASE_URL must be set in .env");
+    connection_pool(&database_url)
+}
+
+// สร้าง Pool จาก URL ที่กำหนด (ใช้กับ Database ชั่วคราวตอนทดสอบด้วย)
+pub fn connection_pool(database_url: &str) -> DbPool {
     let manager = ConnectionManager::<SqliteConnection>::new(database_url); // <<-- ใช้ SqliteConnection
     Pool::builder()
+        .connection_customizer(Box::new(SqlitePragmas))
         .build(manager)
         .expect("Failed to create DB connection pool.")
-}
\ No newline at end of file
+}
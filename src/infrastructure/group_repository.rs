@@ -0,0 +1,258 @@
+// src/infrastructure/group_repository.rs
+
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use diesel::SqliteConnection;
+
+use crate::domain::audit::PendingAudit;
+use crate::domain::group::{Group, GroupChangeset, NewGroup, RoomAccessResponse};
+use crate::domain::organization::Tenant;
+use crate::domain::user::User;
+use crate::error::AppError;
+
+use super::audit_repository::AuditRepository;
+use super::schema::{group_members, groups, organizations, room_allowed_groups, room_allowed_users, room_shares, users};
+
+fn unique_violation(e: diesel::result::Error) -> AppError {
+    match e {
+        diesel::result::Error::DatabaseError(diesel::result::DatabaseErrorKind::UniqueViolation, _) => {
+            AppError::Conflict("Group name already taken in the organization".to_string())
+        }
+        e => e.into(),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GroupRepository {
+    pool: Pool<ConnectionManager<SqliteConnection>>,
+}
+
+impl GroupRepository {
+    pub fn new(pool: Pool<ConnectionManager<SqliteConnection>>) -> Self {
+        GroupRepository { pool }
+    }
+
+    pub async fn list(&self, tenant: Tenant) -> Result<Vec<Group>, AppError> {
+        let mut conn = self.pool.get()?;
+        let mut q = groups::table.into_boxed();
+        if let Some(org) = tenant.organization_id() {
+            q = q.filter(groups::organization_id.eq(org));
+        }
+        let items = q
+            .order((groups::organization_id.asc(), groups::name.asc()))
+            .select(Group::as_select())
+            .load(&mut conn)?;
+        Ok(items)
+    }
+
+    // กลุ่มขององค์กรอื่นถือว่าไม่มีอยู่
+    pub async fn find(&self, tenant: Tenant, id: i32) -> Result<Option<Group>, AppError> {
+        let mut conn = self.pool.get()?;
+        let mut q = groups::table.filter(groups::id.eq(id)).into_boxed();
+        if let Some(org) = tenant.organization_id() {
+            q = q.filter(groups::organization_id.eq(org));
+        }
+        Ok(q.select(Group::as_select()).first(&mut conn).optional()?)
+    }
+
+    pub async fn insert(&self, group: NewGroup<'_>, audit: PendingAudit<'_, Group>) -> Result<Group, AppError> {
+        let mut conn = self.pool.get()?;
+        conn.immediate_transaction(|conn| {
+            let organization_exists = organizations::table
+                .find(group.organization_id)
+                .count()
+                .get_result::<i64>(conn)?
+                > 0;
+            if !organization_exists {
+                return Err(AppError::Validation("`organization_id` does not exist.".to_string()));
+            }
+            diesel::insert_into(groups::table)
+                .values(&group)
+                .execute(conn)
+                .map_err(unique_violation)?;
+            let inserted = groups::table
+                .filter(groups::organization_id.eq(group.organization_id))
+                .filter(groups::name.eq(group.name))
+                .select(Group::as_select())
+                .first(conn)?;
+            AuditRepository::append(conn, audit, &inserted)?;
+            Ok(inserted)
+        })
+    }
+
+    pub async fn update(&self, id: i32, changes: GroupChangeset, audit: PendingAudit<'_, Group>) -> Result<Group, AppError> {
+        let mut conn = self.pool.get()?;
+        conn.immediate_transaction(|conn| {
+            let affected = diesel::update(groups::table.find(id))
+                .set(&changes)
+                .execute(conn)
+                .map_err(unique_violation)?;
+            if affected == 0 {
+                return Err(AppError::NotFound("Group not found".to_string()));
+            }
+            let updated = groups::table.find(id).select(Group::as_select()).first(conn)?;
+            AuditRepository::append(conn, audit, &updated)?;
+            Ok(updated)
+        })
+    }
+
+    // ลบกลุ่มพร้อมสมาชิก กลุ่มที่ยังถูกใช้ในกฎของห้องลบไม่ได้
+    // (ถ้าลบได้ ห้องที่จำกัดไว้เฉพาะกลุ่มนี้จะกลายเป็นห้องที่ทุกคนจองได้)
+    pub async fn delete(&self, id: i32, audit: PendingAudit<'_, ()>) -> Result<(), AppError> {
+        let mut conn = self.pool.get()?;
+        conn.immediate_transaction(|conn| {
+            let in_use = room_allowed_groups::table
+                .filter(room_allowed_groups::group_id.eq(id))
+                .count()
+                .get_result::<i64>(conn)?;
+            if in_use > 0 {
+                return Err(AppError::Conflict(
+                    "Group is still used by room access rules; remove it from those rooms first".to_string(),
+                ));
+            }
+            diesel::delete(group_members::table.filter(group_members::group_id.eq(id))).execute(conn)?;
+            let affected = diesel::delete(groups::table.find(id)).execute(conn)?;
+            if affected == 0 {
+                return Err(AppError::NotFound("Group not found".to_string()));
+            }
+            AuditRepository::append(conn, audit, &())?;
+            Ok(())
+        })
+    }
+
+    pub async fn members(&self, group_id: i32) -> Result<Vec<User>, AppError> {
+        let mut conn = self.pool.get()?;
+        let members = users::table
+            .filter(
+                users::id.eq_any(
+                    group_members::table
+                        .filter(group_members::group_id.eq(group_id))
+                        .select(group_members::user_id),
+                ),
+            )
+            .order(users::id.asc())
+            .load::<User>(&mut conn)?;
+        Ok(members)
+    }
+
+    // เพิ่มสมาชิก (เรียกซ้ำได้) ผู้ใช้ต้องอยู่ในองค์กรเดียวกับกลุ่ม
+    pub async fn add_member(&self, group: &Group, user_id: i32, audit: PendingAudit<'_, ()>) -> Result<(), AppError> {
+        let mut conn = self.pool.get()?;
+        conn.immediate_transaction(|conn| {
+            let user_organization = users::table
+                .find(user_id)
+                .select(users::organization_id)
+                .first::<i32>(conn)
+                .optional()?
+                .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+            if user_organization != group.organization_id {
+                return Err(AppError::Validation(
+                    "Users can only join groups of their own organization.".to_string(),
+                ));
+            }
+            diesel::insert_or_ignore_into(group_members::table)
+                .values((group_members::group_id.eq(group.id), group_members::user_id.eq(user_id)))
+                .execute(conn)?;
+            AuditRepository::append(conn, audit, &())?;
+            Ok(())
+        })
+    }
+
+    pub async fn remove_member(&self, group_id: i32, user_id: i32, audit: PendingAudit<'_, ()>) -> Result<(), AppError> {
+        let mut conn = self.pool.get()?;
+        conn.immediate_transaction(|conn| {
+            let affected = diesel::delete(
+                group_members::table
+                    .filter(group_members::group_id.eq(group_id))
+                    .filter(group_members::user_id.eq(user_id)),
+            )
+            .execute(conn)?;
+            if affected == 0 {
+                return Err(AppError::NotFound("User is not a member of this group".to_string()));
+            }
+            AuditRepository::append(conn, audit, &())?;
+            Ok(())
+        })
+    }
+
+    // กฎการเข้าถึงของห้อง คืนค่า (กลุ่มที่จองได้, ผู้ใช้ที่จองได้)
+    pub async fn room_access(&self, room_id: i32) -> Result<(Vec<i32>, Vec<i32>), AppError> {
+        let mut conn = self.pool.get()?;
+        let group_ids = room_allowed_groups::table
+            .filter(room_allowed_groups::room_id.eq(room_id))
+            .order(room_allowed_groups::group_id.asc())
+            .select(room_allowed_groups::group_id)
+            .load::<i32>(&mut conn)?;
+        let user_ids = room_allowed_users::table
+            .filter(room_allowed_users::room_id.eq(room_id))
+            .order(room_allowed_users::user_id.asc())
+            .select(room_allowed_users::user_id)
+            .load::<i32>(&mut conn)?;
+        Ok((group_ids, user_ids))
+    }
+
+    // แทนที่กฎการเข้าถึงของห้องทั้งหมด
+    // กลุ่มต้องเป็นขององค์กรเจ้าของห้อง ผู้ใช้ต้องอยู่ในองค์กรเจ้าของหรือองค์กรที่ห้องถูกแชร์ให้
+    // group_ids และ user_ids ต้องเรียงและไม่ซ้ำ เพราะใช้เป็นผลลัพธ์
+    pub async fn set_room_access(
+        &self,
+        room_id: i32,
+        owner_id: i32,
+        group_ids: &[i32],
+        user_ids: &[i32],
+        audit: PendingAudit<'_, RoomAccessResponse>,
+    ) -> Result<RoomAccessResponse, AppError> {
+        let mut conn = self.pool.get()?;
+        conn.immediate_transaction(|conn| {
+            let known_groups = groups::table
+                .filter(groups::id.eq_any(group_ids))
+                .filter(groups::organization_id.eq(owner_id))
+                .count()
+                .get_result::<i64>(conn)?;
+            if known_groups as usize != group_ids.len() {
+                return Err(AppError::Validation(
+                    "`group_ids` must list groups of the room's organization.".to_string(),
+                ));
+            }
+            let known_users = users::table
+                .filter(users::id.eq_any(user_ids))
+                .filter(
+                    users::organization_id.eq(owner_id).or(users::organization_id.eq_any(
+                        room_shares::table
+                            .filter(room_shares::room_id.eq(room_id))
+                            .select(room_shares::organization_id),
+                    )),
+                )
+                .count()
+                .get_result::<i64>(conn)?;
+            if known_users as usize != user_ids.len() {
+                return Err(AppError::Validation(
+                    "`user_ids` must list users who can see the room.".to_string(),
+                ));
+            }
+
+            diesel::delete(room_allowed_groups::table.filter(room_allowed_groups::room_id.eq(room_id)))
+                .execute(conn)?;
+            diesel::delete(room_allowed_users::table.filter(room_allowed_users::room_id.eq(room_id)))
+                .execute(conn)?;
+            let group_rows: Vec<_> = group_ids
+                .iter()
+                .map(|id| (room_allowed_groups::room_id.eq(room_id), room_allowed_groups::group_id.eq(*id)))
+                .collect();
+            diesel::insert_into(room_allowed_groups::table).values(&group_rows).execute(conn)?;
+            let user_rows: Vec<_> = user_ids
+                .iter()
+                .map(|id| (room_allowed_users::room_id.eq(room_id), room_allowed_users::user_id.eq(*id)))
+                .collect();
+            diesel::insert_into(room_allowed_users::table).values(&user_rows).execute(conn)?;
+            let access = RoomAccessResponse {
+                room_id,
+                restricted: !group_ids.is_empty() || !user_ids.is_empty(),
+                group_ids: group_ids.to_vec(),
+                user_ids: user_ids.to_vec(),
+            };
+            AuditRepository::append(conn, audit, &access)?;
+            Ok(access)
+        })
+    }
+}
@@ -0,0 +1,164 @@
+// src/infrastructure/impersonation_repository.rs
+
+use chrono::Utc;
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use diesel::SqliteConnection;
+
+use crate::domain::audit::PendingAudit;
+use crate::domain::impersonation::{
+    Impersonation, ImpersonationAction, NewImpersonation, NewImpersonationAction,
+};
+use crate::domain::organization::Tenant;
+use crate::domain::pagination::ListQuery;
+use crate::error::AppError;
+
+use super::audit_repository::AuditRepository;
+use super::pagination::sort_by_column;
+use super::schema::{impersonation_actions, impersonations, revoked_tokens, users};
+
+#[derive(Debug, Clone)]
+pub struct ImpersonationRepository {
+    pool: Pool<ConnectionManager<SqliteConnection>>,
+}
+
+impl ImpersonationRepository {
+    pub const SORT_FIELDS: &'static [&'static str] = &["id", "user_id", "created_at"];
+
+    pub fn new(pool: Pool<ConnectionManager<SqliteConnection>>) -> Self {
+        ImpersonationRepository { pool }
+    }
+
+    pub async fn insert(
+        &self,
+        impersonation: NewImpersonation<'_>,
+        audit: PendingAudit<'_, Impersonation>,
+    ) -> Result<Impersonation, AppError> {
+        let mut conn = self.pool.get()?;
+        conn.immediate_transaction(|conn| {
+            diesel::insert_into(impersonations::table)
+                .values(&impersonation)
+                .execute(conn)?;
+            let inserted = impersonations::table
+                .filter(impersonations::jti.eq(impersonation.jti))
+                .select(Impersonation::as_select())
+                .first(conn)?;
+            AuditRepository::append(conn, audit, &inserted)?;
+            Ok(inserted)
+        })
+    }
+
+    // การสวมสิทธิ์ผู้ใช้ในองค์กรของผู้เรียก
+    pub async fn find(&self, tenant: Tenant, id: i32) -> Result<Option<Impersonation>, AppError> {
+        let mut conn = self.pool.get()?;
+        let mut q = impersonations::table.filter(impersonations::id.eq(id)).into_boxed();
+        if let Some(org) = tenant.organization_id() {
+            q = q.filter(impersonations::user_id.eq_any(users::table.filter(users::organization_id.eq(org)).select(users::id)));
+        }
+        Ok(q.select(Impersonation::as_select()).first(&mut conn).optional()?)
+    }
+
+    pub async fn list(
+        &self,
+        tenant: Tenant,
+        query: &ListQuery,
+        offset: i64,
+    ) -> Result<(Vec<Impersonation>, i64), AppError> {
+        let mut conn = self.pool.get()?;
+        let filtered = || {
+            let mut q = impersonations::table.into_boxed();
+            if let Some(org) = tenant.organization_id() {
+                q = q.filter(
+                    impersonations::user_id.eq_any(users::table.filter(users::organization_id.eq(org)).select(users::id)),
+                );
+            }
+            if let Some(user_id) = query.user_id {
+                q = q.filter(impersonations::user_id.eq(user_id));
+            }
+            if let Some(from) = query.from {
+                q = q.filter(impersonations::created_at.ge(from.naive_utc()));
+            }
+            if let Some(to) = query.to {
+                q = q.filter(impersonations::created_at.lt(to.naive_utc()));
+            }
+            q
+        };
+        let total = filtered().count().get_result::<i64>(&mut conn)?;
+
+        let q = filtered();
+        let direction = query.direction();
+        let q = match query.sort.as_deref().unwrap_or("created_at") {
+            "id" => sort_by_column!(q, impersonations::id, direction),
+            "user_id" => sort_by_column!(q, impersonations::user_id, direction),
+            _ => sort_by_column!(q, impersonations::created_at, direction),
+        };
+        let items = q
+            .then_order_by(impersonations::id.asc())
+            .limit(query.limit())
+            .offset(offset)
+            .select(Impersonation::as_select())
+            .load(&mut conn)?;
+        Ok((items, total))
+    }
+
+    pub async fn actions(&self, impersonation_id: i32) -> Result<Vec<ImpersonationAction>, AppError> {
+        let mut conn = self.pool.get()?;
+        let actions = impersonation_actions::table
+            .filter(impersonation_actions::impersonation_id.eq(impersonation_id))
+            .order(impersonation_actions::id.asc())
+            .select(ImpersonationAction::as_select())
+            .load(&mut conn)?;
+        Ok(actions)
+    }
+
+    // บันทึก Request ที่ใช้ Token ของการสวมสิทธิ์ (jti ที่ไม่รู้จักถูกละเว้น)
+    pub async fn record_action(&self, jti: &str, method: &str, path: &str, status: i32) -> Result<(), AppError> {
+        let mut conn = self.pool.get()?;
+        let impersonation_id = impersonations::table
+            .filter(impersonations::jti.eq(jti))
+            .select(impersonations::id)
+            .first::<i32>(&mut conn)
+            .optional()?;
+        if let Some(impersonation_id) = impersonation_id {
+            diesel::insert_into(impersonation_actions::table)
+                .values(&NewImpersonationAction { impersonation_id, method, path, status })
+                .execute(&mut conn)?;
+        }
+        Ok(())
+    }
+
+    // จบการสวมสิทธิ์ก่อนหมดอายุ: เพิกถอน jti ของ Token
+    pub async fn end(
+        &self,
+        impersonation: &Impersonation,
+        audit: PendingAudit<'_, Impersonation>,
+    ) -> Result<Impersonation, AppError> {
+        let mut conn = self.pool.get()?;
+        let now = Utc::now().naive_utc();
+        conn.immediate_transaction(|conn| {
+            let affected = diesel::update(
+                impersonations::table
+                    .filter(impersonations::id.eq(impersonation.id))
+                    .filter(impersonations::ended_at.is_null())
+                    .filter(impersonations::expires_at.gt(now)),
+            )
+            .set(impersonations::ended_at.eq(now))
+            .execute(conn)?;
+            if affected == 0 {
+                return Err(AppError::Conflict("Impersonation has already ended".to_string()));
+            }
+            diesel::insert_or_ignore_into(revoked_tokens::table)
+                .values((
+                    revoked_tokens::jti.eq(&impersonation.jti),
+                    revoked_tokens::expires_at.eq(impersonation.expires_at),
+                ))
+                .execute(conn)?;
+            let ended = impersonations::table
+                .find(impersonation.id)
+                .select(Impersonation::as_select())
+                .first(conn)?;
+            AuditRepository::append(conn, audit, &ended)?;
+            Ok(ended)
+        })
+    }
+}
@@ -1,43 +1,103 @@
-use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
-use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use jsonwebtoken::{decode, decode_header, encode, jwk::JwkSet, Header, Validation};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use chrono::{Duration, Utc};
 
+use crate::error::AppError;
+use crate::infrastructure::jwt_keys::JwtKeySet;
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Claims {
     pub sub: String,      // subject (user_id)
     pub role: String,     // "user" หรือ "admin"
     pub exp: usize,       // expiration time
     pub iat: usize,       // issued at
+    pub jti: String,      // token id (ใช้ตรวจสอบการเพิกถอน)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub org: Option<i32>, // องค์กร (Tenant) ของบัญชี ไม่มี = Platform Admin
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub act: Option<ActorClaim>, // มีเฉพาะ Token ที่ Admin สวมสิทธิ์ผู้ใช้ (RFC 8693)
+}
+
+// ActorClaim: ผู้ที่กระทำแทนเจ้าของ Token จริง (Admin ที่สวมสิทธิ์)
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct ActorClaim {
+    pub sub: String,  // id ของ Admin
+    pub role: String, // "admin"
+}
+
+impl Claims {
+    // แปลง `sub` เป็น id ของ user/admin
+    pub fn subject_id(&self) -> Result<i32, AppError> {
+        self.sub
+            .parse::<i32>()
+            .map_err(|_| AppError::Unauthorized("Invalid user ID format in token.".to_string()))
+    }
+}
+
+// audience ของ Token คำเชิญ Admin (Access Token ไม่มี aud จึงใช้แทนกันไม่ได้)
+pub const INVITATION_AUDIENCE: &str = "admin-invitation";
+
+// InvitationClaims: Claims ของลิงก์คำเชิญให้สร้างบัญชี Admin
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct InvitationClaims {
+    pub sub: String,      // id ของ Admin ที่ส่งคำเชิญ
+    pub aud: String,      // INVITATION_AUDIENCE
+    pub exp: usize,
+    pub iat: usize,
+    pub jti: String,      // ตรงกับ admin_invitations.jti (ใช้ได้ครั้งเดียว/เพิกถอนได้)
+}
+
+// audience ของ Token ยืนยันอีเมล
+pub const EMAIL_VERIFICATION_AUDIENCE: &str = "email-verification";
+
+// EmailVerificationClaims: Claims ของลิงก์ยืนยันอีเมล (ผูกกับอีเมล ณ ตอนส่ง ถ้าเปลี่ยนอีเมลลิงก์เก่าใช้ไม่ได้)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EmailVerificationClaims {
+    pub sub: String,      // id ของผู้ใช้
+    pub aud: String,      // EMAIL_VERIFICATION_AUDIENCE
+    pub email: String,
+    pub exp: usize,
+    pub iat: usize,
 }
 
+// AccessToken: Token ที่สร้างแล้วพร้อม Claims (ใช้ jti/exp ในการบันทึก session)
+#[derive(Debug, Clone)]
+pub struct AccessToken {
+    pub token: String,
+    pub claims: Claims,
+}
+
+// JwtService: เซ็น Token ด้วย key ที่ active และตรวจสอบ Token ด้วย key ตาม `kid` ใน header
 #[derive(Clone)]
 pub struct JwtService {
-    encoding_key: EncodingKey,
-    decoding_key: DecodingKey,
-    validation: Validation, // เพิ่ม field สำหรับ Validation
+    keys: Arc<JwtKeySet>,   // key ทั้งหมด (active + key เก่าที่ยังใช้ตรวจสอบได้)
+    access_ttl: Duration,   // อายุของ Access Token
 }
 
 impl JwtService {
-     pub fn new(secret: &str) -> Self {
-        let encoding_key = EncodingKey::from_secret(secret.as_bytes());
-        let decoding_key = DecodingKey::from_secret(secret.as_bytes());
-
-        let mut validation = Validation::new(Algorithm::HS512); // ใช้ Algorithm เดียวกันกับตอน encode
-        validation.validate_exp = true; // ตรวจสอบวันหมดอายุ (ควรเป็น true)
-        validation.leeway = 60; // อนุญาตให้มีเวลาเหลื่อมได้ 60 วินาที
-
+     pub fn new(keys: JwtKeySet, access_ttl_seconds: i64) -> Self {
         JwtService {
-            encoding_key,
-            decoding_key,
-            validation,
+            keys: Arc::new(keys),
+            access_ttl: Duration::seconds(access_ttl_seconds),
         }
     }
 
+    // Public Key สำหรับ /.well-known/jwks.json
+    pub fn jwks(&self) -> JwkSet {
+        self.keys.jwks()
+    }
+
+    // อายุของ Access Token (วินาที) สำหรับ `expires_in` ใน Response
+    pub fn access_ttl_seconds(&self) -> i64 {
+        self.access_ttl.num_seconds()
+    }
+
     // *** แก้ไข: เพิ่ม &self และใช้ self.encoding_key ***
-    pub fn create_token(&self, user_id: i32, role: &str) -> Result<String, String> {
+    pub fn create_token(&self, user_id: i32, role: &str, org: Option<i32>) -> Result<AccessToken, AppError> {
         let now = Utc::now();
-        let expires_in = Duration::days(1); // 24 ชั่วโมง
-        let exp = (now + expires_in).timestamp();
+        let exp = (now + self.access_ttl).timestamp();
         let iat = now.timestamp();
 
         let claims = Claims {
@@ -45,23 +105,226 @@ impl JwtService {
             role: role.to_string(),
             exp: exp as usize,
             iat: iat as usize,
+            jti: uuid::Uuid::new_v4().to_string(),
+            org,
+            act: None,
         };
 
-        let header = Header::new(Algorithm::HS512);
-        // *** ใช้ &self.encoding_key แทน Self::get_secret() ***
-        encode(&header, &claims, &self.encoding_key)
-            .map_err(|e| format!("Failed to create token: {}", e))
+        let token = self.sign(&claims)?;
+        Ok(AccessToken { token, claims })
     }
 
-   // *** แก้ไข: เพิ่ม &self และใช้ self.decoding_key กับ self.validation ***
-    pub fn decode_token(&self, token: &str) -> Result<Claims, String> {
-        // *** ใช้ &self.decoding_key และ &self.validation แทนการสร้างใหม่จาก env variable ***
-        decode::<Claims>(
-            token,
-            &self.decoding_key,
-            &self.validation,
-        )
+    // สร้าง Access Token ของผู้ใช้ที่ Admin สวมสิทธิ์ (อายุ ttl ไม่มี Refresh Token)
+    pub fn create_impersonation_token(
+        &self,
+        user_id: i32,
+        org: i32,
+        admin_id: i32,
+        ttl: Duration,
+    ) -> Result<AccessToken, AppError> {
+        let now = Utc::now();
+        let claims = Claims {
+            sub: user_id.to_string(),
+            role: "user".to_string(),
+            exp: (now + ttl).timestamp() as usize,
+            iat: now.timestamp() as usize,
+            jti: uuid::Uuid::new_v4().to_string(),
+            org: Some(org),
+            act: Some(ActorClaim {
+                sub: admin_id.to_string(),
+                role: "admin".to_string(),
+            }),
+        };
+        let token = self.sign(&claims)?;
+        Ok(AccessToken { token, claims })
+    }
+
+    // สร้าง Token คำเชิญ Admin ที่หมดอายุใน ttl
+    pub fn create_invitation_token(&self, invited_by: i32, jti: &str, ttl: Duration) -> Result<(String, InvitationClaims), AppError> {
+        let now = Utc::now();
+        let claims = InvitationClaims {
+            sub: invited_by.to_string(),
+            aud: INVITATION_AUDIENCE.to_string(),
+            exp: (now + ttl).timestamp() as usize,
+            iat: now.timestamp() as usize,
+            jti: jti.to_string(),
+        };
+        let token = self.sign(&claims)?;
+        Ok((token, claims))
+    }
+
+    // สร้าง Token ยืนยันอีเมลที่หมดอายุใน ttl
+    pub fn create_email_verification_token(&self, user_id: i32, email: &str, ttl: Duration) -> Result<String, AppError> {
+        let now = Utc::now();
+        let claims = EmailVerificationClaims {
+            sub: user_id.to_string(),
+            aud: EMAIL_VERIFICATION_AUDIENCE.to_string(),
+            email: email.to_string(),
+            exp: (now + ttl).timestamp() as usize,
+            iat: now.timestamp() as usize,
+        };
+        self.sign(&claims)
+    }
+
+    // เซ็นด้วย key ที่ active และระบุ kid เพื่อให้ผู้ตรวจสอบเลือก key ได้ถูกต้อง
+    fn sign<T: Serialize>(&self, claims: &T) -> Result<String, AppError> {
+        let key = self.keys.active();
+        let encoding_key = key
+            .encoding
+            .as_ref()
+            .ok_or_else(|| AppError::Internal(format!("JWT key '{}' cannot sign tokens", key.kid)))?;
+        let mut header = Header::new(key.algorithm);
+        header.kid = Some(key.kid.clone());
+        encode(&header, claims, encoding_key)
+            .map_err(|e| AppError::Internal(format!("Failed to create token: {}", e)))
+    }
+
+    pub fn decode_token(&self, token: &str) -> Result<Claims, AppError> {
+        self.decode_with::<Claims>(token, None)
+    }
+
+    // ตรวจสอบ Token คำเชิญ (ต้องมี aud = INVITATION_AUDIENCE)
+    pub fn decode_invitation_token(&self, token: &str) -> Result<InvitationClaims, AppError> {
+        self.decode_with::<InvitationClaims>(token, Some(INVITATION_AUDIENCE))
+    }
+
+    // ตรวจสอบ Token ยืนยันอีเมล (ต้องมี aud = EMAIL_VERIFICATION_AUDIENCE)
+    pub fn decode_email_verification_token(&self, token: &str) -> Result<EmailVerificationClaims, AppError> {
+        self.decode_with::<EmailVerificationClaims>(token, Some(EMAIL_VERIFICATION_AUDIENCE))
+    }
+
+    // ตรวจสอบ Token ด้วย key ตาม kid และใช้ Algorithm ของ key นั้นเท่านั้น (ไม่เชื่อ alg จาก header)
+    // audience: ถ้าระบุ Token ต้องมี aud ตรงกัน ถ้าไม่ระบุ Token ที่มี aud จะถูกปฏิเสธ
+    fn decode_with<T: DeserializeOwned>(&self, token: &str, audience: Option<&str>) -> Result<T, AppError> {
+        let invalid = |e: jsonwebtoken::errors::Error| AppError::Unauthorized(format!("Invalid token. {}", e));
+        let header = decode_header(token).map_err(invalid)?;
+        let key = self
+            .keys
+            .get(header.kid.as_deref())
+            .ok_or_else(|| AppError::Unauthorized("Invalid token. Unknown signing key.".to_string()))?;
+
+        let mut validation = Validation::new(key.algorithm);
+        validation.validate_exp = true; // ตรวจสอบวันหมดอายุ (ควรเป็น true)
+        validation.leeway = 60; // อนุญาตให้มีเวลาเหลื่อมได้ 60 วินาที
+        if let Some(audience) = audience {
+            validation.set_audience(&[audience]);
+            validation.set_required_spec_claims(&["exp", "aud"]);
+        }
+
+        decode::<T>(token, &key.decoding, &validation)
         .map(|data| data.claims)
-        .map_err(|e| format!("Invalid token: {}", e))
+        .map_err(invalid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ed25519_dalek::pkcs8::{spki::der::pem::LineEnding, EncodePrivateKey, EncodePublicKey};
+
+    use super::*;
+
+    // เขียน Ed25519 key ลงไฟล์ชั่วคราว แล้วคืน (path ของ Private Key, path ของ Public Key)
+    fn ed25519_files(seed: u8) -> (String, String) {
+        let signing = ed25519_dalek::SigningKey::from_bytes(&[seed; 32]);
+        let dir = std::env::temp_dir();
+        let id = uuid::Uuid::new_v4();
+        let private = dir.join(format!("jwt-{id}-private.pem"));
+        let public = dir.join(format!("jwt-{id}-public.pem"));
+        std::fs::write(&private, signing.to_pkcs8_pem(LineEnding::LF).unwrap().as_bytes()).unwrap();
+        std::fs::write(&public, signing.verifying_key().to_public_key_pem(LineEnding::LF).unwrap()).unwrap();
+        (private.display().to_string(), public.display().to_string())
+    }
+
+    fn service(secret: Option<&str>, keys: Option<&str>, active: Option<&str>) -> JwtService {
+        JwtService::new(JwtKeySet::from_config(secret, keys, active).unwrap(), 60)
+    }
+
+    #[test]
+    fn tokens_signed_with_previous_key_verify_after_rotation() {
+        let (old_key, _) = ed25519_files(1);
+        let (new_key, _) = ed25519_files(2);
+        let before = service(None, Some(&format!("old=EdDSA:{old_key}")), None);
+        let old_token = before.create_token(7, "user", Some(1)).unwrap().token;
+        assert_eq!(decode_header(&old_token).unwrap().kid.as_deref(), Some("old"));
+
+        let after = service(None, Some(&format!("old=EdDSA:{old_key},new=EdDSA:{new_key}")), Some("new"));
+        assert_eq!(after.decode_token(&old_token).unwrap().sub, "7");
+        let new_token = after.create_token(8, "user", Some(1)).unwrap().token;
+        assert_eq!(decode_header(&new_token).unwrap().kid.as_deref(), Some("new"));
+
+        // เมื่อลบ key เก่าออก Token เก่าใช้ไม่ได้อีก
+        let retired = service(None, Some(&format!("new=EdDSA:{new_key}")), None);
+        assert!(matches!(retired.decode_token(&old_token), Err(AppError::Unauthorized(_))));
+    }
+
+    #[test]
+    fn jwks_publishes_only_public_keys() {
+        let (private, _) = ed25519_files(3);
+        let (_, other_public) = ed25519_files(4);
+        let jwt = service(
+            Some("hs512-secret-for-tests"),
+            Some(&format!("signer=EdDSA:{private},verify-only=EdDSA:{other_public}")),
+            Some("signer"),
+        );
+        let kids: Vec<_> = jwt.jwks().keys.iter().filter_map(|k| k.common.key_id.clone()).collect();
+        assert_eq!(kids, vec!["signer".to_string(), "verify-only".to_string()]);
+    }
+
+    #[test]
+    fn invalid_key_configuration_is_rejected() {
+        let (_, public) = ed25519_files(5);
+        assert!(JwtKeySet::from_config(None, None, None).is_err());
+        assert!(JwtKeySet::from_config(Some("secret"), None, Some("missing")).is_err());
+        assert!(JwtKeySet::from_config(None, Some(&format!("pub=EdDSA:{public}")), None).is_err());
+        assert!(JwtKeySet::from_config(None, Some(&format!("k=HS256:{public}")), None).is_err());
+        assert!(JwtKeySet::from_config(None, Some("no-separator"), None).is_err());
+    }
+
+    #[test]
+    fn algorithm_comes_from_the_key_not_the_header() {
+        let jwt = service(Some("hs512-secret-for-tests"), None, None);
+        let claims = jwt.create_token(1, "admin", None).unwrap().claims;
+        // เซ็นด้วย HS256 โดยใช้ secret เดียวกัน: key "default" เป็น HS512 จึงต้องถูกปฏิเสธ
+        let mut header = Header::new(jsonwebtoken::Algorithm::HS256);
+        header.kid = Some("default".to_string());
+        let forged = encode(&header, &claims, &jsonwebtoken::EncodingKey::from_secret(b"hs512-secret-for-tests")).unwrap();
+        assert!(jwt.decode_token(&forged).is_err());
+    }
+
+    #[test]
+    fn audience_tokens_are_not_access_tokens() {
+        let jwt = service(Some("hs512-secret-for-tests"), None, None);
+        let (invitation, _) = jwt.create_invitation_token(1, "jti-1", Duration::hours(1)).unwrap();
+        assert!(jwt.decode_token(&invitation).is_err());
+        assert!(jwt.decode_invitation_token(&invitation).is_ok());
+    }
+
+    #[test]
+    fn email_verification_tokens_carry_the_email_and_are_not_access_tokens() {
+        let jwt = service(Some("hs512-secret-for-tests"), None, None);
+        let token = jwt.create_email_verification_token(5, "peter@example.com", Duration::hours(1)).unwrap();
+        let claims = jwt.decode_email_verification_token(&token).unwrap();
+        assert_eq!((claims.sub.as_str(), claims.email.as_str()), ("5", "peter@example.com"));
+        assert!(jwt.decode_token(&token).is_err());
+        assert!(jwt.decode_invitation_token(&token).is_err());
+
+        // Access token ใช้ยืนยันอีเมลไม่ได้ และลิงก์ที่หมดอายุแล้วใช้ไม่ได้
+        let access = jwt.create_token(5, "user", Some(1)).unwrap().token;
+        assert!(jwt.decode_email_verification_token(&access).is_err());
+        let expired = jwt.create_email_verification_token(5, "peter@example.com", Duration::hours(-2)).unwrap();
+        assert!(jwt.decode_email_verification_token(&expired).is_err());
+    }
+
+    #[test]
+    fn impersonation_tokens_name_the_admin() {
+        let jwt = service(Some("hs512-secret-for-tests"), None, None);
+        let issued = jwt.create_impersonation_token(7, 2, 3, Duration::minutes(15)).unwrap();
+        let claims = jwt.decode_token(&issued.token).unwrap();
+        assert_eq!((claims.sub.as_str(), claims.role.as_str(), claims.org), ("7", "user", Some(2)));
+        let act = claims.act.expect("act claim");
+        assert_eq!((act.sub.as_str(), act.role.as_str()), ("3", "admin"));
+        assert_eq!(claims.exp - claims.iat, 15 * 60);
+        // Token ปกติไม่มี act
+        assert!(jwt.decode_token(&jwt.create_token(7, "user", Some(2)).unwrap().token).unwrap().act.is_none());
     }
 }
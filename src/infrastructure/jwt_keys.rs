@@ -0,0 +1,166 @@
+// src/infrastructure/jwt_keys.rs
+
+use std::collections::HashMap;
+
+use anyhow::{anyhow, bail, Context};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use jsonwebtoken::jwk::{
+    AlgorithmParameters, CommonParameters, EllipticCurve, Jwk, JwkSet, KeyAlgorithm, OctetKeyPairParameters,
+    OctetKeyPairType, PublicKeyUse, RSAKeyParameters, RSAKeyType,
+};
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey};
+use rsa::pkcs1::{DecodeRsaPrivateKey, DecodeRsaPublicKey};
+use rsa::pkcs8::{DecodePrivateKey, DecodePublicKey};
+use rsa::traits::PublicKeyParts;
+
+// kid ของ key แบบ HS512 ที่สร้างจาก JWT_SECRET
+// Token ที่ไม่มี kid (ออกก่อนรองรับหลาย key) จะถูกตรวจสอบด้วย key นี้
+pub const SECRET_KID: &str = "default";
+
+// JwtKey: key หนึ่งตัวใน key set
+// - encoding: มีเฉพาะ key ที่โหลดจาก Private Key (ใช้เซ็น Token ได้)
+// - jwk: Public Key ที่เผยแพร่ผ่าน /.well-known/jwks.json (ไม่มีสำหรับ HS512)
+#[derive(Clone)]
+pub struct JwtKey {
+    pub kid: String,
+    pub algorithm: Algorithm,
+    pub encoding: Option<EncodingKey>,
+    pub decoding: DecodingKey,
+    pub jwk: Option<Jwk>,
+}
+
+// JwtKeySet: key ทั้งหมดที่ใช้ตรวจสอบ Token และ key ที่ใช้เซ็น Token ใหม่ (active)
+// การหมุนเวียน key: เพิ่ม key ใหม่แล้วตั้งเป็น active โดยยังเก็บ key เก่าไว้
+// จนกว่า Token ที่เซ็นด้วย key เก่าจะหมดอายุ แล้วจึงลบออก
+#[derive(Clone)]
+pub struct JwtKeySet {
+    keys: HashMap<String, JwtKey>,
+    active_kid: String,
+}
+
+impl JwtKeySet {
+    // secret: JWT_SECRET (HS512, kid = "default")
+    // keys_spec: JWT_KEYS รูปแบบ `kid=ALG:path,kid=ALG:path` โดย ALG เป็น RS256 หรือ EdDSA
+    //   path เป็นไฟล์ PEM ของ Private Key (เซ็นและตรวจสอบ) หรือ Public Key (ตรวจสอบอย่างเดียว)
+    // active_kid: JWT_ACTIVE_KID ถ้าไม่ระบุจะใช้ key แรกใน JWT_KEYS หรือ "default"
+    pub fn from_config(secret: Option<&str>, keys_spec: Option<&str>, active_kid: Option<&str>) -> anyhow::Result<Self> {
+        let mut keys = HashMap::new();
+        let mut first_kid = None;
+
+        if let Some(secret) = secret.filter(|s| !s.is_empty()) {
+            keys.insert(
+                SECRET_KID.to_string(),
+                JwtKey {
+                    kid: SECRET_KID.to_string(),
+                    algorithm: Algorithm::HS512,
+                    encoding: Some(EncodingKey::from_secret(secret.as_bytes())),
+                    decoding: DecodingKey::from_secret(secret.as_bytes()),
+                    jwk: None,
+                },
+            );
+        }
+
+        for entry in keys_spec.unwrap_or_default().split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let (kid, rest) = entry
+                .split_once('=')
+                .ok_or_else(|| anyhow!("Invalid JWT_KEYS entry '{}': expected kid=ALG:path", entry))?;
+            let (alg, path) = rest
+                .split_once(':')
+                .ok_or_else(|| anyhow!("Invalid JWT_KEYS entry '{}': expected kid=ALG:path", entry))?;
+            let pem = std::fs::read_to_string(path).with_context(|| format!("Failed to read JWT key file {}", path))?;
+            let key = load_pem_key(kid, alg, &pem).with_context(|| format!("Failed to load JWT key '{}'", kid))?;
+            if keys.insert(kid.to_string(), key).is_some() {
+                bail!("Duplicate JWT key id '{}'", kid);
+            }
+            first_kid.get_or_insert_with(|| kid.to_string());
+        }
+
+        let active_kid = active_kid
+            .map(str::to_string)
+            .or(first_kid)
+            .unwrap_or_else(|| SECRET_KID.to_string());
+        match keys.get(&active_kid) {
+            None => bail!("Active JWT key '{}' is not configured (set JWT_SECRET or JWT_KEYS)", active_kid),
+            Some(key) if key.encoding.is_none() => {
+                bail!("Active JWT key '{}' must be a private key to sign tokens", active_kid)
+            }
+            Some(_) => {}
+        }
+
+        Ok(JwtKeySet { keys, active_kid })
+    }
+
+    pub fn active(&self) -> &JwtKey {
+        &self.keys[&self.active_kid]
+    }
+
+    // key สำหรับตรวจสอบ Token ตาม kid ใน header (ไม่มี kid = Token แบบเก่าที่เซ็นด้วย JWT_SECRET)
+    pub fn get(&self, kid: Option<&str>) -> Option<&JwtKey> {
+        self.keys.get(kid.unwrap_or(SECRET_KID))
+    }
+
+    // Public Key ทั้งหมดในรูปแบบ JWKS (ไม่รวม HS512 ที่เป็น secret)
+    pub fn jwks(&self) -> JwkSet {
+        let mut keys: Vec<Jwk> = self.keys.values().filter_map(|k| k.jwk.clone()).collect();
+        keys.sort_by(|a, b| a.common.key_id.cmp(&b.common.key_id));
+        JwkSet { keys }
+    }
+}
+
+fn load_pem_key(kid: &str, alg: &str, pem: &str) -> anyhow::Result<JwtKey> {
+    let is_private = pem.contains("PRIVATE KEY");
+    let common = |key_algorithm| CommonParameters {
+        public_key_use: Some(PublicKeyUse::Signature),
+        key_id: Some(kid.to_string()),
+        key_algorithm: Some(key_algorithm),
+        ..Default::default()
+    };
+
+    let (algorithm, encoding, jwk) = match alg {
+        "RS256" => {
+            let public = if is_private {
+                rsa::RsaPrivateKey::from_pkcs8_pem(pem)
+                    .or_else(|_| rsa::RsaPrivateKey::from_pkcs1_pem(pem))?
+                    .to_public_key()
+            } else {
+                rsa::RsaPublicKey::from_public_key_pem(pem).or_else(|_| rsa::RsaPublicKey::from_pkcs1_pem(pem))?
+            };
+            let encoding = is_private.then(|| EncodingKey::from_rsa_pem(pem.as_bytes())).transpose()?;
+            let jwk = Jwk {
+                common: common(KeyAlgorithm::RS256),
+                algorithm: AlgorithmParameters::RSA(RSAKeyParameters {
+                    key_type: RSAKeyType::RSA,
+                    n: URL_SAFE_NO_PAD.encode(public.n().to_bytes_be()),
+                    e: URL_SAFE_NO_PAD.encode(public.e().to_bytes_be()),
+                }),
+            };
+            (Algorithm::RS256, encoding, jwk)
+        }
+        "EdDSA" => {
+            let public = if is_private {
+                ed25519_dalek::SigningKey::from_pkcs8_pem(pem)?.verifying_key()
+            } else {
+                ed25519_dalek::VerifyingKey::from_public_key_pem(pem)?
+            };
+            let encoding = is_private.then(|| EncodingKey::from_ed_pem(pem.as_bytes())).transpose()?;
+            let jwk = Jwk {
+                common: common(KeyAlgorithm::EdDSA),
+                algorithm: AlgorithmParameters::OctetKeyPair(OctetKeyPairParameters {
+                    key_type: OctetKeyPairType::OctetKeyPair,
+                    curve: EllipticCurve::Ed25519,
+                    x: URL_SAFE_NO_PAD.encode(public.as_bytes()),
+                }),
+            };
+            (Algorithm::EdDSA, encoding, jwk)
+        }
+        other => bail!("Unsupported JWT algorithm '{}' (expected RS256 or EdDSA)", other),
+    };
+
+    Ok(JwtKey {
+        kid: kid.to_string(),
+        algorithm,
+        encoding,
+        decoding: DecodingKey::from_jwk(&jwk)?,
+        jwk: Some(jwk),
+    })
+}
@@ -0,0 +1,175 @@
+// src/infrastructure/login_throttle_repository.rs
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use diesel::SqliteConnection;
+
+use crate::domain::audit::PendingAudit;
+use crate::domain::login_throttle::{
+    LockoutPolicy, LoginEvent, LoginEventQuery, LoginThrottle, NewLoginEvent, NewLoginThrottle,
+};
+use crate::error::AppError;
+
+use super::audit_repository::AuditRepository;
+use super::schema::{login_events, login_throttles};
+
+#[derive(Debug, Clone)]
+pub struct LoginThrottleRepository {
+    pool: Pool<ConnectionManager<SqliteConnection>>,
+}
+
+impl LoginThrottleRepository {
+    pub fn new(pool: Pool<ConnectionManager<SqliteConnection>>) -> Self {
+        LoginThrottleRepository { pool }
+    }
+
+    // รายการที่ยังถูกล็อกอยู่ ณ เวลา now จากคู่ (scope, key) ที่ระบุ
+    pub async fn find_locked(&self, keys: &[(&str, &str)], now: NaiveDateTime) -> Result<Vec<LoginThrottle>, AppError> {
+        let mut conn = self.pool.get()?;
+        let mut locked = Vec::new();
+        for (scope, key) in keys {
+            let throttle = login_throttles::table
+                .filter(login_throttles::scope.eq(scope))
+                .filter(login_throttles::throttle_key.eq(key))
+                .filter(login_throttles::locked_until.gt(now))
+                .select(LoginThrottle::as_select())
+                .first(&mut conn)
+                .optional()?;
+            locked.extend(throttle);
+        }
+        Ok(locked)
+    }
+
+    // เพิ่มจำนวนครั้งที่ Login ผิด และกำหนดเวลาล็อกตาม policy (ทำใน Transaction เดียวกัน)
+    pub async fn record_failure(
+        &self,
+        scope: &str,
+        key: &str,
+        policy: &LockoutPolicy,
+        now: NaiveDateTime,
+    ) -> Result<LoginThrottle, AppError> {
+        let mut conn = self.pool.get()?;
+        conn.immediate_transaction(|conn| {
+            let existing = login_throttles::table
+                .filter(login_throttles::scope.eq(scope))
+                .filter(login_throttles::throttle_key.eq(key))
+                .select(LoginThrottle::as_select())
+                .first(conn)
+                .optional()?;
+
+            // นับ window จากการ Login ผิดครั้งล่าสุดหรือเวลาที่ปลดล็อก (แล้วแต่อันไหนช้ากว่า)
+            let failed_count = match &existing {
+                Some(throttle) => {
+                    let last_activity = throttle
+                        .locked_until
+                        .map_or(throttle.last_failed_at, |until| until.max(throttle.last_failed_at));
+                    if now - last_activity > policy.window {
+                        1
+                    } else {
+                        throttle.failed_count + 1
+                    }
+                }
+                None => 1,
+            };
+            let locked_until = policy.lock_duration(failed_count).map(|delay| now + delay);
+
+            match existing {
+                Some(throttle) => {
+                    diesel::update(login_throttles::table.find(throttle.id))
+                        .set((
+                            login_throttles::failed_count.eq(failed_count),
+                            login_throttles::last_failed_at.eq(now),
+                            login_throttles::locked_until.eq(locked_until),
+                        ))
+                        .execute(conn)?;
+                }
+                None => {
+                    diesel::insert_into(login_throttles::table)
+                        .values(&NewLoginThrottle {
+                            scope,
+                            throttle_key: key,
+                            failed_count,
+                            last_failed_at: now,
+                            locked_until,
+                        })
+                        .execute(conn)?;
+                }
+            }
+
+            login_throttles::table
+                .filter(login_throttles::scope.eq(scope))
+                .filter(login_throttles::throttle_key.eq(key))
+                .select(LoginThrottle::as_select())
+                .first(conn)
+                .map_err(AppError::from)
+        })
+    }
+
+    // ล้างตัวนับเมื่อ Login สำเร็จ
+    pub async fn clear(&self, scope: &str, key: &str) -> Result<(), AppError> {
+        let mut conn = self.pool.get()?;
+        diesel::delete(
+            login_throttles::table
+                .filter(login_throttles::scope.eq(scope))
+                .filter(login_throttles::throttle_key.eq(key)),
+        )
+        .execute(&mut conn)?;
+        Ok(())
+    }
+
+    // รายการที่ยังถูกล็อก หรือมีการ Login ผิดหลัง `since` (ล่าสุดก่อน)
+    pub async fn list_active(&self, now: NaiveDateTime, since: NaiveDateTime) -> Result<Vec<LoginThrottle>, AppError> {
+        let mut conn = self.pool.get()?;
+        let throttles = login_throttles::table
+            .filter(
+                login_throttles::locked_until
+                    .gt(now)
+                    .or(login_throttles::last_failed_at.gt(since)),
+            )
+            .order(login_throttles::last_failed_at.desc())
+            .select(LoginThrottle::as_select())
+            .load(&mut conn)?;
+        Ok(throttles)
+    }
+
+    // ลบรายการ (ปลดล็อก) คืนค่า false ถ้าไม่พบ
+    pub async fn delete_by_id(&self, throttle_id: i32, audit: PendingAudit<'_, ()>) -> Result<bool, AppError> {
+        let mut conn = self.pool.get()?;
+        conn.immediate_transaction(|conn| {
+            let deleted = diesel::delete(login_throttles::table.find(throttle_id)).execute(conn)?;
+            if deleted == 0 {
+                return Ok(false);
+            }
+            AuditRepository::append(conn, audit, &())?;
+            Ok(true)
+        })
+    }
+
+    pub async fn insert_event(&self, event: NewLoginEvent<'_>) -> Result<(), AppError> {
+        let mut conn = self.pool.get()?;
+        diesel::insert_into(login_events::table)
+            .values(&event)
+            .execute(&mut conn)?;
+        Ok(())
+    }
+
+    pub async fn list_events(&self, query: &LoginEventQuery) -> Result<Vec<LoginEvent>, AppError> {
+        let mut conn = self.pool.get()?;
+        let mut events = login_events::table.select(LoginEvent::as_select()).into_boxed();
+        if let Some(username) = &query.username {
+            events = events.filter(login_events::username.eq(username));
+        }
+        if let Some(ip) = &query.ip {
+            events = events.filter(login_events::ip.eq(ip));
+        }
+        if let Some(success) = query.success {
+            events = events.filter(login_events::success.eq(success));
+        }
+        let events = events
+            .order(login_events::id.desc())
+            .limit(query.limit())
+            .load(&mut conn)?;
+        Ok(events)
+    }
+}
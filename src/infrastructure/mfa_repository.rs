@@ -0,0 +1,284 @@
+// src/infrastructure/mfa_repository.rs
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use diesel::SqliteConnection;
+
+use crate::domain::audit::PendingAudit;
+use crate::domain::mfa::{MfaChallenge, MfaStatusResponse, NewMfaChallenge, NewRecoveryCode, NewTotpFactor, TotpFactor};
+use crate::error::AppError;
+
+use super::audit_repository::AuditRepository;
+use super::role_repository::mfa_required;
+use super::schema::{admins, mfa_challenges, mfa_recovery_codes, mfa_totp, users};
+
+#[derive(Debug, Clone)]
+pub struct MfaRepository {
+    pool: Pool<ConnectionManager<SqliteConnection>>,
+}
+
+fn insert_recovery_codes(
+    conn: &mut SqliteConnection,
+    principal_kind: &str,
+    principal_id: i32,
+    code_hashes: &[String],
+) -> Result<(), AppError> {
+    diesel::delete(
+        mfa_recovery_codes::table
+            .filter(mfa_recovery_codes::principal_kind.eq(principal_kind))
+            .filter(mfa_recovery_codes::principal_id.eq(principal_id)),
+    )
+    .execute(conn)?;
+    let codes: Vec<NewRecoveryCode> = code_hashes
+        .iter()
+        .map(|code_hash| NewRecoveryCode { principal_kind, principal_id, code_hash })
+        .collect();
+    diesel::insert_into(mfa_recovery_codes::table)
+        .values(&codes)
+        .execute(conn)?;
+    Ok(())
+}
+
+fn load_factor(
+    conn: &mut SqliteConnection,
+    principal_kind: &str,
+    principal_id: i32,
+) -> Result<Option<TotpFactor>, diesel::result::Error> {
+    mfa_totp::table
+        .filter(mfa_totp::principal_kind.eq(principal_kind))
+        .filter(mfa_totp::principal_id.eq(principal_id))
+        .select(TotpFactor::as_select())
+        .first(conn)
+        .optional()
+}
+
+// สถานะ 2FA ของบัญชี (ใช้ทั้งตอบ Endpoint และบันทึกใน Audit Log หลังเปลี่ยน)
+fn load_status(
+    conn: &mut SqliteConnection,
+    principal_kind: &str,
+    principal_id: i32,
+) -> Result<MfaStatusResponse, diesel::result::Error> {
+    let factor = load_factor(conn, principal_kind, principal_id)?;
+    let recovery_codes_remaining = mfa_recovery_codes::table
+        .filter(mfa_recovery_codes::principal_kind.eq(principal_kind))
+        .filter(mfa_recovery_codes::principal_id.eq(principal_id))
+        .filter(mfa_recovery_codes::used_at.is_null())
+        .count()
+        .get_result::<i64>(conn)?;
+    Ok(MfaStatusResponse {
+        enabled: factor.as_ref().is_some_and(|f| f.confirmed_at.is_some()),
+        pending_enrollment: factor.as_ref().is_some_and(|f| f.confirmed_at.is_none()),
+        required: mfa_required(conn, principal_kind, principal_id)?,
+        recovery_codes_remaining,
+    })
+}
+
+impl MfaRepository {
+    pub fn new(pool: Pool<ConnectionManager<SqliteConnection>>) -> Self {
+        MfaRepository { pool }
+    }
+
+    // ชื่อบัญชีที่แสดงใน Authenticator App
+    pub async fn account_name(&self, principal_kind: &str, principal_id: i32) -> Result<Option<String>, AppError> {
+        let mut conn = self.pool.get()?;
+        let username = match principal_kind {
+            "admin" => admins::table
+                .find(principal_id)
+                .select(admins::username)
+                .first::<String>(&mut conn)
+                .optional()?,
+            _ => users::table
+                .find(principal_id)
+                .select(users::username)
+                .first::<String>(&mut conn)
+                .optional()?,
+        };
+        Ok(username)
+    }
+
+    pub async fn find_factor(&self, principal_kind: &str, principal_id: i32) -> Result<Option<TotpFactor>, AppError> {
+        let mut conn = self.pool.get()?;
+        Ok(load_factor(&mut conn, principal_kind, principal_id)?)
+    }
+
+    pub async fn status(&self, principal_kind: &str, principal_id: i32) -> Result<MfaStatusResponse, AppError> {
+        let mut conn = self.pool.get()?;
+        Ok(load_status(&mut conn, principal_kind, principal_id)?)
+    }
+
+    // เริ่มลงทะเบียนใหม่ (แทนที่การลงทะเบียนที่ยังไม่ยืนยัน) Conflict ถ้าเปิดใช้ 2FA อยู่แล้ว
+    pub async fn start_enrollment(
+        &self,
+        factor: NewTotpFactor<'_>,
+        audit: PendingAudit<'_, MfaStatusResponse>,
+    ) -> Result<(), AppError> {
+        let mut conn = self.pool.get()?;
+        conn.immediate_transaction(|conn| {
+            let existing = load_factor(conn, factor.principal_kind, factor.principal_id)?;
+            match existing {
+                Some(existing) if existing.confirmed_at.is_some() => {
+                    return Err(AppError::Conflict("Two-factor authentication is already enabled.".to_string()));
+                }
+                Some(existing) => {
+                    diesel::delete(mfa_totp::table.find(existing.id)).execute(conn)?;
+                }
+                None => {}
+            }
+            diesel::insert_into(mfa_totp::table)
+                .values(&factor)
+                .execute(conn)?;
+            let status = load_status(conn, factor.principal_kind, factor.principal_id)?;
+            AuditRepository::append(conn, audit, &status)?;
+            Ok(())
+        })
+    }
+
+    // บันทึก time step ของรหัสที่ใช้แล้ว คืนค่า false ถ้ารหัสของ step นี้ (หรือใหม่กว่า) ถูกใช้ไปแล้ว
+    pub async fn use_step(&self, factor_id: i32, step: i64) -> Result<bool, AppError> {
+        let mut conn = self.pool.get()?;
+        let affected = diesel::update(
+            mfa_totp::table
+                .find(factor_id)
+                .filter(mfa_totp::last_used_step.is_null().or(mfa_totp::last_used_step.lt(step))),
+        )
+        .set(mfa_totp::last_used_step.eq(step))
+        .execute(&mut conn)?;
+        Ok(affected > 0)
+    }
+
+    // ยืนยันการลงทะเบียน และบันทึก Recovery Code ชุดแรก
+    pub async fn confirm_factor(
+        &self,
+        factor: &TotpFactor,
+        code_hashes: &[String],
+        now: NaiveDateTime,
+        audit: PendingAudit<'_, MfaStatusResponse>,
+    ) -> Result<(), AppError> {
+        let mut conn = self.pool.get()?;
+        conn.transaction(|conn| {
+            diesel::update(mfa_totp::table.find(factor.id))
+                .set(mfa_totp::confirmed_at.eq(now))
+                .execute(conn)?;
+            insert_recovery_codes(conn, &factor.principal_kind, factor.principal_id, code_hashes)?;
+            let status = load_status(conn, &factor.principal_kind, factor.principal_id)?;
+            AuditRepository::append(conn, audit, &status)?;
+            Ok(())
+        })
+    }
+
+    // แทนที่ Recovery Code ทั้งหมดด้วยชุดใหม่
+    pub async fn replace_recovery_codes(
+        &self,
+        principal_kind: &str,
+        principal_id: i32,
+        code_hashes: &[String],
+        audit: PendingAudit<'_, MfaStatusResponse>,
+    ) -> Result<(), AppError> {
+        let mut conn = self.pool.get()?;
+        conn.transaction(|conn| {
+            insert_recovery_codes(conn, principal_kind, principal_id, code_hashes)?;
+            let status = load_status(conn, principal_kind, principal_id)?;
+            AuditRepository::append(conn, audit, &status)?;
+            Ok(())
+        })
+    }
+
+    // ใช้ Recovery Code (ครั้งเดียว) คืนค่า false ถ้าไม่พบหรือถูกใช้ไปแล้ว
+    pub async fn consume_recovery_code(
+        &self,
+        principal_kind: &str,
+        principal_id: i32,
+        code_hash: &str,
+        now: NaiveDateTime,
+    ) -> Result<bool, AppError> {
+        let mut conn = self.pool.get()?;
+        let affected = diesel::update(
+            mfa_recovery_codes::table
+                .filter(mfa_recovery_codes::principal_kind.eq(principal_kind))
+                .filter(mfa_recovery_codes::principal_id.eq(principal_id))
+                .filter(mfa_recovery_codes::code_hash.eq(code_hash))
+                .filter(mfa_recovery_codes::used_at.is_null()),
+        )
+        .set(mfa_recovery_codes::used_at.eq(now))
+        .execute(&mut conn)?;
+        Ok(affected > 0)
+    }
+
+    // ปิด 2FA: ลบ Authenticator และ Recovery Code คืนค่า false ถ้าไม่ได้ลงทะเบียนไว้
+    pub async fn delete_factor(
+        &self,
+        principal_kind: &str,
+        principal_id: i32,
+        audit: PendingAudit<'_, MfaStatusResponse>,
+    ) -> Result<bool, AppError> {
+        let mut conn = self.pool.get()?;
+        conn.transaction(|conn| {
+            diesel::delete(
+                mfa_recovery_codes::table
+                    .filter(mfa_recovery_codes::principal_kind.eq(principal_kind))
+                    .filter(mfa_recovery_codes::principal_id.eq(principal_id)),
+            )
+            .execute(conn)?;
+            let affected = diesel::delete(
+                mfa_totp::table
+                    .filter(mfa_totp::principal_kind.eq(principal_kind))
+                    .filter(mfa_totp::principal_id.eq(principal_id)),
+            )
+            .execute(conn)?;
+            if affected > 0 {
+                let status = load_status(conn, principal_kind, principal_id)?;
+                AuditRepository::append(conn, audit, &status)?;
+            }
+            Ok(affected > 0)
+        })
+    }
+
+    pub async fn insert_challenge(&self, challenge: NewMfaChallenge<'_>) -> Result<(), AppError> {
+        let mut conn = self.pool.get()?;
+        diesel::insert_into(mfa_challenges::table)
+            .values(&challenge)
+            .execute(&mut conn)?;
+        Ok(())
+    }
+
+    // Challenge ที่ยังใช้ได้ (ยังไม่หมดอายุ ยังไม่สำเร็จ และยังไม่เกินจำนวนครั้งที่กำหนด)
+    pub async fn find_open_challenge(
+        &self,
+        token_hash: &str,
+        now: NaiveDateTime,
+        max_attempts: i32,
+    ) -> Result<Option<MfaChallenge>, AppError> {
+        let mut conn = self.pool.get()?;
+        let challenge = mfa_challenges::table
+            .filter(mfa_challenges::token_hash.eq(token_hash))
+            .filter(mfa_challenges::completed_at.is_null())
+            .filter(mfa_challenges::expires_at.gt(now))
+            .filter(mfa_challenges::attempts.lt(max_attempts))
+            .select(MfaChallenge::as_select())
+            .first(&mut conn)
+            .optional()?;
+        Ok(challenge)
+    }
+
+    pub async fn record_failed_attempt(&self, challenge_id: i32) -> Result<(), AppError> {
+        let mut conn = self.pool.get()?;
+        diesel::update(mfa_challenges::table.find(challenge_id))
+            .set(mfa_challenges::attempts.eq(mfa_challenges::attempts + 1))
+            .execute(&mut conn)?;
+        Ok(())
+    }
+
+    // ปิด Challenge (ใช้ได้ครั้งเดียว) คืนค่า false ถ้าถูกใช้ไปแล้ว
+    pub async fn complete_challenge(&self, challenge_id: i32, now: NaiveDateTime) -> Result<bool, AppError> {
+        let mut conn = self.pool.get()?;
+        let affected = diesel::update(
+            mfa_challenges::table
+                .find(challenge_id)
+                .filter(mfa_challenges::completed_at.is_null()),
+        )
+        .set(mfa_challenges::completed_at.eq(now))
+        .execute(&mut conn)?;
+        Ok(affected > 0)
+    }
+}
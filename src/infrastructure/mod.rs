@@ -4,4 +4,23 @@ pub mod user_repository;
 pub mod admin_repository;
 pub mod database;
 pub mod booking_repository;
-pub mod jwt;
\ No newline at end of file
+pub mod jwt;
+pub mod jwt_keys;
+pub mod report_repository;
+pub mod pagination;
+pub mod token_repository;
+pub mod role_repository;
+pub mod notifier;
+pub mod password_reset_repository;
+pub mod login_throttle_repository;
+pub mod mfa_repository;
+pub mod api_key_repository;
+pub mod oidc_client;
+pub mod oidc_repository;
+pub mod auth_provider;
+pub mod privacy_repository;
+pub mod organization_repository;
+pub mod group_repository;
+pub mod impersonation_repository;
+pub mod audit_repository;
+pub mod logging;
\ No newline at end of file
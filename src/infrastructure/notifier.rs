@@ -0,0 +1,75 @@
+// src/infrastructure/notifier.rs
+
+use std::io::Write;
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use serde::Serialize;
+
+use crate::error::AppError;
+
+// Notification: ข้อความที่ส่งถึงผู้ใช้ (เช่น ลิงก์รีเซ็ตรหัสผ่าน)
+// recipient เป็นอีเมล (ลิงก์ยืนยันอีเมล) หรือ username เมื่อผู้ใช้ไม่มีช่องทางติดต่ออื่น
+#[derive(Debug, Clone, Serialize)]
+pub struct Notification {
+    pub recipient: String,
+    pub subject: String,
+    pub body: String,
+}
+
+// Notifier: ช่องทางส่งข้อความ (เปลี่ยนเป็น Email/SMS ได้โดย implement trait นี้)
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    async fn send(&self, notification: Notification) -> Result<(), AppError>;
+}
+
+// LogNotifier: เขียนข้อความลง Log (ใช้ตอนพัฒนา)
+// Token ในลิงก์ถูกปิดเหมือน Log อื่น ใช้ FileNotifier เมื่อต้องการลิงก์ที่ใช้งานได้
+#[derive(Debug, Clone, Default)]
+pub struct LogNotifier;
+
+#[async_trait]
+impl Notifier for LogNotifier {
+    async fn send(&self, notification: Notification) -> Result<(), AppError> {
+        tracing::info!(
+            recipient = %notification.recipient,
+            subject = %notification.subject,
+            body = %notification.body,
+            "notification"
+        );
+        Ok(())
+    }
+}
+
+// FileNotifier: เขียนข้อความต่อท้ายไฟล์ทีละบรรทัดในรูปแบบ JSON (ใช้ตอนพัฒนา/ทดสอบ)
+#[derive(Debug, Clone)]
+pub struct FileNotifier {
+    path: PathBuf,
+}
+
+impl FileNotifier {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        FileNotifier { path: path.into() }
+    }
+}
+
+#[async_trait]
+impl Notifier for FileNotifier {
+    async fn send(&self, notification: Notification) -> Result<(), AppError> {
+        let line = serde_json::json!({
+            "sent_at": Utc::now(),
+            "recipient": notification.recipient,
+            "subject": notification.subject,
+            "body": notification.body,
+        });
+        let path = self.path.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut file = std::fs::OpenOptions::new().create(true).append(true).open(&path)?;
+            writeln!(file, "{}", line)
+        })
+        .await
+        .map_err(|e| AppError::Internal(format!("Notifier task failed: {}", e)))?
+        .map_err(|e| AppError::Internal(format!("Failed to write notification: {}", e)))
+    }
+}
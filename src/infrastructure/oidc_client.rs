@@ -0,0 +1,234 @@
+// src/infrastructure/oidc_client.rs
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use jsonwebtoken::jwk::{AlgorithmParameters, Jwk, PublicKeyUse};
+use jsonwebtoken::{decode, decode_header, Algorithm, DecodingKey, Validation};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use tokio::sync::RwLock;
+
+use crate::error::AppError;
+
+// ดึง JWKS ใหม่ได้ไม่บ่อยกว่านี้ (กัน Token ที่มี kid สุ่มทำให้ต้องเรียก Identity Provider ทุก Request)
+const JWKS_MIN_REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+
+// ProviderMetadata: ส่วนของ Discovery Document (/.well-known/openid-configuration) ที่ใช้
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProviderMetadata {
+    pub issuer: String,
+    pub authorization_endpoint: String,
+    pub token_endpoint: String,
+    pub jwks_uri: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    id_token: Option<String>,
+}
+
+#[derive(Default)]
+struct KeyCache {
+    keys: Vec<Jwk>,
+    fetched_at: Option<Instant>,
+}
+
+// OidcClient: คุยกับ Identity Provider (Discovery, แลก Authorization Code, ตรวจสอบ ID Token)
+// Discovery Document และ Public Key (JWKS) ถูกโหลดครั้งแรกที่ใช้และเก็บไว้
+// JWKS ถูกโหลดใหม่เมื่อพบ kid ที่ไม่รู้จัก (Identity Provider หมุนเวียน key)
+#[derive(Clone)]
+pub struct OidcClient {
+    http: reqwest::Client,
+    issuer: String,
+    client_id: String,
+    client_secret: Option<String>,
+    metadata: Arc<RwLock<Option<ProviderMetadata>>>,
+    keys: Arc<RwLock<KeyCache>>,
+}
+
+fn upstream_error(e: reqwest::Error) -> AppError {
+    AppError::BadGateway(format!("Identity provider request failed: {}", e))
+}
+
+// Algorithm ต้องเข้ากับชนิดของ key (ไม่รับ HS* เพื่อกันการใช้ Public Key เป็น secret)
+fn key_accepts(jwk: &Jwk, algorithm: Algorithm) -> bool {
+    use Algorithm::*;
+    match &jwk.algorithm {
+        AlgorithmParameters::RSA(_) => matches!(algorithm, RS256 | RS384 | RS512 | PS256 | PS384 | PS512),
+        AlgorithmParameters::EllipticCurve(_) => matches!(algorithm, ES256 | ES384),
+        AlgorithmParameters::OctetKeyPair(_) => algorithm == EdDSA,
+        AlgorithmParameters::OctetKey(_) => false,
+    }
+}
+
+impl OidcClient {
+    pub fn new(issuer: String, client_id: String, client_secret: Option<String>) -> Result<Self, AppError> {
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .map_err(|e| AppError::Internal(format!("Failed to create HTTP client: {}", e)))?;
+        Ok(OidcClient {
+            http,
+            issuer,
+            client_id,
+            client_secret,
+            metadata: Arc::new(RwLock::new(None)),
+            keys: Arc::new(RwLock::new(KeyCache::default())),
+        })
+    }
+
+    pub fn client_id(&self) -> &str {
+        &self.client_id
+    }
+
+    pub fn issuer(&self) -> &str {
+        &self.issuer
+    }
+
+    // Discovery Document ของ Identity Provider (issuer ในเอกสารต้องตรงกับที่ตั้งค่าไว้)
+    pub async fn metadata(&self) -> Result<ProviderMetadata, AppError> {
+        if let Some(metadata) = self.metadata.read().await.as_ref() {
+            return Ok(metadata.clone());
+        }
+
+        let url = format!("{}/.well-known/openid-configuration", self.issuer.trim_end_matches('/'));
+        let metadata: ProviderMetadata = self
+            .http
+            .get(&url)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(upstream_error)?
+            .json()
+            .await
+            .map_err(upstream_error)?;
+        if metadata.issuer != self.issuer {
+            return Err(AppError::BadGateway(format!(
+                "Identity provider issuer mismatch: expected '{}', got '{}'.",
+                self.issuer, metadata.issuer
+            )));
+        }
+
+        *self.metadata.write().await = Some(metadata.clone());
+        Ok(metadata)
+    }
+
+    // แลก Authorization Code เป็น ID Token (ส่ง PKCE code_verifier และ client_secret ถ้ามี)
+    pub async fn exchange_code(&self, code: &str, code_verifier: &str, redirect_uri: &str) -> Result<String, AppError> {
+        let metadata = self.metadata().await?;
+        let mut form = vec![
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", redirect_uri),
+            ("client_id", &self.client_id),
+            ("code_verifier", code_verifier),
+        ];
+        if let Some(secret) = &self.client_secret {
+            form.push(("client_secret", secret));
+        }
+
+        let response = self
+            .http
+            .post(&metadata.token_endpoint)
+            .form(&form)
+            .send()
+            .await
+            .map_err(upstream_error)?;
+        if response.status().is_client_error() {
+            return Err(AppError::Unauthorized(
+                "The identity provider rejected the authorization code.".to_string(),
+            ));
+        }
+        let tokens: TokenResponse = response
+            .error_for_status()
+            .map_err(upstream_error)?
+            .json()
+            .await
+            .map_err(upstream_error)?;
+        tokens
+            .id_token
+            .ok_or_else(|| AppError::BadGateway("The identity provider did not return an ID token.".to_string()))
+    }
+
+    // ตรวจสอบ ID Token: ลายเซ็น (จาก JWKS), iss, aud, exp และ nonce ต้องตรงกับที่ส่งไปตอนเริ่ม Login
+    pub async fn validate_id_token(&self, id_token: &str, nonce: &str) -> Result<Map<String, Value>, AppError> {
+        let invalid = |e: jsonwebtoken::errors::Error| AppError::Unauthorized(format!("Invalid ID token. {}", e));
+        let header = decode_header(id_token).map_err(invalid)?;
+        let jwk = self.signing_key(header.kid.as_deref()).await?;
+        if !key_accepts(&jwk, header.alg) {
+            return Err(AppError::Unauthorized(
+                "Invalid ID token. Signing algorithm does not match the key.".to_string(),
+            ));
+        }
+        let key = DecodingKey::from_jwk(&jwk).map_err(invalid)?;
+
+        let mut validation = Validation::new(header.alg);
+        validation.leeway = 60;
+        validation.set_issuer(&[&self.issuer]);
+        validation.set_audience(&[&self.client_id]);
+        validation.set_required_spec_claims(&["exp", "iss", "aud", "sub"]);
+        let claims = decode::<Map<String, Value>>(id_token, &key, &validation)
+            .map_err(invalid)?
+            .claims;
+
+        if claims.get("nonce").and_then(Value::as_str) != Some(nonce) {
+            return Err(AppError::Unauthorized("Invalid ID token. Nonce mismatch.".to_string()));
+        }
+        // azp (ถ้ามี) ต้องเป็น Client ของเรา
+        if claims.get("azp").and_then(Value::as_str).is_some_and(|azp| azp != self.client_id) {
+            return Err(AppError::Unauthorized("Invalid ID token. Authorized party mismatch.".to_string()));
+        }
+        Ok(claims)
+    }
+
+    // Public Key ตาม kid (โหลด JWKS ใหม่เมื่อไม่พบ) Token ที่ไม่มี kid ใช้ได้เมื่อ JWKS มี key เดียว
+    async fn signing_key(&self, kid: Option<&str>) -> Result<Jwk, AppError> {
+        if let Some(jwk) = Self::find_key(&self.keys.read().await.keys, kid) {
+            return Ok(jwk);
+        }
+
+        let mut cache = self.keys.write().await;
+        let recently_fetched = cache.fetched_at.is_some_and(|t| t.elapsed() < JWKS_MIN_REFRESH_INTERVAL);
+        if !recently_fetched {
+            cache.keys = self.fetch_keys().await?;
+            cache.fetched_at = Some(Instant::now());
+        }
+        Self::find_key(&cache.keys, kid)
+            .ok_or_else(|| AppError::Unauthorized("Invalid ID token. Unknown signing key.".to_string()))
+    }
+
+    fn find_key(keys: &[Jwk], kid: Option<&str>) -> Option<Jwk> {
+        match kid {
+            Some(kid) => keys.iter().find(|k| k.common.key_id.as_deref() == Some(kid)).cloned(),
+            None if keys.len() == 1 => keys.first().cloned(),
+            None => None,
+        }
+    }
+
+    // key ที่อ่านไม่ได้หรือใช้สำหรับเข้ารหัส (use = enc) จะถูกข้าม
+    async fn fetch_keys(&self) -> Result<Vec<Jwk>, AppError> {
+        #[derive(Deserialize)]
+        struct RawJwkSet {
+            keys: Vec<Value>,
+        }
+
+        let metadata = self.metadata().await?;
+        let set: RawJwkSet = self
+            .http
+            .get(&metadata.jwks_uri)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(upstream_error)?
+            .json()
+            .await
+            .map_err(upstream_error)?;
+        Ok(set
+            .keys
+            .into_iter()
+            .filter_map(|key| serde_json::from_value::<Jwk>(key).ok())
+            .filter(|jwk| !matches!(jwk.common.public_key_use, Some(PublicKeyUse::Encryption)))
+            .collect())
+    }
+}
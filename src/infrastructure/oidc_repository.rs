@@ -0,0 +1,55 @@
+// src/infrastructure/oidc_repository.rs
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use diesel::SqliteConnection;
+
+use crate::domain::oidc::{NewOidcLoginState, OidcLoginState};
+use crate::error::AppError;
+
+use super::schema::oidc_login_states;
+
+#[derive(Debug, Clone)]
+pub struct OidcRepository {
+    pool: Pool<ConnectionManager<SqliteConnection>>,
+}
+
+impl OidcRepository {
+    pub fn new(pool: Pool<ConnectionManager<SqliteConnection>>) -> Self {
+        OidcRepository { pool }
+    }
+
+    // บันทึก state ใหม่ และลบ state ที่หมดอายุแล้ว
+    pub async fn insert_state(&self, state: NewOidcLoginState<'_>, now: NaiveDateTime) -> Result<(), AppError> {
+        let mut conn = self.pool.get()?;
+        conn.transaction(|conn| {
+            diesel::delete(oidc_login_states::table.filter(oidc_login_states::expires_at.le(now))).execute(conn)?;
+            diesel::insert_into(oidc_login_states::table)
+                .values(&state)
+                .execute(conn)?;
+            Ok(())
+        })
+    }
+
+    // ใช้ state (ครั้งเดียว) คืนค่า None ถ้าไม่พบ หมดอายุ หรือถูกใช้ไปแล้ว
+    pub async fn consume_state(&self, state_hash: &str, now: NaiveDateTime) -> Result<Option<OidcLoginState>, AppError> {
+        let mut conn = self.pool.get()?;
+        conn.immediate_transaction(|conn| {
+            let Some(state) = oidc_login_states::table
+                .filter(oidc_login_states::state_hash.eq(state_hash))
+                .filter(oidc_login_states::used_at.is_null())
+                .filter(oidc_login_states::expires_at.gt(now))
+                .select(OidcLoginState::as_select())
+                .first(conn)
+                .optional()?
+            else {
+                return Ok(None);
+            };
+            diesel::update(oidc_login_states::table.find(state.id))
+                .set(oidc_login_states::used_at.eq(now))
+                .execute(conn)?;
+            Ok(Some(state))
+        })
+    }
+}
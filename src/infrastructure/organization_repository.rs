@@ -0,0 +1,251 @@
+// src/infrastructure/organization_repository.rs
+
+use chrono::Utc;
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use diesel::SqliteConnection;
+
+use crate::domain::audit::PendingAudit;
+use crate::domain::organization::{NewOrganization, Organization, OrganizationChangeset, RoomSharesResponse, Tenant};
+use crate::domain::user::User;
+use crate::error::AppError;
+
+use super::audit_repository::AuditRepository;
+use super::booking_repository::tenant_scope;
+use super::schema::{admins, bookings, group_members, groups, organizations, room_allowed_users, room_shares, rooms, users};
+
+fn unique_violation(e: diesel::result::Error) -> AppError {
+    match e {
+        diesel::result::Error::DatabaseError(diesel::result::DatabaseErrorKind::UniqueViolation, _) => {
+            AppError::Conflict("Organization name or slug already taken".to_string())
+        }
+        e => e.into(),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct OrganizationRepository {
+    pool: Pool<ConnectionManager<SqliteConnection>>,
+}
+
+impl OrganizationRepository {
+    pub fn new(pool: Pool<ConnectionManager<SqliteConnection>>) -> Self {
+        OrganizationRepository { pool }
+    }
+
+    pub async fn list(&self) -> Result<Vec<Organization>, AppError> {
+        let mut conn = self.pool.get()?;
+        let items = organizations::table
+            .order(organizations::id.asc())
+            .select(Organization::as_select())
+            .load(&mut conn)?;
+        Ok(items)
+    }
+
+    pub async fn find_by_id(&self, id: i32) -> Result<Option<Organization>, AppError> {
+        let mut conn = self.pool.get()?;
+        let organization = organizations::table
+            .find(id)
+            .select(Organization::as_select())
+            .first(&mut conn)
+            .optional()?;
+        Ok(organization)
+    }
+
+    pub async fn find_by_slug(&self, slug: &str) -> Result<Option<Organization>, AppError> {
+        let mut conn = self.pool.get()?;
+        let organization = organizations::table
+            .filter(organizations::slug.eq(slug))
+            .select(Organization::as_select())
+            .first(&mut conn)
+            .optional()?;
+        Ok(organization)
+    }
+
+    pub async fn insert(
+        &self,
+        organization: NewOrganization<'_>,
+        audit: PendingAudit<'_, Organization>,
+    ) -> Result<Organization, AppError> {
+        let mut conn = self.pool.get()?;
+        conn.immediate_transaction(|conn| {
+            diesel::insert_into(organizations::table)
+                .values(&organization)
+                .execute(conn)
+                .map_err(unique_violation)?;
+            let inserted = organizations::table
+                .filter(organizations::slug.eq(organization.slug))
+                .select(Organization::as_select())
+                .first(conn)?;
+            AuditRepository::append(conn, audit, &inserted)?;
+            Ok(inserted)
+        })
+    }
+
+    pub async fn update(
+        &self,
+        id: i32,
+        changes: OrganizationChangeset,
+        audit: PendingAudit<'_, Organization>,
+    ) -> Result<Organization, AppError> {
+        let mut conn = self.pool.get()?;
+        conn.immediate_transaction(|conn| {
+            let affected = diesel::update(organizations::table.find(id))
+                .set(&changes)
+                .execute(conn)
+                .map_err(unique_violation)?;
+            if affected == 0 {
+                return Err(AppError::NotFound("Organization not found".to_string()));
+            }
+            let updated = organizations::table.find(id).select(Organization::as_select()).first(conn)?;
+            AuditRepository::append(conn, audit, &updated)?;
+            Ok(updated)
+        })
+    }
+
+    // องค์กรของบัญชี (ใส่ใน claim `org` ของ Token) None = Platform Admin
+    pub async fn organization_of(&self, principal_kind: &str, principal_id: i32) -> Result<Option<i32>, AppError> {
+        let mut conn = self.pool.get()?;
+        let organization_id = match principal_kind {
+            "user" => users::table
+                .find(principal_id)
+                .select(users::organization_id)
+                .first::<i32>(&mut conn)
+                .optional()?,
+            _ => admins::table
+                .find(principal_id)
+                .select(admins::organization_id)
+                .first::<Option<i32>>(&mut conn)
+                .optional()?
+                .flatten(),
+        };
+        Ok(organization_id)
+    }
+
+    // ผู้ใช้อยู่ในองค์กรของผู้เรียกหรือไม่ (รวมผู้ใช้ที่ถูกลบแล้ว)
+    pub async fn user_in_tenant(&self, tenant: Tenant, user_id: i32) -> Result<bool, AppError> {
+        let mut conn = self.pool.get()?;
+        let mut q = users::table.filter(users::id.eq(user_id)).into_boxed();
+        if let Some(org) = tenant.organization_id() {
+            q = q.filter(users::organization_id.eq(org));
+        }
+        Ok(q.count().get_result::<i64>(&mut conn)? > 0)
+    }
+
+    pub async fn admin_in_tenant(&self, tenant: Tenant, admin_id: i32) -> Result<bool, AppError> {
+        let mut conn = self.pool.get()?;
+        let mut q = admins::table.filter(admins::id.eq(admin_id)).into_boxed();
+        if let Some(org) = tenant.organization_id() {
+            q = q.filter(admins::organization_id.eq(org));
+        }
+        Ok(q.count().get_result::<i64>(&mut conn)? > 0)
+    }
+
+    // ห้องเป็นขององค์กรผู้เรียก (ห้องที่ถูกแชร์มาให้ไม่นับ แก้ไขได้เฉพาะองค์กรเจ้าของ) คืนองค์กรเจ้าของห้อง
+    pub async fn room_owner(&self, tenant: Tenant, room_id: i32) -> Result<Option<i32>, AppError> {
+        let mut conn = self.pool.get()?;
+        let mut q = rooms::table.filter(rooms::id.eq(room_id)).into_boxed();
+        if let Some(org) = tenant.organization_id() {
+            q = q.filter(rooms::organization_id.eq(org));
+        }
+        Ok(q.select(rooms::organization_id).first::<i32>(&mut conn).optional()?)
+    }
+
+    // การจองอยู่ในขอบเขตของผู้เรียก: ผู้จองอยู่ในองค์กร หรือเป็นห้องขององค์กร
+    pub async fn booking_in_tenant(&self, tenant: Tenant, booking_id: i32) -> Result<bool, AppError> {
+        let mut conn = self.pool.get()?;
+        let mut q = bookings::table.filter(bookings::id.eq(booking_id)).into_boxed();
+        if let Some(scope) = tenant_scope(tenant) {
+            q = q.filter(scope);
+        }
+        Ok(q.count().get_result::<i64>(&mut conn)? > 0)
+    }
+
+    pub async fn room_shares(&self, room_id: i32) -> Result<Vec<i32>, AppError> {
+        let mut conn = self.pool.get()?;
+        let shared_with = room_shares::table
+            .filter(room_shares::room_id.eq(room_id))
+            .order(room_shares::organization_id.asc())
+            .select(room_shares::organization_id)
+            .load::<i32>(&mut conn)?;
+        Ok(shared_with)
+    }
+
+    // แทนที่รายการองค์กรที่ห้องถูกแชร์ให้ทั้งหมด (ทุกองค์กรต้องมีอยู่จริงและไม่ใช่เจ้าของห้อง)
+    // organization_ids ต้องเรียงและไม่ซ้ำ เพราะใช้เป็น shared_with ของผลลัพธ์
+    pub async fn set_room_shares(
+        &self,
+        room_id: i32,
+        owner_id: i32,
+        organization_ids: &[i32],
+        audit: PendingAudit<'_, RoomSharesResponse>,
+    ) -> Result<RoomSharesResponse, AppError> {
+        let mut conn = self.pool.get()?;
+        conn.immediate_transaction(|conn| {
+            let known = organizations::table
+                .filter(organizations::id.eq_any(organization_ids))
+                .count()
+                .get_result::<i64>(conn)?;
+            if known as usize != organization_ids.len() || organization_ids.contains(&owner_id) {
+                return Err(AppError::Validation(
+                    "`organization_ids` must list existing organizations other than the room's owner."
+                        .to_string(),
+                ));
+            }
+            diesel::delete(room_shares::table.filter(room_shares::room_id.eq(room_id))).execute(conn)?;
+            let rows: Vec<_> = organization_ids
+                .iter()
+                .map(|org| (room_shares::room_id.eq(room_id), room_shares::organization_id.eq(*org)))
+                .collect();
+            diesel::insert_into(room_shares::table).values(&rows).execute(conn)?;
+            let shares = RoomSharesResponse {
+                room_id,
+                organization_id: owner_id,
+                shared_with: organization_ids.to_vec(),
+            };
+            AuditRepository::append(conn, audit, &shares)?;
+            Ok(shares)
+        })
+    }
+
+    // ย้ายผู้ใช้ไปองค์กรอื่น (Role ที่ได้รับเพิ่มยังคงอยู่)
+    // กลุ่มและสิทธิ์จองห้องรายบุคคลขององค์กรเดิมถูกลบ
+    pub async fn move_user(
+        &self,
+        user_id: i32,
+        organization_id: i32,
+        audit: PendingAudit<'_, User>,
+    ) -> Result<User, AppError> {
+        let mut conn = self.pool.get()?;
+        conn.immediate_transaction(|conn| {
+            let affected = diesel::update(users::table.find(user_id))
+                .set((
+                    users::organization_id.eq(organization_id),
+                    users::updated_at.eq(Utc::now().naive_utc()),
+                ))
+                .execute(conn)?;
+            if affected == 0 {
+                return Err(AppError::NotFound("User not found".to_string()));
+            }
+            diesel::delete(
+                group_members::table
+                    .filter(group_members::user_id.eq(user_id))
+                    .filter(group_members::group_id.ne_all(
+                        groups::table.filter(groups::organization_id.eq(organization_id)).select(groups::id),
+                    )),
+            )
+            .execute(conn)?;
+            diesel::delete(
+                room_allowed_users::table
+                    .filter(room_allowed_users::user_id.eq(user_id))
+                    .filter(room_allowed_users::room_id.ne_all(
+                        rooms::table.filter(rooms::organization_id.eq(organization_id)).select(rooms::id),
+                    )),
+            )
+            .execute(conn)?;
+            let moved = users::table.find(user_id).first::<User>(conn)?;
+            AuditRepository::append(conn, audit, &moved)?;
+            Ok(moved)
+        })
+    }
+}
@@ -0,0 +1,14 @@
+// src/infrastructure/pagination.rs
+
+// เรียงลำดับ Boxed query ตาม column และทิศทางที่ได้จาก ListQuery
+// ใช้ `then_order_by` เพื่อให้ต่อด้วย tie-breaker (เช่น id) ได้ ทำให้การแบ่งหน้าคงที่
+macro_rules! sort_by_column {
+    ($query:expr, $column:expr, $direction:expr) => {
+        match $direction {
+            crate::domain::pagination::SortDirection::Asc => $query.then_order_by($column.asc()),
+            crate::domain::pagination::SortDirection::Desc => $query.then_order_by($column.desc()),
+        }
+    };
+}
+
+pub(crate) use sort_by_column;
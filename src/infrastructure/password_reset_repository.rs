@@ -0,0 +1,82 @@
+// src/infrastructure/password_reset_repository.rs
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use diesel::SqliteConnection;
+
+use crate::domain::audit::PendingAudit;
+use crate::domain::password::NewPasswordResetToken;
+use crate::error::AppError;
+
+use super::audit_repository::AuditRepository;
+use super::schema::password_reset_tokens;
+
+#[derive(Debug, Clone)]
+pub struct PasswordResetRepository {
+    pool: Pool<ConnectionManager<SqliteConnection>>,
+}
+
+impl PasswordResetRepository {
+    pub fn new(pool: Pool<ConnectionManager<SqliteConnection>>) -> Self {
+        PasswordResetRepository { pool }
+    }
+
+    // บันทึก Token ใหม่ และยกเลิก Token เดิมที่ยังไม่ถูกใช้ของผู้ใช้คนนี้ (ใช้ได้เฉพาะลิงก์ล่าสุด)
+    // audit ได้รับ user_id ของ Token
+    pub async fn replace_token(
+        &self,
+        token: NewPasswordResetToken<'_>,
+        now: NaiveDateTime,
+        audit: PendingAudit<'_, i32>,
+    ) -> Result<(), AppError> {
+        let mut conn = self.pool.get()?;
+        conn.transaction(|conn| {
+            diesel::update(
+                password_reset_tokens::table
+                    .filter(password_reset_tokens::user_id.eq(token.user_id))
+                    .filter(password_reset_tokens::used_at.is_null()),
+            )
+            .set(password_reset_tokens::used_at.eq(now))
+            .execute(conn)?;
+            diesel::insert_into(password_reset_tokens::table)
+                .values(&token)
+                .execute(conn)?;
+            AuditRepository::append(conn, audit, &token.user_id)?;
+            Ok(())
+        })
+    }
+
+    // ใช้ Token: ทำเครื่องหมายว่าใช้แล้วถ้ายังไม่ถูกใช้และยังไม่หมดอายุ คืนค่า user_id ของ Token
+    pub async fn consume_token(&self, token_hash: &str, now: NaiveDateTime) -> Result<Option<i32>, AppError> {
+        let mut conn = self.pool.get()?;
+        conn.transaction(|conn| {
+            let user_id = password_reset_tokens::table
+                .filter(password_reset_tokens::token_hash.eq(token_hash))
+                .filter(password_reset_tokens::used_at.is_null())
+                .filter(password_reset_tokens::expires_at.gt(now))
+                .select(password_reset_tokens::user_id)
+                .first::<i32>(conn)
+                .optional()?;
+            if user_id.is_some() {
+                diesel::update(password_reset_tokens::table.filter(password_reset_tokens::token_hash.eq(token_hash)))
+                    .set(password_reset_tokens::used_at.eq(now))
+                    .execute(conn)?;
+            }
+            Ok(user_id)
+        })
+    }
+
+    // ยกเลิก Token ที่ยังไม่ถูกใช้ทั้งหมดของผู้ใช้ (เมื่อรหัสผ่านถูกเปลี่ยน)
+    pub async fn invalidate_for_user(&self, user_id: i32, now: NaiveDateTime) -> Result<(), AppError> {
+        let mut conn = self.pool.get()?;
+        diesel::update(
+            password_reset_tokens::table
+                .filter(password_reset_tokens::user_id.eq(user_id))
+                .filter(password_reset_tokens::used_at.is_null()),
+        )
+        .set(password_reset_tokens::used_at.eq(now))
+        .execute(&mut conn)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,234 @@
+// src/infrastructure/privacy_repository.rs
+
+use chrono::Utc;
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use diesel::SqliteConnection;
+
+use crate::domain::audit::PendingAudit;
+use crate::domain::booking::Booking;
+use crate::domain::login_throttle::LoginEvent;
+use crate::domain::organization::Tenant;
+use crate::domain::pagination::ListQuery;
+use crate::domain::privacy::{erased_username, ErasureRequest, NewErasureRequest};
+use crate::domain::user::User;
+use crate::error::AppError;
+
+use super::audit_repository::AuditRepository;
+use super::booking_repository::BookingRepository;
+use super::pagination::sort_by_column;
+use super::schema::{
+    api_keys, bookings, erasure_requests, external_identities, group_members, login_events, login_throttles,
+    mfa_challenges, mfa_recovery_codes, mfa_totp, password_reset_tokens, role_assignments, room_allowed_users, users,
+};
+
+#[derive(Debug, Clone)]
+pub struct PrivacyRepository {
+    pool: Pool<ConnectionManager<SqliteConnection>>,
+}
+
+impl PrivacyRepository {
+    pub const SORT_FIELDS: &'static [&'static str] = &["id", "user_id", "created_at"];
+
+    pub fn new(pool: Pool<ConnectionManager<SqliteConnection>>) -> Self {
+        PrivacyRepository { pool }
+    }
+
+    // ข้อมูลทั้งหมดของผู้ใช้ที่ยังไม่ถูกลบ: (ผู้ใช้, Role ที่ได้รับ, การจองทั้งหมด, ประวัติ Login)
+    pub async fn export_data(
+        &self,
+        user_id: i32,
+    ) -> Result<(User, Vec<String>, Vec<Booking>, Vec<LoginEvent>), AppError> {
+        let mut conn = self.pool.get()?;
+        let user = users::table
+            .filter(users::id.eq(user_id))
+            .filter(users::deleted_at.is_null())
+            .first::<User>(&mut conn)
+            .optional()?
+            .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+        let roles = role_assignments::table
+            .filter(role_assignments::principal_kind.eq("user"))
+            .filter(role_assignments::principal_id.eq(user_id))
+            .select(role_assignments::role_name)
+            .order(role_assignments::role_name.asc())
+            .load::<String>(&mut conn)?;
+        let bookings = bookings::table
+            .filter(bookings::user_id.eq(user_id))
+            .order(bookings::start_time.asc())
+            .select(Booking::as_select())
+            .load(&mut conn)?;
+        let events = login_events::table
+            .filter(login_events::account_kind.eq("user"))
+            .filter(login_events::username.eq(&user.username))
+            .order(login_events::id.asc())
+            .select(LoginEvent::as_select())
+            .load(&mut conn)?;
+        Ok((user, roles, bookings, events))
+    }
+
+    // ลบข้อมูลส่วนตัวของผู้ใช้ใน Transaction เดียว: ทำให้แถว users ไม่ระบุตัวตน (และ Soft Delete ถ้ายังไม่ถูกลบ)
+    // ยกเลิกการจองที่ยังไม่จบ ลบ Credential/Identity/Role ทั้งหมด และเปลี่ยน username ในประวัติ Login
+    // การจองเดิมยังผูกกับแถวที่ไม่ระบุตัวตนแล้ว รายงานจึงนับได้เหมือนเดิม
+    pub async fn erase_user(
+        &self,
+        user_id: i32,
+        requested_by_kind: &str,
+        requested_by_id: i32,
+        reason: Option<&str>,
+        audit: PendingAudit<'_, ErasureRequest>,
+    ) -> Result<ErasureRequest, AppError> {
+        let mut conn = self.pool.get()?;
+        let now = Utc::now().naive_utc();
+
+        conn.immediate_transaction(|conn| {
+            let user = users::table
+                .find(user_id)
+                .first::<User>(conn)
+                .optional()?
+                .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+            if user.erased_at.is_some() {
+                return Err(AppError::Conflict("User data has already been erased.".to_string()));
+            }
+            let anonymized = erased_username(user_id);
+
+            let cancelled = BookingRepository::cancel_unfinished_bookings(conn, user_id, now)?;
+            let retained = bookings::table
+                .filter(bookings::user_id.eq(user_id))
+                .count()
+                .get_result::<i64>(conn)?;
+
+            diesel::update(users::table.find(user_id))
+                .set((
+                    users::username.eq(&anonymized),
+                    users::password_hash.eq(None::<String>),
+                    users::display_name.eq(None::<String>),
+                    users::directory_groups.eq(None::<String>),
+                    users::email.eq(None::<String>),
+                    users::phone.eq(None::<String>),
+                    users::department.eq(None::<String>),
+                    users::timezone.eq(None::<String>),
+                    users::language.eq(None::<String>),
+                    users::email_verified_at.eq(None::<chrono::NaiveDateTime>),
+                    users::email_verification_pending.eq(false),
+                    users::deleted_at.eq(Some(user.deleted_at.unwrap_or(now))),
+                    users::erased_at.eq(Some(now)),
+                    users::updated_at.eq(now),
+                ))
+                .execute(conn)?;
+
+            diesel::delete(external_identities::table.filter(external_identities::user_id.eq(user_id)))
+                .execute(conn)?;
+            diesel::delete(password_reset_tokens::table.filter(password_reset_tokens::user_id.eq(user_id)))
+                .execute(conn)?;
+            diesel::delete(group_members::table.filter(group_members::user_id.eq(user_id))).execute(conn)?;
+            diesel::delete(room_allowed_users::table.filter(room_allowed_users::user_id.eq(user_id)))
+                .execute(conn)?;
+            diesel::delete(
+                role_assignments::table
+                    .filter(role_assignments::principal_kind.eq("user"))
+                    .filter(role_assignments::principal_id.eq(user_id)),
+            )
+            .execute(conn)?;
+            diesel::delete(
+                mfa_totp::table
+                    .filter(mfa_totp::principal_kind.eq("user"))
+                    .filter(mfa_totp::principal_id.eq(user_id)),
+            )
+            .execute(conn)?;
+            diesel::delete(
+                mfa_recovery_codes::table
+                    .filter(mfa_recovery_codes::principal_kind.eq("user"))
+                    .filter(mfa_recovery_codes::principal_id.eq(user_id)),
+            )
+            .execute(conn)?;
+            diesel::delete(
+                mfa_challenges::table
+                    .filter(mfa_challenges::principal_kind.eq("user"))
+                    .filter(mfa_challenges::principal_id.eq(user_id)),
+            )
+            .execute(conn)?;
+            diesel::delete(
+                api_keys::table
+                    .filter(api_keys::principal_kind.eq("user"))
+                    .filter(api_keys::principal_id.eq(user_id)),
+            )
+            .execute(conn)?;
+            diesel::delete(
+                login_throttles::table
+                    .filter(login_throttles::scope.eq("username"))
+                    .filter(login_throttles::throttle_key.eq(format!("user:{}", user.username))),
+            )
+            .execute(conn)?;
+            diesel::update(
+                login_events::table
+                    .filter(login_events::account_kind.eq("user"))
+                    .filter(login_events::username.eq(&user.username)),
+            )
+            .set(login_events::username.eq(&anonymized))
+            .execute(conn)?;
+
+            diesel::insert_into(erasure_requests::table)
+                .values(&NewErasureRequest {
+                    user_id,
+                    requested_by_kind,
+                    requested_by_id,
+                    reason,
+                    bookings_cancelled: cancelled as i32,
+                    bookings_retained: retained as i32,
+                })
+                .execute(conn)?;
+            let record = erasure_requests::table
+                .order(erasure_requests::id.desc())
+                .select(ErasureRequest::as_select())
+                .first(conn)?;
+            AuditRepository::append(conn, audit, &record)?;
+            Ok(record)
+        })
+    }
+
+    // บันทึกการลบข้อมูล (กรองด้วย user_id และ from/to เทียบกับ created_at) คืนค่า (รายการในหน้านี้, จำนวนทั้งหมด)
+    // เฉพาะผู้ใช้ในองค์กรของผู้เรียก
+    pub async fn list_erasure_requests(
+        &self,
+        tenant: Tenant,
+        query: &ListQuery,
+        offset: i64,
+    ) -> Result<(Vec<ErasureRequest>, i64), AppError> {
+        let mut conn = self.pool.get()?;
+        let filtered = || {
+            let mut q = erasure_requests::table.into_boxed();
+            if let Some(org) = tenant.organization_id() {
+                q = q.filter(
+                    erasure_requests::user_id
+                        .eq_any(users::table.filter(users::organization_id.eq(org)).select(users::id)),
+                );
+            }
+            if let Some(user_id) = query.user_id {
+                q = q.filter(erasure_requests::user_id.eq(user_id));
+            }
+            if let Some(from) = query.from {
+                q = q.filter(erasure_requests::created_at.ge(from.naive_utc()));
+            }
+            if let Some(to) = query.to {
+                q = q.filter(erasure_requests::created_at.lt(to.naive_utc()));
+            }
+            q
+        };
+        let total = filtered().count().get_result::<i64>(&mut conn)?;
+
+        let q = filtered();
+        let direction = query.direction();
+        let q = match query.sort.as_deref().unwrap_or("created_at") {
+            "id" => sort_by_column!(q, erasure_requests::id, direction),
+            "user_id" => sort_by_column!(q, erasure_requests::user_id, direction),
+            _ => sort_by_column!(q, erasure_requests::created_at, direction),
+        };
+        let items = q
+            .then_order_by(erasure_requests::id.asc())
+            .limit(query.limit())
+            .offset(offset)
+            .select(ErasureRequest::as_select())
+            .load(&mut conn)?;
+        Ok((items, total))
+    }
+}
@@ -0,0 +1,177 @@
+// src/infrastructure/report_repository.rs
+use crate::domain::report::{BookingRatesRow, PeakHourCell, ReportPeriod, TopBooker, UtilizationRow};
+use crate::error::AppError;
+
+use chrono::NaiveDateTime;
+use diesel::SqliteConnection;
+use diesel::prelude::*;
+use diesel::r2d2::ConnectionManager;
+use diesel::r2d2::Pool;
+use diesel::sql_types::{BigInt, Integer, Nullable, Timestamp};
+
+// All report queries aggregate directly over `bookings` in SQLite.
+// Only bookings whose start_time falls inside [from, to) are counted.
+// organization_id = None รวมทุกองค์กร (Platform Admin) ไม่เช่นนั้นนับเฉพาะห้อง (หรือผู้ใช้) ขององค์กร
+#[derive(Debug, Clone)]
+pub struct ReportRepository {
+    pool: Pool<ConnectionManager<SqliteConnection>>,
+}
+
+// SQL expression ที่คืนค่าวันแรกของช่วงเวลา (YYYY-MM-DD) สำหรับ column ที่ระบุ
+// Week เริ่มต้นวันจันทร์
+fn period_bucket_sql(period: ReportPeriod, column: &str) -> String {
+    match period {
+        ReportPeriod::Day => format!("date({column})"),
+        ReportPeriod::Week => format!("date({column}, 'weekday 0', '-6 days')"),
+        ReportPeriod::Month => format!("date({column}, 'start of month')"),
+    }
+}
+
+impl ReportRepository {
+    pub fn new(pool: Pool<ConnectionManager<SqliteConnection>>) -> Self {
+        ReportRepository { pool }
+    }
+
+    // Booked hours ต่อห้อง ต่อช่วงเวลา (ไม่นับการจองที่ถูกยกเลิก)
+    pub async fn room_utilization(
+        &self,
+        period: ReportPeriod,
+        from: NaiveDateTime,
+        to: NaiveDateTime,
+        room_id: Option<i32>,
+        organization_id: Option<i32>,
+    ) -> Result<Vec<UtilizationRow>, AppError> {
+        let mut conn = self.pool.get()?;
+
+        let query = format!(
+            "SELECT r.id AS room_id, r.name AS room_name, {bucket} AS period_start, \
+                    COUNT(b.id) AS bookings, \
+                    COALESCE(SUM((strftime('%s', b.end_time) - strftime('%s', b.start_time)) / 3600.0), 0.0) AS booked_hours \
+             FROM rooms r \
+             LEFT JOIN bookings b ON b.room_id = r.id \
+                  AND b.status != 'cancelled' AND b.deleted_at IS NULL \
+                  AND b.start_time >= ? AND b.start_time < ? \
+             WHERE r.deleted_at IS NULL AND (? IS NULL OR r.id = ?) \
+               AND (? IS NULL OR r.organization_id = ?) \
+             GROUP BY r.id, r.name, period_start \
+             ORDER BY r.id, period_start",
+            bucket = period_bucket_sql(period, "b.start_time"),
+        );
+
+        diesel::sql_query(query)
+            .bind::<Timestamp, _>(from)
+            .bind::<Timestamp, _>(to)
+            .bind::<Nullable<Integer>, _>(room_id)
+            .bind::<Nullable<Integer>, _>(room_id)
+            .bind::<Nullable<Integer>, _>(organization_id)
+            .bind::<Nullable<Integer>, _>(organization_id)
+            .load::<UtilizationRow>(&mut conn)
+            .map_err(AppError::from)
+    }
+
+    // จำนวนการจองแยกตามวันในสัปดาห์และชั่วโมงที่เริ่มใช้ห้อง
+    pub async fn peak_hours(
+        &self,
+        from: NaiveDateTime,
+        to: NaiveDateTime,
+        room_id: Option<i32>,
+        organization_id: Option<i32>,
+    ) -> Result<Vec<PeakHourCell>, AppError> {
+        let mut conn = self.pool.get()?;
+
+        diesel::sql_query(
+            "SELECT CAST(strftime('%w', start_time) AS INTEGER) AS weekday, \
+                    CAST(strftime('%H', start_time) AS INTEGER) AS hour, \
+                    COUNT(*) AS bookings \
+             FROM bookings \
+             WHERE status != 'cancelled' AND deleted_at IS NULL \
+               AND start_time >= ? AND start_time < ? \
+               AND (? IS NULL OR room_id = ?) \
+               AND (? IS NULL OR room_id IN (SELECT id FROM rooms WHERE organization_id = ?)) \
+             GROUP BY weekday, hour \
+             ORDER BY weekday, hour",
+        )
+        .bind::<Timestamp, _>(from)
+        .bind::<Timestamp, _>(to)
+        .bind::<Nullable<Integer>, _>(room_id)
+        .bind::<Nullable<Integer>, _>(room_id)
+        .bind::<Nullable<Integer>, _>(organization_id)
+        .bind::<Nullable<Integer>, _>(organization_id)
+        .load::<PeakHourCell>(&mut conn)
+        .map_err(AppError::from)
+    }
+
+    // จำนวนการจองทั้งหมด/ยกเลิก/no-show ต่อห้อง ต่อช่วงเวลา
+    // รวมการจองที่ถูก soft delete ด้วย เพราะการยกเลิกจะตั้งค่า deleted_at
+    pub async fn booking_rates(
+        &self,
+        period: ReportPeriod,
+        from: NaiveDateTime,
+        to: NaiveDateTime,
+        room_id: Option<i32>,
+        organization_id: Option<i32>,
+    ) -> Result<Vec<BookingRatesRow>, AppError> {
+        let mut conn = self.pool.get()?;
+
+        let query = format!(
+            "SELECT r.id AS room_id, r.name AS room_name, {bucket} AS period_start, \
+                    COUNT(*) AS total, \
+                    SUM(CASE WHEN b.status = 'cancelled' THEN 1 ELSE 0 END) AS cancelled, \
+                    SUM(CASE WHEN b.status = 'no_show' THEN 1 ELSE 0 END) AS no_show \
+             FROM bookings b \
+             INNER JOIN rooms r ON r.id = b.room_id \
+             WHERE b.start_time >= ? AND b.start_time < ? \
+               AND (? IS NULL OR b.room_id = ?) \
+               AND (? IS NULL OR r.organization_id = ?) \
+             GROUP BY r.id, r.name, period_start \
+             ORDER BY r.id, period_start",
+            bucket = period_bucket_sql(period, "b.start_time"),
+        );
+
+        diesel::sql_query(query)
+            .bind::<Timestamp, _>(from)
+            .bind::<Timestamp, _>(to)
+            .bind::<Nullable<Integer>, _>(room_id)
+            .bind::<Nullable<Integer>, _>(room_id)
+            .bind::<Nullable<Integer>, _>(organization_id)
+            .bind::<Nullable<Integer>, _>(organization_id)
+            .load::<BookingRatesRow>(&mut conn)
+            .map_err(AppError::from)
+    }
+
+    // ผู้ใช้ที่มีจำนวนการจองสูงสุด (ไม่นับการจองที่ถูกยกเลิก)
+    pub async fn top_bookers(
+        &self,
+        from: NaiveDateTime,
+        to: NaiveDateTime,
+        room_id: Option<i32>,
+        organization_id: Option<i32>,
+        limit: i64,
+    ) -> Result<Vec<TopBooker>, AppError> {
+        let mut conn = self.pool.get()?;
+
+        diesel::sql_query(
+            "SELECT u.id AS user_id, u.username AS username, \
+                    COUNT(*) AS bookings, \
+                    COALESCE(SUM((strftime('%s', b.end_time) - strftime('%s', b.start_time)) / 3600.0), 0.0) AS booked_hours \
+             FROM bookings b \
+             INNER JOIN users u ON u.id = b.user_id \
+             WHERE b.status != 'cancelled' AND b.deleted_at IS NULL \
+               AND b.start_time >= ? AND b.start_time < ? \
+               AND (? IS NULL OR b.room_id = ?) \
+               AND (? IS NULL OR u.organization_id = ?) \
+             GROUP BY u.id, u.username \
+             ORDER BY bookings DESC, booked_hours DESC \
+             LIMIT ?",
+        )
+        .bind::<Timestamp, _>(from)
+        .bind::<Timestamp, _>(to)
+        .bind::<Nullable<Integer>, _>(room_id)
+        .bind::<Nullable<Integer>, _>(room_id)
+        .bind::<Nullable<Integer>, _>(organization_id)
+        .bind::<Nullable<Integer>, _>(organization_id)
+        .bind::<BigInt, _>(limit)
+        .load::<TopBooker>(&mut conn)
+        .map_err(AppError::from)
+    }
+}
@@ -0,0 +1,254 @@
+// src/infrastructure/role_repository.rs
+
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use diesel::SqliteConnection;
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::domain::audit::PendingAudit;
+use crate::domain::permission::{NewRoleAssignment, Permission, PrincipalKind, RoleResponse, UserRolesResponse};
+use crate::error::AppError;
+
+use super::audit_repository::AuditRepository;
+use super::schema::{role_assignments, role_permissions, roles};
+
+// Permission (string) ทั้งหมดของบัญชี: จาก Role พื้นฐาน (ตามประเภทบัญชี) และ Role ที่ถูกกำหนดเพิ่ม
+fn load_permissions(
+    conn: &mut SqliteConnection,
+    principal_kind: &str,
+    principal_id: i32,
+) -> Result<Vec<String>, diesel::result::Error> {
+    let assigned = role_assignments::table
+        .filter(role_assignments::principal_kind.eq(principal_kind))
+        .filter(role_assignments::principal_id.eq(principal_id))
+        .select(role_assignments::role_name);
+
+    role_permissions::table
+        .filter(
+            role_permissions::role_name
+                .eq(principal_kind)
+                .or(role_permissions::role_name.eq_any(assigned)),
+        )
+        .select(role_permissions::permission)
+        .distinct()
+        .load::<String>(conn)
+}
+
+// Role ทั้งหมดพร้อม Permission เรียงตามชื่อ
+fn load_roles(conn: &mut SqliteConnection) -> Result<Vec<RoleResponse>, diesel::result::Error> {
+    let mut permissions: BTreeMap<String, Vec<Permission>> = BTreeMap::new();
+    let pairs = role_permissions::table
+        .select((role_permissions::role_name, role_permissions::permission))
+        .order((role_permissions::role_name.asc(), role_permissions::permission.asc()))
+        .load::<(String, String)>(conn)?;
+    for (role_name, permission) in pairs {
+        if let Some(permission) = Permission::parse(&permission) {
+            permissions.entry(role_name).or_default().push(permission);
+        }
+    }
+
+    Ok(roles::table
+        .select((roles::name, roles::description, roles::require_mfa))
+        .order(roles::name.asc())
+        .load::<(String, String, bool)>(conn)?
+        .into_iter()
+        .map(|(name, description, require_mfa)| RoleResponse {
+            permissions: permissions.remove(&name).unwrap_or_default(),
+            name,
+            description,
+            require_mfa,
+        })
+        .collect())
+}
+
+// Role ที่ถูกกำหนดเพิ่มและ Permission ที่มีผลของผู้ใช้
+fn load_user_roles(conn: &mut SqliteConnection, user_id: i32) -> Result<UserRolesResponse, diesel::result::Error> {
+    let kind = PrincipalKind::User.as_str();
+    let roles = role_assignments::table
+        .filter(role_assignments::principal_kind.eq(kind))
+        .filter(role_assignments::principal_id.eq(user_id))
+        .select(role_assignments::role_name)
+        .order(role_assignments::role_name.asc())
+        .load::<String>(conn)?;
+    let permissions: BTreeSet<Permission> = load_permissions(conn, kind, user_id)?
+        .iter()
+        .filter_map(|p| Permission::parse(p))
+        .collect();
+    Ok(UserRolesResponse {
+        user_id,
+        base_role: kind.to_string(),
+        roles,
+        permissions: permissions.into_iter().collect(),
+    })
+}
+
+// ใช้ใน Transaction ของ MfaRepository ด้วย (สถานะ 2FA ที่บันทึกใน Audit Log)
+pub(super) fn mfa_required(
+    conn: &mut SqliteConnection,
+    principal_kind: &str,
+    principal_id: i32,
+) -> Result<bool, diesel::result::Error> {
+    let assigned = role_assignments::table
+        .filter(role_assignments::principal_kind.eq(principal_kind))
+        .filter(role_assignments::principal_id.eq(principal_id))
+        .select(role_assignments::role_name);
+
+    let count = roles::table
+        .filter(roles::name.eq(principal_kind).or(roles::name.eq_any(assigned)))
+        .filter(roles::require_mfa.eq(true))
+        .count()
+        .get_result::<i64>(conn)?;
+    Ok(count > 0)
+}
+
+#[derive(Debug, Clone)]
+pub struct RoleRepository {
+    pool: Pool<ConnectionManager<SqliteConnection>>,
+}
+
+impl RoleRepository {
+    pub fn new(pool: Pool<ConnectionManager<SqliteConnection>>) -> Self {
+        RoleRepository { pool }
+    }
+
+    pub async fn permissions_for(&self, principal_kind: &str, principal_id: i32) -> Result<Vec<String>, AppError> {
+        let mut conn = self.pool.get()?;
+        Ok(load_permissions(&mut conn, principal_kind, principal_id)?)
+    }
+
+    pub async fn list_roles(&self) -> Result<Vec<RoleResponse>, AppError> {
+        let mut conn = self.pool.get()?;
+        Ok(load_roles(&mut conn)?)
+    }
+
+    // บัญชีต้องใช้ 2FA หรือไม่ (Role พื้นฐานหรือ Role ที่ถูกกำหนดเพิ่มตัวใดตัวหนึ่งกำหนดไว้)
+    pub async fn mfa_required_for(&self, principal_kind: &str, principal_id: i32) -> Result<bool, AppError> {
+        let mut conn = self.pool.get()?;
+        Ok(mfa_required(&mut conn, principal_kind, principal_id)?)
+    }
+
+    // คืนค่า None ถ้าไม่พบ Role
+    pub async fn set_require_mfa(
+        &self,
+        name: &str,
+        required: bool,
+        audit: PendingAudit<'_, RoleResponse>,
+    ) -> Result<Option<RoleResponse>, AppError> {
+        let mut conn = self.pool.get()?;
+        conn.immediate_transaction(|conn| {
+            let affected = diesel::update(roles::table.filter(roles::name.eq(name)))
+                .set(roles::require_mfa.eq(required))
+                .execute(conn)?;
+            if affected == 0 {
+                return Ok(None);
+            }
+            let Some(role) = load_roles(conn)?.into_iter().find(|r| r.name == name) else {
+                return Ok(None);
+            };
+            AuditRepository::append(conn, audit, &role)?;
+            Ok(Some(role))
+        })
+    }
+
+    // Permission ของ Role (None ถ้าไม่พบ Role)
+    pub async fn role_permissions(&self, name: &str) -> Result<Option<Vec<String>>, AppError> {
+        let mut conn = self.pool.get()?;
+        let count = roles::table
+            .filter(roles::name.eq(name))
+            .count()
+            .get_result::<i64>(&mut conn)?;
+        if count == 0 {
+            return Ok(None);
+        }
+        let permissions = role_permissions::table
+            .filter(role_permissions::role_name.eq(name))
+            .select(role_permissions::permission)
+            .load::<String>(&mut conn)?;
+        Ok(Some(permissions))
+    }
+
+    pub async fn user_roles(&self, user_id: i32) -> Result<UserRolesResponse, AppError> {
+        let mut conn = self.pool.get()?;
+        Ok(load_user_roles(&mut conn, user_id)?)
+    }
+
+    // กำหนด Role เพิ่มให้ผู้ใช้ แล้วคืน Role และ Permission ล่าสุด
+    pub async fn assign_user_role(
+        &self,
+        assignment: NewRoleAssignment<'_>,
+        audit: PendingAudit<'_, UserRolesResponse>,
+    ) -> Result<UserRolesResponse, AppError> {
+        let mut conn = self.pool.get()?;
+        conn.immediate_transaction(|conn| {
+            diesel::insert_into(role_assignments::table)
+                .values(&assignment)
+                .execute(conn)
+                .map_err(|e| match e {
+                    diesel::result::Error::DatabaseError(diesel::result::DatabaseErrorKind::UniqueViolation, _) => {
+                        AppError::Conflict(format!("Role '{}' is already assigned", assignment.role_name))
+                    }
+                    e => e.into(),
+                })?;
+            let roles = load_user_roles(conn, assignment.principal_id)?;
+            AuditRepository::append(conn, audit, &roles)?;
+            Ok(roles)
+        })
+    }
+
+    // คืนค่า false ถ้าผู้ใช้ไม่ได้ถือ Role นี้อยู่
+    pub async fn remove_user_role(
+        &self,
+        user_id: i32,
+        role_name: &str,
+        audit: PendingAudit<'_, UserRolesResponse>,
+    ) -> Result<bool, AppError> {
+        let mut conn = self.pool.get()?;
+        conn.immediate_transaction(|conn| {
+            let affected = diesel::delete(
+                role_assignments::table
+                    .filter(role_assignments::principal_kind.eq(PrincipalKind::User.as_str()))
+                    .filter(role_assignments::principal_id.eq(user_id))
+                    .filter(role_assignments::role_name.eq(role_name)),
+            )
+            .execute(conn)?;
+            if affected == 0 {
+                return Ok(false);
+            }
+            let roles = load_user_roles(conn, user_id)?;
+            AuditRepository::append(conn, audit, &roles)?;
+            Ok(true)
+        })
+    }
+
+    // แทนที่ Role ที่ซิงก์จากแหล่งภายนอก (source) ด้วยชุดใหม่ Role ที่ Admin กำหนดเอง (manual) ไม่ถูกแตะต้อง
+    // Role ที่ไม่มีในตาราง roles หรือถูกกำหนดไว้แล้วจะถูกข้าม
+    pub async fn sync_roles(&self, principal_kind: &str, principal_id: i32, source: &str, role_names: &[String]) -> Result<(), AppError> {
+        let mut conn = self.pool.get()?;
+        conn.transaction(|conn| {
+            diesel::delete(
+                role_assignments::table
+                    .filter(role_assignments::principal_kind.eq(principal_kind))
+                    .filter(role_assignments::principal_id.eq(principal_id))
+                    .filter(role_assignments::source.eq(source)),
+            )
+            .execute(conn)?;
+
+            let existing = roles::table
+                .filter(roles::name.eq_any(role_names))
+                .select(roles::name)
+                .load::<String>(conn)?;
+            for role_name in existing {
+                diesel::insert_or_ignore_into(role_assignments::table)
+                    .values((
+                        role_assignments::principal_kind.eq(principal_kind),
+                        role_assignments::principal_id.eq(principal_id),
+                        role_assignments::role_name.eq(&role_name),
+                        role_assignments::source.eq(source),
+                    ))
+                    .execute(conn)?;
+            }
+            Ok(())
+        })
+    }
+}
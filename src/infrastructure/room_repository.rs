@@ -1,6 +1,10 @@
+use crate::domain::audit::PendingAudit;
+use crate::domain::organization::Tenant;
+use crate::domain::pagination::ListQuery;
 use crate::domain::room::NewRoom;
 use crate::domain::room::Room;
 use crate::domain::room::RoomChangeset;
+use crate::error::AppError;
 
 use chrono::Local;
 use chrono::Utc;
@@ -8,8 +12,72 @@ use diesel::SqliteConnection;
 use diesel::prelude::*;
 use diesel::r2d2::ConnectionManager;
 use diesel::r2d2::Pool;
+use diesel::sqlite::Sqlite;
 
-use super::schema::rooms;
+use super::audit_repository::AuditRepository;
+use super::pagination::sort_by_column;
+use super::schema::{group_members, organizations, room_allowed_groups, room_allowed_users, room_shares, rooms};
+
+// Query ห้องพร้อมตัวกรองจาก ListQuery (status)
+// active_only = true จะแสดงเฉพาะห้องที่ยังไม่ถูกลบและมีสถานะ available
+// ไม่เช่นนั้นห้องที่ถูกลบจะแสดงเฉพาะเมื่อ include_deleted
+fn filtered_rooms(
+    tenant: Tenant,
+    restricted_to: Option<i32>,
+    query: &ListQuery,
+    active_only: bool,
+) -> rooms::BoxedQuery<'_, Sqlite> {
+    let mut q = visible_rooms(tenant, restricted_to);
+    if active_only {
+        q = q
+            .filter(rooms::deleted_at.is_null())
+            .filter(rooms::status.eq("available"));
+    } else if !query.include_deleted() {
+        q = q.filter(rooms::deleted_at.is_null());
+    }
+    if let Some(status) = &query.status {
+        q = q.filter(rooms::status.eq(status));
+    }
+    q
+}
+
+// ห้องที่องค์กรเห็นได้: ห้องขององค์กรเอง และห้องขององค์กรอื่นที่แชร์ให้
+// restricted_to = Some(user_id) เหลือเฉพาะห้องที่ผู้ใช้คนนั้นจองได้:
+// ห้องที่ไม่มีกฎการเข้าถึง ห้องที่ระบุผู้ใช้ไว้ตรง ๆ หรือห้องที่ระบุกลุ่มที่ผู้ใช้เป็นสมาชิก
+fn visible_rooms<'a>(tenant: Tenant, restricted_to: Option<i32>) -> rooms::BoxedQuery<'a, Sqlite> {
+    let mut q = rooms::table.into_boxed();
+    if let Some(org) = tenant.organization_id() {
+        q = q.filter(
+            rooms::organization_id.eq(org).or(rooms::id.eq_any(
+                room_shares::table
+                    .filter(room_shares::organization_id.eq(org))
+                    .select(room_shares::room_id),
+            )),
+        );
+    }
+    if let Some(user_id) = restricted_to {
+        q = q.filter(
+            rooms::id
+                .ne_all(room_allowed_groups::table.select(room_allowed_groups::room_id))
+                .and(rooms::id.ne_all(room_allowed_users::table.select(room_allowed_users::room_id)))
+                .or(rooms::id.eq_any(
+                    room_allowed_users::table
+                        .filter(room_allowed_users::user_id.eq(user_id))
+                        .select(room_allowed_users::room_id),
+                ))
+                .or(rooms::id.eq_any(
+                    room_allowed_groups::table
+                        .filter(room_allowed_groups::group_id.eq_any(
+                            group_members::table
+                                .filter(group_members::user_id.eq(user_id))
+                                .select(group_members::group_id),
+                        ))
+                        .select(room_allowed_groups::room_id),
+                )),
+        );
+    }
+    q
+}
 
 #[derive(Debug, Clone)]
 pub struct RoomRepository {
@@ -48,135 +116,182 @@ impl RoomRepository {
     }
 
 
-    pub async fn add_room(&self, new_room_data: NewRoom<'_>) -> Result<Room, String> {
-        let mut conn = self
-            .pool
-            .get()
-            .map_err(|e| format!("Failed to get DB connection: {}", e))?;
-
-        // ตรวจสอบว่า name ซ้ำหรือไม่ (เป็น Good Practice)
-        let existing_room_result = rooms::table
-            .filter(rooms::name.eq(&new_room_data.name))
-            .first::<Room>(&mut conn);
+    pub async fn add_room(&self, new_room_data: NewRoom<'_>, audit: PendingAudit<'_, Room>) -> Result<Room, AppError> {
+        let mut conn = self.pool.get()?;
 
-        match existing_room_result {
-            Ok(_) => {
-                // ถ้า User ถูกพบ แสดงว่า username ซ้ำ
-                return Err("Room name already taken".to_string());
+        conn.immediate_transaction(|conn| {
+            let organization_exists = organizations::table
+                .find(new_room_data.organization_id)
+                .count()
+                .get_result::<i64>(conn)?
+                > 0;
+            if !organization_exists {
+                return Err(AppError::Validation("`organization_id` does not exist.".to_string()));
             }
-            Err(diesel::result::Error::NotFound) => {
-                // ถ้าไม่พบผู้ใช้ ถือว่าถูกต้อง สามารถดำเนินการต่อได้
-                // ไม่ต้องทำอะไรตรงนี้
-            }
-            Err(e) => {
-                // จัดการกับ Error อื่นๆ ที่ไม่ใช่ NotFound
-                return Err(format!("Database error during room check: {}", e));
+
+            // ตรวจสอบว่า name ซ้ำหรือไม่ (ชื่อห้องไม่ซ้ำกันภายในองค์กรเดียวกัน)
+            let existing_room = rooms::table
+                .filter(rooms::organization_id.eq(new_room_data.organization_id))
+                .filter(rooms::name.eq(&new_room_data.name))
+                .first::<Room>(conn)
+                .optional()?;
+
+            if existing_room.is_some() {
+                return Err(AppError::Conflict("Room name already taken".to_string()));
             }
-        }
 
-        diesel::insert_into(rooms::table)
-            .values(&new_room_data)
-            .execute(&mut conn)
-            .map_err(|e| format!("Failed to insert room into DB: {}", e))?;
+            diesel::insert_into(rooms::table)
+                .values(&new_room_data)
+                .execute(conn)?;
 
-        let inserted_room = rooms::table
-            .filter(rooms::name.eq(&new_room_data.name))
-            .first::<Room>(&mut conn)
-            .map_err(|e| format!("Failed to retrieve newly inserted room: {}", e))?;
+            let inserted_room = rooms::table
+                .filter(rooms::organization_id.eq(new_room_data.organization_id))
+                .filter(rooms::name.eq(&new_room_data.name))
+                .first::<Room>(conn)?;
+            AuditRepository::append(conn, audit, &inserted_room)?;
 
-        Ok(inserted_room)
+            Ok(inserted_room)
+        })
     }
 
-    pub async fn get_all_room(&self) -> Result<Vec<Room>, String> {
-        let mut conn = self
-            .pool
-            .get()
-            .map_err(|e| format!("Failed to get DB connection: {}", e))?;
+    pub const SORT_FIELDS: &'static [&'static str] = &["id", "name", "status", "created_at", "updated_at"];
+
+    // ดึงห้องตามตัวกรอง เรียงลำดับ และแบ่งหน้า คืนค่า (รายการในหน้านี้, จำนวนทั้งหมด)
+    pub async fn list_rooms(
+        &self,
+        tenant: Tenant,
+        restricted_to: Option<i32>,
+        query: &ListQuery,
+        offset: i64,
+        active_only: bool,
+    ) -> Result<(Vec<Room>, i64), AppError> {
+        let mut conn = self.pool.get()?;
+
+        let total = filtered_rooms(tenant, restricted_to, query, active_only)
+            .count()
+            .get_result::<i64>(&mut conn)?;
+
+        let q = filtered_rooms(tenant, restricted_to, query, active_only);
+        let direction = query.direction();
+        let q = match query.sort.as_deref().unwrap_or("id") {
+            "name" => sort_by_column!(q, rooms::name, direction),
+            "status" => sort_by_column!(q, rooms::status, direction),
+            "created_at" => sort_by_column!(q, rooms::created_at, direction),
+            "updated_at" => sort_by_column!(q, rooms::updated_at, direction),
+            _ => sort_by_column!(q, rooms::id, direction),
+        };
 
-        let rooms = rooms::table
-            .load::<Room>(&mut conn)
-            .map_err(|e| format!("Failed to retrieve all users: {}", e))?;
-        Ok(rooms)
+        let rooms = q
+            .then_order_by(rooms::id.asc())
+            .limit(query.limit())
+            .offset(offset)
+            .load::<Room>(&mut conn)?;
+        Ok((rooms, total))
     }
-    pub async fn get_room_by_id(&self, room_id: i32) -> Result<Room, String> {
-        let mut conn = self
-            .pool
-            .get()
-            .map_err(|e| format!("Failed to get DB connection: {}", e))?; // <<-- ตรงนี้ดึง Connection จาก Pool
 
-        let room = rooms::table // <<-- อ้างถึงตาราง rooms
+    pub async fn get_room_by_id(
+        &self,
+        tenant: Tenant,
+        restricted_to: Option<i32>,
+        room_id: i32,
+    ) -> Result<Room, AppError> {
+        let mut conn = self.pool.get()?; // <<-- ตรงนี้ดึง Connection จาก Pool
+
+        visible_rooms(tenant, restricted_to) // <<-- อ้างถึงตาราง rooms (เฉพาะห้องที่องค์กรเห็นได้)
             .filter(rooms::id.eq(room_id))
-            .filter(rooms::deleted_at.is_null()) 
+            .filter(rooms::deleted_at.is_null())
             .first::<Room>(&mut conn) // <<-- ดึงข้อมูลแรกที่เจอและแปลงเป็น Struct Room
-            .map_err(|e| format!("Failed to retrieve room by ID: {}", e))?; // <<-- จัดการ Error ถ้าไม่พบหรือไม่สำเร็จ
-
-        Ok(room) // <<-- คืนค่า Room ที่พบ
+            .optional()?
+            .ok_or_else(|| AppError::NotFound("Room not found".to_string()))
     }
 
-    pub async fn get_all_active_rooms(&self)->Result<Vec<Room>,String>{
-        let mut conn = self
-            .pool
-            .get()
-            .map_err(|e| format!("Failed to get DB connection: {}", e))?; // <<-- ตรงนี้ดึง Connection จาก Pool
-        let rooms = rooms::table
-            .filter(rooms::deleted_at.is_null()) 
-            .filter(rooms::status.eq("available"))
-            .load::<Room>(&mut conn)
-            .map_err(|e| format!("Failed to retrieve all users: {}", e))?;
-        Ok(rooms)
+    // ห้องตาม ID รวมที่ถูก Soft Delete (ไม่ตรวจองค์กร ผู้เรียกตรวจด้วย ensure_room แล้ว)
+    pub async fn find_room(&self, room_id: i32) -> Result<Option<Room>, AppError> {
+        let mut conn = self.pool.get()?;
+        Ok(rooms::table.find(room_id).first::<Room>(&mut conn).optional()?)
     }
 
+    // ผู้ใช้จองห้องนี้ได้ตามกฎการเข้าถึงของห้องหรือไม่ (ไม่ตรวจองค์กร)
+    pub async fn is_bookable_by(&self, room_id: i32, user_id: i32) -> Result<bool, AppError> {
+        let mut conn = self.pool.get()?;
+        let count = visible_rooms(Tenant::All, Some(user_id))
+            .filter(rooms::id.eq(room_id))
+            .count()
+            .get_result::<i64>(&mut conn)?;
+        Ok(count > 0)
+    }
 
+    pub async fn update_room(
+        &self,
+        room_id: i32,
+        changes: RoomChangeset,
+        audit: PendingAudit<'_, Room>,
+    ) -> Result<Room, AppError> {
+        let mut conn = self.pool.get()?; // <<-- ตรงนี้ดึง Connection จาก Pool
+        conn.immediate_transaction(|conn| {
+            let update_rows = diesel::update(rooms::table.filter(rooms::id.eq(room_id)))
+                .set(&changes)
+                .execute(conn)?;
+            if update_rows == 0 {
+                return Err(AppError::NotFound("Room not found".to_string()));
+            }
+            let updated_room = rooms::table
+                .filter(rooms::id.eq(room_id))
+                .first::<Room>(conn)?;
+            AuditRepository::append(conn, audit, &updated_room)?;
 
+            Ok(updated_room)
+        })
+    }
 
+    pub async fn delete_room(&self, room_id: i32, audit: PendingAudit<'_, Room>) -> Result<Room, AppError> {
+        let mut conn = self.pool.get()?; // <<-- ตรงนี้ดึง Connection จาก Pool
 
-    pub async fn update_room(&self, room_id: i32, changes: RoomChangeset) -> Result<Room, String> {
-        let mut conn = self
-            .pool
-            .get()
-            .map_err(|e| format!("Failed to get DB connection: {}", e))?; // <<-- ตรงนี้ดึง Connection จาก Pool
-        let update_rows = diesel::update(rooms::table.filter(rooms::id.eq(room_id)))
-            .set(&changes)
-            .execute(&mut conn)
-            .map_err(|e| format!("Failed to retrieve room by ID: {}", e))?; // <<-- จัดการ Error ถ้าไม่พบหรือไม่สำเร็จ
-        if update_rows == 0 {
-            return Err("Room not found".to_string());
-        }
-        let updated_room = rooms::table
-            .filter(rooms::id.eq(room_id))
-            .first::<Room>(&mut conn)
-            .map_err(|e| format!("Failed to retrieve room by ID: {}", e))?;
-
-        Ok(updated_room)
-    }
-    pub async fn delete_room(&self, room_id: i32 ) -> Result<Room, String> {
-        let mut conn = self
-            .pool
-            .get()
-            .map_err(|e| format!("Failed to get DB connection: {}", e))?; // <<-- ตรงนี้ดึง Connection จาก Pool
-        
         let changes = RoomChangeset {
             name: None,          // ไม่ได้อัปเดตชื่อSome
             status: None,   // ไม่ได้อัปเดตคำอธิบาย
             updated_at: Some(Local::now().naive_local()), // อัปเดต updated_at ด้วย
             deleted_at: Some(Local::now().naive_local()), // <<-- ตั้งค่า deleted_at
         };
-        
-        let updated_rows = diesel::update(rooms::table.filter(rooms::id.eq(room_id)))
-            .set(changes)
-            .execute(&mut conn)
-            .map_err(|e| format!("Failed to soft delete room: {}", e))?;
 
-        if updated_rows == 0 {
-            return Err("Room not found or already deleted".to_string());
-        } 
-        
-        let updated_room = rooms::table
-            .filter(rooms::id.eq(room_id))
-            .first::<Room>(&mut conn)
-            .map_err(|e| format!("Failed to retrieve room by ID: {}", e))?;
-        
-        Ok(updated_room)
+        conn.immediate_transaction(|conn| {
+            let updated_rows = diesel::update(rooms::table.filter(rooms::id.eq(room_id)))
+                .set(changes)
+                .execute(conn)?;
+
+            if updated_rows == 0 {
+                return Err(AppError::NotFound("Room not found or already deleted".to_string()));
+            }
+
+            let updated_room = rooms::table
+                .filter(rooms::id.eq(room_id))
+                .first::<Room>(conn)?;
+            AuditRepository::append(conn, audit, &updated_room)?;
 
+            Ok(updated_room)
+        })
+    }
+
+    // กู้คืนห้องที่ถูก Soft Delete (สถานะห้องคงเดิม)
+    pub async fn restore_room(&self, room_id: i32, audit: PendingAudit<'_, Room>) -> Result<Room, AppError> {
+        let mut conn = self.pool.get()?;
+        conn.immediate_transaction(|conn| {
+            let updated_rows = diesel::update(
+                rooms::table
+                    .filter(rooms::id.eq(room_id))
+                    .filter(rooms::deleted_at.is_not_null()),
+            )
+            .set((
+                rooms::deleted_at.eq(None::<chrono::NaiveDateTime>),
+                rooms::updated_at.eq(Utc::now().naive_utc()),
+            ))
+            .execute(conn)?;
+            if updated_rows == 0 {
+                return Err(AppError::NotFound("Room not found or not deleted".to_string()));
+            }
+            let restored = rooms::table.find(room_id).first::<Room>(conn)?;
+            AuditRepository::append(conn, audit, &restored)?;
+            Ok(restored)
+        })
     }
 }
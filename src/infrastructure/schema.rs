@@ -1,5 +1,19 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    admin_invitations (id) {
+        id -> Integer,
+        jti -> Text,
+        invited_by -> Integer,
+        expires_at -> Timestamp,
+        created_at -> Timestamp,
+        accepted_at -> Nullable<Timestamp>,
+        accepted_admin_id -> Nullable<Integer>,
+        revoked_at -> Nullable<Timestamp>,
+        organization_id -> Nullable<Integer>,
+    }
+}
+
 diesel::table! {
     admins (id) {
         id -> Integer,
@@ -8,6 +22,43 @@ diesel::table! {
         created_at -> Timestamp,
         updated_at -> Timestamp,
         deleted_at -> Nullable<Timestamp>,
+        organization_id -> Nullable<Integer>,
+    }
+}
+
+diesel::table! {
+    api_keys (id) {
+        id -> Integer,
+        principal_kind -> Text,
+        principal_id -> Integer,
+        name -> Text,
+        prefix -> Text,
+        key_hash -> Text,
+        scopes -> Text,
+        expires_at -> Timestamp,
+        last_used_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        revoked_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    audit_log (id) {
+        id -> Integer,
+        created_at -> Timestamp,
+        organization_id -> Nullable<Integer>,
+        actor_kind -> Text,
+        actor_id -> Nullable<Integer>,
+        impersonator_id -> Nullable<Integer>,
+        action -> Text,
+        entity_type -> Text,
+        entity_id -> Nullable<Text>,
+        before_json -> Nullable<Text>,
+        after_json -> Nullable<Text>,
+        request_id -> Nullable<Text>,
+        ip -> Nullable<Text>,
+        prev_hash -> Text,
+        hash -> Text,
     }
 }
 
@@ -25,6 +76,242 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    erasure_requests (id) {
+        id -> Integer,
+        user_id -> Integer,
+        requested_by_kind -> Text,
+        requested_by_id -> Integer,
+        reason -> Nullable<Text>,
+        bookings_cancelled -> Integer,
+        bookings_retained -> Integer,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    external_identities (id) {
+        id -> Integer,
+        user_id -> Integer,
+        issuer -> Text,
+        subject -> Text,
+        created_at -> Timestamp,
+        last_login_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    group_members (group_id, user_id) {
+        group_id -> Integer,
+        user_id -> Integer,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    groups (id) {
+        id -> Integer,
+        organization_id -> Integer,
+        name -> Text,
+        description -> Nullable<Text>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    impersonation_actions (id) {
+        id -> Integer,
+        impersonation_id -> Integer,
+        method -> Text,
+        path -> Text,
+        status -> Integer,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    impersonations (id) {
+        id -> Integer,
+        admin_id -> Integer,
+        user_id -> Integer,
+        reason -> Text,
+        jti -> Text,
+        created_at -> Timestamp,
+        expires_at -> Timestamp,
+        ended_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    login_events (id) {
+        id -> Integer,
+        account_kind -> Text,
+        username -> Text,
+        ip -> Text,
+        success -> Bool,
+        reason -> Nullable<Text>,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    login_throttles (id) {
+        id -> Integer,
+        scope -> Text,
+        throttle_key -> Text,
+        failed_count -> Integer,
+        last_failed_at -> Timestamp,
+        locked_until -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    mfa_challenges (id) {
+        id -> Integer,
+        token_hash -> Text,
+        principal_kind -> Text,
+        principal_id -> Integer,
+        enrollment -> Bool,
+        attempts -> Integer,
+        expires_at -> Timestamp,
+        created_at -> Timestamp,
+        completed_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    mfa_recovery_codes (id) {
+        id -> Integer,
+        principal_kind -> Text,
+        principal_id -> Integer,
+        code_hash -> Text,
+        created_at -> Timestamp,
+        used_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    mfa_totp (id) {
+        id -> Integer,
+        principal_kind -> Text,
+        principal_id -> Integer,
+        secret -> Text,
+        last_used_step -> Nullable<BigInt>,
+        confirmed_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    oidc_login_states (id) {
+        id -> Integer,
+        state_hash -> Text,
+        nonce -> Text,
+        code_verifier -> Text,
+        mode -> Text,
+        expires_at -> Timestamp,
+        created_at -> Timestamp,
+        used_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    organizations (id) {
+        id -> Integer,
+        name -> Text,
+        slug -> Text,
+        self_registration -> Bool,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    password_reset_tokens (id) {
+        id -> Integer,
+        user_id -> Integer,
+        token_hash -> Text,
+        expires_at -> Timestamp,
+        created_at -> Timestamp,
+        used_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    refresh_tokens (id) {
+        id -> Integer,
+        token_hash -> Text,
+        family_id -> Text,
+        subject_id -> Integer,
+        role -> Text,
+        access_jti -> Text,
+        access_expires_at -> Timestamp,
+        expires_at -> Timestamp,
+        created_at -> Timestamp,
+        used_at -> Nullable<Timestamp>,
+        revoked_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    revoked_tokens (jti) {
+        jti -> Text,
+        expires_at -> Timestamp,
+        revoked_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    role_assignments (id) {
+        id -> Integer,
+        principal_kind -> Text,
+        principal_id -> Integer,
+        role_name -> Text,
+        source -> Text,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    role_permissions (role_name, permission) {
+        role_name -> Text,
+        permission -> Text,
+    }
+}
+
+diesel::table! {
+    roles (name) {
+        name -> Text,
+        description -> Text,
+        require_mfa -> Bool,
+    }
+}
+
+diesel::table! {
+    room_allowed_groups (room_id, group_id) {
+        room_id -> Integer,
+        group_id -> Integer,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    room_allowed_users (room_id, user_id) {
+        room_id -> Integer,
+        user_id -> Integer,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    room_shares (room_id, organization_id) {
+        room_id -> Integer,
+        organization_id -> Integer,
+        created_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     rooms (id) {
         id -> Integer,
@@ -33,6 +320,7 @@ diesel::table! {
         created_at -> Timestamp,
         updated_at -> Timestamp,
         deleted_at -> Nullable<Timestamp>,
+        organization_id -> Integer,
     }
 }
 
@@ -40,19 +328,74 @@ diesel::table! {
     users (id) {
         id -> Integer,
         username -> Text,
-        password_hash -> Text,
+        password_hash -> Nullable<Text>,
         created_at -> Timestamp,
         updated_at -> Timestamp,
         deleted_at -> Nullable<Timestamp>,
+        display_name -> Nullable<Text>,
+        directory_groups -> Nullable<Text>,
+        email -> Nullable<Text>,
+        phone -> Nullable<Text>,
+        department -> Nullable<Text>,
+        timezone -> Nullable<Text>,
+        language -> Nullable<Text>,
+        email_verified_at -> Nullable<Timestamp>,
+        email_verification_pending -> Bool,
+        erased_at -> Nullable<Timestamp>,
+        organization_id -> Integer,
     }
 }
 
 diesel::joinable!(bookings -> rooms (room_id));
 diesel::joinable!(bookings -> users (user_id));
+diesel::joinable!(erasure_requests -> users (user_id));
+diesel::joinable!(external_identities -> users (user_id));
+diesel::joinable!(group_members -> groups (group_id));
+diesel::joinable!(group_members -> users (user_id));
+diesel::joinable!(groups -> organizations (organization_id));
+diesel::joinable!(impersonation_actions -> impersonations (impersonation_id));
+diesel::joinable!(impersonations -> admins (admin_id));
+diesel::joinable!(impersonations -> users (user_id));
+diesel::joinable!(password_reset_tokens -> users (user_id));
+diesel::joinable!(role_assignments -> roles (role_name));
+diesel::joinable!(role_permissions -> roles (role_name));
+diesel::joinable!(room_allowed_groups -> groups (group_id));
+diesel::joinable!(room_allowed_groups -> rooms (room_id));
+diesel::joinable!(room_allowed_users -> rooms (room_id));
+diesel::joinable!(room_allowed_users -> users (user_id));
+diesel::joinable!(room_shares -> organizations (organization_id));
+diesel::joinable!(room_shares -> rooms (room_id));
+diesel::joinable!(rooms -> organizations (organization_id));
+diesel::joinable!(users -> organizations (organization_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
+    admin_invitations,
     admins,
+    api_keys,
+    audit_log,
     bookings,
+    erasure_requests,
+    external_identities,
+    group_members,
+    groups,
+    impersonation_actions,
+    impersonations,
+    login_events,
+    login_throttles,
+    mfa_challenges,
+    mfa_recovery_codes,
+    mfa_totp,
+    oidc_login_states,
+    organizations,
+    password_reset_tokens,
+    refresh_tokens,
+    revoked_tokens,
+    role_assignments,
+    role_permissions,
+    roles,
+    room_allowed_groups,
+    room_allowed_users,
+    room_shares,
     rooms,
     users,
 );
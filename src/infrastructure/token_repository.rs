@@ -0,0 +1,161 @@
+// src/infrastructure/token_repository.rs
+
+use chrono::{NaiveDateTime, Utc};
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use diesel::SqliteConnection;
+
+use crate::domain::auth::{NewRefreshToken, RefreshToken};
+use crate::error::AppError;
+
+use super::schema::{impersonations, refresh_tokens, revoked_tokens};
+
+// เพิกถอน refresh token ตามเงื่อนไข และเพิ่ม jti ของ Access Token ที่ออกคู่กันลงใน revoked_tokens
+// ต้องเรียกภายใน transaction
+fn revoke_matching<F>(conn: &mut SqliteConnection, filter: F) -> Result<usize, diesel::result::Error>
+where
+    F: Fn() -> refresh_tokens::BoxedQuery<'static, diesel::sqlite::Sqlite>,
+{
+    let now = Utc::now().naive_utc();
+    let active = filter()
+        .filter(refresh_tokens::revoked_at.is_null())
+        .select(RefreshToken::as_select())
+        .load::<RefreshToken>(conn)?;
+
+    for token in &active {
+        // Access Token ที่หมดอายุแล้วไม่ต้องบันทึก
+        if token.access_expires_at > now {
+            diesel::insert_or_ignore_into(revoked_tokens::table)
+                .values((
+                    revoked_tokens::jti.eq(&token.access_jti),
+                    revoked_tokens::expires_at.eq(token.access_expires_at),
+                ))
+                .execute(conn)?;
+        }
+    }
+
+    let ids: Vec<i32> = active.iter().map(|t| t.id).collect();
+    diesel::update(refresh_tokens::table.filter(refresh_tokens::id.eq_any(ids)))
+        .set(refresh_tokens::revoked_at.eq(now))
+        .execute(conn)
+}
+
+// จบการสวมสิทธิ์ที่ยังไม่หมดอายุ และเพิ่ม jti ของ Token ลงใน revoked_tokens (ต้องเรียกภายใน transaction)
+fn end_open_impersonations(conn: &mut SqliteConnection, subject_id: i32, role: &str) -> Result<usize, diesel::result::Error> {
+    let now = Utc::now().naive_utc();
+    let open = impersonations::table
+        .filter(impersonations::ended_at.is_null())
+        .filter(impersonations::expires_at.gt(now))
+        .into_boxed();
+    let open = if role == "admin" {
+        open.filter(impersonations::admin_id.eq(subject_id))
+    } else {
+        open.filter(impersonations::user_id.eq(subject_id))
+    };
+    let open = open
+        .select((impersonations::id, impersonations::jti, impersonations::expires_at))
+        .load::<(i32, String, NaiveDateTime)>(conn)?;
+
+    for (_, jti, expires_at) in &open {
+        diesel::insert_or_ignore_into(revoked_tokens::table)
+            .values((revoked_tokens::jti.eq(jti), revoked_tokens::expires_at.eq(expires_at)))
+            .execute(conn)?;
+    }
+
+    let ids: Vec<i32> = open.iter().map(|(id, _, _)| *id).collect();
+    diesel::update(impersonations::table.filter(impersonations::id.eq_any(ids)))
+        .set(impersonations::ended_at.eq(now))
+        .execute(conn)
+}
+
+#[derive(Debug, Clone)]
+pub struct TokenRepository {
+    pool: Pool<ConnectionManager<SqliteConnection>>,
+}
+
+impl TokenRepository {
+    pub fn new(pool: Pool<ConnectionManager<SqliteConnection>>) -> Self {
+        TokenRepository { pool }
+    }
+
+    pub async fn insert_refresh_token(&self, new_token: NewRefreshToken<'_>) -> Result<(), AppError> {
+        let mut conn = self.pool.get()?;
+        diesel::insert_into(refresh_tokens::table)
+            .values(&new_token)
+            .execute(&mut conn)?;
+        Ok(())
+    }
+
+    pub async fn find_refresh_token(&self, token_hash: &str) -> Result<Option<RefreshToken>, AppError> {
+        let mut conn = self.pool.get()?;
+        let token = refresh_tokens::table
+            .filter(refresh_tokens::token_hash.eq(token_hash))
+            .select(RefreshToken::as_select())
+            .first(&mut conn)
+            .optional()?;
+        Ok(token)
+    }
+
+    // ทำเครื่องหมายว่า token ถูกใช้ rotate แล้ว
+    // คืนค่า false ถ้า token ถูกใช้หรือถูกเพิกถอนไปก่อนแล้ว (เช่น มีคำขอ refresh พร้อมกันสองครั้ง)
+    pub async fn mark_refresh_token_used(&self, id: i32) -> Result<bool, AppError> {
+        let mut conn = self.pool.get()?;
+        let affected = diesel::update(
+            refresh_tokens::table
+                .filter(refresh_tokens::id.eq(id))
+                .filter(refresh_tokens::used_at.is_null())
+                .filter(refresh_tokens::revoked_at.is_null()),
+        )
+        .set(refresh_tokens::used_at.eq(Utc::now().naive_utc()))
+        .execute(&mut conn)?;
+        Ok(affected > 0)
+    }
+
+    // เพิกถอน token ทั้ง family (logout หรือเมื่อตรวจพบการใช้ refresh token ซ้ำ)
+    pub async fn revoke_family(&self, family_id: &str) -> Result<usize, AppError> {
+        let mut conn = self.pool.get()?;
+        let family_id = family_id.to_string();
+        let revoked = conn.transaction(|conn| {
+            revoke_matching(conn, || {
+                refresh_tokens::table
+                    .filter(refresh_tokens::family_id.eq(family_id.clone()))
+                    .into_boxed()
+            })
+        })?;
+        Ok(revoked)
+    }
+
+    // เพิกถอนทุก session ของผู้ใช้/ผู้ดูแลระบบ (เช่น เมื่อบัญชีถูกลบ)
+    // รวมการสวมสิทธิ์ที่ยังเปิดอยู่: ของผู้ใช้ที่ถูกสวมสิทธิ์ (role "user") หรือที่ Admin คนนี้เริ่มไว้ (role "admin")
+    pub async fn revoke_all_for_subject(&self, subject_id: i32, role: &str) -> Result<usize, AppError> {
+        let mut conn = self.pool.get()?;
+        let role = role.to_string();
+        let revoked = conn.transaction(|conn| {
+            let sessions = revoke_matching(conn, || {
+                refresh_tokens::table
+                    .filter(refresh_tokens::subject_id.eq(subject_id))
+                    .filter(refresh_tokens::role.eq(role.clone()))
+                    .into_boxed()
+            })?;
+            Ok::<_, diesel::result::Error>(sessions + end_open_impersonations(conn, subject_id, &role)?)
+        })?;
+        Ok(revoked)
+    }
+
+    pub async fn is_jti_revoked(&self, jti: &str) -> Result<bool, AppError> {
+        let mut conn = self.pool.get()?;
+        let found = revoked_tokens::table
+            .filter(revoked_tokens::jti.eq(jti))
+            .count()
+            .get_result::<i64>(&mut conn)?;
+        Ok(found > 0)
+    }
+
+    // ลบข้อมูลที่หมดอายุแล้ว (refresh token และ jti ที่เลยเวลาหมดอายุ)
+    pub async fn purge_expired(&self, now: NaiveDateTime) -> Result<(), AppError> {
+        let mut conn = self.pool.get()?;
+        diesel::delete(revoked_tokens::table.filter(revoked_tokens::expires_at.lt(now))).execute(&mut conn)?;
+        diesel::delete(refresh_tokens::table.filter(refresh_tokens::expires_at.lt(now))).execute(&mut conn)?;
+        Ok(())
+    }
+}
@@ -1,16 +1,46 @@
+use crate::domain::audit::PendingAudit;
+use crate::domain::oidc::{ExternalIdentity, NewExternalIdentity};
+use crate::domain::organization::Tenant;
+use crate::domain::pagination::ListQuery;
+use crate::domain::user::FutureBookingsPolicy;
 use crate::domain::user::LoginCredentials;
 use crate::domain::user::NewUser;
+use crate::domain::user::ProfileChangeset;
 use crate::domain::user::User;
+use crate::error::AppError;
 
-use super::schema::users;
+use super::audit_repository::AuditRepository;
+use super::booking_repository::BookingRepository;
+use super::pagination::sort_by_column;
+use super::schema::{external_identities, organizations, users};
 
-use chrono::Utc;
+use chrono::{NaiveDateTime, Utc};
 use diesel::SqliteConnection;
 use diesel::prelude::*;
 use diesel::r2d2::ConnectionManager;
 use diesel::r2d2::Pool;
+use diesel::sqlite::Sqlite;
 use bcrypt;
 
+// Query ผู้ใช้ที่ยังไม่ถูกลบ (หรือทั้งหมดเมื่อ include_deleted) พร้อมตัวกรองจาก ListQuery
+// from/to เทียบกับ created_at (วันที่สมัคร) และเฉพาะผู้ใช้ในองค์กรของผู้เรียก
+fn filtered_users(tenant: Tenant, query: &ListQuery) -> users::BoxedQuery<'_, Sqlite> {
+    let mut q = users::table.into_boxed();
+    if let Some(org) = tenant.organization_id() {
+        q = q.filter(users::organization_id.eq(org));
+    }
+    if !query.include_deleted() {
+        q = q.filter(users::deleted_at.is_null()); // กรองเฉพาะ User ที่ยังไม่ถูกลบ
+    }
+    if let Some(from) = query.from {
+        q = q.filter(users::created_at.ge(from.naive_utc()));
+    }
+    if let Some(to) = query.to {
+        q = q.filter(users::created_at.lt(to.naive_utc()));
+    }
+    q
+}
+
 #[derive(Debug, Clone)]
 pub struct UserRepository {
     pool: Pool<ConnectionManager<SqliteConnection>>,
@@ -21,128 +51,427 @@ impl UserRepository {
         UserRepository { pool }
     }
 
-    pub async fn register_user(&self, new_user_data: NewUser<'_>) -> Result<User, String> {
-        let mut conn = self
-            .pool
-            .get()
-            .map_err(|e| format!("Failed to get DB connection: {}", e))?;
+    pub async fn register_user(&self, new_user_data: NewUser<'_>, audit: PendingAudit<'_, User>) -> Result<User, AppError> {
+        let mut conn = self.pool.get()?;
 
-        // ตรวจสอบว่า username ซ้ำหรือไม่ (เป็น Good Practice)
-        let existing_user_result = users::table
-            .filter(users::username.eq(&new_user_data.username))
-            .first::<User>(&mut conn);
+        conn.immediate_transaction(|conn| {
+            // ตรวจสอบว่า username ซ้ำหรือไม่ (เป็น Good Practice)
+            let existing_user = users::table
+                .filter(users::username.eq(&new_user_data.username))
+                .first::<User>(conn)
+                .optional()?;
 
-        match existing_user_result {
-            Ok(_) => {
+            if existing_user.is_some() {
                 // ถ้า User ถูกพบ แสดงว่า username ซ้ำ
-                return Err("Username already taken".to_string());
-            }
-            Err(diesel::result::Error::NotFound) => {
-                // ถ้าไม่พบผู้ใช้ ถือว่าถูกต้อง สามารถดำเนินการต่อได้
-                // ไม่ต้องทำอะไรตรงนี้
-            }
-            Err(e) => {
-                // จัดการกับ Error อื่นๆ ที่ไม่ใช่ NotFound
-                return Err(format!("Database error during user check: {}", e));
+                return Err(AppError::Conflict("Username already taken".to_string()));
             }
-        }
-
-        diesel::insert_into(users::table)
-            .values(&new_user_data)
-            .execute(&mut conn)
-            .map_err(|e| format!("Failed to insert user into DB: {}", e))?;
 
-        let inserted_user = users::table
-            .filter(users::username.eq(&new_user_data.username))
-            .first::<User>(&mut conn)
-            .map_err(|e| format!("Failed to retrieve newly inserted user: {}", e))?;
+            diesel::insert_into(users::table)
+                .values(&new_user_data)
+                .execute(conn)?;
 
-        Ok(inserted_user)
+            let inserted_user = users::table
+                .filter(users::username.eq(&new_user_data.username))
+                .first::<User>(conn)?;
+            AuditRepository::append(conn, audit, &inserted_user)?;
 
+            Ok(inserted_user)
+        })
     }
     // ฟังก์ชันสำหรับค้นหาผู้ใช้ด้วย Username และตรวจสอบรหัสผ่าน›
     pub async fn find_user_by_username_and_verify_password(
         &self,
-        login_credentials: LoginCredentials,
-    ) -> Result<User, String> {
-        let mut conn = self
-            .pool
-            .get()
-            .map_err(|e| format!("Failed to get DB connection: {}", e))?;
+        login_credentials: &LoginCredentials,
+    ) -> Result<User, AppError> {
+        let mut conn = self.pool.get()?;
+
+        let invalid_credentials = || AppError::Unauthorized("Invalid username or password".to_string());
 
+        // ผู้ใช้ที่ถูกลบ Login ไม่ได้จนกว่า Admin จะกู้คืน
         let user = users::table
             .filter(users::username.eq(&login_credentials.username))
+            .filter(users::deleted_at.is_null())
             .first::<User>(&mut conn)
-            .map_err(|e| match e {
-                diesel::result::Error::NotFound => "Invalid username or password".to_string(),
-                _ => format!("Database error while fetching user: {}", e),
-            })?;
+            .optional()?
+            .ok_or_else(invalid_credentials)?;
+
+        // ผู้ใช้ที่สร้างจาก OIDC ไม่มีรหัสผ่านในระบบ ต้อง Login ผ่าน Identity Provider
+        let Some(password_hash) = user.password_hash.as_deref() else {
+            return Err(invalid_credentials());
+        };
 
         // ตรวจสอบรหัสผ่าน (Plain Text) กับ password_hash ที่เก็บใน DB
-        let password_matches = bcrypt::verify(&login_credentials.password, &user.password_hash)
-            .map_err(|e| format!("Password verification internal error: {}", e))?;
+        let password_matches = bcrypt::verify(&login_credentials.password, password_hash)?;
 
         if password_matches {
             Ok(user)
         } else {
-            Err("Invalid username or password".to_string())
+            Err(invalid_credentials())
         }
     }
 
     // *** ฟังก์ชันใหม่สำหรับ Admin Management (Regular Users) ***
 
-    // ฟังก์ชันดึง User ทั้งหมด (Active Users)
-    pub async fn get_all_users(&self) -> Result<Vec<User>, String> {
-        let mut conn = self
-            .pool
-            .get()
-            .map_err(|e| format!("Failed to get DB connection: {}", e))?;
+    pub const SORT_FIELDS: &'static [&'static str] = &["id", "username", "created_at", "updated_at"];
 
-        users::table
-            .filter(users::deleted_at.is_null()) // กรองเฉพาะ User ที่ยังไม่ถูกลบ
-            .order(users::created_at.desc()) // เรียงตามวันที่สร้างใหม่สุด
-            .load::<User>(&mut conn)
-            .map_err(|e| format!("Failed to retrieve all users: {}", e))
+    // ฟังก์ชันดึง User (Active Users) ตามตัวกรอง เรียงลำดับ และแบ่งหน้า
+    // คืนค่า (รายการในหน้านี้, จำนวนทั้งหมด)
+    pub async fn get_all_users(&self, tenant: Tenant, query: &ListQuery, offset: i64) -> Result<(Vec<User>, i64), AppError> {
+        let mut conn = self.pool.get()?;
+
+        let total = filtered_users(tenant, query)
+            .count()
+            .get_result::<i64>(&mut conn)?;
+
+        let q = filtered_users(tenant, query);
+        let direction = query.direction();
+        let q = match query.sort.as_deref().unwrap_or("created_at") {
+            "id" => sort_by_column!(q, users::id, direction),
+            "username" => sort_by_column!(q, users::username, direction),
+            "updated_at" => sort_by_column!(q, users::updated_at, direction),
+            _ => sort_by_column!(q, users::created_at, direction), // ค่าเริ่มต้น: ใหม่สุดก่อน
+        };
+
+        let users = q
+            .then_order_by(users::id.asc())
+            .limit(query.limit())
+            .offset(offset)
+            .load::<User>(&mut conn)?;
+        Ok((users, total))
     }
 
 
-    pub async fn get_user_by_id(&self, user_id: i32) -> Result<User, String> {
-        let mut conn = self
-            .pool
-            .get()
-            .map_err(|e| format!("Failed to get DB connection: {}", e))?;
+    pub async fn get_user_by_id(&self, user_id: i32) -> Result<User, AppError> {
+        let mut conn = self.pool.get()?;
 
         users::table
             .filter(users::id.eq(user_id))
             .filter(users::deleted_at.is_null()) // กรองเฉพาะ User ที่ยังไม่ถูกลบ
             .first::<User>(&mut conn)
-            .map_err(|e| match e {
-                diesel::result::Error::NotFound => "User not found".to_string(),
-                _ => format!("Failed to retrieve user by ID: {}", e),
-            })
+            .optional()?
+            .ok_or_else(|| AppError::NotFound("User not found".to_string()))
     }
 
 
-    // ฟังก์ชัน Soft Delete User
-    pub async fn soft_delete_user(&self, user_id: i32) -> Result<bool, String> {
-        let mut conn = self
-            .pool
-            .get()
-            .map_err(|e| format!("Failed to get DB connection: {}", e))?;
+    // ผู้ใช้ตาม ID รวมที่ถูก Soft Delete แล้ว
+    pub async fn find_user(&self, user_id: i32) -> Result<Option<User>, AppError> {
+        let mut conn = self.pool.get()?;
+        Ok(users::table.find(user_id).first::<User>(&mut conn).optional()?)
+    }
 
-        let affected_rows = diesel::update(
-            users::table
-                .filter(users::id.eq(user_id))
-                .filter(users::deleted_at.is_null()), // ต้องเป็น user ที่ยังไม่ถูกลบ
-        )
-        .set((
-            users::deleted_at.eq(Some(Utc::now().naive_utc())), // ตั้งค่า deleted_at
-            users::updated_at.eq(Utc::now().naive_utc()), // อัปเดต updated_at ด้วย
-        ))
-        .execute(&mut conn)
-        .map_err(|e| format!("Failed to soft delete user: {}", e))?;
+    // ฟังก์ชัน Soft Delete User พร้อมจัดการการจองที่ยังไม่จบตาม policy ใน Transaction เดียวกัน
+    // transfer_to: ผู้ใช้ที่รับโอนการจอง (ใช้เมื่อ policy = Transfer และต้องเป็นผู้ใช้ที่ยังไม่ถูกลบ)
+    pub async fn soft_delete_user(
+        &self,
+        user_id: i32,
+        policy: FutureBookingsPolicy,
+        transfer_to: Option<i32>,
+        audit: PendingAudit<'_, User>,
+    ) -> Result<bool, AppError> {
+        let mut conn = self.pool.get()?;
+        let now = Utc::now().naive_utc();
+
+        conn.immediate_transaction(|conn| {
+            let affected_rows = diesel::update(
+                users::table
+                    .filter(users::id.eq(user_id))
+                    .filter(users::deleted_at.is_null()), // ต้องเป็น user ที่ยังไม่ถูกลบ
+            )
+            .set((
+                users::deleted_at.eq(Some(now)), // ตั้งค่า deleted_at
+                users::updated_at.eq(now), // อัปเดต updated_at ด้วย
+            ))
+            .execute(conn)?;
+            if affected_rows == 0 {
+                return Ok(false);
+            }
+
+            match (policy, transfer_to) {
+                (FutureBookingsPolicy::Cancel, _) => {
+                    BookingRepository::cancel_unfinished_bookings(conn, user_id, now)?;
+                }
+                (FutureBookingsPolicy::Transfer, Some(target_id)) => {
+                    // ผู้รับโอนต้องอยู่ในองค์กรเดียวกับผู้ใช้ที่ถูกลบ
+                    let organization_id = users::table
+                        .find(user_id)
+                        .select(users::organization_id)
+                        .first::<i32>(conn)?;
+                    let target_active = users::table
+                        .filter(users::id.eq(target_id))
+                        .filter(users::deleted_at.is_null())
+                        .filter(users::organization_id.eq(organization_id))
+                        .count()
+                        .get_result::<i64>(conn)?
+                        > 0;
+                    if !target_active {
+                        return Err(AppError::Validation(
+                            "`transfer_to` must be an active user of the same organization, other than the one being deleted.".to_string(),
+                        ));
+                    }
+                    BookingRepository::transfer_unfinished_bookings(conn, user_id, target_id, now)?;
+                }
+                (FutureBookingsPolicy::Transfer, None) => {
+                    return Err(AppError::Validation(
+                        "`transfer_to` is required when future_bookings=transfer.".to_string(),
+                    ));
+                }
+                (FutureBookingsPolicy::Keep, _) => {}
+            }
+            let deleted = users::table.find(user_id).first::<User>(conn)?;
+            AuditRepository::append(conn, audit, &deleted)?;
+            Ok(true) // คืนค่า true ถ้ามีการลบสำเร็จ
+        })
+    }
+
+    // กู้คืนผู้ใช้ที่ถูก Soft Delete (การจองที่ถูกยกเลิกตอนลบไม่ถูกกู้คืนอัตโนมัติ)
+    // ผู้ใช้ที่ถูกลบข้อมูลส่วนตัวแล้ว (erased_at) กู้คืนไม่ได้
+    pub async fn restore_user(&self, user_id: i32, audit: PendingAudit<'_, User>) -> Result<User, AppError> {
+        let mut conn = self.pool.get()?;
+        conn.immediate_transaction(|conn| {
+            let erased = users::table
+                .find(user_id)
+                .select(users::erased_at.is_not_null())
+                .first::<bool>(conn)
+                .optional()?;
+            if erased == Some(true) {
+                return Err(AppError::Conflict("Users whose data has been erased cannot be restored.".to_string()));
+            }
+            let affected_rows = diesel::update(
+                users::table
+                    .filter(users::id.eq(user_id))
+                    .filter(users::deleted_at.is_not_null()),
+            )
+            .set((
+                users::deleted_at.eq(None::<NaiveDateTime>),
+                users::updated_at.eq(Utc::now().naive_utc()),
+            ))
+            .execute(conn)?;
+            if affected_rows == 0 {
+                return Err(AppError::NotFound("User not found or not deleted".to_string()));
+            }
+            let restored = users::table.find(user_id).first::<User>(conn)?;
+            AuditRepository::append(conn, audit, &restored)?;
+            Ok(restored)
+        })
+    }
+
+    // ค้นหาผู้ใช้ที่ยังไม่ถูกลบด้วย username (None ถ้าไม่พบ)
+    pub async fn find_active_by_username(&self, username: &str) -> Result<Option<User>, AppError> {
+        let mut conn = self.pool.get()?;
+        let user = users::table
+            .filter(users::username.eq(username))
+            .filter(users::deleted_at.is_null())
+            .first::<User>(&mut conn)
+            .optional()?;
+        Ok(user)
+    }
 
-        Ok(affected_rows > 0) // คืนค่า true ถ้ามีการลบสำเร็จ (affected_rows > 0)
+    // แก้ไขโปรไฟล์ของผู้ใช้ที่ยังไม่ถูกลบ แล้วคืนข้อมูลล่าสุด
+    pub async fn update_profile(
+        &self,
+        user_id: i32,
+        changes: ProfileChangeset,
+        audit: PendingAudit<'_, User>,
+    ) -> Result<User, AppError> {
+        let mut conn = self.pool.get()?;
+        conn.immediate_transaction(|conn| {
+            let affected_rows = diesel::update(
+                users::table
+                    .filter(users::id.eq(user_id))
+                    .filter(users::deleted_at.is_null()),
+            )
+            .set(&changes)
+            .execute(conn)?;
+            if affected_rows == 0 {
+                return Err(AppError::NotFound("User not found".to_string()));
+            }
+            let updated = users::table.find(user_id).first::<User>(conn)?;
+            AuditRepository::append(conn, audit, &updated)?;
+            Ok(updated)
+        })
     }
 
+    pub async fn update_password(
+        &self,
+        user_id: i32,
+        password_hash: &str,
+        audit: PendingAudit<'_, User>,
+    ) -> Result<bool, AppError> {
+        let mut conn = self.pool.get()?;
+        conn.immediate_transaction(|conn| {
+            let affected_rows = diesel::update(
+                users::table
+                    .filter(users::id.eq(user_id))
+                    .filter(users::deleted_at.is_null()),
+            )
+            .set((
+                users::password_hash.eq(password_hash),
+                users::updated_at.eq(Utc::now().naive_utc()),
+            ))
+            .execute(conn)?;
+            if affected_rows == 0 {
+                return Ok(false);
+            }
+            let user = users::table.find(user_id).first::<User>(conn)?;
+            AuditRepository::append(conn, audit, &user)?;
+            Ok(true)
+        })
+    }
+
+    // หาผู้ใช้ที่ผูกกับ issuer + subject ของระบบยืนยันตัวตนภายนอก (OIDC/LDAP)
+    // หรือสร้างผู้ใช้ใหม่ (ไม่มีรหัสผ่าน) ในองค์กร organization_slug ถ้ายังไม่เคย Login
+    // องค์กรที่ไม่มีอยู่จะถูกปฏิเสธ (403) ผู้ใช้เดิมอยู่องค์กรเดิม (ย้ายด้วย PUT /admin/users/:id/organization)
+    // ไม่ผูกกับบัญชีในระบบที่มี username ซ้ำโดยอัตโนมัติ (กันการยึดบัญชีผ่านระบบภายนอก)
+    // audit ถูกบันทึกเฉพาะเมื่อสร้างผู้ใช้ใหม่
+    pub async fn provision_external_user(
+        &self,
+        issuer: &str,
+        subject: &str,
+        username: &str,
+        organization_slug: &str,
+        now: NaiveDateTime,
+        audit: PendingAudit<'_, User>,
+    ) -> Result<i32, AppError> {
+        let mut conn = self.pool.get()?;
+        conn.immediate_transaction(|conn| {
+            let organization_id = organizations::table
+                .filter(organizations::slug.eq(organization_slug))
+                .select(organizations::id)
+                .first::<i32>(conn)
+                .optional()?
+                .ok_or_else(|| AppError::Forbidden(format!("Organization '{}' does not exist.", organization_slug)))?;
+
+            let identity = external_identities::table
+                .filter(external_identities::issuer.eq(issuer))
+                .filter(external_identities::subject.eq(subject))
+                .select(ExternalIdentity::as_select())
+                .first(conn)
+                .optional()?;
+
+            if let Some(identity) = identity {
+                let deleted_at = users::table
+                    .find(identity.user_id)
+                    .select(users::deleted_at)
+                    .first::<Option<NaiveDateTime>>(conn)?;
+                if deleted_at.is_some() {
+                    return Err(AppError::Forbidden("This account has been deleted.".to_string()));
+                }
+                diesel::update(external_identities::table.find(identity.id))
+                    .set(external_identities::last_login_at.eq(now))
+                    .execute(conn)?;
+                return Ok(identity.user_id);
+            }
+
+            let taken = users::table
+                .filter(users::username.eq(username))
+                .count()
+                .get_result::<i64>(conn)?;
+            if taken > 0 {
+                return Err(AppError::Conflict(format!(
+                    "Username '{}' is already used by another account.",
+                    username
+                )));
+            }
+            diesel::insert_into(users::table)
+                .values(&NewUser {
+                    username,
+                    password_hash: None,
+                    email: None,
+                    email_verification_pending: false,
+                    organization_id,
+                })
+                .execute(conn)?;
+            let user = users::table
+                .filter(users::username.eq(username))
+                .first::<User>(conn)?;
+            diesel::insert_into(external_identities::table)
+                .values(&NewExternalIdentity { user_id: user.id, issuer, subject, last_login_at: now })
+                .execute(conn)?;
+            AuditRepository::append(conn, audit, &user)?;
+            Ok(user.id)
+        })
+    }
+
+    // บันทึกชื่อที่แสดงและกลุ่มล่าสุดจากระบบภายนอก (แทนที่ค่าเดิมทั้งหมด)
+    pub async fn sync_directory_profile(
+        &self,
+        user_id: i32,
+        display_name: Option<&str>,
+        groups: &[String],
+    ) -> Result<(), AppError> {
+        let mut conn = self.pool.get()?;
+        let groups = serde_json::to_string(groups)
+            .map_err(|e| AppError::Internal(format!("Failed to encode groups: {}", e)))?;
+        diesel::update(users::table.find(user_id))
+            .set((
+                users::display_name.eq(display_name),
+                users::directory_groups.eq(groups),
+                users::updated_at.eq(Utc::now().naive_utc()),
+            ))
+            .execute(&mut conn)?;
+        Ok(())
+    }
+
+    // ยืนยันอีเมล: สำเร็จเฉพาะเมื่ออีเมลปัจจุบันยังตรงกับอีเมลใน Token (false ถ้าเปลี่ยนไปแล้ว)
+    pub async fn mark_email_verified(
+        &self,
+        user_id: i32,
+        email: &str,
+        now: NaiveDateTime,
+        audit: PendingAudit<'_, User>,
+    ) -> Result<bool, AppError> {
+        let mut conn = self.pool.get()?;
+        conn.immediate_transaction(|conn| {
+            let affected_rows = diesel::update(
+                users::table
+                    .filter(users::id.eq(user_id))
+                    .filter(users::deleted_at.is_null())
+                    .filter(users::email.eq(email)),
+            )
+            .set((
+                users::email_verified_at.eq(now),
+                users::email_verification_pending.eq(false),
+                users::updated_at.eq(now),
+            ))
+            .execute(conn)?;
+            if affected_rows == 0 {
+                return Ok(false);
+            }
+            let verified = users::table.find(user_id).first::<User>(conn)?;
+            AuditRepository::append(conn, audit, &verified)?;
+            Ok(true)
+        })
+    }
+
+    // Admin ยืนยันแทนผู้ใช้: ปลดล็อกการจอง และถือว่าอีเมลปัจจุบัน (ถ้ามี) ยืนยันแล้ว
+    pub async fn override_email_verification(
+        &self,
+        user_id: i32,
+        now: NaiveDateTime,
+        audit: PendingAudit<'_, User>,
+    ) -> Result<User, AppError> {
+        let mut conn = self.pool.get()?;
+        conn.immediate_transaction(|conn| {
+            let user = users::table
+                .filter(users::id.eq(user_id))
+                .filter(users::deleted_at.is_null())
+                .first::<User>(conn)
+                .optional()?
+                .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+            let verified_at = match (&user.email, user.email_verified_at) {
+                (Some(_), None) => Some(now),
+                (_, verified_at) => verified_at,
+            };
+            diesel::update(users::table.find(user_id))
+                .set((
+                    users::email_verified_at.eq(verified_at),
+                    users::email_verification_pending.eq(false),
+                    users::updated_at.eq(now),
+                ))
+                .execute(conn)?;
+            let verified = users::table.find(user_id).first::<User>(conn)?;
+            AuditRepository::append(conn, audit, &verified)?;
+            Ok(verified)
+        })
+    }
 }
@@ -3,4 +3,5 @@ pub mod domain;
 pub mod infrastructure;
 pub mod presentation;
 pub mod middleware;
-pub mod app_state;
\ No newline at end of file
+pub mod app_state;
+pub mod error;
\ No newline at end of file
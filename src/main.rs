@@ -1,13 +1,13 @@
-use anyhow::Result;
-use axum::{
-    Extension, Router, middleware,
-    routing::{delete, get, patch, post},
-};
+use anyhow::{Context, Result};
+use axum_extra::extract::cookie::SameSite;
+use std::io::Write;
+use std::net::SocketAddr;
 use std::sync::Arc;
 
 use room_booking_api_minimal::{
-    app_state::AppState, application::booking_service::BookingService,
-    infrastructure::jwt::JwtService, presentation::{admin_user_handler, booking_handler::delete_booking_handler},
+    app_state::AppState, application::{api_key_service::ApiKeyService, audit_service::AuditService, auth_service::AuthService, booking_service::BookingService, email_verification_service::EmailVerificationService, group_service::GroupService, impersonation_service::ImpersonationService, invitation_service::InvitationService, login_throttle_service::LoginThrottleService, mfa_service::MfaService, oidc_service::{OidcConfig, OidcService}, organization_service::OrganizationService, password_service::PasswordService, privacy_service::PrivacyService, report_service::ReportService, role_service::RoleService},
+    domain::{api_key::API_KEY_MAX_TTL_DAYS, login_throttle::LockoutPolicy, organization::ExternalOrganization, permission::GroupRoleMap},
+    infrastructure::{api_key_repository::ApiKeyRepository, audit_repository::AuditRepository, auth_provider::{AuthProvider, LdapAuthProvider, LdapConfig, PasswordAuthProvider}, group_repository::GroupRepository, impersonation_repository::ImpersonationRepository, jwt::JwtService, jwt_keys::JwtKeySet, logging::init_tracing, login_throttle_repository::LoginThrottleRepository, mfa_repository::MfaRepository, oidc_repository::OidcRepository, organization_repository::OrganizationRepository, notifier::{FileNotifier, LogNotifier, Notifier}, password_reset_repository::PasswordResetRepository, privacy_repository::PrivacyRepository, report_repository::ReportRepository, role_repository::RoleRepository, token_repository::TokenRepository},
 };
 
 use room_booking_api_minimal::{
@@ -18,49 +18,296 @@ use room_booking_api_minimal::{
         admin_repository::AdminRepository, database::establish_connection_pool,
         room_repository::RoomRepository, user_repository::UserRepository,
     },
-    middleware::auth::{admin_middleware, auth_middleware},
-    presentation::{
-        admin_handler::{login_admin_handler, register_admin_handler},
-        booking_handler::{
-            cancel_booking_handler, create_booking_handler, get_all_bookings_handler,
-            get_user_bookings_handler,
-        },
-        room_handler::{
-            add_room_handler, delete_room_handler, get_all_active_rooms_handler,
-            get_all_room_handler, get_room_by_id_handler, update_room_handler,
-        },
-        test_handler::{test_protected_admin_route, test_protected_user_route},
-        user_handler::{login_user_handler, register_user_handler},
-    },
+    middleware::session::SessionCookies,
+    presentation::router::router,
 };
 use tokio::net::TcpListener;
 
 #[tokio::main]
 async fn main() -> Result<()> {
     dotenv::dotenv().ok();
-    // ดึง Secret Key จาก Environment Variable
-    let jwt_secret = std::env::var("JWT_SECRET").expect("JWT_SECRET must be set in .env file");
+    // Log แบบมีโครงสร้าง (LOG_FORMAT=json สำหรับระบบเก็บ Log, ระดับ Log ตาม RUST_LOG)
+    init_tracing();
+    // โหลด key สำหรับเซ็น/ตรวจสอบ JWT (JWT_SECRET และ/หรือ JWT_KEYS, เลือก key ที่ใช้เซ็นด้วย JWT_ACTIVE_KID)
+    let jwt_keys = JwtKeySet::from_config(
+        std::env::var("JWT_SECRET").ok().as_deref(),
+        std::env::var("JWT_KEYS").ok().as_deref(),
+        std::env::var("JWT_ACTIVE_KID").ok().as_deref(),
+    )?;
     // สร้าง DB Connection Pool
     let db_pool = establish_connection_pool();
 
+    // สร้าง AuditService (Audit Log แบบ Hash Chain ของทุกการเปลี่ยนแปลงข้อมูล)
+    let audit_service = AuditService::new(AuditRepository::new(db_pool.clone()));
+
+    // `room-booking-api-minimal verify-audit`: ตรวจ Hash Chain ของ Audit Log แล้วจบโปรแกรม (exit code 1 เมื่อพบการแก้ไข)
+    if std::env::args().nth(1).as_deref() == Some("verify-audit") {
+        let result = audit_service.verify().await?;
+        println!("{}", serde_json::to_string_pretty(&result)?);
+        std::process::exit(if result.valid { 0 } else { 1 });
+    }
+
+    // สร้าง OrganizationService (องค์กร/Tenant ของผู้ใช้ Admin และห้อง)
+    let organization_repo = OrganizationRepository::new(db_pool.clone());
+    let organization_service = OrganizationService::new(organization_repo.clone());
+
+    // สร้าง GroupService (กลุ่มผู้ใช้ และกฎว่าห้องไหนจองได้เฉพาะกลุ่ม/ผู้ใช้ใด)
+    let group_service = GroupService::new(GroupRepository::new(db_pool.clone()));
+
     // สร้าง RoomService
     let room_repo = RoomRepository::new(db_pool.clone());
     let room_service = RoomService::new(room_repo);
 
-    // สร้าง UserService
+    let role_repo = RoleRepository::new(db_pool.clone());
+
+    // สร้าง UserService พร้อมวิธีตรวจสอบรหัสผ่านตอน Login (AUTH_PROVIDERS ลองตามลำดับ ค่าเริ่มต้น "local")
+    // local = รหัสผ่านในระบบ (bcrypt), ldap = bind กับ LDAP directory (ตั้งค่าด้วย LDAP_*)
+    // LDAP_GROUP_ROLES รูปแบบ `group=role,group=role` กำหนด Role ตามกลุ่มใน LDAP_GROUP_ATTRIBUTE
+    // ผู้ใช้ใหม่อยู่ในองค์กร LDAP_ORGANIZATION (slug) หรือองค์กรตาม attribute LDAP_ORGANIZATION_ATTRIBUTE
     let user_repo = UserRepository::new(db_pool.clone());
-    let user_service = UserService::new(user_repo);
+    let mut auth_providers: Vec<Arc<dyn AuthProvider>> = Vec::new();
+    for name in std::env::var("AUTH_PROVIDERS")
+        .unwrap_or_else(|_| "local".to_string())
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+    {
+        match name {
+            "local" => auth_providers.push(Arc::new(PasswordAuthProvider::new(user_repo.clone()))),
+            "ldap" => {
+                let config = LdapConfig {
+                    url: std::env::var("LDAP_URL").context("LDAP_URL must be set when AUTH_PROVIDERS includes ldap")?,
+                    starttls: std::env::var("LDAP_STARTTLS").map(|v| v == "true").unwrap_or(false),
+                    bind_dn: std::env::var("LDAP_BIND_DN").ok().filter(|v| !v.is_empty()),
+                    bind_password: std::env::var("LDAP_BIND_PASSWORD").ok(),
+                    base_dn: std::env::var("LDAP_BASE_DN").context("LDAP_BASE_DN must be set when AUTH_PROVIDERS includes ldap")?,
+                    user_filter: std::env::var("LDAP_USER_FILTER").unwrap_or_else(|_| "(uid={username})".to_string()),
+                    username_attribute: std::env::var("LDAP_USERNAME_ATTRIBUTE").unwrap_or_else(|_| "uid".to_string()),
+                    display_name_attribute: std::env::var("LDAP_DISPLAY_NAME_ATTRIBUTE")
+                        .unwrap_or_else(|_| "displayName".to_string()),
+                    group_attribute: std::env::var("LDAP_GROUP_ATTRIBUTE").unwrap_or_else(|_| "memberOf".to_string()),
+                    group_roles: GroupRoleMap::parse(&std::env::var("LDAP_GROUP_ROLES").unwrap_or_default())
+                        .map_err(anyhow::Error::msg)
+                        .context("LDAP_GROUP_ROLES")?,
+                    organization: ExternalOrganization::from_settings(
+                        std::env::var("LDAP_ORGANIZATION").ok(),
+                        std::env::var("LDAP_ORGANIZATION_ATTRIBUTE").ok(),
+                    ),
+                    timeout_seconds: std::env::var("LDAP_TIMEOUT_SECONDS")
+                        .ok()
+                        .and_then(|v| v.parse::<u64>().ok())
+                        .unwrap_or(5),
+                };
+                auth_providers.push(Arc::new(LdapAuthProvider::new(config, user_repo.clone(), role_repo.clone())));
+            }
+            other => anyhow::bail!("Unknown auth provider '{}' in AUTH_PROVIDERS (expected local or ldap)", other),
+        }
+    }
+    if auth_providers.is_empty() {
+        anyhow::bail!("AUTH_PROVIDERS must list at least one provider");
+    }
+    let user_service = UserService::new(user_repo.clone(), auth_providers);
 
     // สร้าง AdminService
     let admin_repo = AdminRepository::new(db_pool.clone());
-    let admin_service = AdminService::new(admin_repo);
+    let admin_service = AdminService::new(admin_repo.clone());
+
+    // ยังไม่มี Admin: เปิดให้สร้าง Admin คนแรกผ่าน POST /admin/setup ด้วย Setup Token แบบใช้ครั้งเดียว
+    // กำหนด Token เองได้ด้วย ADMIN_SETUP_TOKEN ไม่เช่นนั้นจะสุ่มและเขียนลงไฟล์ ADMIN_SETUP_TOKEN_FILE (Log มีแค่ที่อยู่ไฟล์)
+    if let Some(setup_token) = admin_service
+        .start_bootstrap(std::env::var("ADMIN_SETUP_TOKEN").ok())
+        .await?
+    {
+        let path = std::env::var("ADMIN_SETUP_TOKEN_FILE").unwrap_or_else(|_| "admin-setup-token".to_string());
+        write_setup_token(&path, &setup_token)?;
+        tracing::warn!(%path, "no admin account exists; create the first admin with POST /admin/setup using the setup token in this file");
+    }
 
     // *** สร้าง BookingService โดยส่ง db_pool เข้าไปโดยตรง ***
     // (BookingRepository ไม่จำเป็นต้องสร้างตรงนี้แล้ว)
     let booking_service = BookingService::new(db_pool.clone());
 
+    // สร้าง ReportService (ชั่วโมงเปิดให้บริการต่อวันใช้คำนวณอัตราการใช้ห้อง)
+    let open_hours_per_day = std::env::var("ROOM_OPEN_HOURS_PER_DAY")
+        .ok()
+        .and_then(|v| v.parse::<f64>().ok())
+        .unwrap_or(10.0);
+    let report_repo = ReportRepository::new(db_pool.clone());
+    let report_service = ReportService::new(report_repo, open_hours_per_day);
+
     // *** สร้าง JwtService instance ***
-    let jwt_service = JwtService::new(&jwt_secret);
+    // Access Token อายุสั้น (ค่าเริ่มต้น 15 นาที) ต่ออายุด้วย Refresh Token ผ่าน /auth/refresh
+    let access_ttl_seconds = std::env::var("ACCESS_TOKEN_TTL_SECONDS")
+        .ok()
+        .and_then(|v| v.parse::<i64>().ok())
+        .unwrap_or(15 * 60);
+    let jwt_service = JwtService::new(jwt_keys, access_ttl_seconds);
+
+    // สร้าง AuthService (Refresh Token อายุค่าเริ่มต้น 30 วัน)
+    let refresh_ttl_days = std::env::var("REFRESH_TOKEN_TTL_DAYS")
+        .ok()
+        .and_then(|v| v.parse::<i64>().ok())
+        .unwrap_or(30);
+    let token_repo = TokenRepository::new(db_pool.clone());
+    let auth_service = AuthService::new(token_repo, organization_repo, jwt_service.clone(), refresh_ttl_days);
+
+    // สร้าง ImpersonationService (Admin สวมสิทธิ์ผู้ใช้) Token อายุค่าเริ่มต้น 15 นาที ต่ออายุไม่ได้
+    let impersonation_ttl_seconds = std::env::var("IMPERSONATION_TTL_SECONDS")
+        .ok()
+        .and_then(|v| v.parse::<i64>().ok())
+        .unwrap_or(15 * 60);
+    let impersonation_service = ImpersonationService::new(
+        ImpersonationRepository::new(db_pool.clone()),
+        jwt_service.clone(),
+        impersonation_ttl_seconds,
+    );
+
+    // สร้าง InvitationService (คำเชิญ Admin อายุค่าเริ่มต้น 72 ชั่วโมง)
+    // ADMIN_INVITE_URL: หน้าที่ผู้ถูกเชิญเปิดเพื่อส่ง token ไปที่ POST /admin/invitations/accept
+    let invite_ttl_hours = std::env::var("ADMIN_INVITE_TTL_HOURS")
+        .ok()
+        .and_then(|v| v.parse::<i64>().ok())
+        .unwrap_or(72);
+    let invite_url = std::env::var("ADMIN_INVITE_URL")
+        .unwrap_or_else(|_| "http://localhost:3000/admin/invitations/accept".to_string());
+    let invitation_service = InvitationService::new(admin_repo, jwt_service.clone(), invite_ttl_hours, invite_url);
+
+    // Notifier สำหรับส่งข้อความถึงผู้ใช้ (NOTIFIER=log เขียนลง Log, NOTIFIER=file เขียนลง NOTIFIER_FILE)
+    let notifier: Arc<dyn Notifier> = match std::env::var("NOTIFIER").as_deref() {
+        Ok("file") => Arc::new(FileNotifier::new(
+            std::env::var("NOTIFIER_FILE").unwrap_or_else(|_| "notifications.log".to_string()),
+        )),
+        _ => Arc::new(LogNotifier),
+    };
+
+    // สร้าง PasswordService (Token รีเซ็ตรหัสผ่านอายุค่าเริ่มต้น 30 นาที)
+    // PASSWORD_RESET_URL: หน้าที่ผู้ใช้เปิดจากลิงก์เพื่อส่ง token ไปที่ POST /auth/password-reset/confirm
+    let reset_ttl_minutes = std::env::var("PASSWORD_RESET_TTL_MINUTES")
+        .ok()
+        .and_then(|v| v.parse::<i64>().ok())
+        .unwrap_or(30);
+    let reset_url = std::env::var("PASSWORD_RESET_URL")
+        .unwrap_or_else(|_| "http://localhost:3000/auth/password-reset/confirm".to_string());
+    let password_service = PasswordService::new(
+        user_repo.clone(),
+        PasswordResetRepository::new(db_pool.clone()),
+        auth_service.clone(),
+        notifier.clone(),
+        reset_ttl_minutes,
+        reset_url,
+    );
+
+    // สร้าง EmailVerificationService (ลิงก์ยืนยันอีเมลอายุค่าเริ่มต้น 24 ชั่วโมง)
+    // EMAIL_VERIFICATION_REQUIRED=true: สมัครต้องมีอีเมล และจองห้องไม่ได้จนกว่าจะยืนยัน (หรือ Admin ยืนยันแทน)
+    // EMAIL_VERIFICATION_URL: หน้าที่ผู้ใช้เปิดจากลิงก์เพื่อส่ง token ไปที่ POST /auth/email-verification/confirm
+    let email_verification_required = std::env::var("EMAIL_VERIFICATION_REQUIRED").map(|v| v == "true").unwrap_or(false);
+    let email_verification_ttl_hours = std::env::var("EMAIL_VERIFICATION_TTL_HOURS")
+        .ok()
+        .and_then(|v| v.parse::<i64>().ok())
+        .unwrap_or(24);
+    let email_verification_url = std::env::var("EMAIL_VERIFICATION_URL")
+        .unwrap_or_else(|_| "http://localhost:3000/auth/email-verification/confirm".to_string());
+    let email_verification_service = EmailVerificationService::new(
+        user_repo.clone(),
+        jwt_service.clone(),
+        notifier.clone(),
+        email_verification_ttl_hours,
+        email_verification_url,
+        email_verification_required,
+    );
+
+    // สร้าง RoleService (Role -> Permission และ Role ที่ Admin กำหนดให้ผู้ใช้)
+    let role_service = RoleService::new(role_repo.clone());
+
+    // สร้าง MfaService (2FA แบบ TOTP) MFA_ISSUER คือชื่อที่แสดงใน Authenticator App
+    // Challenge Token ของ Login ขั้นที่สองอายุค่าเริ่มต้น 5 นาที
+    let mfa_issuer = std::env::var("MFA_ISSUER").unwrap_or_else(|_| "Room Booking API".to_string());
+    let mfa_challenge_ttl_seconds = std::env::var("MFA_CHALLENGE_TTL_SECONDS")
+        .ok()
+        .and_then(|v| v.parse::<i64>().ok())
+        .unwrap_or(5 * 60);
+    let mfa_service = MfaService::new(
+        MfaRepository::new(db_pool.clone()),
+        role_repo.clone(),
+        mfa_issuer,
+        mfa_challenge_ttl_seconds,
+    );
+
+    // สร้าง ApiKeyService (API Key ส่วนตัวสำหรับสคริปต์/อุปกรณ์)
+    // Key ที่ไม่ระบุอายุจะหมดอายุใน API_KEY_DEFAULT_TTL_DAYS วัน (ค่าเริ่มต้น 90 สูงสุด 365)
+    let api_key_default_ttl_days = std::env::var("API_KEY_DEFAULT_TTL_DAYS")
+        .ok()
+        .and_then(|v| v.parse::<i64>().ok())
+        .unwrap_or(90)
+        .clamp(1, API_KEY_MAX_TTL_DAYS);
+    let api_key_service = ApiKeyService::new(ApiKeyRepository::new(db_pool.clone()), api_key_default_ttl_days);
+
+    // สร้าง PrivacyService (ขอสำเนาข้อมูลส่วนตัว และลบข้อมูลแบบไม่ระบุตัวตน)
+    let privacy_service = PrivacyService::new(PrivacyRepository::new(db_pool.clone()));
+
+    // สร้าง OidcService (Login ผ่าน Identity Provider ขององค์กร) เปิดใช้เมื่อกำหนด OIDC_ISSUER_URL
+    // OIDC_REDIRECT_URL ต้องตรงกับ Redirect URI ที่ลงทะเบียนไว้ (ชี้มาที่ /auth/oidc/callback)
+    // OIDC_GROUP_ROLES รูปแบบ `group=role,group=role` กำหนด Role ตามกลุ่มใน claim OIDC_GROUPS_CLAIM
+    // ผู้ใช้ใหม่อยู่ในองค์กร OIDC_ORGANIZATION (slug) หรือองค์กรตาม claim OIDC_ORGANIZATION_CLAIM
+    let oidc_service = match std::env::var("OIDC_ISSUER_URL").ok().filter(|v| !v.is_empty()) {
+        Some(issuer_url) => {
+            let config = OidcConfig {
+                issuer_url,
+                client_id: std::env::var("OIDC_CLIENT_ID").context("OIDC_CLIENT_ID must be set when OIDC_ISSUER_URL is set")?,
+                client_secret: std::env::var("OIDC_CLIENT_SECRET").ok().filter(|v| !v.is_empty()),
+                redirect_url: std::env::var("OIDC_REDIRECT_URL")
+                    .unwrap_or_else(|_| "http://localhost:3000/auth/oidc/callback".to_string()),
+                scopes: std::env::var("OIDC_SCOPES").unwrap_or_else(|_| "openid profile email groups".to_string()),
+                username_claim: std::env::var("OIDC_USERNAME_CLAIM").unwrap_or_else(|_| "preferred_username".to_string()),
+                groups_claim: std::env::var("OIDC_GROUPS_CLAIM").unwrap_or_else(|_| "groups".to_string()),
+                group_roles: GroupRoleMap::parse(&std::env::var("OIDC_GROUP_ROLES").unwrap_or_default())
+                    .map_err(anyhow::Error::msg)
+                    .context("OIDC_GROUP_ROLES")?,
+                organization: ExternalOrganization::from_settings(
+                    std::env::var("OIDC_ORGANIZATION").ok(),
+                    std::env::var("OIDC_ORGANIZATION_CLAIM").ok(),
+                ),
+                post_login_url: std::env::var("OIDC_POST_LOGIN_URL").ok().filter(|v| !v.is_empty()),
+                state_ttl_seconds: 10 * 60,
+            };
+            Some(OidcService::new(config, OidcRepository::new(db_pool.clone()), user_repo.clone(), role_repo)?)
+        }
+        None => None,
+    };
+
+    // สร้าง LoginThrottleService (ล็อก username/IP ชั่วคราวเมื่อ Login ผิดติดกัน)
+    // ผิดครบ LOGIN_MAX_FAILURES (ต่อ username) หรือ LOGIN_IP_MAX_FAILURES (ต่อ IP) จะถูกล็อก
+    // LOGIN_LOCKOUT_BASE_SECONDS และเพิ่มเป็นสองเท่าทุกครั้งที่ผิดต่อ สูงสุด LOGIN_LOCKOUT_MAX_SECONDS
+    // ตัวนับเริ่มใหม่เมื่อไม่มีการ Login ผิดภายใน LOGIN_FAILURE_WINDOW_MINUTES
+    let env_i64 = |name: &str, default: i64| {
+        std::env::var(name)
+            .ok()
+            .and_then(|v| v.parse::<i64>().ok())
+            .unwrap_or(default)
+    };
+    let lockout_policy = |threshold: i64| LockoutPolicy {
+        threshold: threshold.clamp(1, i32::MAX as i64) as i32,
+        base_delay: chrono::Duration::seconds(env_i64("LOGIN_LOCKOUT_BASE_SECONDS", 30)),
+        max_delay: chrono::Duration::seconds(env_i64("LOGIN_LOCKOUT_MAX_SECONDS", 60 * 60)),
+        window: chrono::Duration::minutes(env_i64("LOGIN_FAILURE_WINDOW_MINUTES", 15)),
+    };
+    let login_throttle_service = LoginThrottleService::new(
+        LoginThrottleRepository::new(db_pool.clone()),
+        lockout_policy(env_i64("LOGIN_MAX_FAILURES", 5)),
+        lockout_policy(env_i64("LOGIN_IP_MAX_FAILURES", 20)),
+    );
+    // TRUST_PROXY_HEADERS=true เมื่ออยู่หลัง Reverse Proxy (ใช้ IP จาก X-Forwarded-For)
+    let trust_proxy_headers = std::env::var("TRUST_PROXY_HEADERS").map(|v| v == "true").unwrap_or(false);
+
+    // ตั้งค่า Cookie Session (login แบบ ?mode=cookie)
+    // COOKIE_SECURE=false ใช้เฉพาะตอนทดสอบผ่าน http://localhost
+    let cookie_secure = std::env::var("COOKIE_SECURE").map(|v| v != "false").unwrap_or(true);
+    let cookie_same_site = match std::env::var("COOKIE_SAME_SITE").as_deref() {
+        Ok("lax") => SameSite::Lax,
+        Ok("none") => SameSite::None,
+        _ => SameSite::Strict,
+    };
+    let session_cookies = SessionCookies::new(cookie_secure, cookie_same_site);
 
     let app_state = Arc::new(AppState {
         db_pool: db_pool.clone(),
@@ -68,67 +315,55 @@ async fn main() -> Result<()> {
         user_service: user_service.clone(),
         admin_service: admin_service.clone(),
         booking_service: booking_service.clone(),
+        report_service: report_service.clone(),
         jwt_service: jwt_service.clone(),
+        auth_service: auth_service.clone(),
+        role_service: role_service.clone(),
+        invitation_service: invitation_service.clone(),
+        password_service: password_service.clone(),
+        email_verification_service,
+        login_throttle_service,
+        mfa_service,
+        api_key_service,
+        privacy_service,
+        organization_service,
+        group_service,
+        impersonation_service,
+        audit_service,
+        oidc_service,
+        session_cookies,
+        trust_proxy_headers,
     });
 
-    let app = Router::new()
-        // User Login/Register (ไม่ต้องมี Middleware)
-        // User Login/Register routes
-        .route("/register", post(register_user_handler))
-        .route("/login/user", post(login_user_handler))
-        // Admin Login/Register routes
-        .route("/admin", post(register_admin_handler))
-        .route("/login/admin", post(login_admin_handler))
-        // *** Router สำหรับเส้นทางที่ Admin เท่านั้นที่เข้าถึงได้ ***
-        .nest(
-            "/admin",
-            Router::new() // <--- ไม่ต้องระบุ Router<Arc<AppState>> แล้ว
-                .route("/rooms", post(add_room_handler))
-                .route("/rooms/:room_id", patch(update_room_handler))
-                .route("/rooms/:room_id", delete(delete_room_handler))
-                .route("/bookings", get(get_all_bookings_handler))
-                .route("/bookings/:booking_id", delete(delete_booking_handler)) // Admin can cancel bookings.
-                .route("/users", get(admin_user_handler::get_all_users_handler))
-                .route(
-                    "/users/:user_id",
-                    get(admin_user_handler::get_user_by_id_handler),
-                )
-                .route(
-                    "/users/:user_id",
-                    delete(admin_user_handler::delete_user_by_admin_handler),
-                )
-                .route("/test-admin", get(test_protected_admin_route))
-                // Middleware ใช้ from_fn_with_state แต่ handler ของ middleware ต้องรับ Extension
-                .layer(middleware::from_fn_with_state(
-                    app_state.clone(), // ส่ง Arc<AppState> เหมือนเดิม
-                    admin_middleware,  // admin_middleware ต้องรับ Extension<Arc<AppState>>
-                )), // .with_state(app_state.clone()), // <--- ลบ .with_state() ออก
-        )
-        // *** Router สำหรับเส้นทางที่ User ทั่วไป (ต้อง Login) เข้าถึงได้ ***
-        .nest(
-            "/bookings",
-            Router::new() // <--- ไม่ต้องระบุ Router<Arc<AppState>> แล้ว
-                .route("/", post(create_booking_handler))
-                .route("/:id", delete(cancel_booking_handler))
-                .route("/user", get(get_user_bookings_handler))
-                .route("/test-user", get(test_protected_user_route))
-                .layer(middleware::from_fn_with_state(
-                    app_state.clone(), // ส่ง Arc<AppState> เหมือนเดิม
-                    auth_middleware,   // auth_middleware ต้องรับ Extension<Arc<AppState>>
-                )), // .with_state(app_state.clone()), // <--- ลบ .with_state() ออก
-        )
-        // *** Router สำหรับเส้นทาง Public หรือที่ User ทั่วไปเข้าถึงได้โดยไม่ต้อง Login/Admin ***
-        .route("/rooms/active", get(get_all_active_rooms_handler))
-        .route("/rooms", get(get_all_room_handler))
-        .route("/rooms/:room_id", get(get_room_by_id_handler))
-        // *** ใช้ตัวแปร app_state (ตัวเล็ก) ที่ Router หลักด้วย ***
-        .layer(Extension(app_state.clone()));
+    let app = router(app_state);
 
     let listener = TcpListener::bind("0.0.0.0:3000").await?;
-    println!("listening on {}", listener.local_addr()?);
+    tracing::info!(address = %listener.local_addr()?, "listening");
 
     // *** เรียกใช้ AxumServer แทน Server ***
-    axum::serve(listener, app).await?;
+    // ส่ง address ของ connection ให้ Handler (ClientIp ใช้นับการ Login ผิดต่อ IP)
+    axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>()).await?;
+
+    Ok(())
+}
 
+// เขียน Setup Token ที่สุ่มขึ้นลงไฟล์ที่เจ้าของไฟล์อ่านได้คนเดียว (0600) แทนการเขียนลง Log
+fn write_setup_token(path: &str, token: &str) -> Result<()> {
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        options.mode(0o600);
+        // ไฟล์เดิมที่เปิดกว้างกว่านี้: mode มีผลเฉพาะตอนสร้างไฟล์
+        if std::path::Path::new(path).exists() {
+            std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))
+                .with_context(|| format!("Failed to restrict permissions of {path}"))?;
+        }
+    }
+    let mut file = options
+        .open(path)
+        .with_context(|| format!("Failed to write the admin setup token to {path}"))?;
+    writeln!(file, "{token}")?;
     Ok(())
 }
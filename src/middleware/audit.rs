@@ -0,0 +1,48 @@
+// src/middleware/audit.rs
+
+use axum::{async_trait, extract::FromRequestParts, http::request::Parts};
+
+use crate::domain::audit::{AuditActor, AuditContext};
+use crate::error::AppError;
+use crate::middleware::auth::Principal;
+use crate::middleware::client_ip::ClientIp;
+use crate::middleware::request_id::RequestId;
+
+impl From<&Principal> for AuditActor {
+    fn from(principal: &Principal) -> Self {
+        AuditActor {
+            kind: principal.kind.as_str(),
+            id: Some(principal.id),
+            impersonator_id: principal.impersonator(),
+            organization_id: principal.tenant.organization_id(),
+        }
+    }
+}
+
+// Audit: Extractor ที่รวบรวมผู้กระทำ, Request ID และ IP สำหรับบันทึก Audit Log
+// ต้องวางหลัง Extractor ที่ยืนยันตัวตน (Authenticated/RequirePermission) เพื่อให้ได้ Principal
+// Handler ที่ไม่ต้อง Login จะได้ผู้กระทำเป็น anonymous (เปลี่ยนได้ด้วย AuditEvent::by)
+#[derive(Debug, Clone)]
+pub struct Audit(pub AuditContext);
+
+#[async_trait]
+impl<S> FromRequestParts<S> for Audit
+where
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let ClientIp(ip) = ClientIp::from_request_parts(parts, state).await?;
+        let actor = parts
+            .extensions
+            .get::<Principal>()
+            .map(AuditActor::from)
+            .unwrap_or_else(AuditActor::anonymous);
+        Ok(Audit(AuditContext {
+            actor,
+            request_id: parts.extensions.get::<RequestId>().map(|RequestId(id)| id.clone()),
+            ip: Some(ip),
+        }))
+    }
+}
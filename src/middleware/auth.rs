@@ -1,144 +1,205 @@
 // src/middleware/auth.rs
 
+use std::collections::BTreeSet;
+use std::marker::PhantomData;
+use std::ops::Deref;
 use std::sync::Arc;
 
-use axum::{
-    body::Body, extract::{Request}, http::{header,StatusCode}, middleware::Next, response::{IntoResponse, Response}, Extension, Json
-};
-
-use serde_json::json;
-use crate::app_state::AppState; // เพื่อเข้าถึง Secret หรืออื่นๆ ถ้าจำเป็น
-
-// Middleware สำหรับตรวจสอบ Token จาก Cookie (สำหรับผู้ใช้ทั่วไป)
-pub async fn auth_middleware(
-    // jar: CookieJar, // <--- NOTE: หากไม่ใช้ Cookie แล้ว สามารถลบ CookieJar ออกไปได้
-    // เราจะดึง Token จาก Authorization header แทน
-    Extension(state): Extension<Arc<AppState>>,
-    mut request: Request<Body>, // รับ Request<Body>
-    next: Next,
-) -> Result<Response<Body>, Response<Body>> { // คืนค่า Response<Body>
-    eprintln!("\n--- DEBUG: auth_middleware entered ---");
-    // 1. ดึง Token จาก Authorization Header (Bearer Token)
-    let auth_header = request.headers().get(header::AUTHORIZATION)
-        .and_then(|header| header.to_str().ok());
-
-     let token = if let Some(header_value) = auth_header {
-        eprintln!("DEBUG: Authorization header found: '{}'", header_value); // <--- Debug Point 2
-        if header_value.starts_with("Bearer ") {
-            let extracted_token = header_value[7..].to_owned();
-            eprintln!("DEBUG: Extracted token: '{}'", extracted_token); // <--- Debug Point 3
-            Some(extracted_token)
-        } else {
-            eprintln!("DEBUG: Authorization header found, but missing 'Bearer ' prefix."); // <--- Debug Point 4
-            None
+use axum::{async_trait, extract::FromRequestParts, http::request::Parts};
+
+use crate::app_state::AppState;
+use crate::application::api_key_service::is_api_key;
+use crate::domain::organization::Tenant;
+use crate::domain::permission::{Permission, PrincipalKind, RequiredPermission};
+use crate::error::AppError;
+use crate::infrastructure::jwt::Claims;
+use crate::middleware::session::{request_token, TokenSource};
+
+// Header สำหรับส่ง API Key (หรือส่งเป็น `Authorization: Bearer rbk_...` ก็ได้)
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+// Credential: สิ่งที่ใช้ยืนยันตัวตนใน Request นี้
+#[derive(Debug, Clone)]
+pub enum Credential {
+    Token(Claims),
+    ApiKey { id: i32 },
+}
+
+// Principal: บัญชีที่ login อยู่ พร้อม Permission ที่มีผล (จาก Role พื้นฐานและ Role ที่ถูกกำหนดเพิ่ม)
+#[derive(Debug, Clone)]
+pub struct Principal {
+    pub kind: PrincipalKind,
+    pub id: i32,
+    pub permissions: BTreeSet<Permission>,
+    pub credential: Credential,
+    pub tenant: Tenant, // ขอบเขตข้อมูล (องค์กรจาก claim `org` หรือ All สำหรับ Platform Admin)
+}
+
+impl Principal {
+    pub fn has(&self, permission: Permission) -> bool {
+        self.permissions.contains(&permission)
+    }
+
+    pub fn is_api_key(&self) -> bool {
+        matches!(self.credential, Credential::ApiKey { .. })
+    }
+
+    // id ของ Admin ที่สวมสิทธิ์ผู้ใช้ (claim `act` ของ Token) None = เจ้าของบัญชีใช้งานเอง
+    pub fn impersonator(&self) -> Option<i32> {
+        match &self.credential {
+            Credential::Token(claims) => claims.act.as_ref().and_then(|actor| actor.sub.parse().ok()),
+            Credential::ApiKey { .. } => None,
         }
-    } else {
-        eprintln!("DEBUG: Authorization header NOT found."); // <--- Debug Point 5
-        None
-    };
-
-    if let Some(token) = token {
-        // 2. Decode และ Validate Token ด้วย JwtService
-        // *** แก้ไข: ใช้ app_state.jwt_service.decode_token() ***
-        match state.jwt_service.decode_token(&token) {
-            Ok(claims) => {
-                // 3. ตรวจสอบ Role (ถ้าจำเป็นสำหรับ Middleware นี้)
-                // Middleware นี้จะใช้สำหรับ User ทั่วไปเข้าถึง resource
-                 eprintln!("DEBUG: Token decoded successfully. Claims: {:?}", claims);
-                if claims.role != "user" {
-                    eprintln!("DEBUG: Role mismatch. Expected 'user', got '{}'.", claims.role);
-                    let forbidden_response = (
-                        StatusCode::FORBIDDEN,
-                        Json(json!({"error": "Forbidden: Insufficient permissions."})),
-                    ).into_response(); // into_response() ก็พอ
-                    return Err(forbidden_response.into_response()); // map_into_response เพื่อแปลง Body Type
-                }
-                eprintln!("DEBUG: Role 'user' confirmed. Proceeding to next handler.");
-                // 4. เก็บ Claims ลงใน Request Extensions
-                request.extensions_mut().insert(claims);
-                // 5. ส่ง Request ต่อไปยัง Handler ถัดไป
-                Ok(next.run(request).await)
-            },
-            Err(e) => {
-                eprintln!("ERROR: Token decode failed: {:?}", e);
-                // Token ไม่ถูกต้อง/หมดอายุ
-                
-                let unauthorized_response = (
-                    StatusCode::UNAUTHORIZED,
-                    Json(json!({"error": format!("Unauthorized: Invalid token. {}", e)})),
-                ).into_response();
-                Err(unauthorized_response.into_response()) // map_into_response เพื่อแปลง Body Type
-            }
+    }
+
+    // id ของผู้ใช้ทั่วไป (การจองและโปรไฟล์ผูกกับตาราง users จึงใช้ได้เฉพาะบัญชีผู้ใช้)
+    pub fn user_id(&self) -> Result<i32, AppError> {
+        match self.kind {
+            PrincipalKind::User => Ok(self.id),
+            PrincipalKind::Admin => Err(AppError::Forbidden(
+                "This operation is only available to user accounts.".to_string(),
+            )),
+        }
+    }
+
+    // ผู้ใช้ทั่วไป (ไม่มี rooms:write) เห็นเฉพาะห้องที่ตัวเองจองได้ตามกฎการเข้าถึงของห้อง
+    // None = เห็นทุกห้องที่องค์กรเห็น (Admin และผู้ดูแลห้อง)
+    pub fn rooms_restricted_to(&self) -> Option<i32> {
+        match self.kind {
+            PrincipalKind::User if !self.has(Permission::RoomsWrite) => Some(self.id),
+            _ => None,
+        }
+    }
+
+    // งานระดับ Platform (จัดการองค์กร, ย้ายผู้ใช้ข้ามองค์กร, ข้อมูล Login ของทั้งระบบ)
+    pub fn require_platform(&self) -> Result<(), AppError> {
+        match self.tenant {
+            Tenant::All => Ok(()),
+            Tenant::Organization(_) => Err(AppError::Forbidden(
+                "This operation is only available to platform admins.".to_string(),
+            )),
         }
-    } else {
-        eprintln!("ERROR: No token found in Authorization header. Returning 401."); 
-        // ไม่มี Token ใน Header
-        let unauthorized_response = (
-            StatusCode::UNAUTHORIZED,
-            Json(json!({"error": "Unauthorized: Authorization token missing or malformed."})),
-        ).into_response();
-        Err(unauthorized_response.into_response())
     }
 }
 
-// Middleware สำหรับ Admin (คล้ายกัน แต่ตรวจสอบ role = "admin")
-pub async fn admin_middleware(
-    Extension(state): Extension<Arc<AppState>>,
-    mut request: Request<Body>,
-    next: Next,
-) -> Result<Response<Body>, Response<Body>> {
-    eprintln!("\n--- DEBUG: auth_middleware entered ---"); 
-    let auth_header = request.headers().get(header::AUTHORIZATION)
-        .and_then(|header| header.to_str().ok());
-
-    let token = if let Some(header_value) = auth_header {
-        eprintln!("DEBUG: Authorization header found: '{}'", header_value); // <--- Debug Point 2
-        if header_value.starts_with("Bearer ") {
-            let extracted_token = header_value[7..].to_owned();
-            eprintln!("DEBUG: Extracted token: '{}'", extracted_token); // <--- Debug Point 3
-            Some(extracted_token)
-        } else {
-            eprintln!("DEBUG: Authorization header found, but missing 'Bearer ' prefix."); // <--- Debug Point 4
-            None
+// Authenticated: Extractor สำหรับ Handler ที่ต้อง login (ไม่ตรวจ Permission)
+// 1. ดึง Token จาก Authorization Header (Bearer Token) หรือ Cookie `session`
+//    (Cookie + Method ที่เปลี่ยนแปลงข้อมูล ต้องมี X-CSRF-Token ที่ตรงกับ Cookie `csrf_token`)
+//    หรือ API Key จาก Header `X-API-Key` / Bearer ที่ขึ้นต้นด้วย `rbk_`
+// 2. Decode/Validate Token และตรวจสอบว่า jti ยังไม่ถูกเพิกถอน (logout/reuse)
+// 3. โหลด Permission ของบัญชีจาก DB และเก็บ Principal ไว้ใน Request Extensions
+//    (Tenant มาจาก claim `org` ของ Token ส่วน API Key ใช้องค์กรปัจจุบันของเจ้าของ Key)
+//    (API Key ได้เฉพาะ Permission ที่อยู่ทั้งใน scopes ของ Key และใน Role ของบัญชี)
+#[derive(Debug, Clone)]
+pub struct Authenticated(pub Principal);
+
+#[async_trait]
+impl<S> FromRequestParts<S> for Authenticated
+where
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        // Handler ที่มี Extractor หลายตัวจะยืนยันตัวตนเพียงครั้งเดียว
+        if let Some(principal) = parts.extensions.get::<Principal>() {
+            return Ok(Authenticated(principal.clone()));
         }
-    } else {
-        eprintln!("DEBUG: Authorization header NOT found."); // <--- Debug Point 5
-        None
-    };
-
-
-    if let Some(token) = token {
-        match state.jwt_service.decode_token(&token) { // *** ใช้ decode_token ***
-            Ok(claims) => {
-                eprintln!("DEBUG: Token decoded successfully. Claims: {:?}", claims);
-                if claims.role != "admin" { // ตรวจสอบ role เป็น "admin"
-                eprintln!("DEBUG: Role mismatch. Expected 'user', got '{}'.", claims.role);
-                    let forbidden_response = (
-                        StatusCode::FORBIDDEN,
-                        Json(json!({"error": "Forbidden: Insufficient permissions."})),
-                    ).into_response();
-                    return Err(forbidden_response.into_response());
-                }
-                eprintln!("DEBUG: Role 'user' confirmed. Proceeding to next handler.");
-                request.extensions_mut().insert(claims);
-                Ok(next.run(request).await)
-            },
-            Err(e) => {
-                eprintln!("ERROR: Token decode failed: {:?}", e);
-                let unauthorized_response = (
-                    StatusCode::UNAUTHORIZED,
-                    Json(json!({"error": format!("Unauthorized: Invalid token. {}", e)})),
-                ).into_response();
-                Err(unauthorized_response.into_response())
+
+        let state = parts
+            .extensions
+            .get::<Arc<AppState>>()
+            .cloned()
+            .ok_or_else(|| AppError::Internal("Application state is not configured".to_string()))?;
+
+        let api_key = parts.headers.get(API_KEY_HEADER).and_then(|v| v.to_str().ok()).map(str::to_owned);
+        let (token, source) = match api_key {
+            Some(key) => (key, TokenSource::Bearer),
+            None => request_token(&parts.method, &parts.headers)?
+                .ok_or_else(|| AppError::Unauthorized("Authorization token missing or malformed.".to_string()))?,
+        };
+
+        let principal = if source == TokenSource::Bearer && is_api_key(&token) {
+            let key = state.api_key_service.authenticate(&token).await?;
+            let permissions = state.role_service.permissions_for(key.kind, key.principal_id).await?;
+            let org = state.organization_service.organization_of(key.kind, key.principal_id).await?;
+            Principal {
+                kind: key.kind,
+                id: key.principal_id,
+                permissions: permissions.intersection(&key.scopes).copied().collect(),
+                tenant: Tenant::for_principal(key.kind, org)?,
+                credential: Credential::ApiKey { id: key.key_id },
             }
+        } else {
+            let claims = state.auth_service.authenticate(&token).await?;
+            let kind = PrincipalKind::parse(&claims.role)
+                .ok_or_else(|| AppError::Unauthorized("Invalid token role.".to_string()))?;
+            let id = claims.subject_id()?;
+            let permissions = state.role_service.permissions_for(kind, id).await?;
+            let tenant = Tenant::for_principal(kind, claims.org)?;
+            Principal { kind, id, permissions, tenant, credential: Credential::Token(claims) }
+        };
+        parts.extensions.insert(principal.clone());
+        Ok(Authenticated(principal))
+    }
+}
+
+// Interactive: Extractor สำหรับงานที่ต้องเป็นการ login ของเจ้าของบัญชีเอง
+// (เปลี่ยนรหัสผ่าน, ตั้งค่า 2FA, จัดการ API Key) API Key หรือ Token ที่ Admin สวมสิทธิ์ -> 403
+#[derive(Debug, Clone)]
+pub struct Interactive(pub Principal);
+
+#[async_trait]
+impl<S> FromRequestParts<S> for Interactive
+where
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let Authenticated(principal) = Authenticated::from_request_parts(parts, state).await?;
+        if principal.is_api_key() {
+            return Err(AppError::Forbidden(
+                "This operation requires signing in; API keys are not accepted.".to_string(),
+            ));
         }
-    } else {
-        eprintln!("ERROR: No token found in Authorization header. Returning 401.");
-        let unauthorized_response = (
-            StatusCode::UNAUTHORIZED,
-            Json(json!({"error": "Unauthorized: Authorization token missing or malformed."})),
-        ).into_response();
-        Err(unauthorized_response.into_response())
+        if principal.impersonator().is_some() {
+            return Err(AppError::Forbidden(
+                "This operation is not available while impersonating a user.".to_string(),
+            ));
+        }
+        Ok(Interactive(principal))
     }
+}
+
+// RequirePermission<P>: Extractor ที่ Handler ประกาศเพื่อระบุ Permission ที่ต้องการ
+// เช่น `principal: RequirePermission<perm::RoomsWrite>` ไม่มี Token -> 401, ไม่มี Permission -> 403
+pub struct RequirePermission<P: RequiredPermission>(pub Principal, PhantomData<P>);
 
-}
\ No newline at end of file
+impl<P: RequiredPermission> Deref for RequirePermission<P> {
+    type Target = Principal;
+
+    fn deref(&self) -> &Principal {
+        &self.0
+    }
+}
+
+#[async_trait]
+impl<P, S> FromRequestParts<S> for RequirePermission<P>
+where
+    P: RequiredPermission,
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let Authenticated(principal) = Authenticated::from_request_parts(parts, state).await?;
+        if !principal.has(P::PERMISSION) {
+            return Err(AppError::Forbidden(format!(
+                "Insufficient permissions: '{}' is required.",
+                P::PERMISSION.as_str()
+            )));
+        }
+        Ok(RequirePermission(principal, PhantomData))
+    }
+}
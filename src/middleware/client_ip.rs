@@ -0,0 +1,58 @@
+// src/middleware/client_ip.rs
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use axum::{
+    async_trait,
+    extract::{ConnectInfo, FromRequestParts},
+    http::request::Parts,
+};
+
+use crate::app_state::AppState;
+use crate::error::AppError;
+
+pub const FORWARDED_FOR_HEADER: &str = "x-forwarded-for";
+
+// ClientIp: IP ของ Client ที่ใช้นับการ Login ผิด
+// - ปกติใช้ address ของ connection (ต้อง serve ด้วย into_make_service_with_connect_info)
+// - TRUST_PROXY_HEADERS=true (อยู่หลัง Reverse Proxy) ใช้ address สุดท้ายใน X-Forwarded-For
+//   ซึ่งเป็นค่าที่ Proxy เติมเอง ค่าก่อนหน้านั้น Client ปลอมได้
+#[derive(Debug, Clone)]
+pub struct ClientIp(pub String);
+
+#[async_trait]
+impl<S> FromRequestParts<S> for ClientIp
+where
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let trust_proxy_headers = parts
+            .extensions
+            .get::<Arc<AppState>>()
+            .map(|state| state.trust_proxy_headers)
+            .unwrap_or(false);
+
+        if trust_proxy_headers {
+            let forwarded = parts
+                .headers
+                .get(FORWARDED_FOR_HEADER)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.rsplit(',').next())
+                .map(str::trim)
+                .filter(|ip| !ip.is_empty());
+            if let Some(ip) = forwarded {
+                return Ok(ClientIp(ip.to_string()));
+            }
+        }
+
+        let ip = parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip().to_string())
+            .unwrap_or_else(|| "unknown".to_string());
+        Ok(ClientIp(ip))
+    }
+}
@@ -0,0 +1,40 @@
+// src/middleware/impersonation.rs
+
+use std::sync::Arc;
+
+use axum::{extract::Request, middleware::Next, response::Response};
+
+use crate::app_state::AppState;
+use crate::application::api_key_service::is_api_key;
+use crate::middleware::session::request_token;
+
+// record_impersonated_requests: บันทึกทุก Request ที่ส่งมาด้วย Token ที่ Admin สวมสิทธิ์ผู้ใช้ (มี claim `act`)
+// พร้อม Method, Path และ Status ที่ตอบกลับ (รวม Request ที่ถูกปฏิเสธ เช่น เปลี่ยนรหัสผ่าน -> 403)
+// Token ทั่วไปและ API Key ผ่านไปโดยไม่บันทึก
+pub async fn record_impersonated_requests(request: Request, next: Next) -> Response {
+    let state = request.extensions().get::<Arc<AppState>>().cloned();
+    let impersonation_jti = state.as_ref().and_then(|state| {
+        let (token, _) = request_token(request.method(), request.headers()).ok()??;
+        if is_api_key(&token) {
+            return None;
+        }
+        let claims = state.jwt_service.decode_token(&token).ok()?;
+        claims.act.map(|_| claims.jti)
+    });
+    let method = request.method().to_string();
+    let path = request.uri().path().to_string();
+
+    let response = next.run(request).await;
+
+    let (Some(state), Some(jti)) = (state, impersonation_jti) else {
+        return response;
+    };
+    if let Err(e) = state
+        .impersonation_service
+        .record_action(&jti, &method, &path, response.status().as_u16())
+        .await
+    {
+        tracing::error!(error = %e, "failed to record impersonated request");
+    }
+    response
+}
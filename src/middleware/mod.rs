@@ -1 +1,6 @@
-pub mod auth;
\ No newline at end of file
+pub mod auth;
+pub mod session;
+pub mod client_ip;
+pub mod impersonation;
+pub mod request_id;
+pub mod audit;
\ No newline at end of file
@@ -0,0 +1,61 @@
+// src/middleware/request_id.rs
+
+use std::time::Instant;
+
+use axum::{
+    extract::Request,
+    http::HeaderValue,
+    middleware::Next,
+    response::Response,
+};
+use tracing::Instrument;
+use uuid::Uuid;
+
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+// ความยาวสูงสุดของ X-Request-Id ที่รับจาก Client
+const MAX_REQUEST_ID_LENGTH: usize = 128;
+
+// RequestId: id ของ Request (เก็บใน Request Extensions และบันทึกลง Audit Log)
+#[derive(Debug, Clone)]
+pub struct RequestId(pub String);
+
+// assign_request_id: ใช้ X-Request-Id ที่ Client/Proxy ส่งมา (ตัวอักษรที่พิมพ์ได้ ไม่เกิน 128 ตัว)
+// ไม่เช่นนั้นสร้าง UUID ใหม่ แล้วส่งค่าเดียวกันกลับใน Header X-Request-Id ของ Response
+// ทุก Log ระหว่าง Request อยู่ใน span `request` ที่มี request_id, method และ path
+// และจบด้วย Log หนึ่งบรรทัดที่มี status และ latency_ms
+// ไม่บันทึก Query String, Header และ Body เพราะอาจมี Token หรือรหัสผ่าน
+pub async fn assign_request_id(mut request: Request, next: Next) -> Response {
+    let request_id = request
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .filter(|value| {
+            !value.is_empty() && value.len() <= MAX_REQUEST_ID_LENGTH && value.bytes().all(|b| b.is_ascii_graphic())
+        })
+        .map(str::to_owned)
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+    request.extensions_mut().insert(RequestId(request_id.clone()));
+
+    let span = tracing::info_span!(
+        "request",
+        request_id = %request_id,
+        method = %request.method(),
+        path = %request.uri().path(),
+    );
+    let started = Instant::now();
+    let mut response = next.run(request).instrument(span.clone()).await;
+    let status = response.status().as_u16();
+    let latency_ms = started.elapsed().as_secs_f64() * 1000.0;
+    span.in_scope(|| {
+        if response.status().is_server_error() {
+            tracing::error!(status, latency_ms, "request failed");
+        } else {
+            tracing::info!(status, latency_ms, "request completed");
+        }
+    });
+    if let Ok(value) = HeaderValue::from_str(&request_id) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    response
+}
@@ -0,0 +1,186 @@
+// src/middleware/session.rs
+
+use axum::http::{header, HeaderMap, Method};
+use axum_extra::extract::cookie::{Cookie, CookieJar, SameSite};
+use rand::RngCore;
+use serde::Deserialize;
+use time::Duration;
+use utoipa::IntoParams;
+
+use crate::domain::auth::{SessionResponse, TokenPair};
+use crate::error::AppError;
+
+// ชื่อ Cookie และ Header ที่ใช้ใน Cookie Session
+pub const SESSION_COOKIE: &str = "session"; // Access Token (HttpOnly)
+pub const REFRESH_COOKIE: &str = "refresh_token"; // Refresh Token (HttpOnly, ส่งเฉพาะ /auth/*)
+pub const CSRF_COOKIE: &str = "csrf_token"; // CSRF Token (JavaScript อ่านได้ ต้องส่งกลับมาใน Header)
+pub const CSRF_HEADER: &str = "x-csrf-token";
+
+// LoginMode: รูปแบบการ login (`?mode=cookie` เพื่อใช้ Cookie Session แทน Bearer Token)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum LoginMode {
+    #[default]
+    Bearer,
+    Cookie,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct LoginQuery {
+    pub mode: Option<LoginMode>,
+}
+
+// แหล่งที่มาของ Token ใน Request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenSource {
+    Bearer,
+    Cookie,
+}
+
+// SessionCookies: ตั้งค่าและสร้าง Cookie ของ Session
+// secure/same_site ตั้งค่าได้ (COOKIE_SECURE=false สำหรับทดสอบผ่าน http://localhost เท่านั้น)
+#[derive(Debug, Clone)]
+pub struct SessionCookies {
+    pub secure: bool,
+    pub same_site: SameSite,
+}
+
+impl SessionCookies {
+    pub fn new(secure: bool, same_site: SameSite) -> Self {
+        SessionCookies { secure, same_site }
+    }
+
+    fn cookie(&self, name: &'static str, value: String, path: &'static str, max_age: i64, http_only: bool) -> Cookie<'static> {
+        Cookie::build((name, value))
+            .path(path)
+            .http_only(http_only)
+            .secure(self.secure)
+            .same_site(self.same_site)
+            .max_age(Duration::seconds(max_age))
+            .build()
+    }
+
+    // ใส่ Access/Refresh Token และ CSRF Token ใหม่ลงใน Cookie
+    pub fn start(&self, jar: CookieJar, tokens: TokenPair) -> (CookieJar, SessionResponse) {
+        let csrf_token = random_token();
+        let jar = jar
+            .add(self.cookie(SESSION_COOKIE, tokens.token, "/", tokens.expires_in, true))
+            .add(self.cookie(REFRESH_COOKIE, tokens.refresh_token, "/auth", tokens.refresh_expires_in, true))
+            .add(self.cookie(CSRF_COOKIE, csrf_token.clone(), "/", tokens.refresh_expires_in, false));
+        let session = SessionResponse {
+            role: tokens.role,
+            expires_in: tokens.expires_in,
+            refresh_expires_in: tokens.refresh_expires_in,
+            csrf_token,
+        };
+        (jar, session)
+    }
+
+    // ลบ Cookie ทั้งหมดของ Session (logout)
+    pub fn clear(&self, jar: CookieJar) -> CookieJar {
+        jar.remove(Cookie::build(SESSION_COOKIE).path("/"))
+            .remove(Cookie::build(REFRESH_COOKIE).path("/auth"))
+            .remove(Cookie::build(CSRF_COOKIE).path("/"))
+    }
+}
+
+fn random_token() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+// เปรียบเทียบแบบใช้เวลาคงที่ เพื่อไม่ให้เดาค่า CSRF Token จากเวลาตอบกลับได้
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+// Double-submit CSRF: ค่าใน Header `X-CSRF-Token` ต้องตรงกับ Cookie `csrf_token`
+// เว็บไซต์อื่นส่ง Cookie มากับ Request ได้ แต่ไม่สามารถอ่านค่า Cookie เพื่อใส่ใน Header ได้
+pub fn verify_csrf(headers: &HeaderMap, jar: &CookieJar) -> Result<(), AppError> {
+    let header_value = headers.get(CSRF_HEADER).and_then(|v| v.to_str().ok());
+    match (header_value, jar.get(CSRF_COOKIE)) {
+        (Some(sent), Some(cookie)) if !sent.is_empty() && constant_time_eq(sent.as_bytes(), cookie.value().as_bytes()) => Ok(()),
+        _ => Err(AppError::Forbidden("CSRF token missing or invalid.".to_string())),
+    }
+}
+
+// Method ที่ไม่เปลี่ยนแปลงข้อมูล ไม่ต้องตรวจสอบ CSRF
+fn is_safe_method(method: &Method) -> bool {
+    matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS)
+}
+
+// ดึง Access Token จาก Request: Bearer Header มาก่อน ถ้าไม่มีจึงใช้ Cookie `session`
+// Request ที่ยืนยันตัวตนด้วย Cookie และเปลี่ยนแปลงข้อมูลต้องผ่านการตรวจสอบ CSRF
+pub fn request_token(method: &Method, headers: &HeaderMap) -> Result<Option<(String, TokenSource)>, AppError> {
+    if let Some(header_value) = headers.get(header::AUTHORIZATION).and_then(|h| h.to_str().ok()) {
+        return Ok(header_value
+            .strip_prefix("Bearer ")
+            .map(|token| (token.to_owned(), TokenSource::Bearer)));
+    }
+
+    let jar = CookieJar::from_headers(headers);
+    let Some(session) = jar.get(SESSION_COOKIE) else {
+        return Ok(None);
+    };
+    if !is_safe_method(method) {
+        verify_csrf(headers, &jar)?;
+    }
+    Ok(Some((session.value().to_owned(), TokenSource::Cookie)))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::HeaderValue;
+
+    use super::*;
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.append(*name, HeaderValue::from_str(value).unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn bearer_header_wins_over_cookie() {
+        let headers = headers(&[("authorization", "Bearer abc"), ("cookie", "session=xyz")]);
+        let (token, source) = request_token(&Method::POST, &headers).unwrap().unwrap();
+        assert_eq!((token.as_str(), source), ("abc", TokenSource::Bearer));
+    }
+
+    #[test]
+    fn cookie_reads_need_no_csrf_but_writes_do() {
+        let cookie_only = headers(&[("cookie", "session=xyz; csrf_token=c1")]);
+        let (token, source) = request_token(&Method::GET, &cookie_only).unwrap().unwrap();
+        assert_eq!((token.as_str(), source), ("xyz", TokenSource::Cookie));
+        assert!(matches!(request_token(&Method::POST, &cookie_only), Err(AppError::Forbidden(_))));
+
+        let wrong = headers(&[("cookie", "session=xyz; csrf_token=c1"), (CSRF_HEADER, "c2")]);
+        assert!(matches!(request_token(&Method::DELETE, &wrong), Err(AppError::Forbidden(_))));
+        let right = headers(&[("cookie", "session=xyz; csrf_token=c1"), (CSRF_HEADER, "c1")]);
+        assert!(request_token(&Method::DELETE, &right).unwrap().is_some());
+    }
+
+    #[test]
+    fn session_cookies_are_http_only_except_csrf() {
+        let cookies = SessionCookies::new(true, SameSite::Strict);
+        let tokens = TokenPair {
+            token: "access".to_string(),
+            refresh_token: "refresh".to_string(),
+            role: "user".to_string(),
+            expires_in: 900,
+            refresh_expires_in: 3600,
+        };
+        let (jar, session) = cookies.start(CookieJar::new(), tokens);
+        let session_cookie = jar.get(SESSION_COOKIE).unwrap();
+        assert_eq!(session_cookie.http_only(), Some(true));
+        assert_eq!(session_cookie.secure(), Some(true));
+        assert_eq!(jar.get(REFRESH_COOKIE).unwrap().path(), Some("/auth"));
+        let csrf = jar.get(CSRF_COOKIE).unwrap();
+        assert_eq!(csrf.http_only(), Some(false));
+        assert_eq!(csrf.value(), session.csrf_token);
+    }
+}
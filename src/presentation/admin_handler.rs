@@ -2,111 +2,240 @@
 
 use std::sync::Arc;
 
+use axum::extract::{Path, Query};
+use axum::response::Response;
 use axum::Extension;
 use axum::{Json, http::StatusCode, response::IntoResponse};
+use axum_extra::extract::cookie::CookieJar;
 use serde::{Deserialize, Serialize};
-use serde_json::json; // เพิ่ม import สำหรับ json! macro
+use utoipa::ToSchema;
 
 use crate::app_state::AppState;
 // use crate::application::admin_service::AdminService; // ไม่จำเป็นต้องใช้ตรงๆ แล้ว เพราะเข้าถึงผ่าน AppState
 use crate::domain::admin::{LoginCredentials}; // นำเข้า Struct จาก Domain Layer
+use crate::domain::admin::{
+    AcceptInvitationRequest, Admin, AdminInvitation, AdminResponse, CreateInvitationRequest, InvitationResponse, SetupAdminRequest,
+};
+use crate::domain::audit::{AuditActor, AuditEvent};
+use crate::domain::permission::{perm, PrincipalKind};
+use crate::error::{AppError, ProblemDetails};
+use crate::middleware::audit::Audit;
+use crate::middleware::auth::RequirePermission;
+use crate::middleware::client_ip::ClientIp;
+use crate::middleware::session::{LoginMode, LoginQuery};
+use crate::presentation::validation::ValidatedJson;
 
 
-// Request Body สำหรับการลงทะเบียน (รับรหัสผ่านดิบจาก Client)
-#[derive(Clone, Deserialize)]
-pub struct CreateAdminRequest {
-    pub username: String,
-    pub password: String, // <<-- รหัสผ่านดิบ (Plain Text)
-}
-
 // Request Body สำหรับการ Login (รับรหัสผ่านดิบจาก Client)
-#[derive(Clone, Deserialize)]
+#[derive(Clone, Deserialize, ToSchema)]
 pub struct LoginAdminRequest {
     pub username: String,
     pub password: String, // <<-- รหัสผ่านดิบ (Plain Text)
 }
 
 // Response Body สำหรับการ Login (ถ้าต้องการส่ง Token หรือข้อมูลเพิ่มเติม)
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
+#[schema(as = AdminLoginResponse)]
 pub struct LoginResponse {
     pub admin_id: i32,
     pub token: String,      // เพิ่ม token ใน response
     pub role: String,       // เพิ่ม role ใน response
     pub expires_in: i64,    // เวลาหมดอายุ (วินาที)
+    pub refresh_token: String,   // ใช้กับ POST /auth/refresh และ POST /auth/logout
+    pub refresh_expires_in: i64, // อายุ Refresh Token (วินาที)
 }
 
-// Handler สำหรับการลงทะเบียนผู้ใช้ (POST /register)
-pub async fn register_admin_handler(
-    // *** ลบ State ซ้ำซ้อนออกไป เหลือแค่ 1 อัน ***
+// Handler สำหรับสร้าง Admin คนแรก (POST /admin/setup)
+// ใช้ได้เฉพาะเมื่อยังไม่มี Admin และต้องมี Setup Token ที่แสดงตอนเริ่มระบบ (หรือ ADMIN_SETUP_TOKEN)
+#[utoipa::path(
+    post,
+    path = "/admin/setup",
+    tag = "auth",
+    request_body = SetupAdminRequest,
+    responses(
+        (status = 201, description = "First admin created", body = AdminResponse),
+        (status = 401, description = "Invalid setup token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Setup is not available", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 409, description = "An admin account already exists", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid request body; `errors` lists every failing field", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn setup_admin_handler(
     Extension(state): Extension<Arc<AppState>>,
-    Json(payload): Json<CreateAdminRequest>,
-) -> impl IntoResponse {
-    // 1. ดึง Connection จาก AppState
-    let mut conn = match state.db_pool.get() {
-        Ok(conn) => conn,
-        Err(e) => {
-            eprintln!("Database connection error: {}", e);
-            return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"error": "Failed to connect to database."}))).into_response();
-        }
-    };
-
-    // 2. แปลง CreateAdminRequest ไปเป็น RegisterAdminRequest
-    let admin_request = crate::domain::admin::RegisterAdminRequest {
-        username: payload.username,
-        password: payload.password,
-    };
+    Audit(audit): Audit,
+    ValidatedJson(request): ValidatedJson<SetupAdminRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let event = audit.pending(|admin: &Admin| {
+        AuditEvent::new("admin.setup", "admin", admin.id)
+            .by(AuditActor::account(PrincipalKind::Admin, admin.id, admin.organization_id))
+            .after(&AdminResponse::from(admin.clone()))
+    });
+    let admin = AdminResponse::from(state.admin_service.setup_first_admin(request, event).await?);
+    Ok((StatusCode::CREATED, Json(admin)))
+}
 
-    // 3. เรียกใช้ service และจัดการผลลัพธ์
-    // *** ต้องส่ง &mut conn เข้าไปในพารามิเตอร์แรกของ register_admin ***
-    match state.admin_service.register_admin(&mut conn, admin_request).await {
-        Ok(admin) => (StatusCode::CREATED, Json(json!(admin))).into_response(), // UserEntity (User) derive Serialize
-        Err(e) => {
-            // *** แก้ไขการจัดการ Error ***
-            eprintln!("Admin registration error: {:?}", e); // สำหรับ Debug
-            // สมมติว่า AdminServiceError มี to_string() หรือ Display trait
-            (StatusCode::BAD_REQUEST, Json(json!({"error": e.to_string()}))).into_response()
-            // ถ้าคุณมี AdminServiceError enum ที่ละเอียดกว่านี้ เช่น UsernameAlreadyExists
-            // คุณสามารถใช้ match e { ... } เพื่อจัดการแต่ละ error ได้แม่นยำขึ้น
-        }
+// Handler สำหรับสร้างลิงก์คำเชิญ Admin ใหม่
+#[utoipa::path(
+    post,
+    path = "/admin/invitations",
+    tag = "admin",
+    request_body = CreateInvitationRequest,
+    security(("admin_token" = []), ("session_cookie" = [])),
+    responses(
+        (status = 201, description = "Invitation created; the token is only shown once", body = InvitationResponse),
+        (status = 401, description = "Missing or invalid token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Insufficient permissions", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid request body, or `organization_id` set by an organization admin or unknown", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn create_invitation_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    principal: RequirePermission<perm::AdminsManage>,
+    Audit(audit): Audit,
+    ValidatedJson(request): ValidatedJson<CreateInvitationRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    // invited_by อ้างถึงตาราง admins จึงต้องเป็นบัญชี Admin
+    if principal.kind != PrincipalKind::Admin {
+        return Err(AppError::Forbidden("Only admin accounts can invite admins.".to_string()));
     }
+    let event = audit.pending(|invitation: &AdminInvitation| {
+        AuditEvent::new("invitation.create", "invitation", invitation.id).after(invitation)
+    });
+    let invitation = state.invitation_service.create_invitation(principal.id, principal.tenant, request, event).await?;
+    Ok((StatusCode::CREATED, Json(invitation)))
+}
+
+// Handler สำหรับดูคำเชิญที่ยังใช้ได้
+#[utoipa::path(
+    get,
+    path = "/admin/invitations",
+    tag = "admin",
+    security(("admin_token" = []), ("session_cookie" = [])),
+    responses(
+        (status = 200, description = "Pending invitations", body = Vec<AdminInvitation>),
+        (status = 401, description = "Missing or invalid token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Insufficient permissions", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn list_invitations_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    principal: RequirePermission<perm::AdminsManage>,
+) -> Result<impl IntoResponse, AppError> {
+    let invitations = state.invitation_service.list_pending(principal.tenant).await?;
+    Ok(Json(invitations))
+}
+
+// Handler สำหรับเพิกถอนคำเชิญที่ยังไม่ถูกใช้
+#[utoipa::path(
+    delete,
+    path = "/admin/invitations/{invitation_id}",
+    tag = "admin",
+    params(("invitation_id" = i32, Path, description = "Invitation ID")),
+    security(("admin_token" = []), ("session_cookie" = [])),
+    responses(
+        (status = 204, description = "Invitation revoked"),
+        (status = 401, description = "Missing or invalid token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Insufficient permissions", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Invitation not found or already used", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn revoke_invitation_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    principal: RequirePermission<perm::AdminsManage>,
+    Audit(audit): Audit,
+    Path(invitation_id): Path<i32>,
+) -> Result<impl IntoResponse, AppError> {
+    let event = audit.pending(|_: &()| AuditEvent::new("invitation.revoke", "invitation", invitation_id));
+    state.invitation_service.revoke_invitation(principal.tenant, invitation_id, event).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// Handler สำหรับสร้างบัญชี Admin จากคำเชิญ (Public: ยืนยันด้วย Token ในคำเชิญ)
+#[utoipa::path(
+    post,
+    path = "/admin/invitations/accept",
+    tag = "auth",
+    request_body = AcceptInvitationRequest,
+    responses(
+        (status = 201, description = "Admin account created", body = AdminResponse),
+        (status = 401, description = "Invitation is invalid, expired, revoked or already used", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 409, description = "Username already taken", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid request body; `errors` lists every failing field", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn accept_invitation_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    Audit(audit): Audit,
+    ValidatedJson(request): ValidatedJson<AcceptInvitationRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let event = audit.pending(|admin: &Admin| {
+        AuditEvent::new("invitation.accept", "admin", admin.id)
+            .by(AuditActor::account(PrincipalKind::Admin, admin.id, admin.organization_id))
+            .after(&AdminResponse::from(admin.clone()))
+    });
+    let admin = AdminResponse::from(state.invitation_service.accept_invitation(request, event).await?);
+    Ok((StatusCode::CREATED, Json(admin)))
 }
 
 // Handler สำหรับการ Login Admin (POST /login/admin)
+#[utoipa::path(
+    post,
+    path = "/login/admin",
+    tag = "auth",
+    request_body = LoginAdminRequest,
+    params(LoginQuery),
+    responses(
+        (status = 200, description = "Logged in. With `?mode=cookie` the tokens are set as HttpOnly cookies and the body is a SessionResponse. Accounts with two-factor authentication receive an MfaChallengeResponse instead; finish with POST /auth/mfa/verify", body = LoginResponse),
+        (status = 401, description = "Invalid username or password", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 429, description = "Too many failed attempts; retry after the `Retry-After` seconds", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
 pub async fn login_admin_handler(
     Extension(state): Extension<Arc<AppState>>,
+    Query(login): Query<LoginQuery>,
+    ClientIp(ip): ClientIp,
+    jar: CookieJar,
     Json(payload): Json<LoginAdminRequest>,
-) -> Result<Json<LoginResponse>, (StatusCode, String)> {
+) -> Result<Response, AppError> {
+    // username หรือ IP ที่ Login ผิดบ่อยเกินไปจะถูกล็อกชั่วคราว
+    let throttle = &state.login_throttle_service;
+    throttle.check("admin", &payload.username, &ip).await?;
+
+    let username = payload.username.clone();
     let login_credentials = LoginCredentials {
         username: payload.username,
         password: payload.password,
     };
 
     // ดึง connection สำหรับ login_admin
-    let mut conn = match state.db_pool.get() {
-        Ok(conn) => conn,
-        Err(e) => {
-            eprintln!("Database connection error: {}", e);
-            return Err((StatusCode::INTERNAL_SERVER_ERROR, "Failed to connect to database.".to_string()));
-        }
-    };
+    let mut conn = state.db_pool.get()?;
 
     // *** ต้องส่ง &mut conn เข้าไปในพารามิเตอร์แรกของ login_admin ***
-    match state.admin_service.login_admin(&mut conn, login_credentials).await {
-        Ok(admin_id) => {
-            // สร้าง JWT Token สำหรับ Admin
-            match state.jwt_service.create_token(admin_id, "admin") {
-                Ok(token) => {
-                    let response = LoginResponse {
-                        admin_id,
-                        token,
-                        role: "admin".to_string(),
-                        expires_in: 24 * 60 * 60, // 24 ชั่วโมง
-                    };
-                    Ok(Json(response))
-                }
-                Err(_) => Err((StatusCode::INTERNAL_SERVER_ERROR, "Failed to create token".to_string())),
-            }
-        }
-        Err(e) => Err((StatusCode::UNAUTHORIZED, e.to_string())), // แปลง error เป็น String
+    let result = state.admin_service.login_admin(&mut conn, login_credentials).await;
+    drop(conn);
+    let admin_id = throttle.record_password("admin", &username, &ip, result).await?;
+
+    // บัญชีที่เปิด 2FA (หรือ Role กำหนดให้ต้องใช้) ต้องยืนยันรหัสที่ POST /auth/mfa/verify ก่อนได้รับ Token
+    // ตัวนับการ Login ผิดจะถูกล้างเมื่อยืนยันรหัสสำเร็จเท่านั้น
+    if let Some(challenge) = state.mfa_service.login_challenge(PrincipalKind::Admin, admin_id).await? {
+        return Ok(Json(challenge).into_response());
+    }
+    throttle.record_success("admin", &username, &ip).await?;
+
+    // สร้าง Access Token + Refresh Token สำหรับ Admin
+    let tokens = state.auth_service.issue_tokens(admin_id, "admin").await?;
+
+    // Cookie Session: Token อยู่ใน HttpOnly Cookie และตอบกลับ CSRF Token แทน
+    if login.mode.unwrap_or_default() == LoginMode::Cookie {
+        let (jar, session) = state.session_cookies.start(jar, tokens);
+        return Ok((jar, Json(session)).into_response());
     }
-}
\ No newline at end of file
+    Ok(Json(LoginResponse {
+        admin_id,
+        token: tokens.token,
+        role: tokens.role,
+        expires_in: tokens.expires_in,
+        refresh_token: tokens.refresh_token,
+        refresh_expires_in: tokens.refresh_expires_in,
+    }).into_response())
+}
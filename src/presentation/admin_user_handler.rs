@@ -1,55 +1,162 @@
 // src/presentation/admin_user_handler.rs
 
 use axum::{
-    extract::{Path}, http::StatusCode, response::IntoResponse, Extension, Json
+    extract::{Path, Query}, http::StatusCode, response::IntoResponse, Extension, Json
 };
 use std::sync::Arc;
-use crate::{app_state::AppState};
+use crate::{
+    app_state::AppState,
+    domain::audit::{AuditEvent, UserAuditSnapshot},
+    domain::permission::perm,
+    domain::pagination::{ListQuery, Page},
+    domain::user::{DeleteUserQuery, User, UserResponse},
+    error::{AppError, ProblemDetails},
+    middleware::audit::Audit,
+    middleware::auth::RequirePermission,
+};
 
 
 // Handler สำหรับดึงข้อมูลผู้ใช้ทั้งหมด (Regular Users)
+#[utoipa::path(
+    get,
+    path = "/admin/users",
+    tag = "admin",
+    params(ListQuery),
+    security(("admin_token" = []), ("session_cookie" = [])),
+    responses(
+        (status = 200, description = "Active users of the organization (and deleted ones with include_deleted=true)", body = Page<UserResponse>),
+        (status = 401, description = "Missing or invalid token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Insufficient permissions", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid input", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
 pub async fn get_all_users_handler(
     Extension(state): Extension<Arc<AppState>>, // <--- เปลี่ยนตรงนี้
-) -> impl IntoResponse {
-    match state.user_service.get_all_users().await {
-        Ok(users) => Json(users).into_response(),
-        Err(e) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(serde_json::json!({"error": e})),
-        )
-            .into_response(),
-    }
+    principal: RequirePermission<perm::UsersRead>,
+    Query(query): Query<ListQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let users = state.user_service.get_all_users(principal.tenant, query).await?;
+    Ok(Json(users.map(UserResponse::from)))
 }
 
+#[utoipa::path(
+    get,
+    path = "/admin/users/{user_id}",
+    tag = "admin",
+    params(("user_id" = i32, Path, description = "User ID")),
+    security(("admin_token" = []), ("session_cookie" = [])),
+    responses(
+        (status = 200, description = "User", body = UserResponse),
+        (status = 401, description = "Missing or invalid token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Insufficient permissions", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "User not found", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
 pub async fn get_user_by_id_handler(
     Extension(state): Extension<Arc<AppState>>,
+    principal: RequirePermission<perm::UsersRead>,
     Path(user_id): Path<i32>,
-) -> impl IntoResponse {
-    match state.user_service.get_user_by_id(user_id).await {
-        Ok(user) => Json(user).into_response(),
-        Err(e) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(serde_json::json!({"error": e})),
-        )
-            .into_response(),
-    }
+) -> Result<impl IntoResponse, AppError> {
+    state.organization_service.ensure_user(principal.tenant, user_id).await?;
+    let user = state.user_service.get_user_by_id(user_id).await?;
+    Ok(Json(UserResponse::from(user)))
 }
 
 
 // Handler สำหรับ Soft Delete ผู้ใช้โดย Admin
+// ?future_bookings=cancel|transfer|keep กำหนดสิ่งที่เกิดกับการจองที่ยังไม่จบ (ค่าเริ่มต้น cancel)
+#[utoipa::path(
+    delete,
+    path = "/admin/users/{user_id}",
+    tag = "admin",
+    params(("user_id" = i32, Path, description = "User ID"), DeleteUserQuery),
+    security(("admin_token" = []), ("session_cookie" = [])),
+    responses(
+        (status = 204, description = "User soft deleted"),
+        (status = 401, description = "Missing or invalid token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Insufficient permissions", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "User not found", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Missing or invalid `transfer_to`", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
 pub async fn delete_user_by_admin_handler(
     Extension(state): Extension<Arc<AppState>>,
+    principal: RequirePermission<perm::UsersWrite>,
+    Audit(audit): Audit,
+    Path(user_id): Path<i32>,
+    Query(query): Query<DeleteUserQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    state.organization_service.ensure_user(principal.tenant, user_id).await?;
+    let before = state.user_service.find_user(user_id).await?;
+    let event = audit.pending(|after: &User| {
+        AuditEvent::new("user.delete", "user", user_id)
+            .before(&UserAuditSnapshot::from(&before))
+            .after(&UserAuditSnapshot::from(after))
+    });
+    state.user_service.delete_user(user_id, query, event).await?;
+    // ผู้ใช้ที่ถูกลบต้องไม่สามารถใช้ Token เดิมหรือ refresh ต่อได้
+    state.auth_service.revoke_all_sessions(user_id, "user").await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// Handler สำหรับกู้คืนผู้ใช้ที่ถูก Soft Delete
+#[utoipa::path(
+    post,
+    path = "/admin/users/{user_id}/restore",
+    tag = "admin",
+    params(("user_id" = i32, Path, description = "User ID")),
+    security(("admin_token" = []), ("session_cookie" = [])),
+    responses(
+        (status = 200, description = "User restored", body = UserResponse),
+        (status = 401, description = "Missing or invalid token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Insufficient permissions", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "User not found or not deleted", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn restore_user_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    principal: RequirePermission<perm::UsersWrite>,
+    Audit(audit): Audit,
     Path(user_id): Path<i32>,
-) -> impl IntoResponse {
-    match state.user_service.delete_user(user_id).await {
-        Ok(_) => StatusCode::NO_CONTENT.into_response(),
-        Err(e) => {
-            let status_code = if e.contains("User not found") {
-                StatusCode::NOT_FOUND
-            } else {
-                StatusCode::INTERNAL_SERVER_ERROR
-            };
-            (status_code, Json(serde_json::json!({"error": e}))).into_response()
-        }
-    }
-}
\ No newline at end of file
+) -> Result<impl IntoResponse, AppError> {
+    state.organization_service.ensure_user(principal.tenant, user_id).await?;
+    let before = state.user_service.find_user(user_id).await?;
+    let event = audit.pending(|user: &User| {
+        AuditEvent::new("user.restore", "user", user_id)
+            .before(&UserAuditSnapshot::from(&before))
+            .after(&UserAuditSnapshot::from(user))
+    });
+    let user = state.user_service.restore_user(user_id, event).await?;
+    Ok(Json(UserResponse::from(user)))
+}
+
+// Handler สำหรับยืนยันอีเมลแทนผู้ใช้โดย Admin (ปลดล็อกการจองห้อง)
+#[utoipa::path(
+    post,
+    path = "/admin/users/{user_id}/verify-email",
+    tag = "admin",
+    params(("user_id" = i32, Path, description = "User ID")),
+    security(("admin_token" = []), ("session_cookie" = [])),
+    responses(
+        (status = 200, description = "User marked as verified", body = UserResponse),
+        (status = 401, description = "Missing or invalid token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Insufficient permissions", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "User not found", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn verify_user_email_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    principal: RequirePermission<perm::UsersWrite>,
+    Audit(audit): Audit,
+    Path(user_id): Path<i32>,
+) -> Result<impl IntoResponse, AppError> {
+    state.organization_service.ensure_user(principal.tenant, user_id).await?;
+    let before = state.user_service.find_user(user_id).await?;
+    let event = audit.pending(|user: &User| {
+        AuditEvent::new("user.email.verify", "user", user_id)
+            .before(&UserAuditSnapshot::from(&before))
+            .after(&UserAuditSnapshot::from(user))
+    });
+    let user = state.email_verification_service.override_verification(user_id, event).await?;
+    Ok(Json(UserResponse::from(user)))
+}
@@ -0,0 +1,87 @@
+// src/presentation/api_key_handler.rs
+
+use std::sync::Arc;
+
+use axum::{extract::Path, http::StatusCode, response::IntoResponse, Extension, Json};
+
+use crate::app_state::AppState;
+use crate::domain::audit::AuditEvent;
+use crate::domain::api_key::{ApiKey, ApiKeyResponse, CreateApiKeyRequest, CreatedApiKeyResponse};
+use crate::error::{AppError, ProblemDetails};
+use crate::middleware::audit::Audit;
+use crate::middleware::auth::{Authenticated, Interactive};
+use crate::presentation::validation::ValidatedJson;
+
+// Handler สำหรับดู API Key ทั้งหมดของตัวเอง (ไม่แสดงตัว Key)
+#[utoipa::path(
+    get,
+    path = "/users/me/api-keys",
+    tag = "auth",
+    security(("user_token" = []), ("admin_token" = []), ("session_cookie" = []), ("api_key" = [])),
+    responses(
+        (status = 200, description = "API keys of the current account, newest first", body = [ApiKeyResponse]),
+        (status = 401, description = "Missing or invalid token", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn list_api_keys_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    Authenticated(principal): Authenticated,
+) -> Result<impl IntoResponse, AppError> {
+    let keys = state.api_key_service.list(principal.kind, principal.id).await?;
+    Ok(Json(keys))
+}
+
+// Handler สำหรับสร้าง API Key (ตัว Key แสดงครั้งเดียวใน Response นี้)
+#[utoipa::path(
+    post,
+    path = "/users/me/api-keys",
+    tag = "auth",
+    request_body = CreateApiKeyRequest,
+    security(("user_token" = []), ("admin_token" = []), ("session_cookie" = [])),
+    responses(
+        (status = 201, description = "API key created; `key` is shown only once", body = CreatedApiKeyResponse),
+        (status = 401, description = "Missing or invalid token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "A requested scope is not granted to the account, or authenticated with an API key", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid request body; `errors` lists every failing field", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn create_api_key_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    Interactive(principal): Interactive,
+    Audit(audit): Audit,
+    ValidatedJson(request): ValidatedJson<CreateApiKeyRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let event = audit.pending(|key: &ApiKey| {
+        AuditEvent::new("api_key.create", "api_key", key.id).after(&ApiKeyResponse::from(key.clone()))
+    });
+    let created = state
+        .api_key_service
+        .create(principal.kind, principal.id, &principal.permissions, request, event)
+        .await?;
+    Ok((StatusCode::CREATED, Json(created)))
+}
+
+// Handler สำหรับเพิกถอน API Key ของตัวเอง (มีผลทันที)
+#[utoipa::path(
+    delete,
+    path = "/users/me/api-keys/{key_id}",
+    tag = "auth",
+    params(("key_id" = i32, Path, description = "API key ID")),
+    security(("user_token" = []), ("admin_token" = []), ("session_cookie" = [])),
+    responses(
+        (status = 204, description = "API key revoked"),
+        (status = 401, description = "Missing or invalid token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Authenticated with an API key", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "API key not found or already revoked", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn revoke_api_key_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    Interactive(principal): Interactive,
+    Audit(audit): Audit,
+    Path(key_id): Path<i32>,
+) -> Result<impl IntoResponse, AppError> {
+    let event = audit.pending(|_: &()| AuditEvent::new("api_key.revoke", "api_key", key_id));
+    state.api_key_service.revoke(principal.kind, principal.id, key_id, event).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
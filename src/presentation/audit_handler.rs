@@ -0,0 +1,56 @@
+// src/presentation/audit_handler.rs
+
+use std::sync::Arc;
+
+use axum::{extract::Query, response::IntoResponse, Extension, Json};
+
+use crate::app_state::AppState;
+use crate::domain::audit::{AuditEntryResponse, AuditQuery, AuditVerification};
+use crate::domain::pagination::Page;
+use crate::domain::permission::perm;
+use crate::error::{AppError, ProblemDetails};
+use crate::middleware::auth::RequirePermission;
+
+// Handler สำหรับดู Audit Log (Admin ขององค์กรเห็นเฉพาะรายการขององค์กรตัวเอง)
+#[utoipa::path(
+    get,
+    path = "/admin/audit",
+    tag = "admin",
+    params(AuditQuery),
+    security(("admin_token" = []), ("session_cookie" = []), ("api_key" = [])),
+    responses(
+        (status = 200, description = "Audit log entries, newest first by default", body = Page<AuditEntryResponse>),
+        (status = 401, description = "Missing or invalid token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Insufficient permissions", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid input", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn list_audit_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    principal: RequirePermission<perm::AuditRead>,
+    Query(query): Query<AuditQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let entries = state.audit_service.list(principal.tenant, query).await?;
+    Ok(Json(entries))
+}
+
+// Handler สำหรับตรวจสอบ Hash Chain ของ Audit Log ทั้งหมด (เฉพาะ Platform Admin)
+#[utoipa::path(
+    get,
+    path = "/admin/audit/verify",
+    tag = "admin",
+    security(("admin_token" = []), ("session_cookie" = []), ("api_key" = [])),
+    responses(
+        (status = 200, description = "Verification result; `valid` is false and `broken_at` is set when an entry was modified or removed", body = AuditVerification),
+        (status = 401, description = "Missing or invalid token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Insufficient permissions, or not a platform admin", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn verify_audit_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    principal: RequirePermission<perm::AuditRead>,
+) -> Result<impl IntoResponse, AppError> {
+    principal.require_platform()?;
+    let verification = state.audit_service.verify().await?;
+    Ok(Json(verification))
+}
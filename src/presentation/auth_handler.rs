@@ -0,0 +1,114 @@
+// src/presentation/auth_handler.rs
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{FromRequest, Request},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Extension, Json,
+};
+use axum_extra::extract::cookie::CookieJar;
+
+use crate::app_state::AppState;
+use crate::domain::auth::{RefreshTokenRequest, TokenPair};
+use crate::error::{AppError, ProblemDetails};
+use crate::middleware::session::{verify_csrf, REFRESH_COOKIE};
+use crate::presentation::validation::ValidatedJson;
+
+// Request ที่ไม่มี JSON Body แต่มี Cookie `refresh_token` ถือเป็น Cookie Session
+// (ต้องมี X-CSRF-Token) ส่วนกรณีอื่นอ่าน refresh_token จาก Body ตามปกติ
+fn cookie_refresh_token(jar: &CookieJar, request: &Request) -> Result<Option<String>, AppError> {
+    let has_json_body = request
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("application/json"));
+    match jar.get(REFRESH_COOKIE) {
+        Some(cookie) if !has_json_body => {
+            verify_csrf(request.headers(), jar)?;
+            Ok(Some(cookie.value().to_owned()))
+        }
+        _ => Ok(None),
+    }
+}
+
+async fn body_refresh_token(request: Request) -> Result<String, AppError> {
+    let ValidatedJson(payload) = ValidatedJson::<RefreshTokenRequest>::from_request(request, &()).await?;
+    Ok(payload.refresh_token)
+}
+
+// Handler สำหรับต่ออายุ Token (POST /auth/refresh)
+// Refresh Token เดิมจะใช้ไม่ได้อีก ต้องใช้ตัวใหม่ที่ได้รับใน Response
+// Cookie Session: ส่ง Request โดยไม่มี Body พร้อม Header X-CSRF-Token แล้ว Cookie จะถูกตั้งค่าใหม่
+#[utoipa::path(
+    post,
+    path = "/auth/refresh",
+    tag = "auth",
+    request_body(content = RefreshTokenRequest, description = "Omit the body when using a cookie session"),
+    params(("X-CSRF-Token" = Option<String>, Header, description = "Required when refreshing a cookie session")),
+    responses(
+        (status = 200, description = "New access and refresh token (cookie sessions receive a SessionResponse and new cookies)", body = TokenPair),
+        (status = 401, description = "Refresh token invalid, expired, revoked or reused", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "CSRF token missing or invalid", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid request body", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn refresh_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    jar: CookieJar,
+    request: Request,
+) -> Result<Response, AppError> {
+    if let Some(refresh_token) = cookie_refresh_token(&jar, &request)? {
+        let tokens = state.auth_service.refresh(&refresh_token).await?;
+        let (jar, session) = state.session_cookies.start(jar, tokens);
+        return Ok((jar, Json(session)).into_response());
+    }
+
+    let refresh_token = body_refresh_token(request).await?;
+    let tokens = state.auth_service.refresh(&refresh_token).await?;
+    Ok(Json(tokens).into_response())
+}
+
+// Handler สำหรับ Logout (POST /auth/logout)
+// เพิกถอน Refresh Token และ Access Token ทั้งหมดที่ออกจาก login ครั้งนั้น
+// Cookie Session: ส่ง Request โดยไม่มี Body พร้อม Header X-CSRF-Token แล้ว Cookie จะถูกลบ
+#[utoipa::path(
+    post,
+    path = "/auth/logout",
+    tag = "auth",
+    request_body(content = RefreshTokenRequest, description = "Omit the body when using a cookie session"),
+    params(("X-CSRF-Token" = Option<String>, Header, description = "Required when logging out a cookie session")),
+    responses(
+        (status = 204, description = "Session revoked"),
+        (status = 403, description = "CSRF token missing or invalid", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid request body", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn logout_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    jar: CookieJar,
+    request: Request,
+) -> Result<Response, AppError> {
+    if let Some(refresh_token) = cookie_refresh_token(&jar, &request)? {
+        state.auth_service.logout(&refresh_token).await?;
+        let jar = state.session_cookies.clear(jar);
+        return Ok((StatusCode::NO_CONTENT, jar).into_response());
+    }
+
+    let refresh_token = body_refresh_token(request).await?;
+    state.auth_service.logout(&refresh_token).await?;
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+// Handler สำหรับเผยแพร่ Public Key (GET /.well-known/jwks.json)
+// มีเฉพาะ key แบบ RS256/EdDSA จาก JWT_KEYS (key HS512 จาก JWT_SECRET ไม่ถูกเผยแพร่)
+#[utoipa::path(
+    get,
+    path = "/.well-known/jwks.json",
+    tag = "auth",
+    responses((status = 200, description = "JSON Web Key Set used to verify access tokens", content_type = "application/json")),
+)]
+pub async fn jwks_handler(Extension(state): Extension<Arc<AppState>>) -> impl IntoResponse {
+    Json(state.jwt_service.jwks())
+}
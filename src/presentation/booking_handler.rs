@@ -3,38 +3,71 @@
 use std::sync::Arc;
 
 use axum::{
-    extract::{ Path, Extension},
+    extract::{ Path, Extension, Query},
     response::IntoResponse,
     http::StatusCode,
     Json,
 };
-use serde_json::json;
 use crate::app_state::AppState;
-use crate::application::booking_service::{BookingServiceError};
+use crate::domain::audit::AuditEvent;
+use crate::domain::permission::{perm, Permission};
+use crate::middleware::audit::Audit;
+use crate::middleware::auth::RequirePermission;
 // import ให้ถูกต้องตามที่ใช้
-use crate::domain::booking::{CreateBookingRequest, InternalCreateBookingRequest}; // เพิ่ม InternalCreateBookingRequest, CreateBookingRequest
-use crate::infrastructure::jwt::Claims;
+use crate::domain::booking::{Booking, CreateBookingRequest, InternalCreateBookingRequest}; // เพิ่ม InternalCreateBookingRequest, CreateBookingRequest
+use crate::domain::organization::Tenant;
+use crate::domain::pagination::{ListQuery, Page};
+use crate::error::{AppError, ProblemDetails};
+use crate::presentation::validation::ValidatedJson;
 
 
 // Handler สำหรับสร้างการจองห้องพัก
 // รับ CreateBookingRequest จาก Body
+#[utoipa::path(
+    post,
+    path = "/bookings",
+    tag = "bookings",
+    request_body = CreateBookingRequest,
+    security(("user_token" = []), ("admin_token" = []), ("session_cookie" = [])),
+    responses(
+        (status = 201, description = "Booking created", body = Booking),
+        (status = 401, description = "Missing or invalid token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Insufficient permissions, the user has not verified their email address, or the room is restricted to other groups or users", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Room or user not found, or the room is not visible to the user's organization", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 409, description = "The room is already booked for an overlapping time", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid request body; `errors` lists every failing field", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
 pub async fn create_booking_handler(
     Extension(state): Extension<Arc<AppState>>,
-    Extension(claims): Extension<Claims>,
-    Json(create_request): Json<CreateBookingRequest>, // <--- เปลี่ยนเป็น CreateBookingRequest
-) -> impl IntoResponse {
-    let user_id_str = claims.sub;
-
-    let user_id = match user_id_str.parse::<i32>() {
-        Ok(id) => id,
-        Err(_) => {
-            eprintln!("Failed to parse user_id from claims: {}", user_id_str);
-            return (
-                StatusCode::BAD_REQUEST,
-                Json(json!({"error": "Invalid user ID format in token."})),
-            ).into_response();
+    principal: RequirePermission<perm::BookingsWrite>,
+    Audit(audit): Audit,
+    ValidatedJson(create_request): ValidatedJson<CreateBookingRequest>, // ตรวจสอบ room_id และช่วงเวลาก่อนสร้างการจอง
+) -> Result<impl IntoResponse, AppError> {
+    // จองแทนผู้ใช้คนอื่นได้เฉพาะผู้ที่มี bookings:manage (เช่น Admin)
+    let user_id = match create_request.user_id {
+        Some(user_id) if principal.has(Permission::BookingsManage) => user_id,
+        Some(_) => {
+            return Err(AppError::Forbidden(
+                "Insufficient permissions: 'bookings:manage' is required to book for another user.".to_string(),
+            ))
         }
+        None => principal.user_id()?,
     };
+    // จองแทนได้เฉพาะผู้ใช้ในองค์กรของผู้เรียก
+    state.organization_service.ensure_user(principal.tenant, user_id).await?;
+    // ผู้ใช้ที่ยังไม่ยืนยันอีเมล (EMAIL_VERIFICATION_REQUIRED) จองไม่ได้ ทั้งจองเองและจองแทน
+    let user = state.user_service.get_user_by_id(user_id).await?;
+    if user.email_verification_pending {
+        return Err(AppError::Forbidden("Verify your email address before booking.".to_string()));
+    }
+    // ห้องต้องเป็นขององค์กรของผู้จอง หรือถูกแชร์ให้องค์กรนั้น
+    state
+        .room_service
+        .get_room_by_id(Tenant::Organization(user.organization_id), None, create_request.room_id)
+        .await?;
+    // ห้องที่จำกัดไว้เฉพาะบางกลุ่มหรือบางคน ตรวจกับผู้จอง (รวมกรณีจองแทน)
+    state.room_service.ensure_bookable(create_request.room_id, user_id).await?;
 
     // สร้าง InternalCreateBookingRequest เพื่อส่งให้ Service
     let internal_request = InternalCreateBookingRequest {
@@ -44,187 +77,174 @@ pub async fn create_booking_handler(
         end_time: create_request.end_time,
     };
 
-    let booking_service = state.booking_service.clone(); // <--- เรียกจาก app_state โดยตรง
-
-    match booking_service.create_booking(internal_request).await { // ส่ง internal_request
-        Ok(booking) => (StatusCode::CREATED, Json(booking)).into_response(),
-        Err(e) => match e {
-            BookingServiceError::DbError(db_err) => {
-                eprintln!("Database error creating booking: {}", db_err);
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(json!({"error": "Database error creating booking."})),
-                ).into_response()
-            },
-            BookingServiceError::InvalidInput(msg) => {
-                eprintln!("Invalid input creating booking: {}", msg);
-                (
-                    StatusCode::BAD_REQUEST,
-                    Json(json!({"error": format!("Invalid input: {}", msg)})),
-                ).into_response()
-            },
-            BookingServiceError::NotFound => {
-                (
-                    StatusCode::NOT_FOUND,
-                    Json(json!({"error": "Room not found or unavailable."})),
-                ).into_response()
-            },
-            BookingServiceError::Conflict => {
-                (
-                    StatusCode::CONFLICT,
-                    Json(json!({"error": "Booking time conflict or room unavailable."})),
-                ).into_response()
-            }
-            BookingServiceError::Unauthorized => { // ไม่ควรเกิดขึ้นตรงนี้ถ้า logic ถูกต้อง
-                 (
-                    StatusCode::FORBIDDEN,
-                    Json(json!({"error": "Forbidden: Not authorized to create."})),
-                ).into_response()
-            }
-        },
-    }
+    let event = audit.pending(|booking: &Booking| AuditEvent::new("booking.create", "booking", booking.id).after(booking));
+    let booking = state.booking_service.create_booking(internal_request, event).await?; // ส่ง internal_request
+    Ok((StatusCode::CREATED, Json(booking)))
 }
 
 // Handler สำหรับดึงการจองทั้งหมดของผู้ใช้ (โดยใช้ user_id จาก JWT)
+#[utoipa::path(
+    get,
+    path = "/bookings/user",
+    tag = "bookings",
+    params(ListQuery),
+    security(("user_token" = []), ("admin_token" = []), ("session_cookie" = [])),
+    responses(
+        (status = 200, description = "Bookings of the logged-in user", body = Page<Booking>),
+        (status = 401, description = "Missing or invalid token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Insufficient permissions or not a user account", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid input", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
 pub async fn get_user_bookings_handler(
     Extension(state): Extension<Arc<AppState>>,
-    Extension(claims): Extension<Claims>,
-) -> impl IntoResponse {
-    let user_id_str = claims.sub;
-
-    let user_id = match user_id_str.parse::<i32>() {
-        Ok(id) => id,
-        Err(_) => {
-            eprintln!("Failed to parse user_id from claims: {}", user_id_str);
-            return (
-                StatusCode::BAD_REQUEST,
-                Json(json!({"error": "Invalid user ID format in token."})),
-            ).into_response();
-        }
-    };
+    principal: RequirePermission<perm::BookingsWrite>,
+    Query(query): Query<ListQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let user_id = principal.user_id()?;
 
-    let booking_service = state.booking_service.clone(); // <--- เรียกจาก app_state โดยตรง
-
-    match booking_service.get_bookings_by_user_id(user_id).await {
-        Ok(bookings) => (StatusCode::OK, Json(bookings)).into_response(),
-        Err(e) => {
-            eprintln!("Error getting user bookings: {:?}", e);
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({"error": "Failed to retrieve user bookings."})),
-            ).into_response()
-        }
-    }
+    let bookings = state.booking_service.get_bookings_by_user_id(user_id, query).await?;
+    Ok(Json(bookings))
 }
 
 // Handler สำหรับยกเลิกการจอง
+#[utoipa::path(
+    delete,
+    path = "/bookings/{id}",
+    tag = "bookings",
+    params(("id" = i32, Path, description = "Booking ID")),
+    security(("user_token" = []), ("admin_token" = []), ("session_cookie" = [])),
+    responses(
+        (status = 204, description = "Booking cancelled"),
+        (status = 401, description = "Missing or invalid token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Insufficient permissions", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Booking not found", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
 pub async fn cancel_booking_handler(
     Extension(state): Extension<Arc<AppState>>,
-    Extension(claims): Extension<Claims>,
+    principal: RequirePermission<perm::BookingsWrite>,
+    Audit(audit): Audit,
     Path(booking_id): Path<i32>,
-) -> impl IntoResponse {
-    let user_id_str = claims.sub;
-    let user_id = match user_id_str.parse::<i32>() {
-        Ok(id) => id,
-        Err(_) => {
-            eprintln!("Failed to parse user_id from claims: {}", user_id_str);
-            return (
-                StatusCode::BAD_REQUEST,
-                Json(json!({"error": "Invalid user ID format in token."})),
-            ).into_response();
-        }
+) -> Result<impl IntoResponse, AppError> {
+    // ผู้ที่มี bookings:manage ยกเลิกการจองของใครก็ได้ ผู้ใช้ทั่วไปยกเลิกได้เฉพาะของตัวเอง
+    let owner_id = if principal.has(Permission::BookingsManage) {
+        state.organization_service.ensure_booking(principal.tenant, booking_id).await?;
+        None
+    } else {
+        Some(principal.user_id()?)
     };
 
-    let booking_service = state.booking_service.clone(); // <--- เรียกจาก app_state โดยตรง
-
-    match booking_service.cancel_booking(booking_id, user_id).await {
-        Ok(success) => {
-            if success {
-                StatusCode::NO_CONTENT.into_response()
-            } else {
-                // ถ้า affected_rows เป็น 0 อาจจะหมายถึง booking ไม่เจอหรือไม่ใช่ของ user นี้
-                (
-                    StatusCode::NOT_FOUND, // หรือ Forbidden ถ้าไม่ใช่เจ้าของ
-                    Json(json!({"error": "Booking not found or not owned by user."})),
-                ).into_response()
-            }
-        },
-        Err(e) => match e {
-            BookingServiceError::DbError(db_err) => {
-                eprintln!("Database error canceling booking: {}", db_err);
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(json!({"error": "Database error canceling booking."})),
-                ).into_response()
-            },
-            BookingServiceError::NotFound => {
-                (
-                    StatusCode::NOT_FOUND,
-                    Json(json!({"error": "Booking not found."})),
-                ).into_response()
-            },
-            BookingServiceError::Unauthorized => { // ถ้า Service เช็คแล้วว่าไม่ได้รับอนุญาต
-                 (
-                    StatusCode::FORBIDDEN,
-                    Json(json!({"error": "Forbidden: You do not own this booking."})),
-                ).into_response()
-            },
-            _ => (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({"error": "Failed to cancel booking."})),
-            ).into_response()
-        },
-    }
+    // ถ้าไม่พบการจองหรือไม่ใช่ของ user นี้ Service จะคืน NotFound
+    let before = state.booking_service.find_booking(booking_id).await?;
+    let event = audit.pending(|after: &Booking| AuditEvent::new("booking.cancel", "booking", booking_id).before(&before).after(after));
+    state.booking_service.cancel_booking(principal.tenant, booking_id, owner_id, event).await?;
+    Ok(StatusCode::NO_CONTENT)
 }
 
 // Handler สำหรับดึงการจองทั้งหมด (สำหรับ Admin)
+#[utoipa::path(
+    get,
+    path = "/admin/bookings",
+    tag = "bookings",
+    params(ListQuery),
+    security(("admin_token" = []), ("session_cookie" = [])),
+    responses(
+        (status = 200, description = "Bookings of the organization's users and rooms (cancelled ones only with include_deleted=true)", body = Page<Booking>),
+        (status = 401, description = "Missing or invalid token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Insufficient permissions", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid input", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
 pub async fn get_all_bookings_handler(
     Extension(state): Extension<Arc<AppState>>,
-) -> impl IntoResponse {
-    let booking_service = state.booking_service.clone(); // <--- เรียกจาก app_state โดยตรง
+    principal: RequirePermission<perm::BookingsReadAll>,
+    Query(query): Query<ListQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let bookings = state.booking_service.get_all_bookings(principal.tenant, query).await?;
+    Ok(Json(bookings))
+}
 
-    match booking_service.get_all_bookings().await {
-        Ok(bookings) => (StatusCode::OK, Json(bookings)).into_response(),
-        Err(e) => {
-            eprintln!("Error getting all bookings: {:?}", e);
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({"error": "Failed to retrieve all bookings."})),
-            ).into_response()
-        }
-    }
+// Handler สำหรับ Admin บันทึกว่าผู้จองไม่มาใช้ห้อง (no-show)
+#[utoipa::path(
+    post,
+    path = "/admin/bookings/{booking_id}/no-show",
+    tag = "bookings",
+    params(("booking_id" = i32, Path, description = "Booking ID")),
+    security(("admin_token" = []), ("session_cookie" = [])),
+    responses(
+        (status = 200, description = "Booking marked as no-show", body = Booking),
+        (status = 401, description = "Missing or invalid token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Insufficient permissions", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Booking not found", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn mark_no_show_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    principal: RequirePermission<perm::BookingsManage>,
+    Audit(audit): Audit,
+    Path(booking_id): Path<i32>,
+) -> Result<impl IntoResponse, AppError> {
+    state.organization_service.ensure_booking(principal.tenant, booking_id).await?;
+    let before = state.booking_service.find_booking(booking_id).await?;
+    let event = audit.pending(|booking: &Booking| AuditEvent::new("booking.no_show", "booking", booking_id).before(&before).after(booking));
+    let booking = state.booking_service.mark_no_show(principal.tenant, booking_id, event).await?;
+    Ok(Json(booking))
 }
 
+#[utoipa::path(
+    delete,
+    path = "/admin/bookings/{booking_id}",
+    tag = "bookings",
+    params(("booking_id" = i32, Path, description = "Booking ID")),
+    security(("admin_token" = []), ("session_cookie" = [])),
+    responses(
+        (status = 200, description = "Booking cancelled by admin", body = Booking),
+        (status = 401, description = "Missing or invalid token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Insufficient permissions", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Booking not found", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
 pub async fn delete_booking_handler(
     Extension(state): Extension<Arc<AppState>>, // Extracts shared application state.
+    principal: RequirePermission<perm::BookingsManage>,
+    Audit(audit): Audit,
     Path(booking_id): Path<i32>, // Extracts the booking ID from the URL path.
-) -> impl IntoResponse {
-    let booking_service = state.booking_service.clone(); // Clones the booking service.
-
+) -> Result<impl IntoResponse, AppError> {
+    state.organization_service.ensure_booking(principal.tenant, booking_id).await?;
     // Calls the booking service to perform the soft deletion of the booking.
-    match booking_service.delete_booking(booking_id).await {
-        Ok(booking) => {
-            // On successful soft deletion, return 200 OK.
-            // Since the operation performs a soft delete and returns the updated Booking object,
-            // 200 OK is generally suitable. If no content were returned, 204 No Content would be preferred.
-            (StatusCode::OK, Json(booking)).into_response()
-        },
-        Err(e) => {
-            // Handle specific errors and return appropriate HTTP status codes.
-            let error_message = format!("Failed to delete booking: {}", e);
-            eprintln!("{}", error_message); // Log the error to stderr for debugging.
+    // Since the operation performs a soft delete and returns the updated Booking object,
+    // 200 OK is generally suitable. A missing booking is reported as 404 by AppError.
+    let before = state.booking_service.find_booking(booking_id).await?;
+    let event = audit.pending(|booking: &Booking| AuditEvent::new("booking.delete", "booking", booking_id).before(&before).after(booking));
+    let booking = state.booking_service.delete_booking(principal.tenant, booking_id, event).await?;
+    Ok(Json(booking))
+}
 
-            // Check the type of error to return the correct HTTP status code.
-            // It's recommended that `booking_service.delete_booking` returns a specific
-            // custom error enum (e.g., `ServiceError::NotFound`, `ServiceError::DatabaseError`)
-            // for more robust error handling instead of relying on string content.
-            if e.to_string().contains("NotFound") { // This is a coarse check; prefer custom error types.
-                (StatusCode::NOT_FOUND, error_message).into_response() // Return 404 if the booking isn't found.
-            } else {
-                // For other unhandled or unexpected errors (e.g., database issues),
-                // return a 500 Internal Server Error.
-                (StatusCode::INTERNAL_SERVER_ERROR, error_message).into_response()
-            }
-        }
-    }
-}
\ No newline at end of file
+// Handler สำหรับกู้คืนการจองที่ถูกยกเลิก (ต้องยังไม่จบ และห้องกับผู้จองต้องยังไม่ถูกลบ)
+#[utoipa::path(
+    post,
+    path = "/admin/bookings/{booking_id}/restore",
+    tag = "bookings",
+    params(("booking_id" = i32, Path, description = "Booking ID")),
+    security(("admin_token" = []), ("session_cookie" = [])),
+    responses(
+        (status = 200, description = "Booking restored", body = Booking),
+        (status = 401, description = "Missing or invalid token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Insufficient permissions", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Booking not found or not cancelled", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 409, description = "Booking already ended, its room or user is deleted, or the room is booked for an overlapping time", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn restore_booking_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    principal: RequirePermission<perm::BookingsManage>,
+    Audit(audit): Audit,
+    Path(booking_id): Path<i32>,
+) -> Result<impl IntoResponse, AppError> {
+    state.organization_service.ensure_booking(principal.tenant, booking_id).await?;
+    let before = state.booking_service.find_booking(booking_id).await?;
+    let event = audit.pending(|booking: &Booking| AuditEvent::new("booking.restore", "booking", booking_id).before(&before).after(booking));
+    let booking = state.booking_service.restore_booking(principal.tenant, booking_id, event).await?;
+    Ok(Json(booking))
+}
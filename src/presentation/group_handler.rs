@@ -0,0 +1,223 @@
+// src/presentation/group_handler.rs
+
+use std::sync::Arc;
+
+use axum::{extract::Path, http::StatusCode, response::IntoResponse, Extension, Json};
+
+use crate::app_state::AppState;
+use crate::domain::audit::AuditEvent;
+use crate::domain::group::{CreateGroupRequest, Group, UpdateGroupRequest};
+use crate::domain::permission::perm;
+use crate::domain::user::UserResponse;
+use crate::error::{AppError, ProblemDetails};
+use crate::middleware::audit::Audit;
+use crate::middleware::auth::RequirePermission;
+use crate::presentation::validation::ValidatedJson;
+
+// Handler สำหรับดูกลุ่มทั้งหมดขององค์กร
+#[utoipa::path(
+    get,
+    path = "/admin/groups",
+    tag = "admin",
+    security(("admin_token" = []), ("session_cookie" = [])),
+    responses(
+        (status = 200, description = "Groups of the organization", body = Vec<Group>),
+        (status = 401, description = "Missing or invalid token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Insufficient permissions", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn list_groups_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    principal: RequirePermission<perm::UsersRead>,
+) -> Result<impl IntoResponse, AppError> {
+    let groups = state.group_service.list(principal.tenant).await?;
+    Ok(Json(groups))
+}
+
+// Handler สำหรับสร้างกลุ่มใหม่
+#[utoipa::path(
+    post,
+    path = "/admin/groups",
+    tag = "admin",
+    request_body = CreateGroupRequest,
+    security(("admin_token" = []), ("session_cookie" = [])),
+    responses(
+        (status = 201, description = "Group created", body = Group),
+        (status = 401, description = "Missing or invalid token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Insufficient permissions", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 409, description = "Group name already taken in the organization", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid request body, or `organization_id` set by an organization admin or unknown", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn create_group_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    principal: RequirePermission<perm::UsersWrite>,
+    Audit(audit): Audit,
+    ValidatedJson(request): ValidatedJson<CreateGroupRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let event = audit.pending(|group: &Group| AuditEvent::new("group.create", "group", group.id).after(group));
+    let group = state.group_service.create(principal.tenant, request, event).await?;
+    Ok((StatusCode::CREATED, Json(group)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/admin/groups/{group_id}",
+    tag = "admin",
+    params(("group_id" = i32, Path, description = "Group ID")),
+    security(("admin_token" = []), ("session_cookie" = [])),
+    responses(
+        (status = 200, description = "Group", body = Group),
+        (status = 401, description = "Missing or invalid token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Insufficient permissions", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Group not found", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn get_group_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    principal: RequirePermission<perm::UsersRead>,
+    Path(group_id): Path<i32>,
+) -> Result<impl IntoResponse, AppError> {
+    let group = state.group_service.get(principal.tenant, group_id).await?;
+    Ok(Json(group))
+}
+
+// Handler สำหรับแก้ไขชื่อหรือคำอธิบายของกลุ่ม
+#[utoipa::path(
+    patch,
+    path = "/admin/groups/{group_id}",
+    tag = "admin",
+    params(("group_id" = i32, Path, description = "Group ID")),
+    request_body = UpdateGroupRequest,
+    security(("admin_token" = []), ("session_cookie" = [])),
+    responses(
+        (status = 200, description = "Group updated", body = Group),
+        (status = 401, description = "Missing or invalid token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Insufficient permissions", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Group not found", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 409, description = "Group name already taken in the organization", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid request body; `errors` lists every failing field", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn update_group_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    principal: RequirePermission<perm::UsersWrite>,
+    Audit(audit): Audit,
+    Path(group_id): Path<i32>,
+    ValidatedJson(request): ValidatedJson<UpdateGroupRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let before = state.group_service.get(principal.tenant, group_id).await?;
+    let event = audit.pending(|group: &Group| AuditEvent::new("group.update", "group", group_id).before(&before).after(group));
+    let group = state.group_service.update(principal.tenant, group_id, request, event).await?;
+    Ok(Json(group))
+}
+
+// Handler สำหรับลบกลุ่ม (กลุ่มที่ยังถูกใช้ในกฎการเข้าถึงห้องลบไม่ได้)
+#[utoipa::path(
+    delete,
+    path = "/admin/groups/{group_id}",
+    tag = "admin",
+    params(("group_id" = i32, Path, description = "Group ID")),
+    security(("admin_token" = []), ("session_cookie" = [])),
+    responses(
+        (status = 204, description = "Group and its memberships deleted"),
+        (status = 401, description = "Missing or invalid token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Insufficient permissions", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Group not found", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 409, description = "Group is still used by room access rules", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn delete_group_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    principal: RequirePermission<perm::UsersWrite>,
+    Audit(audit): Audit,
+    Path(group_id): Path<i32>,
+) -> Result<impl IntoResponse, AppError> {
+    let before = state.group_service.get(principal.tenant, group_id).await?;
+    let event = audit.pending(|_: &()| AuditEvent::new("group.delete", "group", group_id).before(&before));
+    state.group_service.delete(principal.tenant, group_id, event).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    get,
+    path = "/admin/groups/{group_id}/members",
+    tag = "admin",
+    params(("group_id" = i32, Path, description = "Group ID")),
+    security(("admin_token" = []), ("session_cookie" = [])),
+    responses(
+        (status = 200, description = "Members of the group", body = Vec<UserResponse>),
+        (status = 401, description = "Missing or invalid token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Insufficient permissions", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Group not found", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn list_group_members_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    principal: RequirePermission<perm::UsersRead>,
+    Path(group_id): Path<i32>,
+) -> Result<impl IntoResponse, AppError> {
+    let members = state.group_service.members(principal.tenant, group_id).await?;
+    Ok(Json(members.into_iter().map(UserResponse::from).collect::<Vec<_>>()))
+}
+
+// Handler สำหรับเพิ่มผู้ใช้เข้ากลุ่ม (เรียกซ้ำได้)
+#[utoipa::path(
+    put,
+    path = "/admin/groups/{group_id}/members/{user_id}",
+    tag = "admin",
+    params(
+        ("group_id" = i32, Path, description = "Group ID"),
+        ("user_id" = i32, Path, description = "User ID"),
+    ),
+    security(("admin_token" = []), ("session_cookie" = [])),
+    responses(
+        (status = 204, description = "User is a member of the group"),
+        (status = 401, description = "Missing or invalid token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Insufficient permissions", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Group or user not found", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "User belongs to another organization than the group", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn add_group_member_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    principal: RequirePermission<perm::UsersWrite>,
+    Audit(audit): Audit,
+    Path((group_id, user_id)): Path<(i32, i32)>,
+) -> Result<impl IntoResponse, AppError> {
+    state.organization_service.ensure_user(principal.tenant, user_id).await?;
+    let event = audit.pending(|_: &()| {
+        AuditEvent::new("group.member.add", "group", group_id).after(&serde_json::json!({ "user_id": user_id }))
+    });
+    state.group_service.add_member(principal.tenant, group_id, user_id, event).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    delete,
+    path = "/admin/groups/{group_id}/members/{user_id}",
+    tag = "admin",
+    params(
+        ("group_id" = i32, Path, description = "Group ID"),
+        ("user_id" = i32, Path, description = "User ID"),
+    ),
+    security(("admin_token" = []), ("session_cookie" = [])),
+    responses(
+        (status = 204, description = "User removed from the group"),
+        (status = 401, description = "Missing or invalid token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Insufficient permissions", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Group not found, or user is not a member", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn remove_group_member_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    principal: RequirePermission<perm::UsersWrite>,
+    Audit(audit): Audit,
+    Path((group_id, user_id)): Path<(i32, i32)>,
+) -> Result<impl IntoResponse, AppError> {
+    let event = audit.pending(|_: &()| {
+        AuditEvent::new("group.member.remove", "group", group_id).before(&serde_json::json!({ "user_id": user_id }))
+    });
+    state.group_service.remove_member(principal.tenant, group_id, user_id, event).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,132 @@
+// src/presentation/impersonation_handler.rs
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, Query},
+    http::StatusCode,
+    response::IntoResponse,
+    Extension, Json,
+};
+
+use crate::app_state::AppState;
+use crate::domain::audit::AuditEvent;
+use crate::domain::impersonation::{ImpersonateRequest, Impersonation, ImpersonationAction, ImpersonationResponse};
+use crate::domain::pagination::{ListQuery, Page};
+use crate::domain::permission::{perm, PrincipalKind};
+use crate::error::{AppError, ProblemDetails};
+use crate::middleware::audit::Audit;
+use crate::middleware::auth::RequirePermission;
+use crate::presentation::validation::ValidatedJson;
+
+// Handler สำหรับออก Token ที่ทำงานในนามผู้ใช้ (เฉพาะบัญชี Admin ที่ Login เอง ไม่รับ API Key)
+#[utoipa::path(
+    post,
+    path = "/admin/users/{user_id}/impersonate",
+    tag = "admin",
+    params(("user_id" = i32, Path, description = "User ID")),
+    request_body = ImpersonateRequest,
+    security(("admin_token" = []), ("session_cookie" = [])),
+    responses(
+        (status = 201, description = "Short-lived access token acting as the user", body = ImpersonationResponse),
+        (status = 401, description = "Missing or invalid token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Insufficient permissions, not an admin account, or an API key", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "User not found or deleted", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid request body; `errors` lists every failing field", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn impersonate_user_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    principal: RequirePermission<perm::UsersWrite>,
+    Audit(audit): Audit,
+    Path(user_id): Path<i32>,
+    ValidatedJson(request): ValidatedJson<ImpersonateRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    if principal.kind != PrincipalKind::Admin || principal.is_api_key() {
+        return Err(AppError::Forbidden(
+            "Only admins signed in with their own account can impersonate users.".to_string(),
+        ));
+    }
+    state.organization_service.ensure_user(principal.tenant, user_id).await?;
+    let user = state.user_service.get_user_by_id(user_id).await?;
+    let event = audit.pending(|impersonation: &Impersonation| {
+        AuditEvent::new("impersonation.start", "impersonation", impersonation.id).after(impersonation)
+    });
+    let response = state.impersonation_service.start(principal.id, &user, &request.reason, event).await?;
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+// Handler สำหรับดูประวัติการสวมสิทธิ์ (ล่าสุดก่อนเป็นค่าเริ่มต้น กรองด้วย user_id, from, to)
+#[utoipa::path(
+    get,
+    path = "/admin/impersonations",
+    tag = "admin",
+    params(ListQuery),
+    security(("admin_token" = []), ("session_cookie" = [])),
+    responses(
+        (status = 200, description = "Impersonations of users in the organization", body = Page<Impersonation>),
+        (status = 401, description = "Missing or invalid token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Insufficient permissions", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid input", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn list_impersonations_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    principal: RequirePermission<perm::UsersRead>,
+    Query(query): Query<ListQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let impersonations = state.impersonation_service.list(principal.tenant, query).await?;
+    Ok(Json(impersonations))
+}
+
+// Handler สำหรับดูทุก Request ที่ทำระหว่างการสวมสิทธิ์
+#[utoipa::path(
+    get,
+    path = "/admin/impersonations/{impersonation_id}/actions",
+    tag = "admin",
+    params(("impersonation_id" = i32, Path, description = "Impersonation ID")),
+    security(("admin_token" = []), ("session_cookie" = [])),
+    responses(
+        (status = 200, description = "Requests made with the impersonation token, oldest first", body = Vec<ImpersonationAction>),
+        (status = 401, description = "Missing or invalid token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Insufficient permissions", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Impersonation not found", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn list_impersonation_actions_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    principal: RequirePermission<perm::UsersRead>,
+    Path(impersonation_id): Path<i32>,
+) -> Result<impl IntoResponse, AppError> {
+    let actions = state.impersonation_service.actions(principal.tenant, impersonation_id).await?;
+    Ok(Json(actions))
+}
+
+// Handler สำหรับจบการสวมสิทธิ์ก่อนหมดอายุ (Token ใช้ไม่ได้ทันที)
+#[utoipa::path(
+    delete,
+    path = "/admin/impersonations/{impersonation_id}",
+    tag = "admin",
+    params(("impersonation_id" = i32, Path, description = "Impersonation ID")),
+    security(("admin_token" = []), ("session_cookie" = [])),
+    responses(
+        (status = 200, description = "Impersonation ended", body = Impersonation),
+        (status = 401, description = "Missing or invalid token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Insufficient permissions", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Impersonation not found", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 409, description = "Impersonation already ended or expired", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn end_impersonation_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    principal: RequirePermission<perm::UsersWrite>,
+    Audit(audit): Audit,
+    Path(impersonation_id): Path<i32>,
+) -> Result<impl IntoResponse, AppError> {
+    let before = state.impersonation_service.get(principal.tenant, impersonation_id).await?;
+    let event = audit.pending(|impersonation: &Impersonation| {
+        AuditEvent::new("impersonation.end", "impersonation", impersonation_id).before(&before).after(impersonation)
+    });
+    let impersonation = state.impersonation_service.end(principal.tenant, impersonation_id, event).await?;
+    Ok(Json(impersonation))
+}
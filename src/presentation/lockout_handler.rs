@@ -0,0 +1,86 @@
+// src/presentation/lockout_handler.rs
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, Query}, http::StatusCode, response::IntoResponse, Extension, Json
+};
+
+use crate::{
+    app_state::AppState,
+    domain::audit::AuditEvent,
+    domain::login_throttle::{LoginEvent, LoginEventQuery, LoginThrottle},
+    domain::permission::perm,
+    error::{AppError, ProblemDetails},
+    middleware::{audit::Audit, auth::RequirePermission},
+};
+
+// Handler สำหรับดู username/IP ที่ถูกล็อกอยู่หรือมีการ Login ผิดที่ยังนับอยู่
+#[utoipa::path(
+    get,
+    path = "/admin/lockouts",
+    tag = "admin",
+    security(("admin_token" = []), ("session_cookie" = [])),
+    responses(
+        (status = 200, description = "Active lockouts and failure counters", body = Vec<LoginThrottle>),
+        (status = 401, description = "Missing or invalid token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Insufficient permissions, or not a platform admin", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn list_lockouts_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    principal: RequirePermission<perm::UsersRead>,
+) -> Result<impl IntoResponse, AppError> {
+    principal.require_platform()?;
+    let lockouts = state.login_throttle_service.list_lockouts().await?;
+    Ok(Json(lockouts))
+}
+
+// Handler สำหรับปลดล็อก (ล้างตัวนับการ Login ผิดของ username/IP)
+#[utoipa::path(
+    delete,
+    path = "/admin/lockouts/{lockout_id}",
+    tag = "admin",
+    params(("lockout_id" = i32, Path, description = "Lockout ID")),
+    security(("admin_token" = []), ("session_cookie" = [])),
+    responses(
+        (status = 204, description = "Lockout cleared"),
+        (status = 401, description = "Missing or invalid token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Insufficient permissions, or not a platform admin", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Lockout not found", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn clear_lockout_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    principal: RequirePermission<perm::UsersWrite>,
+    Audit(audit): Audit,
+    Path(lockout_id): Path<i32>,
+) -> Result<impl IntoResponse, AppError> {
+    principal.require_platform()?;
+    let event = audit.pending(|_: &()| AuditEvent::new("lockout.clear", "lockout", lockout_id));
+    state.login_throttle_service.clear_lockout(lockout_id, event).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// Handler สำหรับดูประวัติการ Login (ล่าสุดก่อน)
+#[utoipa::path(
+    get,
+    path = "/admin/login-events",
+    tag = "admin",
+    params(LoginEventQuery),
+    security(("admin_token" = []), ("session_cookie" = [])),
+    responses(
+        (status = 200, description = "Login events, newest first", body = Vec<LoginEvent>),
+        (status = 401, description = "Missing or invalid token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Insufficient permissions, or not a platform admin", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn list_login_events_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    principal: RequirePermission<perm::UsersRead>,
+    Query(query): Query<LoginEventQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    principal.require_platform()?;
+    let events = state.login_throttle_service.list_events(query).await?;
+    Ok(Json(events))
+}
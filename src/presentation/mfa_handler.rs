@@ -0,0 +1,298 @@
+// src/presentation/mfa_handler.rs
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, Query},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Extension, Json,
+};
+use axum_extra::extract::cookie::CookieJar;
+
+use crate::app_state::AppState;
+use crate::domain::audit::{AuditActor, AuditContext, AuditEvent, PendingAudit};
+use crate::domain::mfa::{
+    MfaEnrollRequest, MfaSessionResponse, MfaStatusResponse, MfaTokensResponse, MfaVerifyRequest,
+    RecoveryCodesResponse, TotpCodeRequest, TotpConfirmRequest, TotpEnrollmentResponse,
+};
+use crate::domain::permission::{perm, PrincipalKind};
+use crate::error::{AppError, ProblemDetails};
+use crate::middleware::audit::Audit;
+use crate::middleware::auth::{Authenticated, Interactive, RequirePermission};
+use crate::middleware::client_ip::ClientIp;
+use crate::middleware::session::{LoginMode, LoginQuery};
+use crate::presentation::validation::ValidatedJson;
+
+// Handler สำหรับดูสถานะ 2FA ของบัญชีตัวเอง
+#[utoipa::path(
+    get,
+    path = "/auth/mfa",
+    tag = "auth",
+    security(("user_token" = []), ("admin_token" = []), ("session_cookie" = [])),
+    responses(
+        (status = 200, description = "Two-factor authentication status", body = MfaStatusResponse),
+        (status = 401, description = "Missing or invalid token", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn mfa_status_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    Authenticated(principal): Authenticated,
+) -> Result<impl IntoResponse, AppError> {
+    let status = state.mfa_service.status(principal.kind, principal.id).await?;
+    Ok(Json(status))
+}
+
+// Handler สำหรับเริ่มลงทะเบียน Authenticator App (ยืนยันด้วย POST /auth/mfa/totp/confirm)
+#[utoipa::path(
+    post,
+    path = "/auth/mfa/totp",
+    tag = "auth",
+    security(("user_token" = []), ("admin_token" = []), ("session_cookie" = [])),
+    responses(
+        (status = 200, description = "TOTP secret and otpauth URI for the authenticator app", body = TotpEnrollmentResponse),
+        (status = 401, description = "Missing or invalid token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Authenticated with an API key", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 409, description = "Two-factor authentication is already enabled", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn start_totp_enrollment_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    Interactive(principal): Interactive,
+    Audit(audit): Audit,
+) -> Result<impl IntoResponse, AppError> {
+    let event = mfa_change(&audit, "mfa.totp.enroll", principal.kind, principal.id, None);
+    let enrollment = state.mfa_service.start_enrollment(principal.kind, principal.id, event).await?;
+    Ok(Json(enrollment))
+}
+
+// Handler สำหรับยืนยันการลงทะเบียนด้วยรหัสแรก (ได้ Recovery Code ชุดแรก)
+#[utoipa::path(
+    post,
+    path = "/auth/mfa/totp/confirm",
+    tag = "auth",
+    request_body = TotpConfirmRequest,
+    security(("user_token" = []), ("admin_token" = []), ("session_cookie" = [])),
+    responses(
+        (status = 200, description = "Two-factor authentication enabled; recovery codes are shown once", body = RecoveryCodesResponse),
+        (status = 401, description = "Missing or invalid token, or wrong code", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Authenticated with an API key", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "No pending enrollment", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 409, description = "Two-factor authentication is already enabled", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid request body", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn confirm_totp_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    Interactive(principal): Interactive,
+    Audit(audit): Audit,
+    ValidatedJson(request): ValidatedJson<TotpConfirmRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let event = mfa_change(&audit, "mfa.totp.enable", principal.kind, principal.id, None);
+    let recovery_codes = state
+        .mfa_service
+        .confirm_enrollment(principal.kind, principal.id, &request.code, event)
+        .await?;
+    Ok(Json(RecoveryCodesResponse { recovery_codes }))
+}
+
+// Handler สำหรับปิด 2FA ของตัวเอง (ต้องส่งรหัส TOTP หรือ Recovery Code)
+#[utoipa::path(
+    delete,
+    path = "/auth/mfa/totp",
+    tag = "auth",
+    request_body = TotpCodeRequest,
+    security(("user_token" = []), ("admin_token" = []), ("session_cookie" = [])),
+    responses(
+        (status = 204, description = "Two-factor authentication disabled"),
+        (status = 401, description = "Missing or invalid token, or wrong code", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Two-factor authentication is required for the account's role, or authenticated with an API key", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Two-factor authentication is not enabled", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn disable_totp_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    Interactive(principal): Interactive,
+    Audit(audit): Audit,
+    ValidatedJson(request): ValidatedJson<TotpCodeRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let event = mfa_change(&audit, "mfa.totp.disable", principal.kind, principal.id, None);
+    state.mfa_service.disable(principal.kind, principal.id, request, event).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// Handler สำหรับสร้าง Recovery Code ชุดใหม่ (ชุดเดิมใช้ไม่ได้อีก)
+#[utoipa::path(
+    post,
+    path = "/auth/mfa/recovery-codes",
+    tag = "auth",
+    request_body = TotpCodeRequest,
+    security(("user_token" = []), ("admin_token" = []), ("session_cookie" = [])),
+    responses(
+        (status = 200, description = "New recovery codes; shown once", body = RecoveryCodesResponse),
+        (status = 401, description = "Missing or invalid token, or wrong code", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Authenticated with an API key", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Two-factor authentication is not enabled", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn regenerate_recovery_codes_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    Interactive(principal): Interactive,
+    Audit(audit): Audit,
+    ValidatedJson(request): ValidatedJson<TotpCodeRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let event = mfa_change(&audit, "mfa.recovery_codes.regenerate", principal.kind, principal.id, None);
+    let recovery_codes = state
+        .mfa_service
+        .regenerate_recovery_codes(principal.kind, principal.id, request, event)
+        .await?;
+    Ok(Json(RecoveryCodesResponse { recovery_codes }))
+}
+
+// Handler สำหรับลงทะเบียน Authenticator ระหว่าง Login (เมื่อ Login ตอบ enrollment_required)
+#[utoipa::path(
+    post,
+    path = "/auth/mfa/enroll",
+    tag = "auth",
+    request_body = MfaEnrollRequest,
+    responses(
+        (status = 200, description = "TOTP secret and otpauth URI; finish with POST /auth/mfa/verify", body = TotpEnrollmentResponse),
+        (status = 401, description = "Challenge token invalid or expired", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 409, description = "Two-factor authentication is already enabled", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid request body", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn enroll_mfa_challenge_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    Audit(audit): Audit,
+    ValidatedJson(request): ValidatedJson<MfaEnrollRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let (kind, id, _) = state.mfa_service.challenge_account(&request.challenge_token).await?;
+    let actor = account_actor(&state, kind, id).await?;
+    let event = mfa_change(&audit, "mfa.totp.enroll", kind, id, Some(actor));
+    let enrollment = state.mfa_service.enroll_with_challenge(&request.challenge_token, event).await?;
+    Ok(Json(enrollment))
+}
+
+// Handler สำหรับ Login ขั้นที่สอง: ยืนยันรหัส 2FA แล้วออก Access Token + Refresh Token
+#[utoipa::path(
+    post,
+    path = "/auth/mfa/verify",
+    tag = "auth",
+    request_body = MfaVerifyRequest,
+    params(LoginQuery),
+    responses(
+        (status = 200, description = "Logged in. With `?mode=cookie` the tokens are set as HttpOnly cookies and the body is a session response", body = MfaTokensResponse),
+        (status = 401, description = "Challenge token invalid, expired or used up, or wrong code", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid request body", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 429, description = "Too many failed attempts; retry after the `Retry-After` seconds", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn verify_mfa_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    Audit(audit): Audit,
+    Query(login): Query<LoginQuery>,
+    ClientIp(ip): ClientIp,
+    jar: CookieJar,
+    ValidatedJson(request): ValidatedJson<MfaVerifyRequest>,
+) -> Result<Response, AppError> {
+    // รหัสผิดนับรวมกับการ Login ผิดของบัญชี (ขอ Challenge ใหม่ไม่ได้ล้างตัวนับ)
+    let throttle = &state.login_throttle_service;
+    let (kind, id, username) = state.mfa_service.challenge_account(&request.challenge_token).await?;
+    throttle.check(kind.as_str(), &username, &ip).await?;
+    // บันทึกเฉพาะเมื่อ Challenge นี้ลงทะเบียน 2FA เสร็จ
+    let actor = account_actor(&state, kind, id).await?;
+    let event = mfa_change(&audit, "mfa.totp.enable", kind, id, Some(actor));
+    let result = state.mfa_service.verify_challenge(request, event).await;
+    let verified = throttle.record_mfa(kind.as_str(), &username, &ip, result).await?;
+    let tokens = state.auth_service.issue_tokens(verified.id, verified.kind.as_str()).await?;
+
+    if login.mode.unwrap_or_default() == LoginMode::Cookie {
+        let (jar, session) = state.session_cookies.start(jar, tokens);
+        let body = MfaSessionResponse { session, recovery_codes: verified.recovery_codes };
+        return Ok((jar, Json(body)).into_response());
+    }
+    Ok(Json(MfaTokensResponse { tokens, recovery_codes: verified.recovery_codes }).into_response())
+}
+
+// Handler สำหรับล้าง 2FA ของผู้ใช้ (เช่น ทำอุปกรณ์และ Recovery Code หาย)
+#[utoipa::path(
+    delete,
+    path = "/admin/users/{user_id}/mfa",
+    tag = "admin",
+    params(("user_id" = i32, Path, description = "User ID")),
+    security(("admin_token" = []), ("session_cookie" = [])),
+    responses(
+        (status = 204, description = "Two-factor authentication removed"),
+        (status = 401, description = "Missing or invalid token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Insufficient permissions", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "User not found, or two-factor authentication is not enabled", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn reset_user_mfa_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    principal: RequirePermission<perm::UsersWrite>,
+    Audit(audit): Audit,
+    Path(user_id): Path<i32>,
+) -> Result<impl IntoResponse, AppError> {
+    state.organization_service.ensure_user(principal.tenant, user_id).await?;
+    let event = mfa_change(&audit, "mfa.reset", PrincipalKind::User, user_id, None);
+    state.mfa_service.reset(PrincipalKind::User, user_id, event).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// Handler สำหรับล้าง 2FA ของ Admin คนอื่น
+#[utoipa::path(
+    delete,
+    path = "/admin/admins/{admin_id}/mfa",
+    tag = "admin",
+    params(("admin_id" = i32, Path, description = "Admin ID")),
+    security(("admin_token" = []), ("session_cookie" = [])),
+    responses(
+        (status = 204, description = "Two-factor authentication removed"),
+        (status = 401, description = "Missing or invalid token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Insufficient permissions, or resetting your own account", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Admin not found, or two-factor authentication is not enabled", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn reset_admin_mfa_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    principal: RequirePermission<perm::AdminsManage>,
+    Audit(audit): Audit,
+    Path(admin_id): Path<i32>,
+) -> Result<impl IntoResponse, AppError> {
+    state.organization_service.ensure_admin(principal.tenant, admin_id).await?;
+    // ล้าง 2FA ของตัวเองผ่านทางนี้ไม่ได้ (ใช้ DELETE /auth/mfa/totp ซึ่งต้องยืนยันด้วยรหัส)
+    if principal.kind == PrincipalKind::Admin && principal.id == admin_id {
+        return Err(AppError::Forbidden(
+            "Use DELETE /auth/mfa/totp to disable your own two-factor authentication.".to_string(),
+        ));
+    }
+    let event = mfa_change(&audit, "mfa.reset", PrincipalKind::Admin, admin_id, None);
+    state.mfa_service.reset(PrincipalKind::Admin, admin_id, event).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// การเปลี่ยนแปลง 2FA พร้อมสถานะหลังเปลี่ยน (ไม่เก็บ Secret หรือ Recovery Code)
+// actor ใช้กับ Endpoint ที่ยืนยันตัวตนด้วย Challenge Token
+fn mfa_change<'a>(
+    audit: &'a AuditContext,
+    action: &'static str,
+    kind: PrincipalKind,
+    id: i32,
+    actor: Option<AuditActor>,
+) -> PendingAudit<'a, MfaStatusResponse> {
+    audit.pending(move |status: &MfaStatusResponse| {
+        let event = AuditEvent::new(action, kind.as_str(), id).after(status);
+        match actor {
+            Some(actor) => event.by(actor),
+            None => event,
+        }
+    })
+}
+
+// ผู้กระทำของ Endpoint ที่ยืนยันตัวตนด้วย Challenge Token แทน Access Token
+async fn account_actor(state: &AppState, kind: PrincipalKind, id: i32) -> Result<AuditActor, AppError> {
+    let organization_id = state.organization_service.organization_of(kind, id).await?;
+    Ok(AuditActor::account(kind, id, organization_id))
+}
@@ -3,4 +3,21 @@ pub mod user_handler;
 pub mod admin_handler;
 pub mod booking_handler;
 pub mod test_handler;
-pub mod admin_user_handler;
\ No newline at end of file
+pub mod admin_user_handler;
+pub mod report_handler;
+pub mod auth_handler;
+pub mod openapi;
+pub mod validation;
+pub mod role_handler;
+pub mod password_handler;
+pub mod lockout_handler;
+pub mod mfa_handler;
+pub mod api_key_handler;
+pub mod oidc_handler;
+pub mod profile_handler;
+pub mod privacy_handler;
+pub mod organization_handler;
+pub mod group_handler;
+pub mod impersonation_handler;
+pub mod audit_handler;
+pub mod router;
\ No newline at end of file
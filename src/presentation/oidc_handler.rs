@@ -0,0 +1,103 @@
+// src/presentation/oidc_handler.rs
+
+use std::sync::Arc;
+
+use axum::{
+    extract::Query,
+    response::{IntoResponse, Redirect, Response},
+    Extension, Json,
+};
+use axum_extra::extract::cookie::CookieJar;
+
+use crate::app_state::AppState;
+use crate::application::oidc_service::OidcService;
+use crate::domain::oidc::OidcCallbackQuery;
+use crate::domain::permission::PrincipalKind;
+use crate::error::{AppError, ProblemDetails};
+use crate::middleware::audit::Audit;
+use crate::middleware::client_ip::ClientIp;
+use crate::middleware::session::{LoginMode, LoginQuery};
+use crate::presentation::user_handler::LoginResponse;
+
+fn oidc_service(state: &AppState) -> Result<&OidcService, AppError> {
+    state
+        .oidc_service
+        .as_ref()
+        .ok_or_else(|| AppError::NotFound("Single sign-on is not configured.".to_string()))
+}
+
+// Handler สำหรับเริ่ม Login ผ่าน Identity Provider (Redirect ไปหน้า Login ของ Identity Provider)
+#[utoipa::path(
+    get,
+    path = "/auth/oidc/login",
+    tag = "auth",
+    params(LoginQuery),
+    responses(
+        (status = 303, description = "Redirect to the identity provider's authorization endpoint"),
+        (status = 404, description = "Single sign-on is not configured", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 502, description = "The identity provider could not be reached", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn oidc_login_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    Query(login): Query<LoginQuery>,
+) -> Result<Redirect, AppError> {
+    let url = oidc_service(&state)?.start_login(login.mode.unwrap_or_default()).await?;
+    Ok(Redirect::to(&url))
+}
+
+// Handler สำหรับ Callback จาก Identity Provider (Redirect URI)
+// สร้างผู้ใช้ใหม่เมื่อ Login ครั้งแรก ซิงก์ Role ตามกลุ่ม แล้วออก Token เหมือน POST /login/user
+#[utoipa::path(
+    get,
+    path = "/auth/oidc/callback",
+    tag = "auth",
+    params(OidcCallbackQuery),
+    responses(
+        (status = 200, description = "Logged in. Cookie logins get a SessionResponse; accounts with two-factor authentication get an MfaChallengeResponse", body = LoginResponse),
+        (status = 303, description = "Cookie login with OIDC_POST_LOGIN_URL configured: cookies are set and the browser is redirected there"),
+        (status = 401, description = "Invalid or expired state, rejected code, or invalid ID token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "The account was deleted, the identity provider sent no usable username, or the organization is missing or unknown", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 409, description = "The username is already used by a local account", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 429, description = "Too many wrong two-factor codes; retry after the `Retry-After` seconds", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 502, description = "The identity provider could not be reached", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn oidc_callback_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    Query(query): Query<OidcCallbackQuery>,
+    ClientIp(ip): ClientIp,
+    Audit(audit): Audit,
+    jar: CookieJar,
+) -> Result<Response, AppError> {
+    let oidc = oidc_service(&state)?;
+    let login = oidc.complete_login(query, &audit).await?;
+    let user_id = login.user_id;
+    let throttle = &state.login_throttle_service;
+
+    // 2FA ของระบบยังมีผลกับผู้ใช้ที่ Login ผ่าน Identity Provider
+    // บัญชีที่ถูกล็อกจากการใส่รหัส 2FA ผิดจะขอ Challenge ใหม่ไม่ได้จนกว่าจะหมดเวลา
+    throttle.check("user", &login.username, &ip).await?;
+    if let Some(challenge) = state.mfa_service.login_challenge(PrincipalKind::User, user_id).await? {
+        return Ok(Json(challenge).into_response());
+    }
+    throttle.record_success("user", &login.username, &ip).await?;
+
+    let tokens = state.auth_service.issue_tokens(user_id, "user").await?;
+    if login.mode == LoginMode::Cookie {
+        let (jar, session) = state.session_cookies.start(jar, tokens);
+        return Ok(match oidc.post_login_url() {
+            Some(url) => (jar, Redirect::to(url)).into_response(),
+            None => (jar, Json(session)).into_response(),
+        });
+    }
+    Ok(Json(LoginResponse {
+        user_id,
+        token: tokens.token,
+        role: tokens.role,
+        expires_in: tokens.expires_in,
+        refresh_token: tokens.refresh_token,
+        refresh_expires_in: tokens.refresh_expires_in,
+    })
+    .into_response())
+}
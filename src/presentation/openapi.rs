@@ -0,0 +1,172 @@
+// src/presentation/openapi.rs
+
+use axum::{response::Html, Json};
+use utoipa::{
+    openapi::security::{ApiKey, ApiKeyValue, Http, HttpAuthScheme, SecurityScheme},
+    Modify, OpenApi,
+};
+use utoipa_scalar::Scalar;
+
+use crate::domain::auth::SessionResponse;
+use crate::domain::mfa::{MfaChallengeResponse, MfaSessionResponse};
+use crate::middleware::auth::API_KEY_HEADER;
+use crate::middleware::session::SESSION_COOKIE;
+use crate::presentation::{
+    admin_handler, admin_user_handler, api_key_handler, audit_handler, auth_handler, booking_handler, lockout_handler, group_handler, impersonation_handler, mfa_handler, oidc_handler, organization_handler, password_handler, privacy_handler, profile_handler,
+    report_handler, role_handler, room_handler, test_handler, user_handler,
+};
+
+// ApiDoc: เอกสาร OpenAPI 3 ที่สร้างจาก #[utoipa::path] ของแต่ละ handler
+// เมื่อเพิ่ม handler ใหม่ต้องเพิ่มลงใน `paths(...)` ด้วย
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "Room Booking API", description = "Room, user and booking management API."),
+    paths(
+        user_handler::register_user_handler,
+        user_handler::login_user_handler,
+        admin_handler::setup_admin_handler,
+        admin_handler::login_admin_handler,
+        admin_handler::accept_invitation_handler,
+        admin_handler::create_invitation_handler,
+        admin_handler::list_invitations_handler,
+        admin_handler::revoke_invitation_handler,
+        auth_handler::refresh_handler,
+        auth_handler::logout_handler,
+        auth_handler::jwks_handler,
+        password_handler::change_password_handler,
+        password_handler::request_password_reset_handler,
+        password_handler::confirm_password_reset_handler,
+        oidc_handler::oidc_login_handler,
+        oidc_handler::oidc_callback_handler,
+        mfa_handler::verify_mfa_handler,
+        mfa_handler::enroll_mfa_challenge_handler,
+        mfa_handler::mfa_status_handler,
+        mfa_handler::start_totp_enrollment_handler,
+        mfa_handler::confirm_totp_handler,
+        mfa_handler::disable_totp_handler,
+        mfa_handler::regenerate_recovery_codes_handler,
+        profile_handler::get_profile_handler,
+        profile_handler::update_profile_handler,
+        profile_handler::resend_email_verification_handler,
+        profile_handler::confirm_email_verification_handler,
+        privacy_handler::export_my_data_handler,
+        privacy_handler::erase_my_account_handler,
+        api_key_handler::list_api_keys_handler,
+        api_key_handler::create_api_key_handler,
+        api_key_handler::revoke_api_key_handler,
+        room_handler::get_all_room_handler,
+        room_handler::get_all_active_rooms_handler,
+        room_handler::get_room_by_id_handler,
+        room_handler::add_room_handler,
+        room_handler::update_room_handler,
+        room_handler::delete_room_handler,
+        room_handler::restore_room_handler,
+        room_handler::get_room_shares_handler,
+        room_handler::set_room_shares_handler,
+        room_handler::get_room_access_handler,
+        room_handler::set_room_access_handler,
+        booking_handler::create_booking_handler,
+        booking_handler::get_user_bookings_handler,
+        booking_handler::cancel_booking_handler,
+        booking_handler::get_all_bookings_handler,
+        booking_handler::mark_no_show_handler,
+        booking_handler::delete_booking_handler,
+        booking_handler::restore_booking_handler,
+        admin_user_handler::get_all_users_handler,
+        admin_user_handler::get_user_by_id_handler,
+        admin_user_handler::delete_user_by_admin_handler,
+        admin_user_handler::restore_user_handler,
+        admin_user_handler::verify_user_email_handler,
+        privacy_handler::erase_user_handler,
+        privacy_handler::list_erasure_requests_handler,
+        organization_handler::list_organizations_handler,
+        organization_handler::create_organization_handler,
+        organization_handler::update_organization_handler,
+        organization_handler::set_user_organization_handler,
+        impersonation_handler::impersonate_user_handler,
+        impersonation_handler::list_impersonations_handler,
+        impersonation_handler::list_impersonation_actions_handler,
+        impersonation_handler::end_impersonation_handler,
+        group_handler::list_groups_handler,
+        group_handler::create_group_handler,
+        group_handler::get_group_handler,
+        group_handler::update_group_handler,
+        group_handler::delete_group_handler,
+        group_handler::list_group_members_handler,
+        group_handler::add_group_member_handler,
+        group_handler::remove_group_member_handler,
+        role_handler::list_roles_handler,
+        role_handler::set_role_mfa_policy_handler,
+        role_handler::get_user_roles_handler,
+        role_handler::assign_user_role_handler,
+        role_handler::remove_user_role_handler,
+        lockout_handler::list_lockouts_handler,
+        lockout_handler::clear_lockout_handler,
+        lockout_handler::list_login_events_handler,
+        audit_handler::list_audit_handler,
+        audit_handler::verify_audit_handler,
+        mfa_handler::reset_user_mfa_handler,
+        mfa_handler::reset_admin_mfa_handler,
+        report_handler::room_utilization_handler,
+        report_handler::peak_hours_handler,
+        report_handler::booking_rates_handler,
+        report_handler::top_bookers_handler,
+        test_handler::test_protected_user_route,
+        test_handler::test_protected_admin_route,
+    ),
+    components(schemas(SessionResponse, MfaChallengeResponse, MfaSessionResponse)),
+    modifiers(&SecurityAddon),
+    tags(
+        (name = "auth", description = "Registration, login, token refresh and logout"),
+        (name = "rooms", description = "Room management"),
+        (name = "bookings", description = "Room bookings"),
+        (name = "admin", description = "User, role, permission, group and organization administration"),
+        (name = "reports", description = "Utilization and booking analytics"),
+    )
+)]
+pub struct ApiDoc;
+
+// เพิ่ม Security Scheme สำหรับ Bearer Token ของ User และ Admin
+// ทั้งสองเป็น JWT จาก /login/user และ /login/admin ตามลำดับ
+struct SecurityAddon;
+
+impl Modify for SecurityAddon {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        for (name, description) in [
+            ("user_token", "JWT issued by POST /login/user"),
+            ("admin_token", "JWT issued by POST /login/admin"),
+        ] {
+            let mut scheme = Http::new(HttpAuthScheme::Bearer);
+            scheme.bearer_format = Some("JWT".to_string());
+            scheme.description = Some(description.to_string());
+            components.add_security_scheme(name, SecurityScheme::Http(scheme));
+        }
+        // Cookie Session จาก `?mode=cookie` (Request ที่เปลี่ยนแปลงข้อมูลต้องส่ง X-CSRF-Token ด้วย)
+        components.add_security_scheme(
+            "session_cookie",
+            SecurityScheme::ApiKey(ApiKey::Cookie(ApiKeyValue::with_description(
+                SESSION_COOKIE,
+                "Access token cookie set by login with ?mode=cookie; state-changing requests also need the X-CSRF-Token header",
+            ))),
+        );
+        // API Key ส่วนตัวจาก POST /users/me/api-keys (ส่งเป็น Bearer ก็ได้)
+        components.add_security_scheme(
+            "api_key",
+            SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::with_description(
+                API_KEY_HEADER,
+                "Personal API key created with POST /users/me/api-keys; may also be sent as `Authorization: Bearer rbk_...`",
+            ))),
+        );
+    }
+}
+
+// Handler สำหรับดึงเอกสาร OpenAPI (GET /openapi.json)
+pub async fn openapi_json_handler() -> Json<utoipa::openapi::OpenApi> {
+    Json(ApiDoc::openapi())
+}
+
+// Handler สำหรับหน้าเอกสาร API แบบ Interactive (GET /docs)
+pub async fn docs_handler() -> Html<String> {
+    Html(Scalar::new(ApiDoc::openapi()).to_html())
+}
@@ -0,0 +1,135 @@
+// src/presentation/organization_handler.rs
+
+use std::sync::Arc;
+
+use axum::{extract::Path, http::StatusCode, response::IntoResponse, Extension, Json};
+
+use crate::app_state::AppState;
+use crate::domain::audit::{AuditEvent, UserAuditSnapshot};
+use crate::domain::organization::{
+    CreateOrganizationRequest, Organization, SetOrganizationRequest, UpdateOrganizationRequest,
+};
+use crate::domain::permission::perm;
+use crate::domain::user::{User, UserResponse};
+use crate::error::{AppError, ProblemDetails};
+use crate::middleware::audit::Audit;
+use crate::middleware::auth::RequirePermission;
+use crate::presentation::validation::ValidatedJson;
+
+// Handler สำหรับดูองค์กรทั้งหมด (Platform Admin เท่านั้น)
+#[utoipa::path(
+    get,
+    path = "/admin/organizations",
+    tag = "admin",
+    security(("admin_token" = []), ("session_cookie" = [])),
+    responses(
+        (status = 200, description = "Organizations", body = Vec<Organization>),
+        (status = 401, description = "Missing or invalid token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Insufficient permissions, or not a platform admin", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn list_organizations_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    principal: RequirePermission<perm::AdminsManage>,
+) -> Result<impl IntoResponse, AppError> {
+    principal.require_platform()?;
+    let organizations = state.organization_service.list().await?;
+    Ok(Json(organizations))
+}
+
+// Handler สำหรับสร้างองค์กรใหม่
+#[utoipa::path(
+    post,
+    path = "/admin/organizations",
+    tag = "admin",
+    request_body = CreateOrganizationRequest,
+    security(("admin_token" = []), ("session_cookie" = [])),
+    responses(
+        (status = 201, description = "Organization created", body = Organization),
+        (status = 401, description = "Missing or invalid token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Insufficient permissions, or not a platform admin", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 409, description = "Organization name or slug already taken", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid request body; `errors` lists every failing field", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn create_organization_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    principal: RequirePermission<perm::AdminsManage>,
+    Audit(audit): Audit,
+    ValidatedJson(request): ValidatedJson<CreateOrganizationRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    principal.require_platform()?;
+    let event = audit.pending(|organization: &Organization| {
+        AuditEvent::new("organization.create", "organization", organization.id).after(organization)
+    });
+    let organization = state.organization_service.create(request, event).await?;
+    Ok((StatusCode::CREATED, Json(organization)))
+}
+
+// Handler สำหรับแก้ไขชื่อองค์กร หรือเปิด/ปิดการสมัครเอง
+#[utoipa::path(
+    patch,
+    path = "/admin/organizations/{organization_id}",
+    tag = "admin",
+    params(("organization_id" = i32, Path, description = "Organization ID")),
+    request_body = UpdateOrganizationRequest,
+    security(("admin_token" = []), ("session_cookie" = [])),
+    responses(
+        (status = 200, description = "Organization updated", body = Organization),
+        (status = 401, description = "Missing or invalid token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Insufficient permissions, or not a platform admin", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Organization not found", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 409, description = "Organization name already taken", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid request body; `errors` lists every failing field", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn update_organization_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    principal: RequirePermission<perm::AdminsManage>,
+    Audit(audit): Audit,
+    Path(organization_id): Path<i32>,
+    ValidatedJson(request): ValidatedJson<UpdateOrganizationRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    principal.require_platform()?;
+    let before = state.organization_service.get(organization_id).await?;
+    let event = audit.pending(|organization: &Organization| {
+        AuditEvent::new("organization.update", "organization", organization_id).before(&before).after(organization)
+    });
+    let organization = state.organization_service.update(organization_id, request, event).await?;
+    Ok(Json(organization))
+}
+
+// Handler สำหรับย้ายผู้ใช้ไปองค์กรอื่น (ทุก Session ถูกเพิกถอน เพื่อให้ Token ใหม่มีองค์กรที่ถูกต้อง)
+#[utoipa::path(
+    put,
+    path = "/admin/users/{user_id}/organization",
+    tag = "admin",
+    params(("user_id" = i32, Path, description = "User ID")),
+    request_body = SetOrganizationRequest,
+    security(("admin_token" = []), ("session_cookie" = [])),
+    responses(
+        (status = 200, description = "User moved", body = UserResponse),
+        (status = 401, description = "Missing or invalid token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Insufficient permissions, or not a platform admin", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "User not found", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Unknown organization", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn set_user_organization_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    principal: RequirePermission<perm::UsersWrite>,
+    Audit(audit): Audit,
+    Path(user_id): Path<i32>,
+    ValidatedJson(request): ValidatedJson<SetOrganizationRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    principal.require_platform()?;
+    let before = state.user_service.find_user(user_id).await?;
+    let event = audit.pending(|user: &User| {
+        AuditEvent::new("user.organization.update", "user", user_id)
+            .before(&UserAuditSnapshot::from(&before))
+            .after(&UserAuditSnapshot::from(user))
+    });
+    let user = state.organization_service.move_user(user_id, request, event).await?;
+    state.auth_service.revoke_all_sessions(user_id, "user").await?;
+    Ok(Json(UserResponse::from(user)))
+}
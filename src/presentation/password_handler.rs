@@ -0,0 +1,91 @@
+// src/presentation/password_handler.rs
+
+use std::sync::Arc;
+
+use axum::{http::StatusCode, response::IntoResponse, Extension};
+use axum_extra::extract::cookie::CookieJar;
+
+use crate::app_state::AppState;
+use crate::domain::audit::{AuditActor, AuditEvent};
+use crate::domain::permission::PrincipalKind;
+use crate::domain::password::{ChangePasswordRequest, PasswordResetConfirmRequest, PasswordResetRequest};
+use crate::domain::user::User;
+use crate::error::{AppError, ProblemDetails};
+use crate::middleware::audit::Audit;
+use crate::middleware::auth::Interactive;
+use crate::presentation::validation::ValidatedJson;
+
+// Handler สำหรับเปลี่ยนรหัสผ่านของตัวเอง
+// ทุก session (รวมถึง session ปัจจุบัน) จะถูกเพิกถอน ต้อง login ใหม่ด้วยรหัสผ่านใหม่
+#[utoipa::path(
+    post,
+    path = "/users/me/password",
+    tag = "auth",
+    request_body = ChangePasswordRequest,
+    security(("user_token" = []), ("session_cookie" = [])),
+    responses(
+        (status = 204, description = "Password changed; all sessions were revoked"),
+        (status = 401, description = "Missing or invalid token, or wrong current password", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Not a user account, signs in through single sign-on or LDAP, or authenticated with an API key", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid request body; `errors` lists every failing field", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn change_password_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    Interactive(principal): Interactive,
+    Audit(audit): Audit,
+    jar: CookieJar,
+    ValidatedJson(request): ValidatedJson<ChangePasswordRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let user_id = principal.user_id()?;
+    let event = audit.pending(|_: &User| AuditEvent::new("user.password.change", "user", user_id));
+    state.password_service.change_password(user_id, request, event).await?;
+    Ok((state.session_cookies.clear(jar), StatusCode::NO_CONTENT))
+}
+
+// Handler สำหรับขอลิงก์รีเซ็ตรหัสผ่าน
+// ตอบ 202 เสมอไม่ว่าจะมี username นี้หรือไม่
+#[utoipa::path(
+    post,
+    path = "/auth/password-reset",
+    tag = "auth",
+    request_body = PasswordResetRequest,
+    responses(
+        (status = 202, description = "If the account exists, a reset link has been sent"),
+        (status = 422, description = "Invalid request body", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn request_password_reset_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    Audit(audit): Audit,
+    ValidatedJson(request): ValidatedJson<PasswordResetRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let event = audit.pending(|user_id: &i32| AuditEvent::new("user.password.reset_request", "user", *user_id));
+    state.password_service.request_reset(&request.username, event).await?;
+    Ok(StatusCode::ACCEPTED)
+}
+
+// Handler สำหรับตั้งรหัสผ่านใหม่ด้วย Token จากลิงก์รีเซ็ต
+#[utoipa::path(
+    post,
+    path = "/auth/password-reset/confirm",
+    tag = "auth",
+    request_body = PasswordResetConfirmRequest,
+    responses(
+        (status = 204, description = "Password reset; all sessions were revoked"),
+        (status = 401, description = "Reset token invalid, expired or already used", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid request body; `errors` lists every failing field", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn confirm_password_reset_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    Audit(audit): Audit,
+    ValidatedJson(request): ValidatedJson<PasswordResetConfirmRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let event = audit.pending(|user: &User| {
+        AuditEvent::new("user.password.reset", "user", user.id)
+            .by(AuditActor::account(PrincipalKind::User, user.id, Some(user.organization_id)))
+    });
+    state.password_service.confirm_reset(request, event).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
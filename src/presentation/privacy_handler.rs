@@ -0,0 +1,134 @@
+// src/presentation/privacy_handler.rs
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, Query},
+    http::header,
+    response::IntoResponse,
+    Extension, Json,
+};
+use axum_extra::extract::cookie::CookieJar;
+
+use crate::app_state::AppState;
+use crate::domain::audit::{AuditEvent, UserAuditSnapshot};
+use crate::domain::pagination::{ListQuery, Page};
+use crate::domain::permission::perm;
+use crate::domain::privacy::{EraseAccountRequest, EraseUserRequest, ErasureRequest, UserDataExport};
+use crate::error::{AppError, ProblemDetails};
+use crate::middleware::audit::Audit;
+use crate::middleware::auth::{Interactive, RequirePermission};
+use crate::presentation::validation::ValidatedJson;
+
+// Handler สำหรับดาวน์โหลดข้อมูลส่วนตัวทั้งหมดของตัวเองเป็นไฟล์ JSON
+#[utoipa::path(
+    get,
+    path = "/users/me/export",
+    tag = "auth",
+    security(("user_token" = []), ("session_cookie" = [])),
+    responses(
+        (status = 200, description = "Profile, roles, every booking and login history of the current user", body = UserDataExport),
+        (status = 401, description = "Missing or invalid token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Not a user account, or authenticated with an API key", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn export_my_data_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    Interactive(principal): Interactive,
+) -> Result<impl IntoResponse, AppError> {
+    let user_id = principal.user_id()?;
+    let export = state.privacy_service.export(user_id).await?;
+    let disposition = format!("attachment; filename=\"user-{user_id}-export.json\"");
+    Ok(([(header::CONTENT_DISPOSITION, disposition)], Json(export)))
+}
+
+// Handler สำหรับลบข้อมูลส่วนตัวของตัวเอง (ย้อนกลับไม่ได้ ทุก Session ถูกเพิกถอน)
+#[utoipa::path(
+    post,
+    path = "/users/me/erasure",
+    tag = "auth",
+    request_body = EraseAccountRequest,
+    security(("user_token" = []), ("session_cookie" = [])),
+    responses(
+        (status = 200, description = "Account anonymized; the record of the erasure", body = ErasureRequest),
+        (status = 401, description = "Missing or invalid token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Not a user account, or authenticated with an API key", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid request body, or `confirm_username` does not match", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn erase_my_account_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    Interactive(principal): Interactive,
+    Audit(audit): Audit,
+    jar: CookieJar,
+    ValidatedJson(request): ValidatedJson<EraseAccountRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let user = state.user_service.get_user_by_id(principal.user_id()?).await?;
+    let event = audit.pending(|record: &ErasureRequest| {
+        AuditEvent::new("user.erase", "user", user.id)
+            .before(&UserAuditSnapshot::from(&user))
+            .after(record)
+    });
+    let record = state.privacy_service.erase_own_account(user.id, &user.username, request, event).await?;
+    state.auth_service.revoke_all_sessions(user.id, "user").await?;
+    Ok((state.session_cookies.clear(jar), Json(record)))
+}
+
+// Handler สำหรับลบข้อมูลส่วนตัวของผู้ใช้โดยผู้ดูแล (ใช้ได้กับผู้ใช้ที่ถูก Soft Delete แล้วด้วย)
+#[utoipa::path(
+    post,
+    path = "/admin/users/{user_id}/erase",
+    tag = "admin",
+    params(("user_id" = i32, Path, description = "User ID")),
+    request_body = EraseUserRequest,
+    security(("admin_token" = []), ("session_cookie" = [])),
+    responses(
+        (status = 200, description = "User anonymized; the record of the erasure", body = ErasureRequest),
+        (status = 401, description = "Missing or invalid token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Insufficient permissions", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "User not found", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 409, description = "User data has already been erased", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid request body; `errors` lists every failing field", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn erase_user_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    principal: RequirePermission<perm::UsersWrite>,
+    Audit(audit): Audit,
+    Path(user_id): Path<i32>,
+    ValidatedJson(request): ValidatedJson<EraseUserRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    state.organization_service.ensure_user(principal.tenant, user_id).await?;
+    let before = state.user_service.find_user(user_id).await?;
+    let event = audit.pending(|record: &ErasureRequest| {
+        AuditEvent::new("user.erase", "user", user_id)
+            .before(&UserAuditSnapshot::from(&before))
+            .after(record)
+    });
+    let record = state.privacy_service.erase_user(user_id, principal.kind, principal.id, request, event).await?;
+    state.auth_service.revoke_all_sessions(user_id, "user").await?;
+    Ok(Json(record))
+}
+
+// Handler สำหรับดูบันทึกการลบข้อมูลส่วนตัว (ล่าสุดก่อนเป็นค่าเริ่มต้น กรองด้วย user_id, from, to)
+#[utoipa::path(
+    get,
+    path = "/admin/erasure-requests",
+    tag = "admin",
+    params(ListQuery),
+    security(("admin_token" = []), ("session_cookie" = [])),
+    responses(
+        (status = 200, description = "Erasure records", body = Page<ErasureRequest>),
+        (status = 401, description = "Missing or invalid token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Insufficient permissions", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid input", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn list_erasure_requests_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    principal: RequirePermission<perm::UsersRead>,
+    Query(query): Query<ListQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let page = state.privacy_service.list_erasure_requests(principal.tenant, query).await?;
+    Ok(Json(page))
+}
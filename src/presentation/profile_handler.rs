@@ -0,0 +1,120 @@
+// src/presentation/profile_handler.rs
+
+use std::sync::Arc;
+
+use axum::{http::StatusCode, response::IntoResponse, Extension, Json};
+
+use crate::app_state::AppState;
+use crate::domain::audit::{AuditActor, AuditEvent, UserAuditSnapshot};
+use crate::domain::permission::PrincipalKind;
+use crate::domain::user::{UpdateProfileRequest, User, UserResponse, VerifyEmailRequest};
+use crate::error::{AppError, ProblemDetails};
+use crate::middleware::audit::Audit;
+use crate::middleware::auth::{Authenticated, Interactive};
+use crate::presentation::validation::ValidatedJson;
+
+// Handler สำหรับดูโปรไฟล์ของตัวเอง
+#[utoipa::path(
+    get,
+    path = "/users/me",
+    tag = "auth",
+    security(("user_token" = []), ("session_cookie" = []), ("api_key" = [])),
+    responses(
+        (status = 200, description = "Profile of the current user", body = UserResponse),
+        (status = 401, description = "Missing or invalid token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Not a user account", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn get_profile_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    Authenticated(principal): Authenticated,
+) -> Result<impl IntoResponse, AppError> {
+    let user = state.user_service.get_user_by_id(principal.user_id()?).await?;
+    Ok(Json(UserResponse::from(user)))
+}
+
+// Handler สำหรับแก้ไขโปรไฟล์ของตัวเอง (เฉพาะ Field ที่ส่งมา ส่ง "" เพื่อล้างค่า)
+#[utoipa::path(
+    patch,
+    path = "/users/me",
+    tag = "auth",
+    request_body = UpdateProfileRequest,
+    security(("user_token" = []), ("session_cookie" = [])),
+    responses(
+        (status = 200, description = "Profile updated (a changed email must be verified again)", body = UserResponse),
+        (status = 401, description = "Missing or invalid token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Not a user account, or authenticated with an API key", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid request body; `errors` lists every failing field", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn update_profile_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    Interactive(principal): Interactive,
+    Audit(audit): Audit,
+    ValidatedJson(request): ValidatedJson<UpdateProfileRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let email_sent = request.email.is_some();
+    let changed_fields = request.changed_fields();
+    let before = state.user_service.get_user_by_id(principal.user_id()?).await?;
+    let event = audit.pending(|user: &User| {
+        AuditEvent::new("user.profile.update", "user", user.id)
+            .before(&UserAuditSnapshot::from(&before))
+            .after(&serde_json::json!({ "user": UserAuditSnapshot::from(user), "changed_fields": changed_fields }))
+    });
+    let user = state.user_service.update_profile(before.id, request, event).await?;
+    // อีเมลใหม่ที่ยังไม่ยืนยันได้รับลิงก์ทันทีเมื่อเปิดโหมดยืนยันอีเมล (ขอเองได้ที่ POST /users/me/email-verification)
+    let verification = &state.email_verification_service;
+    if verification.required() && email_sent {
+        verification.send_link(&user).await?;
+    }
+    Ok(Json(UserResponse::from(user)))
+}
+
+// Handler สำหรับขอลิงก์ยืนยันอีเมลใหม่ (ส่งไปยังอีเมลในโปรไฟล์)
+#[utoipa::path(
+    post,
+    path = "/users/me/email-verification",
+    tag = "auth",
+    security(("user_token" = []), ("session_cookie" = []), ("api_key" = [])),
+    responses(
+        (status = 202, description = "Verification link sent"),
+        (status = 401, description = "Missing or invalid token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Not a user account", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 409, description = "Email address already verified", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "No email address in the profile", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn resend_email_verification_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    Authenticated(principal): Authenticated,
+) -> Result<impl IntoResponse, AppError> {
+    state.email_verification_service.resend(principal.user_id()?).await?;
+    Ok(StatusCode::ACCEPTED)
+}
+
+// Handler สำหรับยืนยันอีเมลด้วย Token จากลิงก์ (ไม่ต้อง Login)
+#[utoipa::path(
+    post,
+    path = "/auth/email-verification/confirm",
+    tag = "auth",
+    request_body = VerifyEmailRequest,
+    responses(
+        (status = 204, description = "Email address verified"),
+        (status = 401, description = "Invalid or expired token, or the email address has changed since the link was sent", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid request body; `errors` lists every failing field", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn confirm_email_verification_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    Audit(audit): Audit,
+    ValidatedJson(request): ValidatedJson<VerifyEmailRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    // ผู้กระทำคือเจ้าของอีเมล (ไม่มี Token ในคำขอ)
+    let event = audit.pending(|user: &User| {
+        AuditEvent::new("user.email.verify", "user", user.id)
+            .by(AuditActor::account(PrincipalKind::User, user.id, Some(user.organization_id)))
+            .after(&UserAuditSnapshot::from(user))
+    });
+    state.email_verification_service.verify(&request.token, event).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
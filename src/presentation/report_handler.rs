@@ -0,0 +1,107 @@
+// src/presentation/report_handler.rs
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Extension, Query},
+    response::IntoResponse,
+    Json,
+};
+
+use crate::app_state::AppState;
+use crate::domain::permission::perm;
+use crate::middleware::auth::RequirePermission;
+use crate::domain::report::{BookingRates, PeakHourCell, ReportQuery, RoomUtilization, TopBooker};
+use crate::error::{AppError, ProblemDetails};
+
+// Handler สำหรับรายงานอัตราการใช้ห้อง (GET /admin/reports/utilization)
+#[utoipa::path(
+    get,
+    path = "/admin/reports/utilization",
+    tag = "reports",
+    params(ReportQuery),
+    security(("admin_token" = []), ("session_cookie" = [])),
+    responses(
+        (status = 200, description = "Booked hours vs. open hours per room and period", body = Vec<RoomUtilization>),
+        (status = 401, description = "Missing or invalid token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Insufficient permissions", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid input", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn room_utilization_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    principal: RequirePermission<perm::ReportsRead>,
+    Query(query): Query<ReportQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let report = state.report_service.room_utilization(principal.tenant, query).await?;
+    Ok(Json(report))
+}
+
+// Handler สำหรับ heatmap ช่วงเวลาที่มีการจองมากที่สุด (GET /admin/reports/peak-hours)
+#[utoipa::path(
+    get,
+    path = "/admin/reports/peak-hours",
+    tag = "reports",
+    params(ReportQuery),
+    security(("admin_token" = []), ("session_cookie" = [])),
+    responses(
+        (status = 200, description = "Bookings per weekday (0 = Sunday) and start hour", body = Vec<PeakHourCell>),
+        (status = 401, description = "Missing or invalid token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Insufficient permissions", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid input", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn peak_hours_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    principal: RequirePermission<perm::ReportsRead>,
+    Query(query): Query<ReportQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let report = state.report_service.peak_hours(principal.tenant, query).await?;
+    Ok(Json(report))
+}
+
+// Handler สำหรับอัตราการยกเลิกและ no-show (GET /admin/reports/booking-rates)
+#[utoipa::path(
+    get,
+    path = "/admin/reports/booking-rates",
+    tag = "reports",
+    params(ReportQuery),
+    security(("admin_token" = []), ("session_cookie" = [])),
+    responses(
+        (status = 200, description = "Cancellation and no-show rates per room and period", body = Vec<BookingRates>),
+        (status = 401, description = "Missing or invalid token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Insufficient permissions", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid input", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn booking_rates_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    principal: RequirePermission<perm::ReportsRead>,
+    Query(query): Query<ReportQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let report = state.report_service.booking_rates(principal.tenant, query).await?;
+    Ok(Json(report))
+}
+
+// Handler สำหรับผู้ใช้ที่จองมากที่สุด (GET /admin/reports/top-bookers)
+#[utoipa::path(
+    get,
+    path = "/admin/reports/top-bookers",
+    tag = "reports",
+    params(ReportQuery),
+    security(("admin_token" = []), ("session_cookie" = [])),
+    responses(
+        (status = 200, description = "Users with the most bookings", body = Vec<TopBooker>),
+        (status = 401, description = "Missing or invalid token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Insufficient permissions", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid input", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn top_bookers_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    principal: RequirePermission<perm::ReportsRead>,
+    Query(query): Query<ReportQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let report = state.report_service.top_bookers(principal.tenant, query).await?;
+    Ok(Json(report))
+}
@@ -0,0 +1,165 @@
+// src/presentation/role_handler.rs
+
+use std::sync::Arc;
+
+use axum::{
+    extract::Path, http::StatusCode, response::IntoResponse, Extension, Json
+};
+
+use crate::{
+    app_state::AppState,
+    domain::audit::AuditEvent,
+    domain::mfa::MfaPolicyRequest,
+    domain::permission::{perm, AssignRoleRequest, RoleResponse, UserRolesResponse},
+    error::{AppError, ProblemDetails},
+    middleware::audit::Audit,
+    middleware::auth::RequirePermission,
+    presentation::validation::ValidatedJson,
+};
+
+// Handler สำหรับดู Role ทั้งหมดและ Permission ของแต่ละ Role
+#[utoipa::path(
+    get,
+    path = "/admin/roles",
+    tag = "admin",
+    security(("admin_token" = []), ("session_cookie" = [])),
+    responses(
+        (status = 200, description = "Roles and their permissions", body = Vec<RoleResponse>),
+        (status = 401, description = "Missing or invalid token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Insufficient permissions", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn list_roles_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    _principal: RequirePermission<perm::RolesManage>,
+) -> Result<impl IntoResponse, AppError> {
+    let roles = state.role_service.list_roles().await?;
+    Ok(Json(roles))
+}
+
+// Handler สำหรับกำหนดว่าบัญชีที่มี Role นี้ต้องใช้ 2FA ตอน Login หรือไม่
+#[utoipa::path(
+    put,
+    path = "/admin/roles/{role}/mfa",
+    tag = "admin",
+    params(("role" = String, Path, description = "Role name")),
+    request_body = MfaPolicyRequest,
+    security(("admin_token" = []), ("session_cookie" = [])),
+    responses(
+        (status = 200, description = "Updated role", body = RoleResponse),
+        (status = 401, description = "Missing or invalid token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Insufficient permissions, or not a platform admin", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Role not found", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid request body", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn set_role_mfa_policy_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    principal: RequirePermission<perm::RolesManage>,
+    Audit(audit): Audit,
+    Path(role): Path<String>,
+    ValidatedJson(request): ValidatedJson<MfaPolicyRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    principal.require_platform()?;
+    let before = state.role_service.list_roles().await?.into_iter().find(|r| r.name == role);
+    let event = audit.pending(|role: &RoleResponse| {
+        let event = AuditEvent::new("role.mfa_policy.update", "role", &role.name).after(role);
+        match &before {
+            Some(before) => event.before(before),
+            None => event,
+        }
+    });
+    let role = state.role_service.set_mfa_policy(&role, request.required, event).await?;
+    Ok(Json(role))
+}
+
+// Handler สำหรับดู Role และ Permission ที่มีผลของผู้ใช้
+#[utoipa::path(
+    get,
+    path = "/admin/users/{user_id}/roles",
+    tag = "admin",
+    params(("user_id" = i32, Path, description = "User ID")),
+    security(("admin_token" = []), ("session_cookie" = [])),
+    responses(
+        (status = 200, description = "Roles of the user", body = UserRolesResponse),
+        (status = 401, description = "Missing or invalid token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Insufficient permissions", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "User not found", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn get_user_roles_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    principal: RequirePermission<perm::RolesManage>,
+    Path(user_id): Path<i32>,
+) -> Result<impl IntoResponse, AppError> {
+    state.organization_service.ensure_user(principal.tenant, user_id).await?;
+    state.user_service.get_user_by_id(user_id).await?;
+    let roles = state.role_service.user_roles(user_id).await?;
+    Ok(Json(roles))
+}
+
+// Handler สำหรับกำหนด Role เพิ่มให้ผู้ใช้
+#[utoipa::path(
+    post,
+    path = "/admin/users/{user_id}/roles",
+    tag = "admin",
+    params(("user_id" = i32, Path, description = "User ID")),
+    request_body = AssignRoleRequest,
+    security(("admin_token" = []), ("session_cookie" = [])),
+    responses(
+        (status = 201, description = "Role assigned", body = UserRolesResponse),
+        (status = 401, description = "Missing or invalid token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Insufficient permissions, or the role is admin-only or grants permissions the caller does not hold", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "User or role not found", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 409, description = "Role already assigned", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid request body", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn assign_user_role_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    principal: RequirePermission<perm::RolesManage>,
+    Audit(audit): Audit,
+    Path(user_id): Path<i32>,
+    ValidatedJson(request): ValidatedJson<AssignRoleRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    state.organization_service.ensure_user(principal.tenant, user_id).await?;
+    state.user_service.get_user_by_id(user_id).await?;
+    let before = state.role_service.user_roles(user_id).await?;
+    let event = audit.pending(|roles: &UserRolesResponse| {
+        AuditEvent::new("user.role.assign", "user", user_id).before(&before).after(roles)
+    });
+    let roles = state.role_service.assign_user_role(&principal.permissions, user_id, request.role.trim(), event).await?;
+    Ok((StatusCode::CREATED, Json(roles)))
+}
+
+// Handler สำหรับถอน Role ที่กำหนดเพิ่มออกจากผู้ใช้
+#[utoipa::path(
+    delete,
+    path = "/admin/users/{user_id}/roles/{role}",
+    tag = "admin",
+    params(
+        ("user_id" = i32, Path, description = "User ID"),
+        ("role" = String, Path, description = "Role name"),
+    ),
+    security(("admin_token" = []), ("session_cookie" = [])),
+    responses(
+        (status = 204, description = "Role removed"),
+        (status = 401, description = "Missing or invalid token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Insufficient permissions", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Role is not assigned to the user", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn remove_user_role_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    principal: RequirePermission<perm::RolesManage>,
+    Audit(audit): Audit,
+    Path((user_id, role)): Path<(i32, String)>,
+) -> Result<impl IntoResponse, AppError> {
+    state.organization_service.ensure_user(principal.tenant, user_id).await?;
+    let before = state.role_service.user_roles(user_id).await?;
+    let event = audit.pending(|after: &UserRolesResponse| {
+        AuditEvent::new("user.role.remove", "user", user_id).before(&before).after(after)
+    });
+    state.role_service.remove_user_role(user_id, &role, event).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
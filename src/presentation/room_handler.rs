@@ -3,78 +3,301 @@
 use std::sync::Arc;
 
 use axum::{
-    extract::{Path}, http::StatusCode, response::IntoResponse, Extension, Json
+    extract::{Path, Query}, http::StatusCode, response::IntoResponse, Extension, Json
 };
-use serde::Deserialize;
 
-use crate::{app_state::AppState};
-
-// Request Body สำหรับการลงทะเบียน (รับรหัสผ่านดิบจาก Client)
-#[derive(Clone, Deserialize)]
-pub struct AddRoomRequest {
-    pub name: String,
-    pub status: String, // <<-- รหัสผ่านดิบ (Plain Text)
-}
+use crate::{
+    app_state::AppState,
+    domain::audit::AuditEvent,
+    domain::permission::perm,
+    domain::pagination::{ListQuery, Page},
+    domain::group::{RoomAccessRequest, RoomAccessResponse},
+    domain::organization::{RoomSharesRequest, RoomSharesResponse},
+    domain::room::{AddRoomRequest, Room, UpdateRoomRequest},
+    error::{AppError, ProblemDetails},
+    middleware::audit::Audit,
+    middleware::auth::{Authenticated, RequirePermission},
+    presentation::validation::ValidatedJson,
+};
 
 // Handler สำหรับการลงทะเบียนผู้ใช้ (POST /register)
+#[utoipa::path(
+    post,
+    path = "/admin/rooms",
+    tag = "rooms",
+    request_body = AddRoomRequest,
+    security(("admin_token" = []), ("session_cookie" = [])),
+    responses(
+        (status = 201, description = "Room created", body = Room),
+        (status = 401, description = "Missing or invalid token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Insufficient permissions", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 409, description = "Room name already taken in the organization", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid request body, or `organization_id` set by an organization admin or unknown", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
 pub async fn add_room_handler(
     Extension(state): Extension<Arc<AppState>>,
-    Json(payload): Json<AddRoomRequest>, // รับ CreateUserRequest
-) -> impl IntoResponse {
-    // แปลง CreateUserRequest ไปเป็น RegisterUserRequest
-    // (ตอนนี้ CreateUserRequest และ RegisterUserRequest มีโครงสร้างคล้ายกันมาก อาจจะใช้ตัวเดียวกันก็ได้ แต่แยกไว้ชัดเจนกว่า)
-    let add_room_request = crate::domain::room::AddRoomRequest {
-        name: payload.name,
-        status: payload.status,
-    };
-
-    match state.room_service.add_room(add_room_request).await {
-        Ok(room) => (StatusCode::CREATED, Json(room)).into_response(), // UserEntity (User) derive Serialize
-        Err(e) => (StatusCode::BAD_REQUEST, e).into_response(), // <<-- เปลี่ยนเป็น BAD_REQUEST สำหรับ Error ทั่วไป เช่น username ซ้ำ
-    }
+    principal: RequirePermission<perm::RoomsWrite>,
+    Audit(audit): Audit,
+    ValidatedJson(add_room_request): ValidatedJson<AddRoomRequest>, // ตรวจสอบชื่อและสถานะห้อง
+) -> Result<impl IntoResponse, AppError> {
+    let event = audit.pending(|room: &Room| AuditEvent::new("room.create", "room", room.id).after(room));
+    let room = state.room_service.add_room(principal.tenant, add_room_request, event).await?;
+    Ok((StatusCode::CREATED, Json(room))) // UserEntity (User) derive Serialize
 }
 
+#[utoipa::path(
+    get,
+    path = "/rooms",
+    tag = "rooms",
+    params(ListQuery),
+    security(("user_token" = []), ("admin_token" = []), ("session_cookie" = [])),
+    responses(
+        (status = 200, description = "Rooms of the caller's organization and rooms shared with it (deleted ones only with include_deleted=true); users only see rooms they may book", body = Page<Room>),
+        (status = 401, description = "Missing or invalid token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid input", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
 pub async fn get_all_room_handler(
     Extension(state): Extension<Arc<AppState>>,
-) -> Result<Json<Vec<crate::domain::room::Room>>, String> {
-    state.room_service.get_all_room().await.map(Json)
+    Authenticated(principal): Authenticated,
+    Query(query): Query<ListQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let rooms = state.room_service.get_all_room(principal.tenant, principal.rooms_restricted_to(), query).await?;
+    Ok(Json(rooms))
 }
 
 // get room by id
+#[utoipa::path(
+    get,
+    path = "/rooms/{room_id}",
+    tag = "rooms",
+    params(("room_id" = i32, Path, description = "Room ID")),
+    security(("user_token" = []), ("admin_token" = []), ("session_cookie" = [])),
+    responses(
+        (status = 200, description = "Room", body = Room),
+        (status = 401, description = "Missing or invalid token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Room not found, not visible to the caller's organization, or restricted to other users", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
 pub async fn get_room_by_id_handler(
     Extension(state): Extension<Arc<AppState>>,
+    Authenticated(principal): Authenticated,
     Path(room_id): Path<i32>,
-) -> Result<Json<crate::domain::room::Room>, String> {
-    state.room_service.get_room_by_id(room_id).await.map(Json)
+) -> Result<Json<crate::domain::room::Room>, AppError> {
+    state.room_service.get_room_by_id(principal.tenant, principal.rooms_restricted_to(), room_id).await.map(Json)
 }
 
 
+#[utoipa::path(
+    get,
+    path = "/rooms/active",
+    tag = "rooms",
+    params(ListQuery),
+    security(("user_token" = []), ("admin_token" = []), ("session_cookie" = [])),
+    responses(
+        (status = 200, description = "Available rooms of the caller's organization and rooms shared with it; users only see rooms they may book", body = Page<Room>),
+        (status = 401, description = "Missing or invalid token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid input", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
 pub async fn get_all_active_rooms_handler(
     Extension(state): Extension<Arc<AppState>>,
-) -> impl IntoResponse {
-    match state.room_service.get_all_active_rooms().await {
-        Ok(rooms) => (StatusCode::OK, Json(rooms)).into_response(),
-        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e).into_response(),
-    }
+    Authenticated(principal): Authenticated,
+    Query(query): Query<ListQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let rooms = state.room_service.get_all_active_rooms(principal.tenant, principal.rooms_restricted_to(), query).await?;
+    Ok(Json(rooms))
 }
 
+#[utoipa::path(
+    patch,
+    path = "/admin/rooms/{room_id}",
+    tag = "rooms",
+    params(("room_id" = i32, Path, description = "Room ID")),
+    request_body = UpdateRoomRequest,
+    security(("admin_token" = []), ("session_cookie" = [])),
+    responses(
+        (status = 201, description = "Room updated", body = Room),
+        (status = 401, description = "Missing or invalid token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Insufficient permissions", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Room not found", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid request body; `errors` lists every failing field", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
 pub async fn update_room_handler(
     Extension(state): Extension<Arc<AppState>>,
+    principal: RequirePermission<perm::RoomsWrite>,
+    Audit(audit): Audit,
     Path(room_id):Path<i32>,
-    Json(payload):Json<crate::domain::room::UpdateRoomRequest>,
-) -> impl IntoResponse{
-    match state.room_service.update_room(room_id,payload).await{
-        Ok(room) => (StatusCode::CREATED,Json(room)).into_response(),
-        Err(e) => (StatusCode::BAD_REQUEST,e).into_response(),
-    }
+    ValidatedJson(payload):ValidatedJson<UpdateRoomRequest>,
+) -> Result<impl IntoResponse, AppError>{
+    state.organization_service.ensure_room(principal.tenant, room_id).await?;
+    let before = state.room_service.find_room(room_id).await?;
+    let event = audit.pending(|room: &Room| AuditEvent::new("room.update", "room", room_id).before(&before).after(room));
+    let room = state.room_service.update_room(room_id,payload,event).await?;
+    Ok((StatusCode::CREATED,Json(room)))
 }
 
+#[utoipa::path(
+    delete,
+    path = "/admin/rooms/{room_id}",
+    tag = "rooms",
+    params(("room_id" = i32, Path, description = "Room ID")),
+    security(("admin_token" = []), ("session_cookie" = [])),
+    responses(
+        (status = 201, description = "Room soft deleted", body = Room),
+        (status = 401, description = "Missing or invalid token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Insufficient permissions", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Room not found", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
 pub async fn delete_room_handler(
     Extension(state): Extension<Arc<AppState>>,
+    principal: RequirePermission<perm::RoomsWrite>,
+    Audit(audit): Audit,
     Path(room_id):Path<i32>,
-) -> impl IntoResponse{
-    match state.room_service.delete_room(room_id).await{
-        Ok(room) => (StatusCode::CREATED,Json(room)).into_response(),
-        Err(e) => (StatusCode::BAD_REQUEST,e).into_response(),
-    }
-}
\ No newline at end of file
+) -> Result<impl IntoResponse, AppError>{
+    state.organization_service.ensure_room(principal.tenant, room_id).await?;
+    let before = state.room_service.find_room(room_id).await?;
+    let event = audit.pending(|room: &Room| AuditEvent::new("room.delete", "room", room_id).before(&before).after(room));
+    let room = state.room_service.delete_room(room_id, event).await?;
+    Ok((StatusCode::CREATED,Json(room)))
+}
+
+// Handler สำหรับกู้คืนห้องที่ถูก Soft Delete
+#[utoipa::path(
+    post,
+    path = "/admin/rooms/{room_id}/restore",
+    tag = "rooms",
+    params(("room_id" = i32, Path, description = "Room ID")),
+    security(("admin_token" = []), ("session_cookie" = [])),
+    responses(
+        (status = 200, description = "Room restored", body = Room),
+        (status = 401, description = "Missing or invalid token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Insufficient permissions", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Room not found or not deleted", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn restore_room_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    principal: RequirePermission<perm::RoomsWrite>,
+    Audit(audit): Audit,
+    Path(room_id): Path<i32>,
+) -> Result<impl IntoResponse, AppError> {
+    state.organization_service.ensure_room(principal.tenant, room_id).await?;
+    let before = state.room_service.find_room(room_id).await?;
+    let event = audit.pending(|room: &Room| AuditEvent::new("room.restore", "room", room_id).before(&before).after(room));
+    let room = state.room_service.restore_room(room_id, event).await?;
+    Ok(Json(room))
+}
+
+// Handler สำหรับดูองค์กรที่ห้องถูกแชร์ให้ (เฉพาะองค์กรเจ้าของห้อง)
+#[utoipa::path(
+    get,
+    path = "/admin/rooms/{room_id}/shares",
+    tag = "rooms",
+    params(("room_id" = i32, Path, description = "Room ID")),
+    security(("admin_token" = []), ("session_cookie" = [])),
+    responses(
+        (status = 200, description = "Organizations the room is shared with", body = RoomSharesResponse),
+        (status = 401, description = "Missing or invalid token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Insufficient permissions", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Room not found in the caller's organization", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn get_room_shares_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    principal: RequirePermission<perm::RoomsWrite>,
+    Path(room_id): Path<i32>,
+) -> Result<impl IntoResponse, AppError> {
+    let shares = state.organization_service.room_shares(principal.tenant, room_id).await?;
+    Ok(Json(shares))
+}
+
+// Handler สำหรับแชร์ห้องให้องค์กรอื่นเห็นและจองได้ (แทนที่รายการเดิมทั้งหมด)
+#[utoipa::path(
+    put,
+    path = "/admin/rooms/{room_id}/shares",
+    tag = "rooms",
+    params(("room_id" = i32, Path, description = "Room ID")),
+    request_body = RoomSharesRequest,
+    security(("admin_token" = []), ("session_cookie" = [])),
+    responses(
+        (status = 200, description = "Sharing updated", body = RoomSharesResponse),
+        (status = 401, description = "Missing or invalid token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Insufficient permissions", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Room not found in the caller's organization", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Unknown organization, or the room's own organization listed", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn set_room_shares_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    principal: RequirePermission<perm::RoomsWrite>,
+    Audit(audit): Audit,
+    Path(room_id): Path<i32>,
+    ValidatedJson(request): ValidatedJson<RoomSharesRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let before = state.organization_service.room_shares(principal.tenant, room_id).await?;
+    let event = audit.pending(|shares: &RoomSharesResponse| {
+        AuditEvent::new("room.shares.update", "room", room_id).before(&before).after(shares)
+    });
+    let shares = state.organization_service.set_room_shares(principal.tenant, room_id, request, event).await?;
+    Ok(Json(shares))
+}
+
+// Handler สำหรับดูกฎการเข้าถึงห้อง (กลุ่มและผู้ใช้ที่จองได้)
+#[utoipa::path(
+    get,
+    path = "/admin/rooms/{room_id}/access",
+    tag = "rooms",
+    params(("room_id" = i32, Path, description = "Room ID")),
+    security(("admin_token" = []), ("session_cookie" = [])),
+    responses(
+        (status = 200, description = "Access rules of the room", body = RoomAccessResponse),
+        (status = 401, description = "Missing or invalid token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Insufficient permissions", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Room not found in the caller's organization", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn get_room_access_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    principal: RequirePermission<perm::RoomsWrite>,
+    Path(room_id): Path<i32>,
+) -> Result<impl IntoResponse, AppError> {
+    state.organization_service.ensure_room(principal.tenant, room_id).await?;
+    let access = state.group_service.room_access(room_id).await?;
+    Ok(Json(access))
+}
+
+// Handler สำหรับจำกัดห้องให้จองได้เฉพาะบางกลุ่มหรือบางคน (แทนที่กฎเดิมทั้งหมด ส่งรายการว่างเพื่อเปิดให้ทุกคน)
+#[utoipa::path(
+    put,
+    path = "/admin/rooms/{room_id}/access",
+    tag = "rooms",
+    params(("room_id" = i32, Path, description = "Room ID")),
+    request_body = RoomAccessRequest,
+    security(("admin_token" = []), ("session_cookie" = [])),
+    responses(
+        (status = 200, description = "Access rules updated", body = RoomAccessResponse),
+        (status = 401, description = "Missing or invalid token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Insufficient permissions", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Room not found in the caller's organization", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Group of another organization, or user who cannot see the room", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn set_room_access_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    principal: RequirePermission<perm::RoomsWrite>,
+    Audit(audit): Audit,
+    Path(room_id): Path<i32>,
+    ValidatedJson(request): ValidatedJson<RoomAccessRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let owner_id = state.organization_service.ensure_room(principal.tenant, room_id).await?;
+    let before = state.group_service.room_access(room_id).await?;
+    let event = audit.pending(|access: &RoomAccessResponse| {
+        AuditEvent::new("room.access.update", "room", room_id).before(&before).after(access)
+    });
+    let access = state.group_service.set_room_access(room_id, owner_id, request, event).await?;
+    Ok(Json(access))
+}
@@ -0,0 +1,222 @@
+// src/presentation/router.rs
+
+use std::sync::Arc;
+
+use axum::{
+    Extension, Router,
+    middleware::from_fn,
+    routing::{delete, get, patch, post, put},
+};
+
+use crate::app_state::AppState;
+use crate::middleware::{impersonation::record_impersonated_requests, request_id::assign_request_id};
+use crate::presentation::{
+    admin_handler::{
+        accept_invitation_handler, create_invitation_handler, list_invitations_handler,
+        login_admin_handler, revoke_invitation_handler, setup_admin_handler,
+    },
+    admin_user_handler,
+    api_key_handler::{create_api_key_handler, list_api_keys_handler, revoke_api_key_handler},
+    audit_handler::{list_audit_handler, verify_audit_handler},
+    auth_handler::{jwks_handler, logout_handler, refresh_handler},
+    booking_handler::{
+        cancel_booking_handler, create_booking_handler, delete_booking_handler, get_all_bookings_handler,
+        get_user_bookings_handler, mark_no_show_handler, restore_booking_handler,
+    },
+    group_handler::{
+        add_group_member_handler, create_group_handler, delete_group_handler, get_group_handler,
+        list_group_members_handler, list_groups_handler, remove_group_member_handler, update_group_handler,
+    },
+    impersonation_handler::{
+        end_impersonation_handler, impersonate_user_handler, list_impersonation_actions_handler,
+        list_impersonations_handler,
+    },
+    lockout_handler::{clear_lockout_handler, list_lockouts_handler, list_login_events_handler},
+    mfa_handler::{
+        confirm_totp_handler, disable_totp_handler, enroll_mfa_challenge_handler, mfa_status_handler,
+        regenerate_recovery_codes_handler, reset_admin_mfa_handler, reset_user_mfa_handler,
+        start_totp_enrollment_handler, verify_mfa_handler,
+    },
+    oidc_handler::{oidc_callback_handler, oidc_login_handler},
+    openapi::{docs_handler, openapi_json_handler},
+    organization_handler::{
+        create_organization_handler, list_organizations_handler, set_user_organization_handler,
+        update_organization_handler,
+    },
+    password_handler::{
+        change_password_handler, confirm_password_reset_handler, request_password_reset_handler,
+    },
+    privacy_handler::{
+        erase_my_account_handler, erase_user_handler, export_my_data_handler,
+        list_erasure_requests_handler,
+    },
+    profile_handler::{
+        confirm_email_verification_handler, get_profile_handler, resend_email_verification_handler,
+        update_profile_handler,
+    },
+    report_handler::{
+        booking_rates_handler, peak_hours_handler, room_utilization_handler,
+        top_bookers_handler,
+    },
+    role_handler::{
+        assign_user_role_handler, get_user_roles_handler, list_roles_handler,
+        remove_user_role_handler, set_role_mfa_policy_handler,
+    },
+    room_handler::{
+        add_room_handler, delete_room_handler, get_all_active_rooms_handler,
+        get_all_room_handler, get_room_access_handler, get_room_by_id_handler, get_room_shares_handler,
+        restore_room_handler, set_room_access_handler, set_room_shares_handler, update_room_handler,
+    },
+    test_handler::{test_protected_admin_route, test_protected_user_route},
+    user_handler::{login_user_handler, register_user_handler},
+};
+
+// router: เส้นทางทั้งหมดของ API พร้อม Middleware (ใช้ทั้งใน main และในการทดสอบ)
+pub fn router(app_state: Arc<AppState>) -> Router {
+    Router::new()
+        // User Login/Register (ไม่ต้องมี Middleware)
+        // User Login/Register routes
+        .route("/register", post(register_user_handler))
+        .route("/login/user", post(login_user_handler))
+        // Admin Login และการสร้าง Admin (Admin คนแรกด้วย Setup Token, คนต่อไปด้วยคำเชิญ)
+        .route("/admin/setup", post(setup_admin_handler))
+        .route("/admin/invitations/accept", post(accept_invitation_handler))
+        .route("/login/admin", post(login_admin_handler))
+        // ต่ออายุ Token และ Logout (ใช้ Refresh Token ใน Body)
+        .route("/auth/refresh", post(refresh_handler))
+        .route("/auth/logout", post(logout_handler))
+        // โปรไฟล์ของตัวเอง
+        .route("/users/me", get(get_profile_handler).patch(update_profile_handler))
+        // ยืนยันอีเมล: ขอลิงก์ใหม่ (ต้อง Login) และยืนยันด้วย Token จากลิงก์
+        .route("/users/me/email-verification", post(resend_email_verification_handler))
+        .route("/auth/email-verification/confirm", post(confirm_email_verification_handler))
+        // เปลี่ยนรหัสผ่าน (ต้อง Login) และรีเซ็ตรหัสผ่านผ่านลิงก์ที่ส่งทาง Notifier
+        .route("/users/me/password", post(change_password_handler))
+        .route("/auth/password-reset", post(request_password_reset_handler))
+        .route("/auth/password-reset/confirm", post(confirm_password_reset_handler))
+        // Login ผ่าน Identity Provider (OIDC Authorization Code + PKCE)
+        .route("/auth/oidc/login", get(oidc_login_handler))
+        .route("/auth/oidc/callback", get(oidc_callback_handler))
+        // 2FA: Login ขั้นที่สอง (ใช้ Challenge Token) และการจัดการ 2FA ของบัญชีตัวเอง (ต้อง Login)
+        .route("/auth/mfa/verify", post(verify_mfa_handler))
+        .route("/auth/mfa/enroll", post(enroll_mfa_challenge_handler))
+        .route("/auth/mfa", get(mfa_status_handler))
+        .route(
+            "/auth/mfa/totp",
+            post(start_totp_enrollment_handler).delete(disable_totp_handler),
+        )
+        .route("/auth/mfa/totp/confirm", post(confirm_totp_handler))
+        .route("/auth/mfa/recovery-codes", post(regenerate_recovery_codes_handler))
+        // API Key ของบัญชีตัวเอง (ใช้แทน JWT ผ่าน Header X-API-Key)
+        .route("/users/me/api-keys", get(list_api_keys_handler).post(create_api_key_handler))
+        .route("/users/me/api-keys/:key_id", delete(revoke_api_key_handler))
+        // ข้อมูลส่วนตัว: ดาวน์โหลดสำเนา และลบข้อมูลของตัวเอง (ย้อนกลับไม่ได้)
+        .route("/users/me/export", get(export_my_data_handler))
+        .route("/users/me/erasure", post(erase_my_account_handler))
+        // Public Key สำหรับให้ Service อื่นตรวจสอบ Token ของเรา
+        .route("/.well-known/jwks.json", get(jwks_handler))
+        // *** Router สำหรับเส้นทางจัดการระบบ ***
+        // Handler แต่ละตัวประกาศ Permission ที่ต้องการเอง (RequirePermission<...>)
+        .nest(
+            "/admin",
+            Router::new() // <--- ไม่ต้องระบุ Router<Arc<AppState>> แล้ว
+                .route("/rooms", post(add_room_handler))
+                .route("/rooms/:room_id", patch(update_room_handler))
+                .route("/rooms/:room_id", delete(delete_room_handler))
+                .route("/rooms/:room_id/restore", post(restore_room_handler))
+                .route(
+                    "/rooms/:room_id/shares",
+                    get(get_room_shares_handler).put(set_room_shares_handler),
+                )
+                .route(
+                    "/rooms/:room_id/access",
+                    get(get_room_access_handler).put(set_room_access_handler),
+                )
+                .route("/bookings", get(get_all_bookings_handler))
+                .route("/bookings/:booking_id", delete(delete_booking_handler)) // Admin can cancel bookings.
+                .route("/bookings/:booking_id/no-show", post(mark_no_show_handler))
+                .route("/bookings/:booking_id/restore", post(restore_booking_handler))
+                .route("/reports/utilization", get(room_utilization_handler))
+                .route("/reports/peak-hours", get(peak_hours_handler))
+                .route("/reports/booking-rates", get(booking_rates_handler))
+                .route("/reports/top-bookers", get(top_bookers_handler))
+                .route("/users", get(admin_user_handler::get_all_users_handler))
+                .route(
+                    "/users/:user_id",
+                    get(admin_user_handler::get_user_by_id_handler),
+                )
+                .route(
+                    "/users/:user_id",
+                    delete(admin_user_handler::delete_user_by_admin_handler),
+                )
+                .route("/users/:user_id/restore", post(admin_user_handler::restore_user_handler))
+                .route(
+                    "/users/:user_id/verify-email",
+                    post(admin_user_handler::verify_user_email_handler),
+                )
+                .route("/users/:user_id/erase", post(erase_user_handler))
+                .route("/erasure-requests", get(list_erasure_requests_handler))
+                .route("/users/:user_id/organization", put(set_user_organization_handler))
+                .route("/users/:user_id/impersonate", post(impersonate_user_handler))
+                .route("/impersonations", get(list_impersonations_handler))
+                .route("/impersonations/:impersonation_id", delete(end_impersonation_handler))
+                .route("/impersonations/:impersonation_id/actions", get(list_impersonation_actions_handler))
+                .route(
+                    "/organizations",
+                    get(list_organizations_handler).post(create_organization_handler),
+                )
+                .route("/organizations/:organization_id", patch(update_organization_handler))
+                .route("/groups", get(list_groups_handler).post(create_group_handler))
+                .route(
+                    "/groups/:group_id",
+                    get(get_group_handler).patch(update_group_handler).delete(delete_group_handler),
+                )
+                .route("/groups/:group_id/members", get(list_group_members_handler))
+                .route(
+                    "/groups/:group_id/members/:user_id",
+                    put(add_group_member_handler).delete(remove_group_member_handler),
+                )
+                .route(
+                    "/users/:user_id/roles",
+                    get(get_user_roles_handler).post(assign_user_role_handler),
+                )
+                .route("/users/:user_id/roles/:role", delete(remove_user_role_handler))
+                .route("/roles", get(list_roles_handler))
+                .route("/roles/:role/mfa", put(set_role_mfa_policy_handler))
+                .route("/users/:user_id/mfa", delete(reset_user_mfa_handler))
+                .route("/admins/:admin_id/mfa", delete(reset_admin_mfa_handler))
+                .route("/lockouts", get(list_lockouts_handler))
+                .route("/lockouts/:lockout_id", delete(clear_lockout_handler))
+                .route("/login-events", get(list_login_events_handler))
+                .route("/audit", get(list_audit_handler))
+                .route("/audit/verify", get(verify_audit_handler))
+                .route(
+                    "/invitations",
+                    get(list_invitations_handler).post(create_invitation_handler),
+                )
+                .route("/invitations/:invitation_id", delete(revoke_invitation_handler))
+                .route("/test-admin", get(test_protected_admin_route)),
+        )
+        // *** Router สำหรับการจอง (ต้อง Login และมี Permission bookings:write) ***
+        .nest(
+            "/bookings",
+            Router::new() // <--- ไม่ต้องระบุ Router<Arc<AppState>> แล้ว
+                .route("/", post(create_booking_handler))
+                .route("/:id", delete(cancel_booking_handler))
+                .route("/user", get(get_user_bookings_handler))
+                .route("/test-user", get(test_protected_user_route)),
+        )
+        // *** Router สำหรับดูห้อง (ต้อง Login เห็นเฉพาะห้องขององค์กรตัวเองและห้องที่ถูกแชร์ให้) ***
+        .route("/rooms/active", get(get_all_active_rooms_handler))
+        .route("/rooms", get(get_all_room_handler))
+        .route("/rooms/:room_id", get(get_room_by_id_handler))
+        // เอกสาร OpenAPI และหน้า Docs UI (Public)
+        .route("/openapi.json", get(openapi_json_handler))
+        .route("/docs", get(docs_handler))
+        // บันทึกทุก Request ที่ใช้ Token ของการสวมสิทธิ์ผู้ใช้ (ต้องอยู่ใน Extension ของ AppState)
+        .layer(from_fn(record_impersonated_requests))
+        // *** ใช้ตัวแปร app_state (ตัวเล็ก) ที่ Router หลักด้วย ***
+        .layer(Extension(app_state))
+        // กำหนด Request ID (X-Request-Id) ให้ทุก Request ก่อน Middleware อื่น
+        .layer(from_fn(assign_request_id))
+}
@@ -7,16 +7,34 @@ use axum::{
 };
 use serde_json::json;
 
-// Handler ทดสอบสำหรับเส้นทางที่ต้องผ่าน auth_middleware (User)
-pub async fn test_protected_user_route() -> impl IntoResponse {
+use crate::domain::permission::perm;
+use crate::error::ProblemDetails;
+use crate::middleware::auth::RequirePermission;
+
+// Handler ทดสอบสำหรับเส้นทางที่ต้องมี Permission bookings:write (User)
+#[utoipa::path(
+    get,
+    path = "/bookings/test-user",
+    tag = "bookings",
+    security(("user_token" = []), ("session_cookie" = [])),
+    responses((status = 200, description = "Token accepted"), (status = 401, description = "Missing or invalid token", body = ProblemDetails, content_type = "application/problem+json"), (status = 403, description = "Insufficient permissions", body = ProblemDetails, content_type = "application/problem+json"))
+)]
+pub async fn test_protected_user_route(_principal: RequirePermission<perm::BookingsWrite>) -> impl IntoResponse {
     (
         StatusCode::OK,
         Json(json!({"message": "Successfully accessed user-protected route!"})),
     )
 }
 
-// Handler ทดสอบสำหรับเส้นทางที่ต้องผ่าน admin_middleware (Admin)
-pub async fn test_protected_admin_route() -> impl IntoResponse {
+// Handler ทดสอบสำหรับเส้นทางที่ต้องมี Permission roles:manage (Admin)
+#[utoipa::path(
+    get,
+    path = "/admin/test-admin",
+    tag = "admin",
+    security(("admin_token" = []), ("session_cookie" = [])),
+    responses((status = 200, description = "Token accepted"), (status = 401, description = "Missing or invalid token", body = ProblemDetails, content_type = "application/problem+json"), (status = 403, description = "Insufficient permissions", body = ProblemDetails, content_type = "application/problem+json"))
+)]
+pub async fn test_protected_admin_route(_principal: RequirePermission<perm::RolesManage>) -> impl IntoResponse {
     (
         StatusCode::OK,
         Json(json!({"message": "Successfully accessed admin-protected route!"})),
@@ -2,84 +2,142 @@
 
 use std::sync::Arc;
 
+use axum::extract::Query;
+use axum::response::Response;
 use axum::Extension;
 use axum::{Json, http::StatusCode, response::IntoResponse};
+use axum_extra::extract::cookie::CookieJar;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
 
 use crate::app_state::AppState;
+use crate::domain::audit::{AuditActor, AuditEvent, UserAuditSnapshot};
+use crate::domain::permission::PrincipalKind;
+use crate::domain::user::{RegisterUserRequest, User, UserResponse};
+use crate::error::{AppError, ProblemDetails};
+use crate::middleware::audit::Audit;
+use crate::middleware::client_ip::ClientIp;
+use crate::middleware::session::{LoginMode, LoginQuery};
+use crate::presentation::validation::ValidatedJson;
 
 // นำเข้า Struct จาก Domain Layer
 use crate::domain::user::LoginCredentials;
 
 
-// Request Body สำหรับการลงทะเบียน (รับรหัสผ่านดิบจาก Client)
-#[derive(Clone, Deserialize)]
-pub struct CreateUserRequest {
-    pub username: String,
-    pub password: String, // <<-- รหัสผ่านดิบ (Plain Text)
-}
-
 // Request Body สำหรับการ Login (รับรหัสผ่านดิบจาก Client)
-#[derive(Clone, Deserialize)]
+#[derive(Clone, Deserialize, ToSchema)]
 pub struct LoginUserRequest {
     pub username: String,
     pub password: String, // <<-- รหัสผ่านดิบ (Plain Text)
 }
 
 // Response Body สำหรับการ Login (ถ้าต้องการส่ง Token หรือข้อมูลเพิ่มเติม)
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct LoginResponse {
     pub user_id: i32,
     pub token: String,      // เพิ่ม token ใน response
     pub role: String,       // เพิ่ม role ใน response
     pub expires_in: i64,    // เวลาหมดอายุ (วินาที)
+    pub refresh_token: String,   // ใช้กับ POST /auth/refresh และ POST /auth/logout
+    pub refresh_expires_in: i64, // อายุ Refresh Token (วินาที)
 }
 
 // Handler สำหรับการลงทะเบียนผู้ใช้ (POST /register)
+#[utoipa::path(
+    post,
+    path = "/register",
+    tag = "auth",
+    request_body = RegisterUserRequest,
+    responses(
+        (status = 201, description = "User registered (unverified and sent a verification link when EMAIL_VERIFICATION_REQUIRED is on)", body = UserResponse),
+        (status = 409, description = "Username already taken", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid request body, email missing while email verification is required, or an organization that does not accept self-registration", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
 pub async fn register_user_handler(
     Extension(state): Extension<Arc<AppState>>,
-    Json(payload): Json<CreateUserRequest>, // รับ CreateUserRequest
-) -> impl IntoResponse {
-    // แปลง CreateUserRequest ไปเป็น RegisterUserRequest
-    // (ตอนนี้ CreateUserRequest และ RegisterUserRequest มีโครงสร้างคล้ายกันมาก อาจจะใช้ตัวเดียวกันก็ได้ แต่แยกไว้ชัดเจนกว่า)
-    let user_request = crate::domain::user::RegisterUserRequest {
-        username: payload.username,
-        password: payload.password,
-    };
-
-    match state.user_service.register_user(user_request).await {
-        Ok(user) => (StatusCode::CREATED, Json(user)).into_response(), // UserEntity (User) derive Serialize
-        Err(e) => (StatusCode::BAD_REQUEST, e).into_response(), // <<-- เปลี่ยนเป็น BAD_REQUEST สำหรับ Error ทั่วไป เช่น username ซ้ำ
+    Audit(audit): Audit,
+    ValidatedJson(user_request): ValidatedJson<RegisterUserRequest>, // ตรวจสอบ username/password ก่อนเข้า Service
+) -> Result<impl IntoResponse, AppError> {
+    let verification = &state.email_verification_service;
+    let organization_id = state
+        .organization_service
+        .organization_for_registration(user_request.organization.as_deref())
+        .await?;
+    let event = audit.pending(|user: &User| {
+        AuditEvent::new("user.register", "user", user.id)
+            .by(AuditActor::account(PrincipalKind::User, user.id, Some(user.organization_id)))
+            .after(&UserAuditSnapshot::from(user))
+    });
+    let user = state
+        .user_service
+        .register_user(user_request, verification.required(), organization_id, event)
+        .await?;
+    if verification.required() {
+        verification.send_link(&user).await?;
     }
+    Ok((StatusCode::CREATED, Json(UserResponse::from(user))))
 }
 
 // Handler สำหรับการ Login ผู้ใช้ (POST /login)
+#[utoipa::path(
+    post,
+    path = "/login/user",
+    tag = "auth",
+    request_body = LoginUserRequest,
+    params(LoginQuery),
+    responses(
+        (status = 200, description = "Logged in. With `?mode=cookie` the tokens are set as HttpOnly cookies and the body is a SessionResponse. Accounts with two-factor authentication receive an MfaChallengeResponse instead; finish with POST /auth/mfa/verify", body = LoginResponse),
+        (status = 401, description = "Invalid username or password", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "LDAP login: the account was deleted, the directory username is not a valid username, or the organization is missing or unknown", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 409, description = "LDAP login: the username is already used by a local account", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 429, description = "Too many failed attempts; retry after the `Retry-After` seconds", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 502, description = "LDAP login: the directory could not be reached", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
 pub async fn login_user_handler(
     Extension(state): Extension<Arc<AppState>>,
+    Query(login): Query<LoginQuery>,
+    ClientIp(ip): ClientIp,
+    Audit(audit): Audit,
+    jar: CookieJar,
     Json(payload): Json<LoginUserRequest>,
-) -> Result<Json<LoginResponse>, (StatusCode, String)> {
+) -> Result<Response, AppError> {
+    // username หรือ IP ที่ Login ผิดบ่อยเกินไปจะถูกล็อกชั่วคราว
+    let throttle = &state.login_throttle_service;
+    throttle.check("user", &payload.username, &ip).await?;
+
+    let username = payload.username.clone();
     let login_credentials = LoginCredentials {
         username: payload.username,
         password: payload.password,
     };
 
-    match state.user_service.login_user(login_credentials).await {
-        Ok(user_id) => {
-            // สร้าง JWT Token
-            match state.jwt_service.create_token(user_id, "user") {
-                Ok(token) => {
-                    let response = LoginResponse {
-                        user_id,
-                        token,
-                        role: "user".to_string(),
-                        expires_in: 24 * 60 * 60, // 24 ชั่วโมง
-                    };
-                    Ok(Json(response))
-                }
-                Err(_) => Err((StatusCode::INTERNAL_SERVER_ERROR, "Failed to create token".to_string())),
-            }
-        }
-        Err(e) => Err((StatusCode::UNAUTHORIZED, e)),
+    let result = state.user_service.login_user(login_credentials, &audit).await;
+    let user_id = throttle.record_password("user", &username, &ip, result).await?;
+
+    // บัญชีที่เปิด 2FA (หรือ Role กำหนดให้ต้องใช้) ต้องยืนยันรหัสที่ POST /auth/mfa/verify ก่อนได้รับ Token
+    // ตัวนับการ Login ผิดจะถูกล้างเมื่อยืนยันรหัสสำเร็จเท่านั้น
+    if let Some(challenge) = state.mfa_service.login_challenge(PrincipalKind::User, user_id).await? {
+        return Ok(Json(challenge).into_response());
     }
-}
\ No newline at end of file
+    throttle.record_success("user", &username, &ip).await?;
+
+    // สร้าง Access Token + Refresh Token
+    let tokens = state.auth_service.issue_tokens(user_id, "user").await?;
+
+    // Cookie Session: Token อยู่ใน HttpOnly Cookie และตอบกลับ CSRF Token แทน
+    if login.mode.unwrap_or_default() == LoginMode::Cookie {
+        let (jar, session) = state.session_cookies.start(jar, tokens);
+        return Ok((jar, Json(session)).into_response());
+    }
+    Ok(Json(LoginResponse {
+        user_id,
+        token: tokens.token,
+        role: tokens.role,
+        expires_in: tokens.expires_in,
+        refresh_token: tokens.refresh_token,
+        refresh_expires_in: tokens.refresh_expires_in,
+    }).into_response())
+}
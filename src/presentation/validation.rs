@@ -0,0 +1,77 @@
+// src/presentation/validation.rs
+
+use axum::{
+    async_trait,
+    extract::{rejection::JsonRejection, FromRequest, Request},
+    Json,
+};
+use serde::de::DeserializeOwned;
+use validator::Validate;
+
+use crate::error::AppError;
+
+// ValidatedJson: ใช้แทน `Json<T>` สำหรับ Request Body ที่ต้องตรวจสอบข้อมูล
+// 1. แปลง JSON เป็น T: JSON ผิดไวยากรณ์ -> 400, ไม่ได้ส่ง Content-Type: application/json -> 415,
+//    JSON ถูกต้องแต่ชนิดข้อมูลผิด/ขาด field -> 422 (ข้อความจาก serde)
+// 2. เรียก `T::validate()` และส่ง error ของทุก field กลับไปพร้อมกัน (AppError::InvalidFields)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ValidatedJson<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequest<S> for ValidatedJson<T>
+where
+    T: DeserializeOwned + Validate,
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let Json(value) = Json::<T>::from_request(req, state)
+            .await
+            .map_err(|rejection| match rejection {
+                JsonRejection::JsonDataError(e) => AppError::Validation(e.body_text()),
+                JsonRejection::MissingJsonContentType(e) => AppError::UnsupportedMediaType(e.body_text()),
+                other => AppError::BadRequest(other.body_text()),
+            })?;
+        value.validate()?;
+        Ok(ValidatedJson(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::Body;
+    use axum::http::{header, StatusCode};
+    use serde::Deserialize;
+
+    use super::*;
+
+    #[derive(Debug, Deserialize, Validate)]
+    struct Payload {
+        #[validate(range(min = 1))]
+        count: i32,
+    }
+
+    async fn extract(content_type: Option<&str>, body: &str) -> Result<i32, StatusCode> {
+        let mut request = Request::builder().method("POST").uri("/");
+        if let Some(content_type) = content_type {
+            request = request.header(header::CONTENT_TYPE, content_type);
+        }
+        let request = request.body(Body::from(body.to_string())).unwrap();
+        ValidatedJson::<Payload>::from_request(request, &())
+            .await
+            .map(|ValidatedJson(payload)| payload.count)
+            .map_err(|e| e.status_code())
+    }
+
+    #[tokio::test]
+    async fn rejections_map_to_distinct_statuses() {
+        let json = Some("application/json");
+        assert_eq!(extract(json, r#"{"count": 3}"#).await, Ok(3));
+        assert_eq!(extract(json, r#"{"count": 3"#).await, Err(StatusCode::BAD_REQUEST));
+        assert_eq!(extract(None, r#"{"count": 3}"#).await, Err(StatusCode::UNSUPPORTED_MEDIA_TYPE));
+        assert_eq!(extract(Some("text/plain"), r#"{"count": 3}"#).await, Err(StatusCode::UNSUPPORTED_MEDIA_TYPE));
+        assert_eq!(extract(json, r#"{"count": "three"}"#).await, Err(StatusCode::UNPROCESSABLE_ENTITY));
+        assert_eq!(extract(json, r#"{"count": 0}"#).await, Err(StatusCode::UNPROCESSABLE_ENTITY));
+    }
+}
@@ -0,0 +1,60 @@
+// tests/admin_bootstrap.rs
+mod common;
+
+use axum::http::{Method, StatusCode};
+use common::{TestApp, PASSWORD, SETUP_TOKEN};
+use serde_json::json;
+
+#[tokio::test]
+async fn setup_token_creates_exactly_one_admin() {
+    let app = TestApp::new().await;
+
+    let wrong = app
+        .post("/admin/setup", None, json!({ "setup_token": "guess", "username": "root", "password": PASSWORD }))
+        .await;
+    assert_eq!(wrong.status, StatusCode::UNAUTHORIZED);
+
+    app.admin_token().await;
+
+    let again = app
+        .post("/admin/setup", None, json!({ "setup_token": SETUP_TOKEN, "username": "second", "password": PASSWORD }))
+        .await;
+    assert_eq!(again.status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn invitations_are_single_use_and_revocable() {
+    let app = TestApp::new().await;
+    let admin = app.admin_token().await;
+
+    let invitation = app.post("/admin/invitations", Some(&admin), json!({})).await;
+    assert_eq!(invitation.status, StatusCode::CREATED, "{}", invitation.body);
+    let token = invitation.body["token"].as_str().unwrap().to_string();
+
+    let accept = |username: &'static str| {
+        app.post("/admin/invitations/accept", None, json!({ "token": token, "username": username, "password": PASSWORD }))
+    };
+    assert_eq!(accept("helper").await.status, StatusCode::CREATED);
+    app.login("/login/admin", "helper", PASSWORD).await;
+    assert!(accept("helper2").await.status.is_client_error());
+
+    let revoked = app.post("/admin/invitations", Some(&admin), json!({})).await;
+    let revoked_id = revoked.body["invitation"]["id"].as_i64().unwrap();
+    let revoked_token = revoked.body["token"].as_str().unwrap();
+    let revoke = app
+        .request(Method::DELETE, &format!("/admin/invitations/{revoked_id}"), Some(&admin), None)
+        .await;
+    assert!(revoke.status.is_success(), "{}", revoke.body);
+    let late = app
+        .post("/admin/invitations/accept", None, json!({ "token": revoked_token, "username": "late", "password": PASSWORD }))
+        .await;
+    assert!(late.status.is_client_error());
+}
+
+#[tokio::test]
+async fn users_cannot_invite_admins() {
+    let app = TestApp::new().await;
+    let (_, user) = app.user("kate").await;
+    let invitation = app.post("/admin/invitations", Some(&user), json!({})).await;
+    assert_eq!(invitation.status, StatusCode::FORBIDDEN);
+}
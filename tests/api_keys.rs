@@ -0,0 +1,92 @@
+// tests/api_keys.rs
+mod common;
+
+use axum::{
+    body::Body,
+    http::{header, Method, Request, StatusCode},
+};
+use common::TestApp;
+use serde_json::json;
+
+async fn create_key(app: &TestApp, token: &str, scopes: &[&str]) -> (i64, String) {
+    let created = app.post("/users/me/api-keys", Some(token), json!({ "name": "Script", "scopes": scopes })).await;
+    assert_eq!(created.status, StatusCode::CREATED, "{}", created.body);
+    let key = created.body["key"].as_str().unwrap().to_string();
+    assert!(key.starts_with(created.body["api_key"]["prefix"].as_str().unwrap()));
+    (created.body["api_key"]["id"].as_i64().unwrap(), key)
+}
+
+#[tokio::test]
+async fn keys_only_get_their_scopes() {
+    let app = TestApp::new().await;
+    let admin = app.admin_token().await;
+    let (_, key) = create_key(&app, &admin, &["reports:read"]).await;
+
+    assert_eq!(app.get("/admin/reports/peak-hours", &key).await.status, StatusCode::OK);
+    assert_eq!(app.get("/admin/users", &key).await.status, StatusCode::FORBIDDEN);
+
+    // ส่งเป็น Bearer ได้เหมือน X-API-Key
+    let request = Request::builder()
+        .uri("/admin/reports/peak-hours")
+        .header(header::AUTHORIZATION, format!("Bearer {key}"))
+        .body(Body::empty())
+        .unwrap();
+    assert_eq!(app.send(request).await.status, StatusCode::OK);
+
+    // ตัว Key แสดงครั้งเดียว รายการมีแค่ prefix
+    let listed = app.get("/users/me/api-keys", &admin).await;
+    assert!(!listed.body.to_string().contains(&key));
+}
+
+#[tokio::test]
+async fn scopes_must_be_held_when_created_and_when_used() {
+    let app = TestApp::new().await;
+    let admin = app.admin_token().await;
+    let (user_id, token) = app.user("vera").await;
+
+    let denied = app.post("/users/me/api-keys", Some(&token), json!({ "name": "x", "scopes": ["rooms:write"] })).await;
+    assert_eq!(denied.status, StatusCode::FORBIDDEN);
+
+    let roles = format!("/admin/users/{user_id}/roles");
+    app.post(&roles, Some(&admin), json!({ "role": "room_manager" })).await;
+    let (_, key) = create_key(&app, &token, &["rooms:write"]).await;
+    let create_room = || app.post("/admin/rooms", Some(&key), json!({ "name": "Lab", "status": "available" }));
+    assert_eq!(create_room().await.status, StatusCode::CREATED);
+
+    // Role ถูกถอน: Key ใช้ Permission นั้นไม่ได้อีก
+    let removed = app.request(Method::DELETE, &format!("{roles}/room_manager"), Some(&admin), None).await;
+    assert!(removed.status.is_success(), "{}", removed.body);
+    assert_eq!(create_room().await.status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn revoked_and_expired_keys_are_rejected() {
+    let app = TestApp::new().await;
+    let (_, token) = app.user("walt").await;
+    let (revoked_id, revoked) = create_key(&app, &token, &["bookings:write"]).await;
+    let (expired_id, expired) = create_key(&app, &token, &["bookings:write"]).await;
+    assert_eq!(app.get("/bookings/user", &revoked).await.status, StatusCode::OK);
+
+    let revoke = app.request(Method::DELETE, &format!("/users/me/api-keys/{revoked_id}"), Some(&token), None).await;
+    assert_eq!(revoke.status, StatusCode::NO_CONTENT);
+    assert_eq!(app.get("/bookings/user", &revoked).await.status, StatusCode::UNAUTHORIZED);
+
+    app.execute(&format!("UPDATE api_keys SET expires_at = '2000-01-01 00:00:00' WHERE id = {expired_id}"));
+    assert_eq!(app.get("/bookings/user", &expired).await.status, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn keys_cannot_manage_keys_or_change_the_password() {
+    let app = TestApp::new().await;
+    let (_, token) = app.user("xena").await;
+    let (key_id, key) = create_key(&app, &token, &["bookings:write"]).await;
+
+    let create = app.post("/users/me/api-keys", Some(&key), json!({ "name": "y", "scopes": ["bookings:write"] })).await;
+    assert_eq!(create.status, StatusCode::FORBIDDEN);
+    let revoke = app.request(Method::DELETE, &format!("/users/me/api-keys/{key_id}"), Some(&key), None).await;
+    assert_eq!(revoke.status, StatusCode::FORBIDDEN);
+    let password = app
+        .post("/users/me/password", Some(&key), json!({ "current_password": common::PASSWORD, "new_password": "another-passphrase" }))
+        .await;
+    assert_eq!(password.status, StatusCode::FORBIDDEN);
+}
@@ -0,0 +1,167 @@
+// tests/audit.rs
+mod common;
+
+use std::process::Command;
+use std::sync::Arc;
+
+use axum::http::{Method, StatusCode};
+use common::TestApp;
+use serde_json::{json, Value};
+
+async fn verify(app: &TestApp, admin: &str) -> common::TestResponse {
+    let verification = app.get("/admin/audit/verify", admin).await;
+    assert_eq!(verification.status, StatusCode::OK, "{}", verification.body);
+    verification
+}
+
+// `verify-audit` กับ Database ของ Test: คืน (exit code เป็น 0 หรือไม่, ผลที่พิมพ์ออกมา)
+fn verify_audit_command(app: &TestApp) -> (bool, Value) {
+    let output = Command::new(env!("CARGO_BIN_EXE_room-booking-api-minimal"))
+        .arg("verify-audit")
+        .env("DATABASE_URL", app.database_url())
+        .env("JWT_SECRET", "test-secret-that-is-long-enough-for-hs256")
+        .env("RUST_LOG", "off")
+        .output()
+        .unwrap();
+    let result = serde_json::from_slice(&output.stdout).unwrap_or_else(|_| {
+        panic!("{}{}", String::from_utf8_lossy(&output.stdout), String::from_utf8_lossy(&output.stderr))
+    });
+    (output.status.success(), result)
+}
+
+#[tokio::test]
+async fn changes_are_recorded_with_the_request_and_the_chain_verifies() {
+    let app = TestApp::new().await;
+    let admin = app.admin_token().await;
+    let room = app.room(&admin, "Audited").await;
+    let (user_id, token) = app.user("lia").await;
+    let booking = app.book(&token, room, 1, 9, 10).await.body["id"].as_i64().unwrap();
+    app.request(Method::DELETE, &format!("/bookings/{booking}"), Some(&token), None).await;
+
+    let created = app.get(&format!("/admin/audit?action=room.create&entity_id={room}"), &admin).await;
+    assert_eq!(created.body["total"], 1, "{}", created.body);
+    assert_eq!(created.body["items"][0]["actor_kind"], "admin");
+    assert_eq!(created.body["items"][0]["after"]["name"], "Audited");
+
+    let cancelled = app.get(&format!("/admin/audit?action=booking.cancel&entity_id={booking}"), &admin).await;
+    assert_eq!(cancelled.body["total"], 1, "{}", cancelled.body);
+    let entry = &cancelled.body["items"][0];
+    assert_eq!(entry["actor_kind"], "user");
+    assert_eq!(entry["actor_id"], user_id);
+    assert_eq!(entry["before"]["status"], "active");
+    assert_eq!(entry["after"]["status"], "cancelled");
+    assert!(entry["request_id"].is_string());
+
+    let verification = verify(&app, &admin).await;
+    assert_eq!(verification.body["valid"], true, "{}", verification.body);
+    assert!(verification.body["checked"].as_i64().unwrap() >= 4);
+    assert_eq!(verification.body["broken_at"], json!(null));
+    assert_eq!(verify_audit_command(&app), (true, verification.body));
+}
+
+#[tokio::test]
+async fn a_failed_audit_write_rolls_back_the_change() {
+    let app = TestApp::new().await;
+    let admin = app.admin_token().await;
+    let room = app.room(&admin, "Kept").await;
+    app.execute(
+        "CREATE TRIGGER audit_log_fail BEFORE INSERT ON audit_log BEGIN SELECT RAISE(ABORT, 'audit log unavailable'); END;",
+    );
+
+    let created = app.post("/admin/rooms", Some(&admin), json!({ "name": "Lost", "status": "available" })).await;
+    assert_eq!(created.status, StatusCode::INTERNAL_SERVER_ERROR, "{}", created.body);
+    let path = format!("/admin/rooms/{room}");
+    let updated = app.request(Method::PATCH, &path, Some(&admin), Some(json!({ "name": "Renamed" }))).await;
+    assert_eq!(updated.status, StatusCode::INTERNAL_SERVER_ERROR, "{}", updated.body);
+    app.execute("DROP TRIGGER audit_log_fail;");
+
+    // ไม่มีการเปลี่ยนแปลงที่ไม่มีแถวใน Audit Log
+    let rooms = app.get("/rooms", &admin).await;
+    let names: Vec<_> = rooms.body["items"].as_array().unwrap().iter().map(|r| r["name"].as_str().unwrap()).collect();
+    assert_eq!(names, vec!["Kept"]);
+    assert_eq!(verify(&app, &admin).await.body["valid"], true);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn concurrent_writes_keep_the_chain_intact() {
+    let app = Arc::new(TestApp::new().await);
+    let admin = app.admin_token().await;
+    let (_, token) = app.user("noa").await;
+    let room = app.room(&admin, "Busy").await;
+
+    // เขียนพร้อมกันจากหลาย Connection: ต้องรอ Lock แทนที่จะได้ SQLITE_BUSY (500)
+    let mut tasks = Vec::new();
+    for i in 0..8u32 {
+        let (app, admin, token) = (app.clone(), admin.clone(), token.clone());
+        tasks.push(tokio::spawn(async move {
+            let created = app.post("/admin/rooms", Some(&admin), json!({ "name": format!("Room {i}"), "status": "available" })).await;
+            let booked = app.book(&token, room, 1, i + 8, i + 9).await;
+            (created.status, booked.status)
+        }));
+    }
+    for task in tasks {
+        assert_eq!(task.await.unwrap(), (StatusCode::CREATED, StatusCode::CREATED));
+    }
+
+    let created = app.get("/admin/audit?action=room.create", &admin).await;
+    assert_eq!(created.body["total"], 9, "{}", created.body);
+    let verification = verify(&app, &admin).await;
+    assert_eq!(verification.body["valid"], true, "{}", verification.body);
+}
+
+#[tokio::test]
+async fn modified_entries_are_detected() {
+    let app = TestApp::new().await;
+    let admin = app.admin_token().await;
+    app.room(&admin, "First").await;
+    let room = app.room(&admin, "Second").await;
+    app.room(&admin, "Third").await;
+    let entry = app.get(&format!("/admin/audit?action=room.create&entity_id={room}"), &admin).await;
+    let id = entry.body["items"][0]["id"].as_i64().unwrap();
+
+    // ผู้ที่เข้าถึงฐานข้อมูลได้โดยตรงลบ Trigger แล้วแก้แถวย้อนหลัง
+    app.execute(&format!(
+        "DROP TRIGGER audit_log_no_update; UPDATE audit_log SET after_json = '{{\"name\":\"Forged\"}}' WHERE id = {id};"
+    ));
+    let verification = verify(&app, &admin).await;
+    assert_eq!(verification.body["valid"], false, "{}", verification.body);
+    assert_eq!(verification.body["broken_at"], id);
+    assert!(verification.body["reason"].as_str().unwrap().contains("modified"));
+    assert_eq!(verification.body["checked"], id - 1);
+    assert_eq!(verify_audit_command(&app), (false, verification.body));
+
+    // คำนวณ hash ใหม่ของแถวนั้นด้วย: แถวถัดไปยังชี้ไปที่ hash เดิม
+    app.execute(&format!("UPDATE audit_log SET hash = 'forged' WHERE id = {id};"));
+    let verification = verify(&app, &admin).await;
+    assert_eq!(verification.body["valid"], false);
+    assert_eq!(verification.body["broken_at"], id);
+}
+
+#[tokio::test]
+async fn removed_entries_are_detected() {
+    let app = TestApp::new().await;
+    let admin = app.admin_token().await;
+    app.room(&admin, "First").await;
+    let room = app.room(&admin, "Second").await;
+    app.room(&admin, "Third").await;
+    let entry = app.get(&format!("/admin/audit?action=room.create&entity_id={room}"), &admin).await;
+    let id = entry.body["items"][0]["id"].as_i64().unwrap();
+
+    app.execute(&format!("DROP TRIGGER audit_log_no_delete; DELETE FROM audit_log WHERE id = {id};"));
+    let verification = verify(&app, &admin).await;
+    assert_eq!(verification.body["valid"], false, "{}", verification.body);
+    assert_eq!(verification.body["broken_at"], id + 1);
+    assert!(verification.body["reason"].as_str().unwrap().contains("removed"));
+    assert_eq!(verify_audit_command(&app), (false, verification.body));
+}
+
+#[tokio::test]
+async fn only_platform_admins_verify_the_chain() {
+    let app = TestApp::new().await;
+    let admin = app.admin_token().await;
+    let organization = app.organization(&admin, "acme").await;
+    let org_admin = app.invited_admin(&admin, Some(organization), "orgadmin").await;
+    assert_eq!(app.get("/admin/audit/verify", &org_admin).await.status, StatusCode::FORBIDDEN);
+    let (_, user) = app.user("max").await;
+    assert_eq!(app.get("/admin/audit/verify", &user).await.status, StatusCode::FORBIDDEN);
+}
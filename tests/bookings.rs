@@ -0,0 +1,41 @@
+// tests/bookings.rs
+mod common;
+
+use axum::http::{Method, StatusCode};
+use common::TestApp;
+
+#[tokio::test]
+async fn users_cancel_only_their_own_bookings() {
+    let app = TestApp::new().await;
+    let admin = app.admin_token().await;
+    let room = app.room(&admin, "Harness").await;
+    let (user_id, token) = app.user("ann").await;
+    let (_, other) = app.user("ben").await;
+
+    let booked = app.book(&token, room, 1, 9, 10).await;
+    assert_eq!(booked.status, StatusCode::CREATED, "{}", booked.body);
+    assert_eq!(booked.body["user_id"], user_id);
+    assert_eq!(booked.body["room_id"], room);
+
+    let path = format!("/bookings/{}", booked.body["id"]);
+    assert!(!app.request(Method::DELETE, &path, Some(&other), None).await.status.is_success());
+    assert!(app.request(Method::DELETE, &path, Some(&token), None).await.status.is_success());
+    assert!(!app.request(Method::DELETE, &path, Some(&token), None).await.status.is_success());
+}
+
+#[tokio::test]
+async fn overlapping_bookings_are_rejected() {
+    let app = TestApp::new().await;
+    let admin = app.admin_token().await;
+    let room = app.room(&admin, "Overlap").await;
+    let other_room = app.room(&admin, "Other").await;
+    let (_, ann) = app.user("ann").await;
+    let (_, ben) = app.user("ben").await;
+
+    assert_eq!(app.book(&ann, room, 1, 9, 11).await.status, StatusCode::CREATED);
+    assert_eq!(app.book(&ben, room, 1, 10, 12).await.status, StatusCode::CONFLICT);
+    assert_eq!(app.book(&ben, room, 1, 8, 12).await.status, StatusCode::CONFLICT);
+    // ช่วงเวลาที่ต่อกันพอดี หรือห้องอื่น จองได้
+    assert_eq!(app.book(&ben, room, 1, 11, 12).await.status, StatusCode::CREATED);
+    assert_eq!(app.book(&ben, other_room, 1, 9, 11).await.status, StatusCode::CREATED);
+}
@@ -0,0 +1,141 @@
+// tests/common/idp.rs
+// Identity Provider จำลองสำหรับ Integration Test ของ OIDC (แบบเดียวกับ examples/mock_idp.rs แต่ไม่มีหน้า Login)
+// Test กำหนด claim ของผู้ใช้เองด้วย issue_code แล้วส่ง code ไปที่ /auth/oidc/callback
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use axum::{extract::State, http::StatusCode, response::IntoResponse, routing::{get, post}, Form, Json, Router};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use ed25519_dalek::pkcs8::{spki::der::pem::LineEnding, EncodePrivateKey};
+use jsonwebtoken::jwk::{
+    AlgorithmParameters, CommonParameters, EllipticCurve, Jwk, JwkSet, KeyAlgorithm, OctetKeyPairParameters,
+    OctetKeyPairType, PublicKeyUse,
+};
+use jsonwebtoken::{encode, Algorithm, EncodingKey, Header};
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+
+use room_booking_api_minimal::application::oidc_service::OidcConfig;
+use room_booking_api_minimal::domain::organization::ExternalOrganization;
+use room_booking_api_minimal::domain::permission::GroupRoleMap;
+
+pub const CLIENT_ID: &str = "room-booking";
+const KID: &str = "test-1";
+
+struct Idp {
+    issuer: String,
+    encoding: EncodingKey,
+    jwks: JwkSet,
+    codes: Mutex<HashMap<String, Map<String, Value>>>,
+}
+
+#[derive(Clone)]
+pub struct MockIdp {
+    inner: Arc<Idp>,
+}
+
+#[derive(Deserialize)]
+struct TokenForm {
+    code: String,
+}
+
+async fn discovery(State(idp): State<Arc<Idp>>) -> Json<Value> {
+    Json(json!({
+        "issuer": idp.issuer,
+        "authorization_endpoint": format!("{}/authorize", idp.issuer),
+        "token_endpoint": format!("{}/token", idp.issuer),
+        "jwks_uri": format!("{}/jwks", idp.issuer),
+    }))
+}
+
+async fn jwks(State(idp): State<Arc<Idp>>) -> Json<JwkSet> {
+    Json(idp.jwks.clone())
+}
+
+async fn token(State(idp): State<Arc<Idp>>, Form(form): Form<TokenForm>) -> axum::response::Response {
+    let Some(claims) = idp.codes.lock().unwrap().remove(&form.code) else {
+        return (StatusCode::BAD_REQUEST, Json(json!({ "error": "invalid_grant" }))).into_response();
+    };
+    let mut header = Header::new(Algorithm::EdDSA);
+    header.kid = Some(KID.to_string());
+    let id_token = encode(&header, &claims, &idp.encoding).unwrap();
+    Json(json!({ "access_token": "unused", "token_type": "Bearer", "id_token": id_token })).into_response()
+}
+
+impl MockIdp {
+    pub async fn start() -> MockIdp {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let issuer = format!("http://{}", listener.local_addr().unwrap());
+
+        let signing_key = ed25519_dalek::SigningKey::from_bytes(&[7u8; 32]);
+        let pem = signing_key.to_pkcs8_pem(LineEnding::LF).unwrap();
+        let jwk = Jwk {
+            common: CommonParameters {
+                public_key_use: Some(PublicKeyUse::Signature),
+                key_id: Some(KID.to_string()),
+                key_algorithm: Some(KeyAlgorithm::EdDSA),
+                ..Default::default()
+            },
+            algorithm: AlgorithmParameters::OctetKeyPair(OctetKeyPairParameters {
+                key_type: OctetKeyPairType::OctetKeyPair,
+                curve: EllipticCurve::Ed25519,
+                x: URL_SAFE_NO_PAD.encode(signing_key.verifying_key().as_bytes()),
+            }),
+        };
+        let inner = Arc::new(Idp {
+            issuer,
+            encoding: EncodingKey::from_ed_pem(pem.as_bytes()).unwrap(),
+            jwks: JwkSet { keys: vec![jwk] },
+            codes: Mutex::new(HashMap::new()),
+        });
+
+        let app = Router::new()
+            .route("/.well-known/openid-configuration", get(discovery))
+            .route("/jwks", get(jwks))
+            .route("/token", post(token))
+            .with_state(inner.clone());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        MockIdp { inner }
+    }
+
+    // ค่าตั้งค่า OIDC ที่ชี้มาที่ IdP นี้ (องค์กรกำหนดได้ตาม Test)
+    pub fn config(&self, organization: ExternalOrganization) -> OidcConfig {
+        OidcConfig {
+            issuer_url: self.inner.issuer.clone(),
+            client_id: CLIENT_ID.to_string(),
+            client_secret: None,
+            redirect_url: "http://localhost/auth/oidc/callback".to_string(),
+            scopes: "openid profile groups".to_string(),
+            username_claim: "preferred_username".to_string(),
+            groups_claim: "groups".to_string(),
+            group_roles: GroupRoleMap::parse("facilities=room_manager").unwrap(),
+            organization,
+            post_login_url: None,
+            state_ttl_seconds: 600,
+        }
+    }
+
+    // ออก Authorization Code ที่แลกได้เป็น ID Token ที่มี claim ตาม extra (sub มาจาก username)
+    pub fn issue_code(&self, nonce: &str, extra: Value) -> String {
+        let now = chrono::Utc::now().timestamp();
+        let mut claims = json!({
+            "iss": self.inner.issuer,
+            "aud": CLIENT_ID,
+            "iat": now,
+            "exp": now + 300,
+            "nonce": nonce,
+        });
+        let claims_map = claims.as_object_mut().unwrap();
+        for (key, value) in extra.as_object().unwrap() {
+            claims_map.insert(key.clone(), value.clone());
+        }
+        if !claims_map.contains_key("sub") {
+            let sub = format!("sub|{}", claims_map["preferred_username"].as_str().unwrap_or("anonymous"));
+            claims_map.insert("sub".to_string(), Value::String(sub));
+        }
+        let code = uuid::Uuid::new_v4().to_string();
+        self.inner.codes.lock().unwrap().insert(code.clone(), claims_map.clone());
+        code
+    }
+}
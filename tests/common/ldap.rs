@@ -0,0 +1,122 @@
+// tests/common/ldap.rs
+// LDAP directory จำลองสำหรับ Integration Test (แบบเดียวกับ examples/mock_ldap.rs)
+// ผู้ใช้ทุกคนมีรหัสผ่าน DIRECTORY_PASSWORD และกำหนด attribute เพิ่มได้ต่อคน
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use futures_util::{SinkExt, StreamExt};
+use ldap3_proto::simple::{LdapFilter, LdapMsg, LdapPartialAttribute, LdapSearchResultEntry, ServerOps};
+use ldap3_proto::LdapCodec;
+use tokio::net::TcpListener;
+use tokio_util::codec::{FramedRead, FramedWrite};
+
+use room_booking_api_minimal::domain::organization::ExternalOrganization;
+use room_booking_api_minimal::domain::permission::GroupRoleMap;
+use room_booking_api_minimal::infrastructure::auth_provider::LdapConfig;
+
+pub const BASE_DN: &str = "dc=example,dc=com";
+pub const DIRECTORY_PASSWORD: &str = "directory-password";
+
+pub struct Entry {
+    dn: String,
+    attributes: Vec<(String, Vec<String>)>,
+}
+
+// รายการผู้ใช้ uid พร้อม attribute เพิ่มเติม เช่น ("o", "acme")
+pub fn person(uid: &str, extra: &[(&str, &str)]) -> Entry {
+    let mut attributes = vec![
+        ("uid".to_string(), vec![uid.to_string()]),
+        ("displayName".to_string(), vec![format!("Directory {uid}")]),
+    ];
+    attributes.extend(extra.iter().map(|(key, value)| (key.to_string(), vec![value.to_string()])));
+    Entry { dn: format!("uid={uid},ou=people,{BASE_DN}"), attributes }
+}
+
+impl Entry {
+    fn values(&self, name: &str) -> &[String] {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, values)| values.as_slice())
+            .unwrap_or(&[])
+    }
+
+    fn matches(&self, filter: &LdapFilter) -> bool {
+        match filter {
+            LdapFilter::And(filters) => filters.iter().all(|f| self.matches(f)),
+            LdapFilter::Or(filters) => filters.iter().any(|f| self.matches(f)),
+            LdapFilter::Equality(name, value) => self.values(name).iter().any(|v| v.eq_ignore_ascii_case(value)),
+            LdapFilter::Present(name) => !self.values(name).is_empty(),
+            _ => false,
+        }
+    }
+}
+
+async fn serve(directory: Arc<Vec<Entry>>, listener: TcpListener) {
+    while let Ok((socket, _)) = listener.accept().await {
+        let directory = directory.clone();
+        tokio::spawn(async move {
+            let (reader, writer) = tokio::io::split(socket);
+            let mut requests = FramedRead::new(reader, LdapCodec::default());
+            let mut responses = FramedWrite::new(writer, LdapCodec::default());
+            while let Some(Ok(message)) = requests.next().await {
+                let replies: Vec<LdapMsg> = match ServerOps::try_from(message) {
+                    Ok(ServerOps::SimpleBind(request)) => {
+                        let accepted = (request.dn.is_empty() && request.pw.is_empty())
+                            || (request.pw == DIRECTORY_PASSWORD && directory.iter().any(|e| e.dn.eq_ignore_ascii_case(&request.dn)));
+                        vec![if accepted { request.gen_success() } else { request.gen_invalid_cred() }]
+                    }
+                    Ok(ServerOps::Search(request)) => {
+                        let mut replies: Vec<LdapMsg> = directory
+                            .iter()
+                            .filter(|e| e.matches(&request.filter))
+                            .map(|e| {
+                                request.gen_result_entry(LdapSearchResultEntry {
+                                    dn: e.dn.clone(),
+                                    attributes: e
+                                        .attributes
+                                        .iter()
+                                        .map(|(key, values)| LdapPartialAttribute {
+                                            atype: key.clone(),
+                                            vals: values.iter().map(|v| v.as_bytes().to_vec()).collect(),
+                                        })
+                                        .collect(),
+                                })
+                            })
+                            .collect();
+                        replies.push(request.gen_success());
+                        replies
+                    }
+                    _ => return,
+                };
+                for reply in replies {
+                    if responses.send(reply).await.is_err() {
+                        return;
+                    }
+                }
+            }
+        });
+    }
+}
+
+// เริ่ม directory แล้วคืน LdapConfig ที่ชี้มาที่มัน
+pub async fn start(entries: Vec<Entry>, organization: ExternalOrganization) -> LdapConfig {
+    let listener = TcpListener::bind(SocketAddr::from(([127, 0, 0, 1], 0))).await.unwrap();
+    let url = format!("ldap://{}", listener.local_addr().unwrap());
+    tokio::spawn(serve(Arc::new(entries), listener));
+    LdapConfig {
+        url,
+        starttls: false,
+        bind_dn: None,
+        bind_password: None,
+        base_dn: BASE_DN.to_string(),
+        user_filter: "(uid={username})".to_string(),
+        username_attribute: "uid".to_string(),
+        display_name_attribute: "displayName".to_string(),
+        group_attribute: "memberOf".to_string(),
+        group_roles: GroupRoleMap::default(),
+        organization,
+        timeout_seconds: 5,
+    }
+}
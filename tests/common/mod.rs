@@ -0,0 +1,360 @@
+// tests/common/mod.rs
+// ตัวช่วยสำหรับ Integration Test: สร้าง AppState กับ Database SQLite ชั่วคราว แล้วยิง Request ผ่าน Router โดยตรง
+#![allow(dead_code)]
+
+pub mod idp;
+pub mod ldap;
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use axum::body::{to_bytes, Body};
+use axum::http::{header, HeaderMap, Method, Request, StatusCode};
+use axum::Router;
+use axum_extra::extract::cookie::SameSite;
+use diesel::connection::SimpleConnection;
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use tower::ServiceExt;
+
+use room_booking_api_minimal::app_state::AppState;
+use room_booking_api_minimal::application::{
+    admin_service::AdminService, api_key_service::ApiKeyService, audit_service::AuditService,
+    auth_service::AuthService, booking_service::BookingService,
+    email_verification_service::EmailVerificationService,
+    group_service::GroupService, impersonation_service::ImpersonationService,
+    invitation_service::InvitationService, login_throttle_service::LoginThrottleService,
+    mfa_service::MfaService, organization_service::OrganizationService, password_service::PasswordService,
+    privacy_service::PrivacyService, report_service::ReportService, role_service::RoleService,
+    oidc_service::{OidcConfig, OidcService}, room_service::RoomService, user_service::UserService,
+};
+use room_booking_api_minimal::domain::login_throttle::LockoutPolicy;
+use room_booking_api_minimal::error::AppError;
+use room_booking_api_minimal::infrastructure::{
+    admin_repository::AdminRepository, api_key_repository::ApiKeyRepository,
+    audit_repository::AuditRepository, auth_provider::{AuthProvider, LdapAuthProvider, LdapConfig, PasswordAuthProvider},
+    database::{connection_pool, DbPool}, group_repository::GroupRepository,
+    impersonation_repository::ImpersonationRepository, jwt::JwtService, jwt_keys::JwtKeySet,
+    login_throttle_repository::LoginThrottleRepository, mfa_repository::MfaRepository,
+    notifier::{Notification, Notifier}, oidc_repository::OidcRepository,
+    organization_repository::OrganizationRepository,
+    password_reset_repository::PasswordResetRepository, privacy_repository::PrivacyRepository,
+    report_repository::ReportRepository, role_repository::RoleRepository,
+    room_repository::RoomRepository, token_repository::TokenRepository,
+    user_repository::UserRepository,
+};
+use room_booking_api_minimal::middleware::session::SessionCookies;
+use room_booking_api_minimal::presentation::router::router;
+
+pub const PASSWORD: &str = "correct-horse-battery";
+pub const SETUP_TOKEN: &str = "test-setup-token";
+
+// ค่าที่ปรับได้ต่อ Test (ค่าเริ่มต้นเหมือน main)
+#[derive(Clone)]
+pub struct TestConfig {
+    pub login_max_failures: i32,
+    pub login_ip_max_failures: i32,
+    pub email_verification_required: bool,
+    pub oidc: Option<OidcConfig>,
+    pub ldap: Option<LdapConfig>, // ตั้งแล้ว Login ผู้ใช้ลอง LDAP ก่อนรหัสผ่านในระบบ (AUTH_PROVIDERS=ldap,local)
+}
+
+impl Default for TestConfig {
+    fn default() -> Self {
+        TestConfig {
+            login_max_failures: 5,
+            login_ip_max_failures: 20,
+            email_verification_required: false,
+            oidc: None,
+            ldap: None,
+        }
+    }
+}
+
+// MemoryNotifier: เก็บข้อความไว้ให้ Test อ่าน Token จากลิงก์
+#[derive(Clone, Default)]
+pub struct MemoryNotifier {
+    pub sent: Arc<Mutex<Vec<Notification>>>,
+}
+
+#[async_trait]
+impl Notifier for MemoryNotifier {
+    async fn send(&self, notification: Notification) -> Result<(), AppError> {
+        self.sent.lock().unwrap().push(notification);
+        Ok(())
+    }
+}
+
+// ลำดับของ Database ชั่วคราวภายใน Process เดียวกัน (Test รันขนานกันได้)
+static DATABASES: AtomicUsize = AtomicUsize::new(0);
+
+pub struct TestResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: Value,
+}
+
+pub struct TestApp {
+    pub router: Router,
+    pub state: Arc<AppState>,
+    pub pool: DbPool,
+    pub notifier: MemoryNotifier,
+    path: PathBuf,
+}
+
+impl Drop for TestApp {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+        // ไฟล์ของ WAL
+        for suffix in ["-wal", "-shm"] {
+            let mut path = self.path.clone().into_os_string();
+            path.push(suffix);
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+impl TestApp {
+    pub async fn new() -> TestApp {
+        TestApp::with_config(TestConfig::default()).await
+    }
+
+    pub async fn with_config(config: TestConfig) -> TestApp {
+        let name = format!(
+            "room-booking-test-{}-{}.db",
+            std::process::id(),
+            DATABASES.fetch_add(1, Ordering::Relaxed)
+        );
+        let path = std::env::temp_dir().join(name);
+        let _ = std::fs::remove_file(&path);
+        let pool = connection_pool(path.to_str().unwrap());
+        pool.get()
+            .unwrap()
+            .batch_execute(include_str!("../../migrations/up.sql"))
+            .expect("migrations");
+
+        let jwt_keys = JwtKeySet::from_config(Some("test-secret-that-is-long-enough-for-hs256"), None, None).unwrap();
+        let jwt_service = JwtService::new(jwt_keys, 15 * 60);
+        let organization_repo = OrganizationRepository::new(pool.clone());
+        let role_repo = RoleRepository::new(pool.clone());
+        let user_repo = UserRepository::new(pool.clone());
+        let mut providers: Vec<Arc<dyn AuthProvider>> = Vec::new();
+        if let Some(ldap) = config.ldap {
+            providers.push(Arc::new(LdapAuthProvider::new(ldap, user_repo.clone(), role_repo.clone())));
+        }
+        providers.push(Arc::new(PasswordAuthProvider::new(user_repo.clone())));
+        let notifier = MemoryNotifier::default();
+        let auth_service = AuthService::new(
+            TokenRepository::new(pool.clone()),
+            organization_repo.clone(),
+            jwt_service.clone(),
+            30,
+        );
+        let admin_repo = AdminRepository::new(pool.clone());
+        let admin_service = AdminService::new(admin_repo.clone());
+        admin_service.start_bootstrap(Some(SETUP_TOKEN.to_string())).await.unwrap();
+        let policy = |threshold: i32| LockoutPolicy {
+            threshold,
+            base_delay: chrono::Duration::seconds(30),
+            max_delay: chrono::Duration::hours(1),
+            window: chrono::Duration::minutes(15),
+        };
+
+        let oidc_service = config
+            .oidc
+            .map(|oidc| OidcService::new(oidc, OidcRepository::new(pool.clone()), user_repo.clone(), role_repo.clone()).unwrap());
+
+        let state = Arc::new(AppState {
+            db_pool: pool.clone(),
+            room_service: RoomService::new(RoomRepository::new(pool.clone())),
+            user_service: UserService::new(user_repo.clone(), providers),
+            admin_service,
+            jwt_service: jwt_service.clone(),
+            booking_service: BookingService::new(pool.clone()),
+            report_service: ReportService::new(ReportRepository::new(pool.clone()), 10.0),
+            auth_service: auth_service.clone(),
+            role_service: RoleService::new(role_repo.clone()),
+            invitation_service: InvitationService::new(
+                admin_repo,
+                jwt_service.clone(),
+                72,
+                "http://localhost/admin/invitations/accept".to_string(),
+            ),
+            password_service: PasswordService::new(
+                user_repo.clone(),
+                PasswordResetRepository::new(pool.clone()),
+                auth_service,
+                Arc::new(notifier.clone()),
+                30,
+                "http://localhost/auth/password-reset/confirm".to_string(),
+            ),
+            email_verification_service: EmailVerificationService::new(
+                user_repo,
+                jwt_service.clone(),
+                Arc::new(notifier.clone()),
+                24,
+                "http://localhost/auth/email-verification/confirm".to_string(),
+                config.email_verification_required,
+            ),
+            login_throttle_service: LoginThrottleService::new(
+                LoginThrottleRepository::new(pool.clone()),
+                policy(config.login_max_failures),
+                policy(config.login_ip_max_failures),
+            ),
+            mfa_service: MfaService::new(MfaRepository::new(pool.clone()), role_repo, "Test".to_string(), 5 * 60),
+            api_key_service: ApiKeyService::new(ApiKeyRepository::new(pool.clone()), 90),
+            privacy_service: PrivacyService::new(PrivacyRepository::new(pool.clone())),
+            organization_service: OrganizationService::new(organization_repo),
+            group_service: GroupService::new(GroupRepository::new(pool.clone())),
+            impersonation_service: ImpersonationService::new(
+                ImpersonationRepository::new(pool.clone()),
+                jwt_service,
+                15 * 60,
+            ),
+            audit_service: AuditService::new(AuditRepository::new(pool.clone())),
+            oidc_service,
+            session_cookies: SessionCookies::new(false, SameSite::Strict),
+            trust_proxy_headers: false,
+        });
+
+        TestApp { router: router(state.clone()), state, pool, notifier, path }
+    }
+
+    // ส่ง Request แบบ JSON (token = Access Token)
+    pub async fn request(&self, method: Method, uri: &str, token: Option<&str>, body: Option<Value>) -> TestResponse {
+        let mut builder = Request::builder().method(method).uri(uri);
+        if let Some(token) = token {
+            builder = builder.header(header::AUTHORIZATION, format!("Bearer {token}"));
+        }
+        let request = match body {
+            Some(body) => builder
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap(),
+            None => builder.body(Body::empty()).unwrap(),
+        };
+        self.send(request).await
+    }
+
+    pub async fn send(&self, request: Request<Body>) -> TestResponse {
+        let response = self.router.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let headers = response.headers().clone();
+        let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body = serde_json::from_slice(&bytes)
+            .unwrap_or_else(|_| Value::String(String::from_utf8_lossy(&bytes).into_owned()));
+        TestResponse { status, headers, body }
+    }
+
+    pub async fn get(&self, uri: &str, token: &str) -> TestResponse {
+        self.request(Method::GET, uri, Some(token), None).await
+    }
+
+    pub async fn post(&self, uri: &str, token: Option<&str>, body: Value) -> TestResponse {
+        self.request(Method::POST, uri, token, Some(body)).await
+    }
+
+    // สร้าง Admin คนแรก (Platform Admin) แล้วคืน Access Token
+    pub async fn admin_token(&self) -> String {
+        let created = self
+            .post("/admin/setup", None, json!({ "setup_token": SETUP_TOKEN, "username": "root", "password": PASSWORD }))
+            .await;
+        assert_eq!(created.status, StatusCode::CREATED, "{}", created.body);
+        self.login("/login/admin", "root", PASSWORD).await
+    }
+
+    // สมัครผู้ใช้แล้วคืน (user_id, Access Token)
+    pub async fn user(&self, username: &str) -> (i64, String) {
+        let registered = self
+            .post("/register", None, json!({ "username": username, "password": PASSWORD }))
+            .await;
+        assert_eq!(registered.status, StatusCode::CREATED, "{}", registered.body);
+        let id = registered.body["id"].as_i64().unwrap();
+        (id, self.login("/login/user", username, PASSWORD).await)
+    }
+
+    pub async fn login(&self, path: &str, username: &str, password: &str) -> String {
+        let response = self.login_response(path, username, password).await;
+        response.body["token"].as_str().expect("login token").to_string()
+    }
+
+    // Body ทั้งหมดของการ Login ที่สำเร็จ (มี refresh_token หรือ Challenge ของ 2FA)
+    pub async fn login_response(&self, path: &str, username: &str, password: &str) -> TestResponse {
+        let response = self.post(path, None, json!({ "username": username, "password": password })).await;
+        assert_eq!(response.status, StatusCode::OK, "{}", response.body);
+        response
+    }
+
+    // สร้างห้องด้วย Admin Token แล้วคืน room_id
+    pub async fn room(&self, admin: &str, name: &str) -> i64 {
+        let response = self
+            .post("/admin/rooms", Some(admin), json!({ "name": name, "status": "available" }))
+            .await;
+        assert_eq!(response.status, StatusCode::CREATED, "{}", response.body);
+        response.body["id"].as_i64().unwrap()
+    }
+
+    // สร้างองค์กรที่เปิดให้สมัครเองด้วย Platform Admin แล้วคืน organization_id
+    pub async fn organization(&self, admin: &str, slug: &str) -> i64 {
+        let response = self
+            .post("/admin/organizations", Some(admin), json!({ "name": slug, "slug": slug, "self_registration": true }))
+            .await;
+        assert_eq!(response.status, StatusCode::CREATED, "{}", response.body);
+        response.body["id"].as_i64().unwrap()
+    }
+
+    // เชิญ Admin เข้าองค์กร (None = Platform Admin) รับคำเชิญแล้วคืน Access Token
+    pub async fn invited_admin(&self, admin: &str, organization_id: Option<i64>, username: &str) -> String {
+        let invitation = self
+            .post("/admin/invitations", Some(admin), json!({ "organization_id": organization_id }))
+            .await;
+        assert_eq!(invitation.status, StatusCode::CREATED, "{}", invitation.body);
+        let token = invitation.body["token"].as_str().unwrap();
+        let accepted = self
+            .post("/admin/invitations/accept", None, json!({ "token": token, "username": username, "password": PASSWORD }))
+            .await;
+        assert!(accepted.status.is_success(), "{}", accepted.body);
+        self.login("/login/admin", username, PASSWORD).await
+    }
+
+    // จองห้องในวันที่ days_ahead วันจากวันนี้ (เวลา UTC) ตั้งแต่ start_hour ถึง end_hour
+    pub async fn book(&self, token: &str, room_id: i64, days_ahead: i64, start_hour: u32, end_hour: u32) -> TestResponse {
+        let day = chrono::Utc::now().date_naive() + chrono::Duration::days(days_ahead);
+        let at = |hour: u32| day.and_hms_opt(hour, 0, 0).unwrap().and_utc().to_rfc3339();
+        self.post(
+            "/bookings",
+            Some(token),
+            json!({ "room_id": room_id, "start_time": at(start_hour), "end_time": at(end_hour) }),
+        )
+        .await
+    }
+
+    // ค่า token= จากลิงก์ในข้อความล่าสุดที่ส่งถึง recipient
+    pub fn last_link_token(&self, recipient: &str) -> String {
+        let sent = self.notifier.sent.lock().unwrap();
+        let notification = sent.iter().rev().find(|n| n.recipient == recipient).expect("notification");
+        let start = notification.body.find("token=").expect("token in link") + "token=".len();
+        notification.body[start..].split_whitespace().next().unwrap().to_string()
+    }
+
+    // Login ผ่าน OIDC กับ IdP จำลอง: เริ่ม Login, ให้ IdP ออก code ด้วย claims แล้วเรียก Callback
+    pub async fn oidc_login(&self, idp: &idp::MockIdp, claims: Value) -> TestResponse {
+        let start = self.request(Method::GET, "/auth/oidc/login", None, None).await;
+        assert_eq!(start.status, StatusCode::SEE_OTHER, "{}", start.body);
+        let location = reqwest::Url::parse(start.headers[header::LOCATION].to_str().unwrap()).unwrap();
+        let param = |name: &str| location.query_pairs().find(|(k, _)| k == name).unwrap().1.into_owned();
+        let code = idp.issue_code(&param("nonce"), claims);
+        let uri = format!("/auth/oidc/callback?code={}&state={}", code, param("state"));
+        self.request(Method::GET, &uri, None, None).await
+    }
+
+    // ไฟล์ Database ของ Test (ใช้กับคำสั่ง Command Line ของโปรแกรม)
+    pub fn database_url(&self) -> &str {
+        self.path.to_str().unwrap()
+    }
+
+    pub fn execute(&self, sql: &str) {
+        self.pool.get().unwrap().batch_execute(sql).unwrap();
+    }
+}
@@ -0,0 +1,80 @@
+// tests/cookie_session.rs
+mod common;
+
+use axum::body::Body;
+use axum::http::{header, Method, Request, StatusCode};
+use common::{TestApp, PASSWORD};
+use serde_json::json;
+
+// รวม Set-Cookie ของ Response เป็นค่า Header Cookie สำหรับ Request ถัดไป
+fn cookie_header(response: &common::TestResponse) -> String {
+    response
+        .headers
+        .get_all(header::SET_COOKIE)
+        .iter()
+        .map(|value| value.to_str().unwrap().split(';').next().unwrap().to_string())
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+#[tokio::test]
+async fn cookie_session_requires_csrf_header_for_writes() {
+    let app = TestApp::new().await;
+    app.user("gina").await;
+    let login = app
+        .post("/login/user?mode=cookie", None, json!({ "username": "gina", "password": PASSWORD }))
+        .await;
+    assert_eq!(login.status, StatusCode::OK, "{}", login.body);
+    assert!(login.body.get("token").is_none(), "tokens must not be in the body");
+    let csrf = login.body["csrf_token"].as_str().unwrap().to_string();
+    let cookies = cookie_header(&login);
+    let set_cookies: Vec<_> = login.headers.get_all(header::SET_COOKIE).iter().collect();
+    assert!(set_cookies.iter().any(|c| c.to_str().unwrap().starts_with("session=") && c.to_str().unwrap().contains("HttpOnly")));
+
+    let own = Request::builder().uri("/bookings/user").header(header::COOKIE, &cookies).body(Body::empty()).unwrap();
+    assert_eq!(app.send(own).await.status, StatusCode::OK);
+
+    let admin = app.admin_token().await;
+    let room = app.room(&admin, "Cookie").await;
+    let day = chrono::Utc::now().date_naive() + chrono::Duration::days(1);
+    let at = |hour: u32| day.and_hms_opt(hour, 0, 0).unwrap().and_utc().to_rfc3339();
+    let book = |csrf: Option<&str>, hour: u32| {
+        let mut request = Request::builder()
+            .method(Method::POST)
+            .uri("/bookings")
+            .header(header::COOKIE, &cookies)
+            .header(header::CONTENT_TYPE, "application/json");
+        if let Some(csrf) = csrf {
+            request = request.header("x-csrf-token", csrf);
+        }
+        let body = json!({ "room_id": room, "start_time": at(hour), "end_time": at(hour + 1) });
+        request.body(Body::from(body.to_string())).unwrap()
+    };
+    assert_eq!(app.send(book(None, 9)).await.status, StatusCode::FORBIDDEN);
+    assert_eq!(app.send(book(Some("forged"), 9)).await.status, StatusCode::FORBIDDEN);
+    assert_eq!(app.send(book(Some(&csrf), 9)).await.status, StatusCode::CREATED);
+}
+
+#[tokio::test]
+async fn cookie_logout_clears_the_session() {
+    let app = TestApp::new().await;
+    app.user("hank").await;
+    let login = app
+        .post("/login/user?mode=cookie", None, json!({ "username": "hank", "password": PASSWORD }))
+        .await;
+    let csrf = login.body["csrf_token"].as_str().unwrap().to_string();
+    let cookies = cookie_header(&login);
+
+    let logout = Request::builder()
+        .method(Method::POST)
+        .uri("/auth/logout")
+        .header(header::COOKIE, &cookies)
+        .header("x-csrf-token", &csrf)
+        .body(Body::empty())
+        .unwrap();
+    let response = app.send(logout).await;
+    assert_eq!(response.status, StatusCode::NO_CONTENT);
+
+    let own = Request::builder().uri("/bookings/user").header(header::COOKIE, &cookies).body(Body::empty()).unwrap();
+    assert_eq!(app.send(own).await.status, StatusCode::UNAUTHORIZED);
+}
@@ -0,0 +1,91 @@
+// tests/email_verification.rs
+mod common;
+
+use axum::http::{Method, StatusCode};
+use common::{TestApp, TestConfig, PASSWORD};
+use serde_json::json;
+
+async fn app() -> TestApp {
+    TestApp::with_config(TestConfig { email_verification_required: true, ..TestConfig::default() }).await
+}
+
+// สมัครพร้อมอีเมลแล้วคืน (user_id, Access Token)
+async fn register(app: &TestApp, username: &str, email: &str) -> (i64, String) {
+    let registered = app
+        .post("/register", None, json!({ "username": username, "password": PASSWORD, "email": email }))
+        .await;
+    assert_eq!(registered.status, StatusCode::CREATED, "{}", registered.body);
+    assert_eq!(registered.body["email_verification_pending"], true);
+    let id = registered.body["id"].as_i64().unwrap();
+    (id, app.login("/login/user", username, PASSWORD).await)
+}
+
+async fn confirm(app: &TestApp, token: &str) -> StatusCode {
+    app.post("/auth/email-verification/confirm", None, json!({ "token": token })).await.status
+}
+
+#[tokio::test]
+async fn registration_requires_an_email_address() {
+    let app = app().await;
+    let response = app.post("/register", None, json!({ "username": "kate", "password": PASSWORD })).await;
+    assert_eq!(response.status, StatusCode::UNPROCESSABLE_ENTITY, "{}", response.body);
+}
+
+#[tokio::test]
+async fn unverified_users_cannot_book_until_they_confirm_the_link() {
+    let app = app().await;
+    let admin = app.admin_token().await;
+    let room = app.room(&admin, "Verify").await;
+    let (_, token) = register(&app, "liam", "liam@example.com").await;
+
+    assert_eq!(app.book(&token, room, 1, 9, 10).await.status, StatusCode::FORBIDDEN);
+
+    assert_eq!(confirm(&app, "not-a-token").await, StatusCode::UNAUTHORIZED);
+    let link = app.last_link_token("liam@example.com");
+    assert_eq!(confirm(&app, &link).await, StatusCode::NO_CONTENT);
+    assert_eq!(app.get("/users/me", &token).await.body["email_verification_pending"], false);
+    assert_eq!(app.book(&token, room, 1, 9, 10).await.status, StatusCode::CREATED);
+
+    // ยืนยันแล้วขอลิงก์ใหม่ไม่ได้
+    let resend = app.request(Method::POST, "/users/me/email-verification", Some(&token), None).await;
+    assert_eq!(resend.status, StatusCode::CONFLICT);
+}
+
+#[tokio::test]
+async fn changing_the_email_invalidates_old_links() {
+    let app = app().await;
+    let (_, token) = register(&app, "maya", "maya@example.com").await;
+    let old_link = app.last_link_token("maya@example.com");
+
+    let changed = app
+        .request(Method::PATCH, "/users/me", Some(&token), Some(json!({ "email": "maya@example.org" })))
+        .await;
+    assert_eq!(changed.status, StatusCode::OK, "{}", changed.body);
+    assert_eq!(changed.body["email_verification_pending"], true);
+    assert_eq!(confirm(&app, &old_link).await, StatusCode::UNAUTHORIZED);
+
+    // ขอลิงก์ใหม่ได้ และลิงก์ที่ส่งไปอีเมลใหม่ใช้ได้
+    let resend = app.request(Method::POST, "/users/me/email-verification", Some(&token), None).await;
+    assert_eq!(resend.status, StatusCode::ACCEPTED);
+    let new_link = app.last_link_token("maya@example.org");
+    assert_eq!(confirm(&app, &new_link).await, StatusCode::NO_CONTENT);
+    assert_eq!(app.get("/users/me", &token).await.body["email_verification_pending"], false);
+}
+
+#[tokio::test]
+async fn admins_can_mark_an_email_as_verified() {
+    let app = app().await;
+    let admin = app.admin_token().await;
+    let room = app.room(&admin, "Override").await;
+    let (user_id, token) = register(&app, "nora", "nora@example.com").await;
+    let (_, other) = register(&app, "omar", "omar@example.com").await;
+
+    // ผู้ใช้ทั่วไปยืนยันแทนผู้อื่นไม่ได้
+    let path = format!("/admin/users/{user_id}/verify-email");
+    assert_eq!(app.request(Method::POST, &path, Some(&other), None).await.status, StatusCode::FORBIDDEN);
+
+    let verified = app.request(Method::POST, &path, Some(&admin), None).await;
+    assert_eq!(verified.status, StatusCode::OK, "{}", verified.body);
+    assert_eq!(verified.body["email_verification_pending"], false);
+    assert_eq!(app.book(&token, room, 2, 9, 10).await.status, StatusCode::CREATED);
+}
@@ -0,0 +1,33 @@
+// tests/errors.rs
+mod common;
+
+use axum::http::{header, Method, StatusCode};
+use common::TestApp;
+
+#[tokio::test]
+async fn errors_are_problem_details_with_stable_codes() {
+    let app = TestApp::new().await;
+
+    let unauthorized = app.request(Method::GET, "/bookings/user", None, None).await;
+    assert_eq!(unauthorized.status, StatusCode::UNAUTHORIZED);
+    assert_eq!(unauthorized.headers[header::CONTENT_TYPE], "application/problem+json");
+    assert_eq!(unauthorized.body["code"], "unauthorized");
+    assert_eq!(unauthorized.body["status"], 401);
+    assert_eq!(unauthorized.body["type"], "/problems/unauthorized");
+
+    let admin = app.admin_token().await;
+    let missing = app.get("/rooms/9999", &admin).await;
+    assert_eq!(missing.status, StatusCode::NOT_FOUND);
+    assert_eq!(missing.body["code"], "not_found");
+}
+
+#[tokio::test]
+async fn duplicate_username_is_a_conflict() {
+    let app = TestApp::new().await;
+    app.user("carol").await;
+    let again = app
+        .post("/register", None, serde_json::json!({ "username": "carol", "password": common::PASSWORD }))
+        .await;
+    assert_eq!(again.status, StatusCode::CONFLICT);
+    assert_eq!(again.body["code"], "conflict");
+}
@@ -0,0 +1,111 @@
+// tests/groups.rs
+mod common;
+
+use axum::http::{Method, StatusCode};
+use common::{TestApp, PASSWORD};
+use serde_json::json;
+
+async fn group(app: &TestApp, admin: &str, name: &str) -> i64 {
+    let created = app.post("/admin/groups", Some(admin), json!({ "name": name })).await;
+    assert_eq!(created.status, StatusCode::CREATED, "{}", created.body);
+    created.body["id"].as_i64().unwrap()
+}
+
+async fn join(app: &TestApp, admin: &str, group_id: i64, user_id: i64) -> StatusCode {
+    let path = format!("/admin/groups/{group_id}/members/{user_id}");
+    app.request(Method::PUT, &path, Some(admin), None).await.status
+}
+
+#[tokio::test]
+async fn restricted_rooms_can_only_be_booked_by_listed_groups_and_users() {
+    let app = TestApp::new().await;
+    let admin = app.admin_token().await;
+    let room = app.room(&admin, "Lab").await;
+    let lab_team = group(&app, &admin, "Lab team").await;
+    let (member_id, member) = app.user("mira").await;
+    let (guest_id, guest) = app.user("gus").await;
+    let (outsider_id, outsider) = app.user("otto").await;
+    assert_eq!(join(&app, &admin, lab_team, member_id).await, StatusCode::NO_CONTENT);
+
+    let access = format!("/admin/rooms/{room}/access");
+    let rules = app
+        .request(Method::PUT, &access, Some(&admin), Some(json!({ "group_ids": [lab_team], "user_ids": [guest_id] })))
+        .await;
+    assert_eq!(rules.status, StatusCode::OK, "{}", rules.body);
+    assert_eq!(rules.body["restricted"], true);
+
+    assert_eq!(app.book(&member, room, 1, 9, 10).await.status, StatusCode::CREATED);
+    assert_eq!(app.book(&guest, room, 1, 10, 11).await.status, StatusCode::CREATED);
+    assert_eq!(app.book(&outsider, room, 1, 11, 12).await.status, StatusCode::FORBIDDEN);
+    // จองแทนผู้ใช้ที่ไม่มีสิทธิ์ก็ไม่ได้
+    let on_behalf = app.post("/bookings", Some(&admin), json!({
+        "room_id": room,
+        "user_id": outsider_id,
+        "start_time": (chrono::Utc::now() + chrono::Duration::days(3)).to_rfc3339(),
+        "end_time": (chrono::Utc::now() + chrono::Duration::days(3) + chrono::Duration::hours(1)).to_rfc3339(),
+    })).await;
+    assert_eq!(on_behalf.status, StatusCode::FORBIDDEN, "{}", on_behalf.body);
+
+    // ห้องถูกซ่อนจากผู้ที่จองไม่ได้ แต่ Admin ยังเห็น
+    assert_eq!(app.get(&format!("/rooms/{room}"), &outsider).await.status, StatusCode::NOT_FOUND);
+    assert_eq!(app.get("/rooms", &outsider).await.body["total"], 0);
+    assert_eq!(app.get("/rooms", &member).await.body["total"], 1);
+    assert_eq!(app.get("/rooms", &admin).await.body["total"], 1);
+
+    // ออกจากกลุ่มแล้วจองไม่ได้ เปิดห้องแล้วทุกคนจองได้
+    let leave = app.request(Method::DELETE, &format!("/admin/groups/{lab_team}/members/{member_id}"), Some(&admin), None).await;
+    assert_eq!(leave.status, StatusCode::NO_CONTENT);
+    assert_eq!(app.book(&member, room, 2, 9, 10).await.status, StatusCode::FORBIDDEN);
+    let opened = app.request(Method::PUT, &access, Some(&admin), Some(json!({ "group_ids": [], "user_ids": [] }))).await;
+    assert_eq!(opened.body["restricted"], false);
+    assert_eq!(app.book(&outsider, room, 2, 9, 10).await.status, StatusCode::CREATED);
+}
+
+#[tokio::test]
+async fn groups_used_by_rooms_cannot_be_deleted() {
+    let app = TestApp::new().await;
+    let admin = app.admin_token().await;
+    let room = app.room(&admin, "Studio").await;
+    let team = group(&app, &admin, "Studio team").await;
+    assert_eq!(app.post("/admin/groups", Some(&admin), json!({ "name": "Studio team" })).await.status, StatusCode::CONFLICT);
+
+    let access = format!("/admin/rooms/{room}/access");
+    app.request(Method::PUT, &access, Some(&admin), Some(json!({ "group_ids": [team] }))).await;
+    let path = format!("/admin/groups/{team}");
+    assert_eq!(app.request(Method::DELETE, &path, Some(&admin), None).await.status, StatusCode::CONFLICT);
+
+    app.request(Method::PUT, &access, Some(&admin), Some(json!({ "group_ids": [] }))).await;
+    assert_eq!(app.request(Method::DELETE, &path, Some(&admin), None).await.status, StatusCode::NO_CONTENT);
+    assert_eq!(app.get(&path, &admin).await.status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn groups_stay_inside_their_organization() {
+    let app = TestApp::new().await;
+    let platform = app.admin_token().await;
+    let acme = app.organization(&platform, "acme").await;
+    let acme_admin = app.invited_admin(&platform, Some(acme), "acme-admin").await;
+    let default_group = group(&app, &platform, "Default team").await;
+    let acme_group = group(&app, &acme_admin, "Acme team").await;
+    let (default_user, user_token) = app.user("nell").await;
+    let room = app.room(&platform, "Default room").await;
+
+    // ผู้ใช้องค์กรอื่นเข้ากลุ่มไม่ได้ และกลุ่มขององค์กรอื่นมองไม่เห็น
+    let acme_user = app
+        .post("/register", None, json!({ "username": "acme-user", "password": PASSWORD, "organization": "acme" }))
+        .await
+        .body["id"]
+        .as_i64()
+        .unwrap();
+    assert_eq!(join(&app, &platform, default_group, acme_user).await, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(join(&app, &acme_admin, acme_group, default_user).await, StatusCode::NOT_FOUND);
+    assert_eq!(app.get(&format!("/admin/groups/{default_group}"), &acme_admin).await.status, StatusCode::NOT_FOUND);
+    let listed = app.get("/admin/groups", &acme_admin).await;
+    assert_eq!(listed.body.as_array().unwrap().len(), 1);
+
+    // กฎของห้องใช้กลุ่มขององค์กรอื่นไม่ได้ และผู้ใช้ทั่วไปจัดการกลุ่มไม่ได้
+    let access = format!("/admin/rooms/{room}/access");
+    let foreign = app.request(Method::PUT, &access, Some(&platform), Some(json!({ "group_ids": [acme_group] }))).await;
+    assert_eq!(foreign.status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(app.post("/admin/groups", Some(&user_token), json!({ "name": "Mine" })).await.status, StatusCode::FORBIDDEN);
+}
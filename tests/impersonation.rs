@@ -0,0 +1,84 @@
+// tests/impersonation.rs
+mod common;
+
+use axum::http::{Method, StatusCode};
+use common::{TestApp, PASSWORD};
+use serde_json::json;
+
+// เริ่มสวมสิทธิ์ผู้ใช้แล้วคืน (impersonation_id, Token)
+async fn impersonate(app: &TestApp, admin: &str, user_id: i64) -> (i64, String) {
+    let started = app
+        .post(&format!("/admin/users/{user_id}/impersonate"), Some(admin), json!({ "reason": "Ticket 1234" }))
+        .await;
+    assert_eq!(started.status, StatusCode::CREATED, "{}", started.body);
+    (started.body["impersonation_id"].as_i64().unwrap(), started.body["token"].as_str().unwrap().to_string())
+}
+
+#[tokio::test]
+async fn impersonation_acts_as_the_user_and_records_requests() {
+    let app = TestApp::new().await;
+    let admin = app.admin_token().await;
+    let (user_id, user) = app.user("pia").await;
+    let (id, token) = impersonate(&app, &admin, user_id).await;
+
+    let me = app.get("/users/me", &token).await;
+    assert_eq!(me.status, StatusCode::OK);
+    assert_eq!(me.body["username"], "pia");
+    // งานที่สงวนไว้ให้เจ้าของบัญชีทำไม่ได้
+    let password = app
+        .post("/users/me/password", Some(&token), json!({ "current_password": PASSWORD, "new_password": "another-password-1" }))
+        .await;
+    assert_eq!(password.status, StatusCode::FORBIDDEN);
+    assert_eq!(app.get("/users/me/export", &token).await.status, StatusCode::FORBIDDEN);
+
+    let actions = app.get(&format!("/admin/impersonations/{id}/actions"), &admin).await;
+    let paths: Vec<_> = actions.body.as_array().unwrap().iter().map(|a| (a["path"].clone(), a["status"].clone())).collect();
+    assert_eq!(paths[0], (json!("/users/me"), json!(200)));
+    assert_eq!(paths[1], (json!("/users/me/password"), json!(403)));
+    let listed = app.get(&format!("/admin/impersonations?user_id={user_id}"), &admin).await;
+    assert_eq!(listed.body["items"][0]["reason"], "Ticket 1234");
+
+    // ผู้ใช้ทั่วไปและ Token ของการสวมสิทธิ์เริ่มการสวมสิทธิ์ไม่ได้
+    let (other_id, _) = app.user("quin").await;
+    let path = format!("/admin/users/{other_id}/impersonate");
+    assert_eq!(app.post(&path, Some(&user), json!({ "reason": "x" })).await.status, StatusCode::FORBIDDEN);
+    assert_eq!(app.post(&path, Some(&token), json!({ "reason": "x" })).await.status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn ending_an_impersonation_revokes_its_token() {
+    let app = TestApp::new().await;
+    let admin = app.admin_token().await;
+    let (user_id, _) = app.user("rhea").await;
+    let (id, token) = impersonate(&app, &admin, user_id).await;
+
+    let path = format!("/admin/impersonations/{id}");
+    assert_eq!(app.request(Method::DELETE, &path, Some(&admin), None).await.status, StatusCode::OK);
+    assert_eq!(app.get("/users/me", &token).await.status, StatusCode::UNAUTHORIZED);
+    assert_eq!(app.request(Method::DELETE, &path, Some(&admin), None).await.status, StatusCode::CONFLICT);
+}
+
+#[tokio::test]
+async fn revoking_the_users_sessions_ends_open_impersonations() {
+    let app = TestApp::new().await;
+    let admin = app.admin_token().await;
+    let (user_id, user) = app.user("sven").await;
+    let (id, token) = impersonate(&app, &admin, user_id).await;
+
+    let changed = app
+        .post("/users/me/password", Some(&user), json!({ "current_password": PASSWORD, "new_password": "another-password-1" }))
+        .await;
+    assert!(changed.status.is_success(), "{}", changed.body);
+    assert_eq!(app.get("/users/me", &token).await.status, StatusCode::UNAUTHORIZED);
+    let listed = app.get(&format!("/admin/impersonations?user_id={user_id}"), &admin).await;
+    assert_eq!(listed.body["items"][0]["id"], id);
+    assert!(!listed.body["items"][0]["ended_at"].is_null());
+
+    // การลบบัญชีก็จบการสวมสิทธิ์เช่นกัน
+    let (_, token) = impersonate(&app, &admin, user_id).await;
+    let deleted = app.request(Method::DELETE, &format!("/admin/users/{user_id}"), Some(&admin), None).await;
+    assert_eq!(deleted.status, StatusCode::NO_CONTENT);
+    let restored = app.request(Method::POST, &format!("/admin/users/{user_id}/restore"), Some(&admin), None).await;
+    assert_eq!(restored.status, StatusCode::OK);
+    assert_eq!(app.get("/users/me", &token).await.status, StatusCode::UNAUTHORIZED);
+}
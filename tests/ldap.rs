@@ -0,0 +1,80 @@
+// tests/ldap.rs
+mod common;
+
+use axum::http::StatusCode;
+use common::ldap::{person, start, DIRECTORY_PASSWORD};
+use common::{TestApp, TestConfig};
+use room_booking_api_minimal::domain::organization::ExternalOrganization;
+use serde_json::json;
+
+async fn app_with(organization: ExternalOrganization) -> TestApp {
+    let directory = vec![person("dana", &[("o", "acme")]), person("eli", &[])];
+    let ldap = start(directory, organization).await;
+    TestApp::with_config(TestConfig { ldap: Some(ldap), ..Default::default() }).await
+}
+
+async fn ldap_login(app: &TestApp, username: &str, password: &str) -> common::TestResponse {
+    app.post("/login/user", None, json!({ "username": username, "password": password })).await
+}
+
+#[tokio::test]
+async fn directory_users_are_provisioned_in_the_default_organization() {
+    let app = app_with(ExternalOrganization::from_settings(None, None)).await;
+    let login = ldap_login(&app, "eli", DIRECTORY_PASSWORD).await;
+    assert_eq!(login.status, StatusCode::OK, "{}", login.body);
+    let me = app.get("/users/me", login.body["token"].as_str().unwrap()).await;
+    assert_eq!(me.body["organization_id"], 1);
+    assert_eq!(me.body["display_name"], "Directory eli");
+
+    // รหัสผ่านผิดและรหัสผ่านว่างถูกปฏิเสธ
+    assert_eq!(ldap_login(&app, "eli", "wrong").await.status, StatusCode::UNAUTHORIZED);
+    assert_eq!(ldap_login(&app, "eli", "").await.status, StatusCode::UNAUTHORIZED);
+    // ผู้ใช้ในระบบยัง Login ด้วยรหัสผ่านในระบบได้
+    app.user("fred").await;
+}
+
+#[tokio::test]
+async fn unknown_fixed_organization_rejects_the_login() {
+    let app = app_with(ExternalOrganization::Fixed("acme".to_string())).await;
+    let admin = app.admin_token().await;
+    assert_eq!(ldap_login(&app, "eli", DIRECTORY_PASSWORD).await.status, StatusCode::FORBIDDEN);
+
+    let created = app.post("/admin/organizations", Some(&admin), json!({ "name": "Acme", "slug": "acme" })).await;
+    let login = ldap_login(&app, "eli", DIRECTORY_PASSWORD).await;
+    assert_eq!(login.status, StatusCode::OK, "{}", login.body);
+    let me = app.get("/users/me", login.body["token"].as_str().unwrap()).await;
+    assert_eq!(me.body["organization_id"], created.body["id"]);
+}
+
+#[tokio::test]
+async fn organization_can_come_from_an_attribute() {
+    let app = app_with(ExternalOrganization::Claim("o".to_string())).await;
+    let admin = app.admin_token().await;
+    let created = app.post("/admin/organizations", Some(&admin), json!({ "name": "Acme", "slug": "acme" })).await;
+
+    let login = ldap_login(&app, "dana", DIRECTORY_PASSWORD).await;
+    assert_eq!(login.status, StatusCode::OK, "{}", login.body);
+    let me = app.get("/users/me", login.body["token"].as_str().unwrap()).await;
+    assert_eq!(me.body["organization_id"], created.body["id"]);
+
+    // ไม่มี attribute องค์กร: ไม่ย้อนไปใช้องค์กรเริ่มต้น
+    assert_eq!(ldap_login(&app, "eli", DIRECTORY_PASSWORD).await.status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn provisioned_users_are_audited_once() {
+    let app = app_with(ExternalOrganization::from_settings(None, None)).await;
+    let admin = app.admin_token().await;
+    let login = ldap_login(&app, "eli", DIRECTORY_PASSWORD).await;
+    assert_eq!(login.status, StatusCode::OK, "{}", login.body);
+    ldap_login(&app, "eli", DIRECTORY_PASSWORD).await;
+    let me = app.get("/users/me", login.body["token"].as_str().unwrap()).await;
+
+    let audit = app.get("/admin/audit?action=user.provision", &admin).await;
+    assert_eq!(audit.body["total"], 1, "{}", audit.body);
+    let entry = &audit.body["items"][0];
+    assert_eq!(entry["actor_kind"], "user");
+    assert_eq!(entry["actor_id"], me.body["id"]);
+    assert_eq!(entry["organization_id"], 1);
+    assert!(entry["ip"].is_string());
+}
@@ -0,0 +1,69 @@
+// tests/login_throttle.rs
+mod common;
+
+use axum::http::{header, Method, StatusCode};
+use common::{TestApp, TestConfig, PASSWORD};
+use serde_json::json;
+
+async fn throttled_app() -> TestApp {
+    TestApp::with_config(TestConfig { login_max_failures: 3, ..Default::default() }).await
+}
+
+#[tokio::test]
+async fn repeated_failures_lock_the_username_with_retry_after() {
+    let app = throttled_app().await;
+    app.user("olivia").await;
+
+    for _ in 0..3 {
+        let wrong = app.post("/login/user", None, json!({ "username": "olivia", "password": "wrong-password" })).await;
+        assert_eq!(wrong.status, StatusCode::UNAUTHORIZED);
+    }
+
+    // ระหว่างถูกล็อก รหัสผ่านที่ถูกก็ยังเข้าไม่ได้
+    let locked = app.post("/login/user", None, json!({ "username": "olivia", "password": PASSWORD })).await;
+    assert_eq!(locked.status, StatusCode::TOO_MANY_REQUESTS);
+    let retry_after: u64 = locked.headers[header::RETRY_AFTER].to_str().unwrap().parse().unwrap();
+    assert!(retry_after >= 1);
+}
+
+#[tokio::test]
+async fn admin_sees_login_events_and_can_clear_a_lockout() {
+    let app = throttled_app().await;
+    let admin = app.admin_token().await;
+    app.user("peter").await;
+    for _ in 0..3 {
+        app.post("/login/user", None, json!({ "username": "peter", "password": "wrong-password" })).await;
+    }
+
+    let events = app.get("/admin/login-events?username=peter", &admin).await;
+    assert_eq!(events.status, StatusCode::OK);
+    let events = events.body.as_array().unwrap();
+    assert!(events.iter().any(|event| event["success"] == false && event["reason"] == "invalid_credentials"));
+
+    let lockouts = app.get("/admin/lockouts", &admin).await;
+    let lockout = lockouts
+        .body
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|lockout| lockout["throttle_key"] == "user:peter")
+        .expect("username lockout")
+        .clone();
+    assert!(!lockout["locked_until"].is_null());
+
+    let uri = format!("/admin/lockouts/{}", lockout["id"]);
+    let cleared = app.request(Method::DELETE, &uri, Some(&admin), None).await;
+    assert_eq!(cleared.status, StatusCode::NO_CONTENT);
+    app.login("/login/user", "peter", PASSWORD).await;
+
+    let missing = app.request(Method::DELETE, &uri, Some(&admin), None).await;
+    assert_eq!(missing.status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn users_cannot_manage_lockouts() {
+    let app = throttled_app().await;
+    let (_, token) = app.user("quinn").await;
+    assert_eq!(app.get("/admin/lockouts", &token).await.status, StatusCode::FORBIDDEN);
+    assert_eq!(app.get("/admin/login-events", &token).await.status, StatusCode::FORBIDDEN);
+}
@@ -0,0 +1,142 @@
+// tests/mfa.rs
+mod common;
+
+use axum::http::{Method, StatusCode};
+use common::{TestApp, TestConfig, PASSWORD};
+use serde_json::{json, Value};
+use totp_rs::{Algorithm, Secret, TOTP};
+
+// รหัส TOTP ของ step ที่เลื่อนไป `steps_ahead` step (แต่ละ step ใช้ได้ครั้งเดียว)
+fn code(secret: &str, steps_ahead: u64) -> String {
+    let bytes = Secret::Encoded(secret.to_string()).to_bytes().unwrap();
+    let totp = TOTP::new_unchecked(Algorithm::SHA1, 6, 0, 30, bytes, None, String::new());
+    let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs();
+    totp.generate(now + steps_ahead * 30)
+}
+
+// เปิด 2FA ให้บัญชี คืน Secret กับ Recovery Code
+async fn enable_mfa(app: &TestApp, token: &str) -> (String, Vec<Value>) {
+    let enrollment = app.post("/auth/mfa/totp", Some(token), json!({})).await;
+    assert_eq!(enrollment.status, StatusCode::OK, "{}", enrollment.body);
+    let secret = enrollment.body["secret"].as_str().unwrap().to_string();
+    let confirmed = app.post("/auth/mfa/totp/confirm", Some(token), json!({ "code": code(&secret, 0) })).await;
+    assert_eq!(confirmed.status, StatusCode::OK, "{}", confirmed.body);
+    (secret, confirmed.body["recovery_codes"].as_array().unwrap().clone())
+}
+
+async fn challenge(app: &TestApp, username: &str) -> String {
+    let login = app.login_response("/login/user", username, PASSWORD).await;
+    assert_eq!(login.body["mfa_required"], true);
+    login.body["challenge_token"].as_str().unwrap().to_string()
+}
+
+async fn verify(app: &TestApp, body: Value) -> common::TestResponse {
+    app.post("/auth/mfa/verify", None, body).await
+}
+
+#[tokio::test]
+async fn login_with_totp_and_single_use_recovery_codes() {
+    let app = TestApp::new().await;
+    let (_, token) = app.user("rosa").await;
+    let (secret, recovery_codes) = enable_mfa(&app, &token).await;
+    assert_eq!(recovery_codes.len(), 10);
+
+    let challenge_token = challenge(&app, "rosa").await;
+    let wrong = verify(&app, json!({ "challenge_token": challenge_token, "code": "000000" })).await;
+    assert_eq!(wrong.status, StatusCode::UNAUTHORIZED);
+    let ok = verify(&app, json!({ "challenge_token": challenge_token, "code": code(&secret, 1) })).await;
+    assert_eq!(ok.status, StatusCode::OK, "{}", ok.body);
+    let access = ok.body["token"].as_str().unwrap();
+    assert_eq!(app.get("/bookings/user", access).await.status, StatusCode::OK);
+
+    // Challenge ใช้ได้ครั้งเดียว
+    let reused = verify(&app, json!({ "challenge_token": challenge_token, "code": code(&secret, 1) })).await;
+    assert_eq!(reused.status, StatusCode::UNAUTHORIZED);
+
+    let recovery_code = recovery_codes[0].as_str().unwrap();
+    let first = verify(&app, json!({ "challenge_token": challenge(&app, "rosa").await, "recovery_code": recovery_code })).await;
+    assert_eq!(first.status, StatusCode::OK);
+    let again = verify(&app, json!({ "challenge_token": challenge(&app, "rosa").await, "recovery_code": recovery_code })).await;
+    assert_eq!(again.status, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn wrong_codes_lock_the_account_across_fresh_challenges() {
+    let app = TestApp::with_config(TestConfig { login_max_failures: 3, ..Default::default() }).await;
+    let (_, token) = app.user("sven").await;
+    enable_mfa(&app, &token).await;
+
+    // ขอ Challenge ใหม่ทุกครั้ง: ตัวนับไม่ถูกล้างโดยขั้นรหัสผ่าน
+    for _ in 0..3 {
+        let challenge_token = challenge(&app, "sven").await;
+        let wrong = verify(&app, json!({ "challenge_token": challenge_token, "code": "000000" })).await;
+        assert_eq!(wrong.status, StatusCode::UNAUTHORIZED);
+    }
+
+    let login = app.post("/login/user", None, json!({ "username": "sven", "password": PASSWORD })).await;
+    assert_eq!(login.status, StatusCode::TOO_MANY_REQUESTS);
+    assert!(login.headers.contains_key("retry-after"));
+}
+
+#[tokio::test]
+async fn locked_account_cannot_use_an_open_challenge() {
+    let app = TestApp::with_config(TestConfig { login_max_failures: 2, ..Default::default() }).await;
+    let (_, token) = app.user("tara").await;
+    let (secret, _) = enable_mfa(&app, &token).await;
+
+    let open = challenge(&app, "tara").await;
+    for _ in 0..2 {
+        let challenge_token = challenge(&app, "tara").await;
+        verify(&app, json!({ "challenge_token": challenge_token, "code": "000000" })).await;
+    }
+    let blocked = verify(&app, json!({ "challenge_token": open, "code": code(&secret, 1) })).await;
+    assert_eq!(blocked.status, StatusCode::TOO_MANY_REQUESTS);
+}
+
+#[tokio::test]
+async fn full_login_resets_the_failure_counter() {
+    let app = TestApp::with_config(TestConfig { login_max_failures: 3, ..Default::default() }).await;
+    let admin = app.admin_token().await;
+    let (_, token) = app.user("uma").await;
+    let (secret, _) = enable_mfa(&app, &token).await;
+
+    let challenge_token = challenge(&app, "uma").await;
+    verify(&app, json!({ "challenge_token": challenge_token, "code": "000000" })).await;
+    let has_counter = |body: &Value| body.as_array().unwrap().iter().any(|row| row["throttle_key"] == "user:uma");
+    assert!(has_counter(&app.get("/admin/lockouts", &admin).await.body));
+
+    let ok = verify(&app, json!({ "challenge_token": challenge_token, "code": code(&secret, 1) })).await;
+    assert_eq!(ok.status, StatusCode::OK);
+    assert!(!has_counter(&app.get("/admin/lockouts", &admin).await.body));
+}
+
+#[tokio::test]
+async fn mfa_changes_are_audited_with_the_status_after_the_change() {
+    let app = TestApp::new().await;
+    let admin = app.admin_token().await;
+    let (user_id, token) = app.user("tess").await;
+    enable_mfa(&app, &token).await;
+    let reset = app.request(Method::DELETE, &format!("/admin/users/{user_id}/mfa"), Some(&admin), None).await;
+    assert_eq!(reset.status, StatusCode::NO_CONTENT);
+
+    // Role กำหนดให้ต้องใช้ 2FA: ลงทะเบียนระหว่าง Login ด้วย Challenge Token ผู้กระทำคือบัญชีนั้น
+    let policy = app.request(Method::PUT, "/admin/roles/user/mfa", Some(&admin), Some(json!({ "required": true }))).await;
+    assert_eq!(policy.status, StatusCode::OK, "{}", policy.body);
+    let challenge_token = challenge(&app, "tess").await;
+    let enrollment = app.post("/auth/mfa/enroll", None, json!({ "challenge_token": challenge_token })).await;
+    let secret = enrollment.body["secret"].as_str().unwrap();
+    let verified = verify(&app, json!({ "challenge_token": challenge_token, "code": code(secret, 0) })).await;
+    assert_eq!(verified.status, StatusCode::OK, "{}", verified.body);
+
+    let audit = app.get(&format!("/admin/audit?entity_type=user&entity_id={user_id}&action=mfa.&order=asc"), &admin).await;
+    let entries = audit.body["items"].as_array().unwrap();
+    let actions: Vec<_> = entries.iter().map(|e| e["action"].as_str().unwrap()).collect();
+    assert_eq!(actions, ["mfa.totp.enroll", "mfa.totp.enable", "mfa.reset", "mfa.totp.enroll", "mfa.totp.enable"]);
+    assert_eq!(entries[1]["after"]["enabled"], true);
+    assert_eq!(entries[1]["after"]["recovery_codes_remaining"], 10);
+    assert_eq!(entries[2]["actor_kind"], "admin");
+    assert_eq!(entries[2]["after"]["enabled"], false);
+    assert_eq!(entries[4]["actor_kind"], "user");
+    assert_eq!(entries[4]["actor_id"], user_id);
+    assert_eq!(entries[4]["after"]["required"], true);
+}
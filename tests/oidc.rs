@@ -0,0 +1,106 @@
+// tests/oidc.rs
+mod common;
+
+use axum::http::StatusCode;
+use common::idp::MockIdp;
+use common::{TestApp, TestConfig};
+use room_booking_api_minimal::domain::organization::ExternalOrganization;
+use serde_json::json;
+
+async fn app_with(organization: ExternalOrganization) -> (TestApp, MockIdp) {
+    let idp = MockIdp::start().await;
+    let app = TestApp::with_config(TestConfig { oidc: Some(idp.config(organization)), ..Default::default() }).await;
+    (app, idp)
+}
+
+async fn create_organization(app: &TestApp, admin: &str, slug: &str) -> i64 {
+    let created = app.post("/admin/organizations", Some(admin), json!({ "name": slug, "slug": slug })).await;
+    assert_eq!(created.status, StatusCode::CREATED, "{}", created.body);
+    created.body["id"].as_i64().unwrap()
+}
+
+#[tokio::test]
+async fn first_login_provisions_the_user_with_group_roles() {
+    let (app, idp) = app_with(ExternalOrganization::from_settings(None, None)).await;
+    let login = app
+        .oidc_login(&idp, json!({ "preferred_username": "yara", "name": "Yara", "groups": ["facilities"] }))
+        .await;
+    assert_eq!(login.status, StatusCode::OK, "{}", login.body);
+    let token = login.body["token"].as_str().unwrap();
+
+    let me = app.get("/users/me", token).await;
+    assert_eq!(me.body["username"], "yara");
+    assert_eq!(me.body["organization_id"], 1);
+    assert_eq!(me.body["has_password"], false);
+    let create_room = app.post("/admin/rooms", Some(token), json!({ "name": "Hall", "status": "available" })).await;
+    assert_eq!(create_room.status, StatusCode::CREATED);
+
+    // Login ครั้งต่อไปใช้บัญชีเดิม (ผูกด้วย sub)
+    let again = app.oidc_login(&idp, json!({ "preferred_username": "yara", "groups": [] })).await;
+    assert_eq!(again.body["user_id"], login.body["user_id"]);
+}
+
+#[tokio::test]
+async fn users_are_created_in_the_configured_organization() {
+    let (app, idp) = app_with(ExternalOrganization::Fixed("acme".to_string())).await;
+    let admin = app.admin_token().await;
+
+    // ยังไม่มีองค์กร acme: ปฏิเสธ ไม่สร้างผู้ใช้ในองค์กรเริ่มต้น
+    let unknown = app.oidc_login(&idp, json!({ "preferred_username": "zoe" })).await;
+    assert_eq!(unknown.status, StatusCode::FORBIDDEN, "{}", unknown.body);
+    let users = app.get("/admin/users?search=zoe", &admin).await;
+    assert!(!users.body.to_string().contains("\"zoe\""), "{}", users.body);
+
+    let acme = create_organization(&app, &admin, "acme").await;
+    let login = app.oidc_login(&idp, json!({ "preferred_username": "zoe" })).await;
+    assert_eq!(login.status, StatusCode::OK, "{}", login.body);
+    let me = app.get("/users/me", login.body["token"].as_str().unwrap()).await;
+    assert_eq!(me.body["organization_id"], acme);
+}
+
+#[tokio::test]
+async fn organization_can_come_from_a_claim() {
+    let (app, idp) = app_with(ExternalOrganization::Claim("org".to_string())).await;
+    let admin = app.admin_token().await;
+    let globex = create_organization(&app, &admin, "globex").await;
+
+    let login = app.oidc_login(&idp, json!({ "preferred_username": "abel", "org": "globex" })).await;
+    assert_eq!(login.status, StatusCode::OK, "{}", login.body);
+    let me = app.get("/users/me", login.body["token"].as_str().unwrap()).await;
+    assert_eq!(me.body["organization_id"], globex);
+
+    let missing = app.oidc_login(&idp, json!({ "preferred_username": "bella" })).await;
+    assert_eq!(missing.status, StatusCode::FORBIDDEN);
+    let unknown = app.oidc_login(&idp, json!({ "preferred_username": "bella", "org": "initech" })).await;
+    assert_eq!(unknown.status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn callback_rejects_forged_state_and_taken_usernames() {
+    let (app, idp) = app_with(ExternalOrganization::from_settings(None, None)).await;
+    app.user("carl").await;
+
+    // username ซ้ำกับบัญชีในระบบ: ไม่ผูกอัตโนมัติ
+    let taken = app.oidc_login(&idp, json!({ "preferred_username": "carl" })).await;
+    assert_eq!(taken.status, StatusCode::CONFLICT);
+
+    let forged = app.request(axum::http::Method::GET, "/auth/oidc/callback?code=x&state=forged", None, None).await;
+    assert_eq!(forged.status, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn provisioned_users_are_audited_once() {
+    let (app, idp) = app_with(ExternalOrganization::from_settings(None, None)).await;
+    let admin = app.admin_token().await;
+    let login = app.oidc_login(&idp, json!({ "preferred_username": "yuki" })).await;
+    assert_eq!(login.status, StatusCode::OK, "{}", login.body);
+    app.oidc_login(&idp, json!({ "preferred_username": "yuki" })).await;
+
+    let audit = app.get("/admin/audit?action=user.provision", &admin).await;
+    assert_eq!(audit.body["total"], 1, "{}", audit.body);
+    let entry = &audit.body["items"][0];
+    assert_eq!(entry["actor_kind"], "user");
+    assert_eq!(entry["actor_id"], login.body["user_id"]);
+    assert_eq!(entry["entity_id"], login.body["user_id"].to_string());
+    assert_eq!(entry["after"]["has_password"], false);
+}
@@ -0,0 +1,120 @@
+// tests/openapi.rs
+mod common;
+
+use axum::http::{Method, StatusCode};
+use common::TestApp;
+
+// ทุกเส้นทางใน Router (ยกเว้นเอกสารเองและ Route ทดสอบ) ต้องอยู่ในเอกสาร OpenAPI
+const DOCUMENTED: &[(&str, &str)] = &[
+    ("post", "/register"),
+    ("post", "/login/user"),
+    ("post", "/admin/setup"),
+    ("post", "/admin/invitations/accept"),
+    ("post", "/login/admin"),
+    ("post", "/auth/refresh"),
+    ("post", "/auth/logout"),
+    ("get", "/users/me"),
+    ("patch", "/users/me"),
+    ("post", "/users/me/email-verification"),
+    ("post", "/auth/email-verification/confirm"),
+    ("post", "/users/me/password"),
+    ("post", "/auth/password-reset"),
+    ("post", "/auth/password-reset/confirm"),
+    ("get", "/auth/oidc/login"),
+    ("get", "/auth/oidc/callback"),
+    ("post", "/auth/mfa/verify"),
+    ("post", "/auth/mfa/enroll"),
+    ("get", "/auth/mfa"),
+    ("post", "/auth/mfa/totp"),
+    ("delete", "/auth/mfa/totp"),
+    ("post", "/auth/mfa/totp/confirm"),
+    ("post", "/auth/mfa/recovery-codes"),
+    ("get", "/users/me/api-keys"),
+    ("post", "/users/me/api-keys"),
+    ("delete", "/users/me/api-keys/{key_id}"),
+    ("get", "/users/me/export"),
+    ("post", "/users/me/erasure"),
+    ("get", "/.well-known/jwks.json"),
+    ("post", "/admin/rooms"),
+    ("patch", "/admin/rooms/{room_id}"),
+    ("delete", "/admin/rooms/{room_id}"),
+    ("post", "/admin/rooms/{room_id}/restore"),
+    ("get", "/admin/rooms/{room_id}/shares"),
+    ("put", "/admin/rooms/{room_id}/shares"),
+    ("get", "/admin/rooms/{room_id}/access"),
+    ("put", "/admin/rooms/{room_id}/access"),
+    ("get", "/admin/bookings"),
+    ("delete", "/admin/bookings/{booking_id}"),
+    ("post", "/admin/bookings/{booking_id}/no-show"),
+    ("post", "/admin/bookings/{booking_id}/restore"),
+    ("get", "/admin/reports/utilization"),
+    ("get", "/admin/reports/peak-hours"),
+    ("get", "/admin/reports/booking-rates"),
+    ("get", "/admin/reports/top-bookers"),
+    ("get", "/admin/users"),
+    ("get", "/admin/users/{user_id}"),
+    ("delete", "/admin/users/{user_id}"),
+    ("post", "/admin/users/{user_id}/restore"),
+    ("post", "/admin/users/{user_id}/verify-email"),
+    ("post", "/admin/users/{user_id}/erase"),
+    ("get", "/admin/erasure-requests"),
+    ("put", "/admin/users/{user_id}/organization"),
+    ("post", "/admin/users/{user_id}/impersonate"),
+    ("get", "/admin/impersonations"),
+    ("delete", "/admin/impersonations/{impersonation_id}"),
+    ("get", "/admin/impersonations/{impersonation_id}/actions"),
+    ("get", "/admin/organizations"),
+    ("post", "/admin/organizations"),
+    ("patch", "/admin/organizations/{organization_id}"),
+    ("get", "/admin/groups"),
+    ("post", "/admin/groups"),
+    ("get", "/admin/groups/{group_id}"),
+    ("patch", "/admin/groups/{group_id}"),
+    ("delete", "/admin/groups/{group_id}"),
+    ("get", "/admin/groups/{group_id}/members"),
+    ("put", "/admin/groups/{group_id}/members/{user_id}"),
+    ("delete", "/admin/groups/{group_id}/members/{user_id}"),
+    ("get", "/admin/users/{user_id}/roles"),
+    ("post", "/admin/users/{user_id}/roles"),
+    ("delete", "/admin/users/{user_id}/roles/{role}"),
+    ("get", "/admin/roles"),
+    ("put", "/admin/roles/{role}/mfa"),
+    ("delete", "/admin/users/{user_id}/mfa"),
+    ("delete", "/admin/admins/{admin_id}/mfa"),
+    ("get", "/admin/lockouts"),
+    ("delete", "/admin/lockouts/{lockout_id}"),
+    ("get", "/admin/login-events"),
+    ("get", "/admin/audit"),
+    ("get", "/admin/audit/verify"),
+    ("get", "/admin/invitations"),
+    ("post", "/admin/invitations"),
+    ("delete", "/admin/invitations/{invitation_id}"),
+    ("post", "/bookings"),
+    ("delete", "/bookings/{id}"),
+    ("get", "/bookings/user"),
+    ("get", "/rooms/active"),
+    ("get", "/rooms"),
+    ("get", "/rooms/{room_id}"),
+];
+
+#[tokio::test]
+async fn every_route_is_documented() {
+    let app = TestApp::new().await;
+    let document = app.request(Method::GET, "/openapi.json", None, None).await;
+    assert_eq!(document.status, StatusCode::OK);
+
+    let missing: Vec<String> = DOCUMENTED
+        .iter()
+        .filter(|(method, path)| document.body["paths"][*path][*method].is_null())
+        .map(|(method, path)| format!("{} {}", method.to_uppercase(), path))
+        .collect();
+    assert!(missing.is_empty(), "undocumented routes: {missing:?}");
+}
+
+#[tokio::test]
+async fn docs_ui_is_served() {
+    let app = TestApp::new().await;
+    let docs = app.request(Method::GET, "/docs", None, None).await;
+    assert_eq!(docs.status, StatusCode::OK);
+    assert!(docs.body.as_str().unwrap().contains("/login/user"));
+}
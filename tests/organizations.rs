@@ -0,0 +1,155 @@
+// tests/organizations.rs
+mod common;
+
+use axum::http::{Method, StatusCode};
+use common::{TestApp, PASSWORD};
+use room_booking_api_minimal::domain::audit::{AuditActor, AuditContext, AuditEvent};
+use room_booking_api_minimal::domain::booking::Booking;
+use room_booking_api_minimal::domain::organization::Tenant;
+use room_booking_api_minimal::error::AppError;
+use serde_json::json;
+
+// สมัครผู้ใช้เข้าองค์กรตาม slug แล้วคืน (user_id, Access Token)
+async fn member(app: &TestApp, slug: &str, username: &str) -> (i64, String) {
+    let registered = app
+        .post("/register", None, json!({ "username": username, "password": PASSWORD, "organization": slug }))
+        .await;
+    assert_eq!(registered.status, StatusCode::CREATED, "{}", registered.body);
+    (registered.body["id"].as_i64().unwrap(), app.login("/login/user", username, PASSWORD).await)
+}
+
+#[tokio::test]
+async fn organization_admins_only_see_their_own_organization() {
+    let app = TestApp::new().await;
+    let platform = app.admin_token().await;
+    let acme = app.organization(&platform, "acme").await;
+    let acme_admin = app.invited_admin(&platform, Some(acme), "acme-admin").await;
+    let (acme_user, acme_token) = member(&app, "acme", "avery").await;
+    let (default_user, default_token) = app.user("bodhi").await;
+    let default_room = app.room(&platform, "Default room").await;
+    let default_booking = app.book(&default_token, default_room, 1, 9, 10).await.body["id"].as_i64().unwrap();
+
+    let users = app.get("/admin/users", &acme_admin).await;
+    let ids: Vec<_> = users.body["items"].as_array().unwrap().iter().map(|u| u["id"].as_i64().unwrap()).collect();
+    assert_eq!(ids, vec![acme_user]);
+    assert_eq!(app.get(&format!("/admin/users/{default_user}"), &acme_admin).await.status, StatusCode::NOT_FOUND);
+    assert_eq!(app.get("/admin/bookings", &acme_admin).await.body["total"], 0);
+    let delete_user = app.request(Method::DELETE, &format!("/admin/users/{default_user}"), Some(&acme_admin), None).await;
+    assert_eq!(delete_user.status, StatusCode::NOT_FOUND);
+    let cancel = app.request(Method::DELETE, &format!("/admin/bookings/{default_booking}"), Some(&acme_admin), None).await;
+    assert_eq!(cancel.status, StatusCode::NOT_FOUND);
+
+    // ห้องขององค์กรอื่นมองไม่เห็นและจองไม่ได้
+    assert_eq!(app.get(&format!("/rooms/{default_room}"), &acme_token).await.status, StatusCode::NOT_FOUND);
+    assert_eq!(app.book(&acme_token, default_room, 2, 9, 10).await.status, StatusCode::NOT_FOUND);
+    let patch = app
+        .request(Method::PATCH, &format!("/admin/rooms/{default_room}"), Some(&acme_admin), Some(json!({ "name": "Mine" })))
+        .await;
+    assert_eq!(patch.status, StatusCode::NOT_FOUND);
+
+    // ข้อมูลที่ใช้ร่วมกันทุกองค์กรเป็นของ Platform Admin เท่านั้น
+    assert_eq!(app.get("/admin/lockouts", &acme_admin).await.status, StatusCode::FORBIDDEN);
+    let create = app.post("/admin/organizations", Some(&acme_admin), json!({ "name": "x", "slug": "xx" })).await;
+    assert_eq!(create.status, StatusCode::FORBIDDEN);
+    assert_eq!(app.get("/admin/users", &platform).await.body["total"], 2);
+}
+
+#[tokio::test]
+async fn booking_changes_are_limited_to_the_tenant() {
+    let app = TestApp::new().await;
+    let platform = app.admin_token().await;
+    let acme = app.organization(&platform, "acme").await;
+    let (_, token) = app.user("dana").await;
+    let room = app.room(&platform, "Default room").await;
+    let active = app.book(&token, room, 1, 9, 10).await.body["id"].as_i64().unwrap() as i32;
+    let cancelled = app.book(&token, room, 1, 11, 12).await.body["id"].as_i64().unwrap() as i32;
+    let cancel = app.request(Method::DELETE, &format!("/bookings/{cancelled}"), Some(&token), None).await;
+    assert_eq!(cancel.status, StatusCode::NO_CONTENT);
+
+    // Repository ต้องกรององค์กรเองด้วย ไม่พึ่งการตรวจใน Handler
+    let tenant = Tenant::Organization(acme as i32);
+    let context = AuditContext { actor: AuditActor::anonymous(), request_id: None, ip: None };
+    let event = || context.pending(|b: &Booking| AuditEvent::new("booking.test", "booking", b.id));
+    let bookings = &app.state.booking_service;
+    let not_found = |result: Result<_, AppError>| matches!(result, Err(AppError::NotFound(_)));
+    assert!(not_found(bookings.cancel_booking(tenant, active, None, event()).await));
+    assert!(not_found(bookings.mark_no_show(tenant, active, event()).await.map(drop)));
+    assert!(not_found(bookings.delete_booking(tenant, active, event()).await.map(drop)));
+    assert!(not_found(bookings.restore_booking(tenant, cancelled, event()).await.map(drop)));
+
+    assert_eq!(bookings.find_booking(active).await.unwrap().status, "active");
+    assert_eq!(bookings.find_booking(cancelled).await.unwrap().status, "cancelled");
+}
+
+#[tokio::test]
+async fn organization_admins_cannot_invite_into_other_organizations() {
+    let app = TestApp::new().await;
+    let platform = app.admin_token().await;
+    let acme = app.organization(&platform, "acme").await;
+    let acme_admin = app.invited_admin(&platform, Some(acme), "acme-admin").await;
+
+    let other = app.post("/admin/invitations", Some(&acme_admin), json!({ "organization_id": 1 })).await;
+    assert_eq!(other.status, StatusCode::UNPROCESSABLE_ENTITY, "{}", other.body);
+    // คำเชิญที่ไม่ระบุองค์กรสร้าง Admin ในองค์กรเดียวกัน ไม่ใช่ Platform Admin
+    let colleague = app.invited_admin(&acme_admin, None, "acme-colleague").await;
+    assert_eq!(app.get("/admin/lockouts", &colleague).await.status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn shared_rooms_are_visible_and_bookable_but_not_editable() {
+    let app = TestApp::new().await;
+    let platform = app.admin_token().await;
+    let acme = app.organization(&platform, "acme").await;
+    let acme_admin = app.invited_admin(&platform, Some(acme), "acme-admin").await;
+    let (_, acme_token) = member(&app, "acme", "cyrus").await;
+    let room = app.room(&platform, "Shared").await;
+
+    let shares = format!("/admin/rooms/{room}/shares");
+    let shared = app.request(Method::PUT, &shares, Some(&platform), Some(json!({ "organization_ids": [acme] }))).await;
+    assert_eq!(shared.status, StatusCode::OK, "{}", shared.body);
+    assert_eq!(shared.body["shared_with"], json!([acme]));
+
+    assert_eq!(app.get(&format!("/rooms/{room}"), &acme_token).await.status, StatusCode::OK);
+    assert_eq!(app.book(&acme_token, room, 1, 9, 10).await.status, StatusCode::CREATED);
+    let reshare = app.request(Method::PUT, &shares, Some(&acme_admin), Some(json!({ "organization_ids": [] }))).await;
+    assert_eq!(reshare.status, StatusCode::NOT_FOUND);
+    // การจองห้องขององค์กรอื่นนับเป็นของทั้งสององค์กร
+    assert_eq!(app.get("/admin/bookings", &acme_admin).await.body["total"], 1);
+
+    let unshared = app.request(Method::PUT, &shares, Some(&platform), Some(json!({ "organization_ids": [] }))).await;
+    assert_eq!(unshared.status, StatusCode::OK);
+    assert_eq!(app.get(&format!("/rooms/{room}"), &acme_token).await.status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn registration_and_moves_follow_the_organization_settings() {
+    let app = TestApp::new().await;
+    let platform = app.admin_token().await;
+    let closed = app
+        .post("/admin/organizations", Some(&platform), json!({ "name": "Closed", "slug": "closed" }))
+        .await;
+    assert_eq!(closed.status, StatusCode::CREATED, "{}", closed.body);
+    let closed = closed.body["id"].as_i64().unwrap();
+
+    for slug in ["closed", "missing"] {
+        let response = app
+            .post("/register", None, json!({ "username": "dee", "password": PASSWORD, "organization": slug }))
+            .await;
+        assert_eq!(response.status, StatusCode::UNPROCESSABLE_ENTITY, "{slug}");
+    }
+
+    let (user_id, token) = app.user("dee").await;
+    let path = format!("/admin/users/{user_id}/organization");
+    let moved = app.request(Method::PUT, &path, Some(&platform), Some(json!({ "organization_id": closed }))).await;
+    assert_eq!(moved.status, StatusCode::OK, "{}", moved.body);
+    assert_eq!(moved.body["organization_id"], closed);
+    // ย้ายแล้วต้อง Login ใหม่ Token ใหม่มีองค์กรใหม่
+    assert_eq!(app.get("/users/me", &token).await.status, StatusCode::UNAUTHORIZED);
+    let token = app.login("/login/user", "dee", PASSWORD).await;
+    assert_eq!(app.get("/users/me", &token).await.body["organization_id"], closed);
+
+    let acme = app.organization(&platform, "acme").await;
+    let acme_admin = app.invited_admin(&platform, Some(acme), "acme-admin").await;
+    let denied = app.request(Method::PUT, &path, Some(&acme_admin), Some(json!({ "organization_id": acme }))).await;
+    assert_eq!(denied.status, StatusCode::FORBIDDEN);
+}
@@ -0,0 +1,41 @@
+// tests/pagination.rs
+mod common;
+
+use axum::http::StatusCode;
+use common::TestApp;
+
+#[tokio::test]
+async fn rooms_are_paged_with_next_cursor() {
+    let app = TestApp::new().await;
+    let admin = app.admin_token().await;
+    for name in ["A", "B", "C"] {
+        app.room(&admin, name).await;
+    }
+
+    let first = app.get("/rooms?limit=2&sort=name&order=asc", &admin).await;
+    assert_eq!(first.status, StatusCode::OK, "{}", first.body);
+    assert_eq!(first.body["total"], 3);
+    assert_eq!(first.body["items"][0]["name"], "A");
+    assert_eq!(first.body["next_cursor"], "2");
+
+    let second = app.get("/rooms?limit=2&sort=name&order=asc&cursor=2", &admin).await;
+    assert_eq!(second.body["items"].as_array().unwrap().len(), 1);
+    assert_eq!(second.body["items"][0]["name"], "C");
+    assert!(second.body["next_cursor"].is_null());
+
+    let by_page = app.get("/rooms?limit=2&page=2&sort=name&order=asc", &admin).await;
+    assert_eq!(by_page.body["items"], second.body["items"]);
+}
+
+#[tokio::test]
+async fn invalid_paging_is_rejected() {
+    let app = TestApp::new().await;
+    let admin = app.admin_token().await;
+
+    let huge = app.get(&format!("/rooms?page={}&limit=100", i64::MAX), &admin).await;
+    assert_eq!(huge.status, StatusCode::UNPROCESSABLE_ENTITY);
+    let sort = app.get("/admin/users?sort=password_hash", &admin).await;
+    assert_eq!(sort.status, StatusCode::UNPROCESSABLE_ENTITY);
+    let cursor = app.get("/admin/bookings?cursor=-5", &admin).await;
+    assert_eq!(cursor.status, StatusCode::UNPROCESSABLE_ENTITY);
+}
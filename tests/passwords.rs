@@ -0,0 +1,61 @@
+// tests/passwords.rs
+mod common;
+
+use axum::http::StatusCode;
+use common::{TestApp, PASSWORD};
+use serde_json::json;
+
+const NEW_PASSWORD: &str = "a-brand-new-passphrase";
+
+#[tokio::test]
+async fn change_password_checks_current_password_and_revokes_sessions() {
+    let app = TestApp::new().await;
+    let (_, token) = app.user("liam").await;
+
+    let wrong = app
+        .post("/users/me/password", Some(&token), json!({ "current_password": "nope-nope", "new_password": NEW_PASSWORD }))
+        .await;
+    assert_eq!(wrong.status, StatusCode::UNAUTHORIZED);
+
+    let changed = app
+        .post("/users/me/password", Some(&token), json!({ "current_password": PASSWORD, "new_password": NEW_PASSWORD }))
+        .await;
+    assert!(changed.status.is_success(), "{}", changed.body);
+    assert_eq!(app.get("/bookings/user", &token).await.status, StatusCode::UNAUTHORIZED);
+
+    let old = app.post("/login/user", None, json!({ "username": "liam", "password": PASSWORD })).await;
+    assert_eq!(old.status, StatusCode::UNAUTHORIZED);
+    app.login("/login/user", "liam", NEW_PASSWORD).await;
+}
+
+#[tokio::test]
+async fn reset_token_works_once_and_unknown_users_look_the_same() {
+    let app = TestApp::new().await;
+    let (_, session) = app.user("mia").await;
+
+    let unknown = app.post("/auth/password-reset", None, json!({ "username": "nobody" })).await;
+    let known = app.post("/auth/password-reset", None, json!({ "username": "mia" })).await;
+    assert_eq!(unknown.status, known.status);
+    assert_eq!(unknown.body, known.body);
+
+    let token = app.last_link_token("mia");
+    let confirm = || app.post("/auth/password-reset/confirm", None, json!({ "token": token, "new_password": NEW_PASSWORD }));
+    assert!(confirm().await.status.is_success());
+    assert_eq!(confirm().await.status, StatusCode::UNAUTHORIZED);
+    assert_eq!(app.get("/bookings/user", &session).await.status, StatusCode::UNAUTHORIZED);
+    app.login("/login/user", "mia", NEW_PASSWORD).await;
+}
+
+#[tokio::test]
+async fn requesting_a_new_reset_link_invalidates_the_previous_one() {
+    let app = TestApp::new().await;
+    app.user("noah").await;
+    app.post("/auth/password-reset", None, json!({ "username": "noah" })).await;
+    let first = app.last_link_token("noah");
+    app.post("/auth/password-reset", None, json!({ "username": "noah" })).await;
+
+    let stale = app
+        .post("/auth/password-reset/confirm", None, json!({ "token": first, "new_password": NEW_PASSWORD }))
+        .await;
+    assert_eq!(stale.status, StatusCode::UNAUTHORIZED);
+}
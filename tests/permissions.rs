@@ -0,0 +1,68 @@
+// tests/permissions.rs
+mod common;
+
+use axum::http::{Method, StatusCode};
+use common::TestApp;
+use serde_json::json;
+
+#[tokio::test]
+async fn assigned_roles_grant_and_removed_roles_revoke_permissions() {
+    let app = TestApp::new().await;
+    let admin = app.admin_token().await;
+    let (user_id, user) = app.user("ivan").await;
+    let create_room = || app.post("/admin/rooms", Some(&user), json!({ "name": "Lab", "status": "available" }));
+
+    assert_eq!(create_room().await.status, StatusCode::FORBIDDEN);
+
+    let assigned = app
+        .post(&format!("/admin/users/{user_id}/roles"), Some(&admin), json!({ "role": "room_manager" }))
+        .await;
+    assert!(assigned.status.is_success(), "{}", assigned.body);
+    let roles = app.get(&format!("/admin/users/{user_id}/roles"), &admin).await;
+    assert!(roles.body["permissions"].as_array().unwrap().contains(&json!("rooms:write")), "{}", roles.body);
+    assert_eq!(create_room().await.status, StatusCode::CREATED);
+
+    let removed = app
+        .request(Method::DELETE, &format!("/admin/users/{user_id}/roles/room_manager"), Some(&admin), None)
+        .await;
+    assert!(removed.status.is_success(), "{}", removed.body);
+    assert_eq!(create_room().await.status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn unknown_roles_and_non_managers_are_rejected() {
+    let app = TestApp::new().await;
+    let admin = app.admin_token().await;
+    let (user_id, user) = app.user("judy").await;
+
+    let unknown = app
+        .post(&format!("/admin/users/{user_id}/roles"), Some(&admin), json!({ "role": "superuser" }))
+        .await;
+    assert!(unknown.status.is_client_error());
+    let self_grant = app
+        .post(&format!("/admin/users/{user_id}/roles"), Some(&user), json!({ "role": "admin" }))
+        .await;
+    assert_eq!(self_grant.status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn roles_beyond_the_granters_permissions_are_rejected() {
+    let app = TestApp::new().await;
+    let admin = app.admin_token().await;
+    let (user_id, _) = app.user("kate").await;
+    let roles = format!("/admin/users/{user_id}/roles");
+
+    // Role พื้นฐานของ Admin ให้ผู้ใช้ไม่ได้
+    let admin_role = app.post(&roles, Some(&admin), json!({ "role": "admin" })).await;
+    assert_eq!(admin_role.status, StatusCode::FORBIDDEN, "{}", admin_role.body);
+
+    // Key ที่มีแค่ roles:manage กำหนด Role ที่มี Permission มากกว่านั้นไม่ได้
+    let created = app.post("/users/me/api-keys", Some(&admin), json!({ "name": "Roles", "scopes": ["roles:manage"] })).await;
+    assert_eq!(created.status, StatusCode::CREATED, "{}", created.body);
+    let key = created.body["key"].as_str().unwrap().to_string();
+    let escalated = app.post(&roles, Some(&key), json!({ "role": "room_manager" })).await;
+    assert_eq!(escalated.status, StatusCode::FORBIDDEN, "{}", escalated.body);
+
+    let current = app.get(&roles, &admin).await;
+    assert_eq!(current.body["roles"], json!([]), "{}", current.body);
+}
@@ -0,0 +1,95 @@
+// tests/privacy.rs
+mod common;
+
+use axum::http::{Method, StatusCode};
+use common::{TestApp, PASSWORD};
+use serde_json::json;
+
+#[tokio::test]
+async fn users_export_all_of_their_data() {
+    let app = TestApp::new().await;
+    let admin = app.admin_token().await;
+    let room = app.room(&admin, "Export").await;
+    let (user_id, token) = app.user("ida").await;
+    let kept = app.book(&token, room, 1, 9, 10).await.body["id"].as_i64().unwrap();
+    let cancelled = app.book(&token, room, 2, 9, 10).await.body["id"].as_i64().unwrap();
+    app.request(Method::DELETE, &format!("/bookings/{cancelled}"), Some(&token), None).await;
+    app.post(&format!("/admin/users/{user_id}/roles"), Some(&admin), json!({ "role": "room_manager" })).await;
+
+    let export = app.get("/users/me/export", &token).await;
+    assert_eq!(export.status, StatusCode::OK, "{}", export.body);
+    assert_eq!(export.body["profile"]["username"], "ida");
+    assert_eq!(export.body["roles"], json!(["room_manager"]));
+    let bookings: Vec<_> = export.body["bookings"].as_array().unwrap().iter().map(|b| b["id"].as_i64().unwrap()).collect();
+    assert_eq!(bookings.len(), 2);
+    assert!(bookings.contains(&kept) && bookings.contains(&cancelled));
+    assert!(!export.body["login_events"].as_array().unwrap().is_empty());
+    assert!(!export.body.to_string().contains("$2b$"));
+
+    // ผู้อื่นไม่เห็นข้อมูลนี้ และ API Key เรียกไม่ได้
+    let (_, other) = app.user("jon").await;
+    assert_eq!(app.get("/users/me/export", &other).await.body["bookings"], json!([]));
+    let key = app.post("/users/me/api-keys", Some(&token), json!({ "name": "k", "scopes": ["bookings:write"] })).await;
+    let key = key.body["key"].as_str().unwrap().to_string();
+    assert_eq!(app.get("/users/me/export", &key).await.status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn users_can_erase_their_own_account() {
+    let app = TestApp::new().await;
+    let admin = app.admin_token().await;
+    let room = app.room(&admin, "Erase").await;
+    let (user_id, token) = app.user("kai").await;
+    let past = app.book(&token, room, 1, 9, 10).await.body["id"].as_i64().unwrap();
+    app.book(&token, room, 2, 9, 10).await;
+    // ทำให้การจองแรกจบไปแล้ว
+    app.execute(&format!(
+        "UPDATE bookings SET start_time = datetime('now', '-2 days'), end_time = datetime('now', '-2 days', '+1 hour') WHERE id = {past}"
+    ));
+
+    let erase = |body| app.request(Method::POST, "/users/me/erasure", Some(&token), Some(body));
+    assert_eq!(erase(json!({ "confirm_username": "someone" })).await.status, StatusCode::UNPROCESSABLE_ENTITY);
+    let erased = erase(json!({ "confirm_username": "kai", "reason": "leaving" })).await;
+    assert_eq!(erased.status, StatusCode::OK, "{}", erased.body);
+    assert_eq!(erased.body["requested_by_kind"], "user");
+    assert_eq!(erased.body["bookings_cancelled"], 1);
+    assert_eq!(erased.body["bookings_retained"], 2);
+
+    // Login และ Token เดิมใช้ไม่ได้ username ถูกแทนที่
+    let login = app.post("/login/user", None, json!({ "username": "kai", "password": PASSWORD })).await;
+    assert_eq!(login.status, StatusCode::UNAUTHORIZED);
+    assert_eq!(app.get("/users/me", &token).await.status, StatusCode::UNAUTHORIZED);
+    let users = app.get("/admin/users?include_deleted=true", &admin).await;
+    let user = users.body["items"].as_array().unwrap().iter().find(|u| u["id"] == user_id).unwrap();
+    assert_eq!(user["username"], format!("erased#{user_id}"));
+    assert!(user["email"].is_null() && user["display_name"].is_null());
+
+    // ผู้ใช้ที่ถูกลบข้อมูลแล้วกู้คืนหรือลบซ้ำไม่ได้ และสมัครชื่อเดิมใหม่ได้
+    let restore = app.request(Method::POST, &format!("/admin/users/{user_id}/restore"), Some(&admin), None).await;
+    assert_eq!(restore.status, StatusCode::CONFLICT);
+    let again = app.post(&format!("/admin/users/{user_id}/erase"), Some(&admin), json!({})).await;
+    assert_eq!(again.status, StatusCode::CONFLICT);
+    app.user("kai").await;
+}
+
+#[tokio::test]
+async fn admins_erase_users_and_list_the_records() {
+    let app = TestApp::new().await;
+    let admin = app.admin_token().await;
+    let (user_id, _) = app.user("lea").await;
+    let (_, other) = app.user("max").await;
+
+    let path = format!("/admin/users/{user_id}/erase");
+    assert_eq!(app.post(&path, Some(&other), json!({})).await.status, StatusCode::FORBIDDEN);
+    assert_eq!(app.post("/admin/users/9999/erase", Some(&admin), json!({})).await.status, StatusCode::NOT_FOUND);
+
+    let erased = app.post(&path, Some(&admin), json!({ "reason": "ticket 42" })).await;
+    assert_eq!(erased.status, StatusCode::OK, "{}", erased.body);
+    assert_eq!(erased.body["requested_by_kind"], "admin");
+
+    let records = app.get(&format!("/admin/erasure-requests?user_id={user_id}"), &admin).await;
+    assert_eq!(records.status, StatusCode::OK);
+    assert_eq!(records.body["total"], 1);
+    assert_eq!(records.body["items"][0]["reason"], "ticket 42");
+    assert_eq!(app.get("/admin/erasure-requests", &other).await.status, StatusCode::FORBIDDEN);
+}
@@ -0,0 +1,69 @@
+// tests/profile.rs
+mod common;
+
+use axum::http::{Method, StatusCode};
+use common::TestApp;
+use serde_json::json;
+
+#[tokio::test]
+async fn users_read_and_update_their_own_profile() {
+    let app = TestApp::new().await;
+    let (_, token) = app.user("gina").await;
+
+    let me = app.get("/users/me", &token).await;
+    assert_eq!(me.status, StatusCode::OK);
+    assert_eq!(me.body["username"], "gina");
+    assert_eq!(me.body["has_password"], true);
+
+    let patch = |body| app.request(Method::PATCH, "/users/me", Some(&token), Some(body));
+    let updated = patch(json!({
+        "display_name": "Gina G",
+        "phone": "+66 81 234 5678",
+        "department": "Facilities",
+        "timezone": "Asia/Bangkok",
+        "language": "th"
+    }))
+    .await;
+    assert_eq!(updated.status, StatusCode::OK, "{}", updated.body);
+    assert_eq!(updated.body["timezone"], "Asia/Bangkok");
+    assert_eq!(updated.body["department"], "Facilities");
+
+    // Field ที่ไม่ส่งไม่เปลี่ยน และ "" ล้างค่า
+    let cleared = patch(json!({ "department": "" })).await;
+    assert_eq!(cleared.body["department"], serde_json::Value::Null);
+    assert_eq!(cleared.body["display_name"], "Gina G");
+}
+
+#[tokio::test]
+async fn invalid_profile_values_are_rejected() {
+    let app = TestApp::new().await;
+    let (_, token) = app.user("hugo").await;
+    for body in [
+        json!({ "email": "not-an-email" }),
+        json!({ "timezone": "Mars/Olympus" }),
+        json!({ "language": "not a tag" }),
+        json!({ "phone": "call me" }),
+    ] {
+        let response = app.request(Method::PATCH, "/users/me", Some(&token), Some(body.clone())).await;
+        assert_eq!(response.status, StatusCode::UNPROCESSABLE_ENTITY, "{body}");
+    }
+}
+
+#[tokio::test]
+async fn no_response_contains_the_password_hash() {
+    let app = TestApp::new().await;
+    let admin = app.admin_token().await;
+    let (user_id, token) = app.user("iris").await;
+
+    let responses = [
+        app.get("/users/me", &token).await,
+        app.get("/admin/users", &admin).await,
+        app.get(&format!("/admin/users/{user_id}"), &admin).await,
+        app.post("/register", None, json!({ "username": "jack", "password": common::PASSWORD })).await,
+    ];
+    for response in responses {
+        assert!(response.status.is_success(), "{}", response.body);
+        let body = response.body.to_string();
+        assert!(!body.contains("password_hash") && !body.contains("$2b$"), "{body}");
+    }
+}
@@ -0,0 +1,47 @@
+// tests/refresh_tokens.rs
+mod common;
+
+use axum::http::StatusCode;
+use common::{TestApp, PASSWORD};
+use serde_json::json;
+
+#[tokio::test]
+async fn refresh_rotates_and_reuse_revokes_the_family() {
+    let app = TestApp::new().await;
+    app.user("erin").await;
+    let login = app.login_response("/login/user", "erin", PASSWORD).await;
+    let first_refresh = login.body["refresh_token"].as_str().unwrap().to_string();
+
+    let rotated = app.post("/auth/refresh", None, json!({ "refresh_token": first_refresh })).await;
+    assert_eq!(rotated.status, StatusCode::OK, "{}", rotated.body);
+    let second_refresh = rotated.body["refresh_token"].as_str().unwrap().to_string();
+    let second_access = rotated.body["token"].as_str().unwrap().to_string();
+    assert_ne!(second_refresh, first_refresh);
+    assert_eq!(app.get("/bookings/user", &second_access).await.status, StatusCode::OK);
+
+    // ใช้ Refresh Token เดิมซ้ำ: ถูกปฏิเสธ และ token ทั้ง family ใช้ไม่ได้อีก
+    let reused = app.post("/auth/refresh", None, json!({ "refresh_token": first_refresh })).await;
+    assert_eq!(reused.status, StatusCode::UNAUTHORIZED);
+    let after = app.post("/auth/refresh", None, json!({ "refresh_token": second_refresh })).await;
+    assert_eq!(after.status, StatusCode::UNAUTHORIZED);
+    assert_eq!(app.get("/bookings/user", &second_access).await.status, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn logout_revokes_access_and_refresh_tokens() {
+    let app = TestApp::new().await;
+    app.user("frank").await;
+    let login = app.login_response("/login/user", "frank", PASSWORD).await;
+    let access = login.body["token"].as_str().unwrap();
+    let refresh = login.body["refresh_token"].as_str().unwrap();
+
+    let logout = app.post("/auth/logout", None, json!({ "refresh_token": refresh })).await;
+    assert_eq!(logout.status, StatusCode::NO_CONTENT);
+    assert_eq!(app.get("/bookings/user", access).await.status, StatusCode::UNAUTHORIZED);
+    let refreshed = app.post("/auth/refresh", None, json!({ "refresh_token": refresh })).await;
+    assert_eq!(refreshed.status, StatusCode::UNAUTHORIZED);
+
+    // Logout ซ้ำด้วย Token เดิมไม่เป็น error
+    let again = app.post("/auth/logout", None, json!({ "refresh_token": refresh })).await;
+    assert_eq!(again.status, StatusCode::NO_CONTENT);
+}
@@ -0,0 +1,40 @@
+// tests/reports.rs
+mod common;
+
+use axum::http::StatusCode;
+use common::TestApp;
+
+#[tokio::test]
+async fn utilization_counts_booked_hours_against_open_hours() {
+    let app = TestApp::new().await;
+    let admin = app.admin_token().await;
+    let room = app.room(&admin, "Focus").await;
+    let (_, user) = app.user("alice").await;
+    assert_eq!(app.book(&user, room, 1, 9, 11).await.status, StatusCode::CREATED);
+
+    let day = (chrono::Utc::now().date_naive() + chrono::Duration::days(1)).to_string();
+    let report = app
+        .get(&format!("/admin/reports/utilization?from={day}&to={day}&room_id={room}"), &admin)
+        .await;
+    assert_eq!(report.status, StatusCode::OK, "{}", report.body);
+    let rows = report.body.as_array().unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0]["bookings"], 1);
+    assert_eq!(rows[0]["booked_hours"], 2.0);
+    assert_eq!(rows[0]["open_hours"], 10.0);
+    assert_eq!(rows[0]["utilization"], 0.2);
+
+    let top = app.get(&format!("/admin/reports/top-bookers?from={day}&to={day}"), &admin).await;
+    assert_eq!(top.body[0]["username"], "alice");
+}
+
+#[tokio::test]
+async fn reports_are_admin_only_and_validate_the_range() {
+    let app = TestApp::new().await;
+    let (_, user) = app.user("bob").await;
+    assert_eq!(app.get("/admin/reports/peak-hours", &user).await.status, StatusCode::FORBIDDEN);
+
+    let admin = app.admin_token().await;
+    let invalid = app.get("/admin/reports/booking-rates?from=2024-05-02&to=2024-05-01", &admin).await;
+    assert_eq!(invalid.status, StatusCode::UNPROCESSABLE_ENTITY);
+}
@@ -0,0 +1,116 @@
+// tests/restore.rs
+mod common;
+
+use axum::http::{Method, StatusCode};
+use common::{TestApp, PASSWORD};
+use serde_json::json;
+
+#[tokio::test]
+async fn restoring_a_booking_cannot_double_book_the_room() {
+    let app = TestApp::new().await;
+    let admin = app.admin_token().await;
+    let room = app.room(&admin, "Restore").await;
+    let (_, ann) = app.user("cara").await;
+    let (_, ben) = app.user("dan").await;
+
+    let first = app.book(&ann, room, 1, 9, 10).await;
+    let first_id = first.body["id"].as_i64().unwrap();
+    let cancelled = app.request(Method::DELETE, &format!("/bookings/{first_id}"), Some(&ann), None).await;
+    assert!(cancelled.status.is_success(), "{}", cancelled.body);
+
+    // ช่วงเวลาถูกจองใหม่หลังยกเลิก จึงกู้คืนการจองเดิมไม่ได้
+    let second = app.book(&ben, room, 1, 9, 10).await;
+    assert_eq!(second.status, StatusCode::CREATED);
+    let restore = format!("/admin/bookings/{first_id}/restore");
+    let conflict = app.request(Method::POST, &restore, Some(&admin), None).await;
+    assert_eq!(conflict.status, StatusCode::CONFLICT, "{}", conflict.body);
+
+    let second_id = second.body["id"].as_i64().unwrap();
+    let deleted = app.request(Method::DELETE, &format!("/admin/bookings/{second_id}"), Some(&admin), None).await;
+    assert!(deleted.status.is_success(), "{}", deleted.body);
+    let restored = app.request(Method::POST, &restore, Some(&admin), None).await;
+    assert_eq!(restored.status, StatusCode::OK, "{}", restored.body);
+    assert_eq!(restored.body["status"], "active");
+
+    // กู้คืนซ้ำได้ 404 และผู้ใช้ทั่วไปกู้คืนไม่ได้
+    assert_eq!(app.request(Method::POST, &restore, Some(&admin), None).await.status, StatusCode::NOT_FOUND);
+    let forbidden = app.request(Method::POST, &format!("/admin/bookings/{second_id}/restore"), Some(&ben), None).await;
+    assert_eq!(forbidden.status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn bookings_of_deleted_rooms_and_users_cannot_be_restored() {
+    let app = TestApp::new().await;
+    let admin = app.admin_token().await;
+    let room = app.room(&admin, "Gone").await;
+    let (user_id, token) = app.user("eve").await;
+    let booking = app.book(&token, room, 1, 9, 10).await.body["id"].as_i64().unwrap();
+
+    let deleted = app.request(Method::DELETE, &format!("/admin/users/{user_id}"), Some(&admin), None).await;
+    assert_eq!(deleted.status, StatusCode::NO_CONTENT, "{}", deleted.body);
+    let restore = format!("/admin/bookings/{booking}/restore");
+    assert_eq!(app.request(Method::POST, &restore, Some(&admin), None).await.status, StatusCode::CONFLICT);
+
+    let user = app.request(Method::POST, &format!("/admin/users/{user_id}/restore"), Some(&admin), None).await;
+    assert_eq!(user.status, StatusCode::OK, "{}", user.body);
+    let room_deleted = app.request(Method::DELETE, &format!("/admin/rooms/{room}"), Some(&admin), None).await;
+    assert!(room_deleted.status.is_success(), "{}", room_deleted.body);
+    assert_eq!(app.request(Method::POST, &restore, Some(&admin), None).await.status, StatusCode::CONFLICT);
+
+    let room_restored = app.request(Method::POST, &format!("/admin/rooms/{room}/restore"), Some(&admin), None).await;
+    assert_eq!(room_restored.status, StatusCode::OK, "{}", room_restored.body);
+    assert_eq!(app.request(Method::POST, &restore, Some(&admin), None).await.status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn deleting_a_user_handles_future_bookings() {
+    let app = TestApp::new().await;
+    let admin = app.admin_token().await;
+    let room = app.room(&admin, "Future").await;
+    let (leaver, leaver_token) = app.user("finn").await;
+    let (heir, _) = app.user("gail").await;
+    app.book(&leaver_token, room, 1, 9, 10).await;
+    app.book(&leaver_token, room, 2, 9, 10).await;
+
+    let path = format!("/admin/users/{leaver}?future_bookings=transfer");
+    let missing_target = app.request(Method::DELETE, &path, Some(&admin), None).await;
+    assert_eq!(missing_target.status, StatusCode::UNPROCESSABLE_ENTITY);
+    let transferred = app.request(Method::DELETE, &format!("{path}&transfer_to={heir}"), Some(&admin), None).await;
+    assert_eq!(transferred.status, StatusCode::NO_CONTENT, "{}", transferred.body);
+
+    let moved = app.get(&format!("/admin/bookings?user_id={heir}"), &admin).await;
+    assert_eq!(moved.body["total"], 2, "{}", moved.body);
+    // ผู้ใช้ที่ถูกลบ Login ไม่ได้ และไม่อยู่ในรายการเว้นแต่ include_deleted=true
+    let login = app.post("/login/user", None, json!({ "username": "finn", "password": PASSWORD })).await;
+    assert_eq!(login.status, StatusCode::UNAUTHORIZED);
+    let listed = |query: &'static str| app.get(query, &admin);
+    let active = listed("/admin/users").await.body.to_string();
+    assert!(!active.contains("\"finn\""));
+    assert!(listed("/admin/users?include_deleted=true").await.body.to_string().contains("\"finn\""));
+
+    let restored = app.request(Method::POST, &format!("/admin/users/{leaver}/restore"), Some(&admin), None).await;
+    assert_eq!(restored.status, StatusCode::OK, "{}", restored.body);
+    app.login("/login/user", "finn", PASSWORD).await;
+    assert_eq!(
+        app.request(Method::POST, &format!("/admin/users/{leaver}/restore"), Some(&admin), None).await.status,
+        StatusCode::NOT_FOUND
+    );
+}
+
+#[tokio::test]
+async fn deleting_a_user_cancels_future_bookings_by_default() {
+    let app = TestApp::new().await;
+    let admin = app.admin_token().await;
+    let room = app.room(&admin, "Cancelled").await;
+    let (user_id, token) = app.user("hana").await;
+    app.book(&token, room, 1, 9, 10).await;
+
+    let deleted = app.request(Method::DELETE, &format!("/admin/users/{user_id}"), Some(&admin), None).await;
+    assert_eq!(deleted.status, StatusCode::NO_CONTENT);
+    let visible = app.get(&format!("/admin/bookings?user_id={user_id}"), &admin).await;
+    assert_eq!(visible.body["total"], 0);
+    let all = app.get(&format!("/admin/bookings?user_id={user_id}&include_deleted=true"), &admin).await;
+    assert_eq!(all.body["total"], 1);
+    assert_eq!(all.body["items"][0]["status"], "cancelled");
+    assert_eq!(app.get(&format!("/rooms/{room}"), &admin).await.body["status"], "available");
+}
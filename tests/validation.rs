@@ -0,0 +1,45 @@
+// tests/validation.rs
+mod common;
+
+use axum::body::Body;
+use axum::http::{header, Request, StatusCode};
+use common::TestApp;
+use serde_json::json;
+
+async fn register(app: &TestApp, content_type: Option<&str>, body: &str) -> common::TestResponse {
+    let mut request = Request::builder().method("POST").uri("/register");
+    if let Some(content_type) = content_type {
+        request = request.header(header::CONTENT_TYPE, content_type);
+    }
+    app.send(request.body(Body::from(body.to_string())).unwrap()).await
+}
+
+#[tokio::test]
+async fn malformed_bodies_get_400_and_415() {
+    let app = TestApp::new().await;
+
+    let syntax = register(&app, Some("application/json"), "{\"username\": ").await;
+    assert_eq!(syntax.status, StatusCode::BAD_REQUEST);
+    assert_eq!(syntax.body["code"], "bad_request");
+
+    let media = register(&app, None, r#"{"username": "dave", "password": "long-enough"}"#).await;
+    assert_eq!(media.status, StatusCode::UNSUPPORTED_MEDIA_TYPE);
+    assert_eq!(media.body["code"], "unsupported_media_type");
+
+    let data = register(&app, Some("application/json"), r#"{"username": 5}"#).await;
+    assert_eq!(data.status, StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[tokio::test]
+async fn every_invalid_field_is_reported() {
+    let app = TestApp::new().await;
+    let response = app.post("/register", None, json!({ "username": "x!", "password": "short" })).await;
+    assert_eq!(response.status, StatusCode::UNPROCESSABLE_ENTITY);
+    let fields: Vec<&str> = response.body["errors"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|error| error["field"].as_str().unwrap())
+        .collect();
+    assert!(fields.contains(&"username") && fields.contains(&"password"), "{fields:?}");
+}